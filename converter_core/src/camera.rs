@@ -0,0 +1,155 @@
+//! Cameras of a scene: capture views imported with it, or views set up to render it.
+
+use crate::Scene;
+use crate::helpers::bounds::robust_bounds;
+
+/// Share of splats ignored at each end of every axis when framing a scene.
+const FRAMING_OUTLIERS: f32 = 0.02;
+
+/// A pinhole camera in the COLMAP / OpenCV convention: it looks along +Z with +X to the right and
+/// +Y down the image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Camera {
+  /// Name of the image taken from this view, empty for synthetic views.
+  pub name: String,
+  /// Camera centre in world space.
+  pub position: [f32; 3],
+  /// World-to-camera rotation, row-major.
+  pub rotation: [[f32; 3]; 3],
+  pub width: u32,
+  pub height: u32,
+  /// Focal lengths (x, y) in pixels.
+  pub focal: [f32; 2],
+  /// Principal point (x, y) in pixels.
+  pub principal_point: [f32; 2],
+}
+
+/// Where `Camera::framing` places the camera around the scene.
+#[derive(Debug, Clone)]
+pub struct FramingOptions {
+  /// Rotation around the up axis, in degrees.
+  pub azimuth_degrees: f32,
+  /// Angle above the horizontal plane, in degrees.
+  pub elevation_degrees: f32,
+  /// Vertical field of view, in degrees.
+  pub fov_y_degrees: f32,
+  /// World-space up direction. 3DGS scenes from COLMAP are usually -Y up.
+  pub up: [f32; 3],
+}
+
+impl Default for FramingOptions {
+  fn default() -> Self {
+    Self {
+      azimuth_degrees: 0.0,
+      elevation_degrees: 20.0,
+      fov_y_degrees: 50.0,
+      up: [0.0, -1.0, 0.0],
+    }
+  }
+}
+
+pub(crate) fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+  [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+pub(crate) fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+  a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub(crate) fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+  [
+    a[1] * b[2] - a[2] * b[1],
+    a[2] * b[0] - a[0] * b[2],
+    a[0] * b[1] - a[1] * b[0],
+  ]
+}
+
+pub(crate) fn normalize(v: [f32; 3]) -> Option<[f32; 3]> {
+  let length = dot(v, v).sqrt();
+  (length > 0.0 && length.is_finite()).then(|| v.map(|c| c / length))
+}
+
+impl Camera {
+  /// A camera at `eye` looking at `target`, with a vertical field of view in degrees.
+  /// Falls back to another up vector when `up` is parallel to the view direction.
+  pub fn look_at(
+    eye: [f32; 3],
+    target: [f32; 3],
+    up: [f32; 3],
+    width: u32,
+    height: u32,
+    fov_y_degrees: f32,
+  ) -> Self {
+    let forward = normalize(sub(target, eye)).unwrap_or([0.0, 0.0, 1.0]);
+    let right = normalize(cross(forward, up))
+      .or_else(|| normalize(cross(forward, [0.0, 0.0, 1.0])))
+      .or_else(|| normalize(cross(forward, [1.0, 0.0, 0.0])))
+      .unwrap();
+    let down = cross(forward, right);
+
+    let focal = height as f32 / 2.0 / (fov_y_degrees.to_radians() / 2.0).tan();
+    Self {
+      name: String::new(),
+      position: eye,
+      rotation: [right, down, forward],
+      width,
+      height,
+      focal: [focal, focal],
+      principal_point: [width as f32 / 2.0, height as f32 / 2.0],
+    }
+  }
+
+  /// An orbit camera looking at the centre of the scene, far enough away that the bounds of the
+  /// splat centres fit in the image. The outermost 2% of splats on each axis are ignored so that
+  /// floaters don't shrink the scene to a dot. At azimuth and elevation 0 the camera looks along
+  /// the horizontal axis closest to +Z.
+  pub fn framing(scene: &Scene, width: u32, height: u32, options: &FramingOptions) -> Self {
+    let (min, max) = robust_bounds(scene, FRAMING_OUTLIERS).unwrap_or(([-1.0; 3], [1.0; 3]));
+    let center = [0, 1, 2].map(|axis| (min[axis] + max[axis]) / 2.0);
+    let radius = (dot(sub(max, min), sub(max, min)).sqrt() / 2.0).max(1e-3);
+
+    let up = normalize(options.up).unwrap_or([0.0, -1.0, 0.0]);
+    let reference = if up[0].abs() < 0.9 {
+      [1.0, 0.0, 0.0]
+    } else {
+      [0.0, 1.0, 0.0]
+    };
+    let forward_axis = normalize(cross(up, reference)).unwrap();
+    let side_axis = cross(forward_axis, up);
+
+    // The sphere around the bounds has to fit in the narrower field of view
+    let half_fov_y = options.fov_y_degrees.to_radians() / 2.0;
+    let half_fov_x = (half_fov_y.tan() * width as f32 / height as f32).atan();
+    let distance = radius / half_fov_x.min(half_fov_y).sin();
+
+    let (azimuth, elevation) = (
+      options.azimuth_degrees.to_radians(),
+      options.elevation_degrees.to_radians(),
+    );
+    let horizontal =
+      [0, 1, 2].map(|axis| azimuth.cos() * forward_axis[axis] + azimuth.sin() * side_axis[axis]);
+    let eye = [0, 1, 2].map(|axis| {
+      center[axis] - distance * elevation.cos() * horizontal[axis]
+        + distance * elevation.sin() * up[axis]
+    });
+
+    Self::look_at(eye, center, up, width, height, options.fov_y_degrees)
+  }
+
+  /// The same view at `factor` times the resolution, e.g. to preview a full-size capture camera.
+  pub fn scaled(&self, factor: f32) -> Self {
+    Self {
+      width: ((self.width as f32 * factor).round() as u32).max(1),
+      height: ((self.height as f32 * factor).round() as u32).max(1),
+      focal: self.focal.map(|f| f * factor),
+      principal_point: self.principal_point.map(|c| c * factor),
+      ..self.clone()
+    }
+  }
+
+  /// World-space position to camera space.
+  pub(crate) fn to_camera(&self, point: [f32; 3]) -> [f32; 3] {
+    let relative = sub(point, self.position);
+    self.rotation.map(|row| dot(row, relative))
+  }
+}
@@ -17,3 +17,26 @@ pub enum ConversionError {
   #[error("An unknown error occurred")]
   Unknown,
 }
+
+impl ConversionError {
+  /// Short, stable name of the error's category, for machine-readable reports.
+  pub fn kind(&self) -> &'static str {
+    match self {
+      ConversionError::Io(_) => "io",
+      ConversionError::ParseError { .. } => "parse",
+      ConversionError::WriteError { .. } => "write",
+      ConversionError::UnsupportedFormat => "unsupported_format",
+      ConversionError::Unknown => "unknown",
+    }
+  }
+
+  /// The format being read or written when the error happened, if known.
+  pub fn format(&self) -> Option<&str> {
+    match self {
+      ConversionError::ParseError { format, .. } | ConversionError::WriteError { format, .. } => {
+        Some(format)
+      }
+      _ => None,
+    }
+  }
+}
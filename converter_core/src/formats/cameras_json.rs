@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Camera, ConversionError};
+
+/// Reads the `cameras.json` written next to a trained INRIA 3DGS model.
+pub struct CamerasJsonImporter;
+/// Writes cameras in the INRIA 3DGS `cameras.json` layout, which viewers like SIBR read.
+pub struct CamerasJsonExporter;
+
+/// One entry of `cameras.json`. `rotation` is the camera-to-world rotation (rows), `position` the
+/// camera centre; the principal point is always the image centre.
+#[derive(Serialize, Deserialize)]
+struct CameraEntry {
+  id: usize,
+  img_name: String,
+  width: u32,
+  height: u32,
+  position: [f32; 3],
+  rotation: [[f32; 3]; 3],
+  fy: f32,
+  fx: f32,
+}
+
+fn transpose(matrix: [[f32; 3]; 3]) -> [[f32; 3]; 3] {
+  [0, 1, 2].map(|row| [0, 1, 2].map(|column| matrix[column][row]))
+}
+
+impl CamerasJsonImporter {
+  pub fn import(reader: &mut impl std::io::Read) -> Result<Vec<Camera>, ConversionError> {
+    let entries: Vec<CameraEntry> =
+      serde_json::from_reader(reader).map_err(|e| ConversionError::ParseError {
+        format: "cameras.json".to_string(),
+        message: e.to_string(),
+      })?;
+
+    Ok(
+      entries
+        .into_iter()
+        .map(|entry| Camera {
+          name: entry.img_name,
+          position: entry.position,
+          rotation: transpose(entry.rotation),
+          width: entry.width,
+          height: entry.height,
+          focal: [entry.fx, entry.fy],
+          principal_point: [entry.width as f32 / 2.0, entry.height as f32 / 2.0],
+        })
+        .collect(),
+    )
+  }
+}
+
+impl CamerasJsonExporter {
+  pub fn export(
+    cameras: &[Camera],
+    writer: &mut impl std::io::Write,
+  ) -> Result<(), ConversionError> {
+    let entries: Vec<CameraEntry> = cameras
+      .iter()
+      .enumerate()
+      .map(|(id, camera)| CameraEntry {
+        id,
+        img_name: camera.name.clone(),
+        width: camera.width,
+        height: camera.height,
+        position: camera.position,
+        rotation: transpose(camera.rotation),
+        fy: camera.focal[1],
+        fx: camera.focal[0],
+      })
+      .collect();
+
+    serde_json::to_writer(writer, &entries).map_err(|e| ConversionError::WriteError {
+      format: "cameras.json".to_string(),
+      message: e.to_string(),
+    })
+  }
+}
@@ -0,0 +1,377 @@
+use std::collections::HashMap;
+
+use crate::Camera;
+use crate::formats::point_cloud::{PointCloudImportOptions, scene_from_points};
+use crate::helpers::rotation::quaternion_to_matrix;
+use crate::{ConversionError, Importer, Scene};
+
+/// Reads the sparse reconstruction of a COLMAP model (`points3D.bin` or `points3D.txt`) and
+/// initialises a Gaussian per point, which is how 3DGS training starts.
+pub struct ColmapPointsImporter;
+
+/// Reads the registered views of a COLMAP model from `cameras.bin` / `cameras.txt` and
+/// `images.bin` / `images.txt`. Lens distortion is ignored, every view becomes a pinhole camera.
+pub struct ColmapCamerasImporter;
+
+/// Camera models in the order of their COLMAP IDs, with their parameter counts.
+const CAMERA_MODELS: [(&str, usize); 11] = [
+  ("SIMPLE_PINHOLE", 3),
+  ("PINHOLE", 4),
+  ("SIMPLE_RADIAL", 4),
+  ("RADIAL", 5),
+  ("OPENCV", 8),
+  ("OPENCV_FISHEYE", 8),
+  ("FULL_OPENCV", 12),
+  ("FOV", 5),
+  ("SIMPLE_RADIAL_FISHEYE", 4),
+  ("RADIAL_FISHEYE", 5),
+  ("THIN_PRISM_FISHEYE", 12),
+];
+
+/// Image size, focal lengths and principal point of a COLMAP camera.
+struct Intrinsics {
+  width: u32,
+  height: u32,
+  focal: [f32; 2],
+  principal_point: [f32; 2],
+}
+
+/// A registered image: its name, camera ID and world-to-camera pose.
+struct View {
+  name: String,
+  camera_id: u32,
+  rotation: [f32; 4],
+  translation: [f64; 3],
+}
+
+/// Positions and 0..1 RGB colours of the points.
+type Points = (Vec<[f32; 3]>, Vec<[f32; 3]>);
+
+fn parse_error(message: impl Into<String>) -> ConversionError {
+  ConversionError::ParseError {
+    format: "COLMAP".to_string(),
+    message: message.into(),
+  }
+}
+
+/// Whether a points3D file is in COLMAP's text format. Text models start with a `#` comment,
+/// binary ones with a little-endian point count whose high bytes are zero.
+fn is_text(data: &[u8]) -> bool {
+  data.first() == Some(&b'#')
+    || (!data.is_empty()
+      && data
+        .iter()
+        .take(64)
+        .all(|b| b.is_ascii_graphic() || b.is_ascii_whitespace()))
+}
+
+/// Parses `POINT3D_ID X Y Z R G B ERROR TRACK[]` lines.
+fn parse_text(text: &str) -> Result<Points, ConversionError> {
+  let mut positions = Vec::new();
+  let mut colors = Vec::new();
+
+  for (line_number, line) in text.lines().enumerate() {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+    let fields: Vec<&str> = line.split_whitespace().take(7).collect();
+    if fields.len() < 7 {
+      return Err(parse_error(format!(
+        "Line {}: expected at least 7 fields",
+        line_number + 1
+      )));
+    }
+    let number = |index: usize| {
+      fields[index].parse::<f64>().map_err(|e| {
+        parse_error(format!(
+          "Line {}: invalid number \"{}\": {}",
+          line_number + 1,
+          fields[index],
+          e
+        ))
+      })
+    };
+    positions.push([number(1)? as f32, number(2)? as f32, number(3)? as f32]);
+    colors.push([
+      number(4)? as f32 / 255.0,
+      number(5)? as f32 / 255.0,
+      number(6)? as f32 / 255.0,
+    ]);
+  }
+
+  Ok((positions, colors))
+}
+
+/// Parses the binary layout: a `u64` point count, then per point a `u64` ID, `f64` XYZ, `u8` RGB,
+/// an `f64` reprojection error and a `u64`-counted track of `(u32 image, u32 point2D)` pairs.
+fn parse_binary(data: &[u8]) -> Result<Points, ConversionError> {
+  let mut reader = BinaryReader { data, cursor: 0 };
+  let count = reader.u64()?;
+  // Every point takes at least 51 bytes, which bounds the allocation for corrupt counts
+  let capacity = usize::try_from(count).map_or(0, |count| count.min(data.len() / 51));
+  let mut positions = Vec::with_capacity(capacity);
+  let mut colors = Vec::with_capacity(capacity);
+
+  for _ in 0..count {
+    reader.u64()?;
+    positions.push([
+      reader.f64()? as f32,
+      reader.f64()? as f32,
+      reader.f64()? as f32,
+    ]);
+    let rgb = reader.take(3)?;
+    colors.push([0, 1, 2].map(|c| rgb[c] as f32 / 255.0));
+    reader.f64()?;
+    let track_length = usize::try_from(reader.u64()?)
+      .ok()
+      .and_then(|length| length.checked_mul(8))
+      .ok_or_else(|| parse_error("Invalid track length"))?;
+    reader.take(track_length)?;
+  }
+
+  Ok((positions, colors))
+}
+
+impl Importer for ColmapPointsImporter {
+  fn import(reader: &mut impl std::io::Read) -> Result<Scene, ConversionError> {
+    Self::import_with_options(reader, &PointCloudImportOptions::default())
+  }
+}
+
+impl ColmapPointsImporter {
+  pub fn import_with_options(
+    reader: &mut impl std::io::Read,
+    options: &PointCloudImportOptions,
+  ) -> Result<Scene, ConversionError> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    let (positions, colors) = if is_text(&data) {
+      let text = std::str::from_utf8(&data).map_err(|e| parse_error(e.to_string()))?;
+      parse_text(text)?
+    } else {
+      parse_binary(&data)?
+    };
+
+    Ok(scene_from_points(positions, colors, options))
+  }
+}
+
+/// Bounds-checked little-endian reads from a binary COLMAP file.
+struct BinaryReader<'a> {
+  data: &'a [u8],
+  cursor: usize,
+}
+
+impl<'a> BinaryReader<'a> {
+  fn take(&mut self, length: usize) -> Result<&'a [u8], ConversionError> {
+    let bytes = self
+      .cursor
+      .checked_add(length)
+      .and_then(|end| self.data.get(self.cursor..end))
+      .ok_or_else(|| parse_error("Unexpected end of file"))?;
+    self.cursor += length;
+    Ok(bytes)
+  }
+
+  fn u32(&mut self) -> Result<u32, ConversionError> {
+    Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+  }
+
+  fn u64(&mut self) -> Result<u64, ConversionError> {
+    Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+  }
+
+  fn f64(&mut self) -> Result<f64, ConversionError> {
+    Ok(f64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+  }
+}
+
+/// Pinhole intrinsics from the model's parameters; the single-focal models start with `f`.
+fn intrinsics(model: usize, width: u64, height: u64, params: &[f64]) -> Intrinsics {
+  let (focal, principal_point) = match model {
+    0 | 2 | 3 | 8 | 9 => ([params[0], params[0]], [params[1], params[2]]),
+    _ => ([params[0], params[1]], [params[2], params[3]]),
+  };
+  Intrinsics {
+    width: width as u32,
+    height: height as u32,
+    focal: focal.map(|f| f as f32),
+    principal_point: principal_point.map(|c| c as f32),
+  }
+}
+
+/// Parses `CAMERA_ID MODEL WIDTH HEIGHT PARAMS[]` lines.
+fn parse_cameras_text(text: &str) -> Result<HashMap<u32, Intrinsics>, ConversionError> {
+  let mut cameras = HashMap::new();
+  for (line_number, line) in text.lines().enumerate() {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if fields.is_empty() || fields[0].starts_with('#') {
+      continue;
+    }
+    let invalid = || parse_error(format!("Line {}: invalid camera", line_number + 1));
+    let model = CAMERA_MODELS
+      .iter()
+      .position(|(name, _)| Some(name) == fields.get(1))
+      .ok_or_else(|| {
+        parse_error(format!(
+          "Line {}: unknown camera model \"{}\"",
+          line_number + 1,
+          fields.get(1).unwrap_or(&"")
+        ))
+      })?;
+    let param_count = CAMERA_MODELS[model].1;
+    if fields.len() < 4 + param_count {
+      return Err(invalid());
+    }
+    let id = fields[0].parse::<u32>().map_err(|_| invalid())?;
+    let width = fields[2].parse::<u64>().map_err(|_| invalid())?;
+    let height = fields[3].parse::<u64>().map_err(|_| invalid())?;
+    let params = fields[4..4 + param_count]
+      .iter()
+      .map(|field| field.parse::<f64>().map_err(|_| invalid()))
+      .collect::<Result<Vec<_>, _>>()?;
+    cameras.insert(id, intrinsics(model, width, height, &params));
+  }
+  Ok(cameras)
+}
+
+/// Parses the binary layout: a `u64` camera count, then per camera a `u32` ID, `i32` model ID,
+/// `u64` width and height and the model's `f64` parameters.
+fn parse_cameras_binary(data: &[u8]) -> Result<HashMap<u32, Intrinsics>, ConversionError> {
+  let mut reader = BinaryReader { data, cursor: 0 };
+  let count = reader.u64()?;
+  let mut cameras = HashMap::new();
+  for _ in 0..count {
+    let id = reader.u32()?;
+    let model = reader.u32()? as usize;
+    let param_count = CAMERA_MODELS
+      .get(model)
+      .ok_or_else(|| parse_error(format!("Unknown camera model {}", model)))?
+      .1;
+    let (width, height) = (reader.u64()?, reader.u64()?);
+    let params = (0..param_count)
+      .map(|_| reader.f64())
+      .collect::<Result<Vec<_>, _>>()?;
+    cameras.insert(id, intrinsics(model, width, height, &params));
+  }
+  Ok(cameras)
+}
+
+/// Parses pairs of `IMAGE_ID QW QX QY QZ TX TY TZ CAMERA_ID NAME` and `POINTS2D[]` lines. The
+/// second line of a pair is empty for images without observations.
+fn parse_images_text(text: &str) -> Result<Vec<View>, ConversionError> {
+  let mut views = Vec::new();
+  let mut lines = text
+    .lines()
+    .enumerate()
+    .filter(|(_, line)| !line.trim_start().starts_with('#'));
+  while let Some((line_number, line)) = lines.next() {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if fields.is_empty() {
+      continue;
+    }
+    let invalid = || parse_error(format!("Line {}: invalid image", line_number + 1));
+    if fields.len() < 10 {
+      return Err(invalid());
+    }
+    let number = |index: usize| fields[index].parse::<f64>().map_err(|_| invalid());
+    views.push(View {
+      name: fields[9..].join(" "),
+      camera_id: fields[8].parse().map_err(|_| invalid())?,
+      rotation: [number(1)?, number(2)?, number(3)?, number(4)?].map(|q| q as f32),
+      translation: [number(5)?, number(6)?, number(7)?],
+    });
+    // Skip the observations
+    lines.next();
+  }
+  Ok(views)
+}
+
+/// Parses the binary layout: a `u64` image count, then per image a `u32` ID, `f64` quaternion
+/// (w, x, y, z) and translation, `u32` camera ID, NUL-terminated name and a `u64`-counted list of
+/// 24-byte observations.
+fn parse_images_binary(data: &[u8]) -> Result<Vec<View>, ConversionError> {
+  let mut reader = BinaryReader { data, cursor: 0 };
+  let count = reader.u64()? as usize;
+  let mut views = Vec::with_capacity(count.min(data.len() / 64));
+  for _ in 0..count {
+    reader.u32()?;
+    let rotation = [reader.f64()?, reader.f64()?, reader.f64()?, reader.f64()?].map(|q| q as f32);
+    let translation = [reader.f64()?, reader.f64()?, reader.f64()?];
+    let camera_id = reader.u32()?;
+    let name_length = data[reader.cursor..]
+      .iter()
+      .position(|&b| b == 0)
+      .ok_or_else(|| parse_error("Unterminated image name"))?;
+    let name = String::from_utf8_lossy(reader.take(name_length + 1)?[..name_length].as_ref());
+    let observations = reader.u64()? as usize;
+    reader.take(
+      observations
+        .checked_mul(24)
+        .ok_or_else(|| parse_error("Invalid observation count"))?,
+    )?;
+    views.push(View {
+      name: name.into_owned(),
+      camera_id,
+      rotation,
+      translation,
+    });
+  }
+  Ok(views)
+}
+
+impl ColmapCamerasImporter {
+  /// Reads `cameras` and `images` files in either format and returns a camera per registered
+  /// image, named and sorted by the image name.
+  pub fn import(
+    cameras_reader: &mut impl std::io::Read,
+    images_reader: &mut impl std::io::Read,
+  ) -> Result<Vec<Camera>, ConversionError> {
+    let mut data = Vec::new();
+    cameras_reader.read_to_end(&mut data)?;
+    let cameras = if is_text(&data) {
+      parse_cameras_text(std::str::from_utf8(&data).map_err(|e| parse_error(e.to_string()))?)?
+    } else {
+      parse_cameras_binary(&data)?
+    };
+
+    data.clear();
+    images_reader.read_to_end(&mut data)?;
+    let mut views = if is_text(&data) {
+      parse_images_text(std::str::from_utf8(&data).map_err(|e| parse_error(e.to_string()))?)?
+    } else {
+      parse_images_binary(&data)?
+    };
+    views.sort_by(|a, b| a.name.cmp(&b.name));
+
+    views
+      .into_iter()
+      .map(|view| {
+        let camera = cameras.get(&view.camera_id).ok_or_else(|| {
+          parse_error(format!(
+            "Image \"{}\" uses missing camera {}",
+            view.name, view.camera_id
+          ))
+        })?;
+        // x_camera = R x_world + t, so the centre is -Rᵀ t
+        let rotation = quaternion_to_matrix(view.rotation);
+        let position = [0, 1, 2].map(|axis| {
+          -(0..3)
+            .map(|row| rotation[row][axis] as f64 * view.translation[row])
+            .sum::<f64>() as f32
+        });
+        Ok(Camera {
+          name: view.name,
+          position,
+          rotation,
+          width: camera.width,
+          height: camera.height,
+          focal: camera.focal,
+          principal_point: camera.principal_point,
+        })
+      })
+      .collect()
+  }
+}
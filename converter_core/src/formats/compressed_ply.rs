@@ -0,0 +1,387 @@
+use ply_rs::parser::Parser;
+use ply_rs::ply;
+
+use crate::formats::ply::property_to_f32;
+use crate::helpers::activation::{inverse_sigmoid, sigmoid};
+use crate::helpers::morton_order::morton_order;
+use crate::helpers::spherical_harmonics::SH_C0;
+use crate::{
+  ConversionError, Exporter, GaussianSplat, Importer, Scene, sh_coefficients_per_channel,
+};
+
+/// Reads and writes the "compressed.ply" layout produced by PlayCanvas and SuperSplat:
+/// splats are grouped in chunks of 256 that store min/max bounds, and every splat is packed
+/// relative to its chunk (11-10-11 positions and log scales, 2-10-10-10 quaternions, 8-bit colour and SH).
+pub struct CompressedPlyImporter;
+pub struct CompressedPlyExporter;
+
+const CHUNK_SIZE: usize = 256;
+const CHUNK_PROPERTIES: [&str; 18] = [
+  "min_x",
+  "min_y",
+  "min_z",
+  "max_x",
+  "max_y",
+  "max_z",
+  "min_scale_x",
+  "min_scale_y",
+  "min_scale_z",
+  "max_scale_x",
+  "max_scale_y",
+  "max_scale_z",
+  "min_r",
+  "min_g",
+  "min_b",
+  "max_r",
+  "max_g",
+  "max_b",
+];
+
+fn parse_error(message: impl Into<String>) -> ConversionError {
+  ConversionError::ParseError {
+    format: "Compressed PLY".to_string(),
+    message: message.into(),
+  }
+}
+
+fn property_to_u32(prop: &ply::Property) -> u32 {
+  match prop {
+    ply::Property::UInt(v) => *v,
+    ply::Property::Int(v) => *v as u32,
+    ply::Property::UChar(v) => *v as u32,
+    ply::Property::UShort(v) => *v as u32,
+    _ => 0,
+  }
+}
+
+fn unpack_unorm(value: u32, bits: u32) -> f32 {
+  let max = (1u32 << bits) - 1;
+  (value & max) as f32 / max as f32
+}
+
+fn pack_unorm(value: f32, bits: u32) -> u32 {
+  let max = (1u32 << bits) - 1;
+  let value = if value.is_finite() { value } else { 0.0 };
+  (value.clamp(0.0, 1.0) * max as f32 + 0.5).floor() as u32
+}
+
+fn unpack_111011(value: u32) -> [f32; 3] {
+  [
+    unpack_unorm(value >> 21, 11),
+    unpack_unorm(value >> 11, 10),
+    unpack_unorm(value, 11),
+  ]
+}
+
+fn pack_111011(v: [f32; 3]) -> u32 {
+  (pack_unorm(v[0], 11) << 21) | (pack_unorm(v[1], 10) << 11) | pack_unorm(v[2], 11)
+}
+
+/// Quaternions are packed as [x, y, z, w] with the index of the largest component in the top
+/// two bits and the other three in 10 bits each; the largest is recovered from the unit norm.
+fn unpack_rotation(value: u32) -> [f32; 4] {
+  let norm = std::f32::consts::SQRT_2;
+  let a = (unpack_unorm(value >> 20, 10) - 0.5) * norm;
+  let b = (unpack_unorm(value >> 10, 10) - 0.5) * norm;
+  let c = (unpack_unorm(value, 10) - 0.5) * norm;
+  let m = (1.0 - (a * a + b * b + c * c)).max(0.0).sqrt();
+
+  let [x, y, z, w] = match value >> 30 {
+    0 => [m, a, b, c],
+    1 => [a, m, b, c],
+    2 => [a, b, m, c],
+    _ => [a, b, c, m],
+  };
+  [w, x, y, z]
+}
+
+fn pack_rotation(rotation: [f32; 4]) -> u32 {
+  let len = rotation.iter().map(|v| v * v).sum::<f32>().sqrt();
+  let [w, x, y, z] = if len > 0.0 {
+    rotation.map(|v| v / len)
+  } else {
+    [1.0, 0.0, 0.0, 0.0]
+  };
+  let mut q = [x, y, z, w];
+
+  let largest = (0..4)
+    .max_by(|&a, &b| q[a].abs().total_cmp(&q[b].abs()))
+    .unwrap_or(3);
+  if q[largest] < 0.0 {
+    q = q.map(|v| -v);
+  }
+
+  let norm = std::f32::consts::FRAC_1_SQRT_2;
+  (0..4)
+    .filter(|&i| i != largest)
+    .fold(largest as u32, |packed, i| {
+      (packed << 10) | pack_unorm(q[i] * norm + 0.5, 10)
+    })
+}
+
+fn lerp(min: f32, max: f32, t: f32) -> f32 {
+  min + (max - min) * t
+}
+
+/// Normalizes `value` into the `min..max` range of its chunk.
+fn normalize(value: f32, min: f32, max: f32) -> f32 {
+  if max - min > 0.0 {
+    (value - min) / (max - min)
+  } else {
+    0.0
+  }
+}
+
+fn sh_from_byte(value: u8) -> f32 {
+  let n = if value == 0 {
+    0.0
+  } else {
+    (value as f32 + 0.5) / 256.0
+  };
+  (n - 0.5) * 8.0
+}
+
+fn sh_to_byte(value: f32) -> u8 {
+  ((value / 8.0 + 0.5) * 256.0).trunc().clamp(0.0, 255.0) as u8
+}
+
+/// Whether a parsed PLY uses the compressed layout rather than plain `vertex` floats.
+pub(crate) fn is_compressed_ply(ply: &ply::Ply<ply::DefaultElement>) -> bool {
+  ply.header.elements.contains_key("chunk")
+    && ply
+      .header
+      .elements
+      .get("vertex")
+      .is_some_and(|vertex| vertex.properties.contains_key("packed_position"))
+}
+
+/// Decodes an already parsed compressed PLY, shared with `PlyImporter` which delegates here.
+pub(crate) fn scene_from_compressed_ply(
+  ply: &ply::Ply<ply::DefaultElement>,
+) -> Result<Scene, ConversionError> {
+  let chunks = ply
+    .payload
+    .get("chunk")
+    .ok_or_else(|| parse_error("Missing \"chunk\" element"))?;
+  let vertices = ply
+    .payload
+    .get("vertex")
+    .ok_or_else(|| parse_error("Missing \"vertex\" element"))?;
+  let sh = ply.payload.get("sh");
+
+  if chunks.len() * CHUNK_SIZE < vertices.len() {
+    return Err(parse_error(format!(
+      "{} chunks cannot hold {} splats",
+      chunks.len(),
+      vertices.len()
+    )));
+  }
+
+  // Older files have no colour bounds and store colours directly
+  let has_color_bounds = ply
+    .header
+    .elements
+    .get("chunk")
+    .is_some_and(|chunk| chunk.properties.contains_key("min_r"));
+
+  let sh_rest_len = ply.header.elements.get("sh").map_or(0, |element| {
+    element
+      .properties
+      .keys()
+      .filter(|key| key.starts_with("f_rest_"))
+      .count()
+  });
+
+  let mut splats = Vec::with_capacity(vertices.len());
+
+  for (i, vertex) in vertices.iter().enumerate() {
+    let chunk = &chunks[i / CHUNK_SIZE];
+    let bound = |name: &str| chunk.get(name).map_or(0.0, property_to_f32);
+    let packed = |name: &str| {
+      vertex
+        .get(name)
+        .map(property_to_u32)
+        .ok_or_else(|| parse_error(format!("Missing \"{}\" property", name)))
+    };
+
+    let p = unpack_111011(packed("packed_position")?);
+    let position = [
+      lerp(bound("min_x"), bound("max_x"), p[0]),
+      lerp(bound("min_y"), bound("max_y"), p[1]),
+      lerp(bound("min_z"), bound("max_z"), p[2]),
+    ];
+
+    let s = unpack_111011(packed("packed_scale")?);
+    let scale = [
+      lerp(bound("min_scale_x"), bound("max_scale_x"), s[0]),
+      lerp(bound("min_scale_y"), bound("max_scale_y"), s[1]),
+      lerp(bound("min_scale_z"), bound("max_scale_z"), s[2]),
+    ];
+
+    let color = packed("packed_color")?;
+    let mut rgb = [
+      unpack_unorm(color >> 24, 8),
+      unpack_unorm(color >> 16, 8),
+      unpack_unorm(color >> 8, 8),
+    ];
+    if has_color_bounds {
+      rgb = [
+        lerp(bound("min_r"), bound("max_r"), rgb[0]),
+        lerp(bound("min_g"), bound("max_g"), rgb[1]),
+        lerp(bound("min_b"), bound("max_b"), rgb[2]),
+      ];
+    }
+    let alpha = unpack_unorm(color, 8);
+
+    let spherical_harmonics_rest = match sh.and_then(|sh| sh.get(i)) {
+      Some(coefficients) => (0..sh_rest_len)
+        .map(|k| {
+          coefficients
+            .get(&format!("f_rest_{}", k))
+            .map_or(0.0, |prop| sh_from_byte(property_to_u32(prop) as u8))
+        })
+        .collect(),
+      None => Vec::new(),
+    };
+
+    splats.push(GaussianSplat {
+      position,
+      normal: [0.0, 0.0, 0.0],
+      spherical_harmonics_dc: rgb.map(|c| (c - 0.5) / SH_C0),
+      spherical_harmonics_rest,
+      opacity: inverse_sigmoid(alpha),
+      scale,
+      rotation: unpack_rotation(packed("packed_rotation")?),
+    });
+  }
+
+  Ok(Scene {
+    splats,
+    cameras: Vec::new(),
+  })
+}
+
+// --- IMPORTER ---
+
+impl Importer for CompressedPlyImporter {
+  fn import(reader: &mut impl std::io::Read) -> Result<Scene, ConversionError> {
+    let parser = Parser::<ply::DefaultElement>::new();
+    let ply = parser
+      .read_ply(reader)
+      .map_err(|e| parse_error(e.to_string()))?;
+
+    if !is_compressed_ply(&ply) {
+      return Err(parse_error(
+        "Missing \"chunk\" element or packed vertex properties",
+      ));
+    }
+
+    scene_from_compressed_ply(&ply)
+  }
+}
+
+// --- EXPORTER ---
+
+impl Exporter for CompressedPlyExporter {
+  fn export(scene: &Scene, writer: &mut impl std::io::Write) -> Result<(), ConversionError> {
+    let map_io_err = |e: std::io::Error| ConversionError::WriteError {
+      format: "Compressed PLY".to_string(),
+      message: e.to_string(),
+    };
+
+    // Chunks of spatially close splats have tighter bounds, so sort along a Morton curve first
+    let order = morton_order(scene);
+    let num_splats = order.len();
+    let num_chunks = num_splats.div_ceil(CHUNK_SIZE);
+    let sh_rest_len = 3 * sh_coefficients_per_channel(scene.sh_degree());
+
+    // Header
+    writeln!(writer, "ply").map_err(map_io_err)?;
+    writeln!(writer, "format binary_little_endian 1.0").map_err(map_io_err)?;
+    writeln!(writer, "element chunk {}", num_chunks).map_err(map_io_err)?;
+    for name in CHUNK_PROPERTIES {
+      writeln!(writer, "property float {}", name).map_err(map_io_err)?;
+    }
+    writeln!(writer, "element vertex {}", num_splats).map_err(map_io_err)?;
+    writeln!(writer, "property uint packed_position").map_err(map_io_err)?;
+    writeln!(writer, "property uint packed_rotation").map_err(map_io_err)?;
+    writeln!(writer, "property uint packed_scale").map_err(map_io_err)?;
+    writeln!(writer, "property uint packed_color").map_err(map_io_err)?;
+    if sh_rest_len > 0 {
+      writeln!(writer, "element sh {}", num_splats).map_err(map_io_err)?;
+      for i in 0..sh_rest_len {
+        writeln!(writer, "property uchar f_rest_{}", i).map_err(map_io_err)?;
+      }
+    }
+    writeln!(writer, "end_header").map_err(map_io_err)?;
+
+    let color = |splat: &GaussianSplat| splat.spherical_harmonics_dc.map(|dc| 0.5 + SH_C0 * dc);
+    let scale = |splat: &GaussianSplat| splat.scale.map(|s| s.clamp(-20.0, 20.0));
+
+    // Chunk bounds
+    let mut vertex_data = Vec::with_capacity(num_splats * 16);
+    for chunk in order.chunks(CHUNK_SIZE) {
+      let mut bounds = [[f32::INFINITY; 3], [f32::NEG_INFINITY; 3]];
+      let mut scale_bounds = bounds;
+      let mut color_bounds = bounds;
+      for splat in chunk.iter().map(|&i| &scene.splats[i]) {
+        let values = [splat.position, scale(splat), color(splat)];
+        for (target, value) in [&mut bounds, &mut scale_bounds, &mut color_bounds]
+          .into_iter()
+          .zip(values)
+        {
+          for axis in 0..3 {
+            target[0][axis] = target[0][axis].min(value[axis]);
+            target[1][axis] = target[1][axis].max(value[axis]);
+          }
+        }
+      }
+
+      for value in [bounds, scale_bounds, color_bounds]
+        .iter()
+        .flatten()
+        .flatten()
+      {
+        writer.write_all(&value.to_le_bytes()).map_err(map_io_err)?;
+      }
+
+      for splat in chunk.iter().map(|&i| &scene.splats[i]) {
+        let normalized = |v: [f32; 3], b: &[[f32; 3]; 2]| {
+          [0, 1, 2].map(|axis| normalize(v[axis], b[0][axis], b[1][axis]))
+        };
+        let rgb = normalized(color(splat), &color_bounds);
+        let packed_color = (pack_unorm(rgb[0], 8) << 24)
+          | (pack_unorm(rgb[1], 8) << 16)
+          | (pack_unorm(rgb[2], 8) << 8)
+          | pack_unorm(sigmoid(splat.opacity), 8);
+
+        for packed in [
+          pack_111011(normalized(splat.position, &bounds)),
+          pack_rotation(splat.rotation),
+          pack_111011(normalized(scale(splat), &scale_bounds)),
+          packed_color,
+        ] {
+          vertex_data.extend_from_slice(&packed.to_le_bytes());
+        }
+      }
+    }
+
+    writer.write_all(&vertex_data).map_err(map_io_err)?;
+
+    // SH, in the same channel-major order as `f_rest_*` in uncompressed PLY
+    if sh_rest_len > 0 {
+      let per_channel = sh_rest_len / 3;
+      let mut sh_data = Vec::with_capacity(num_splats * sh_rest_len);
+      for splat in order.iter().map(|&i| &scene.splats[i]) {
+        for channel in 0..3 {
+          for k in 0..per_channel {
+            sh_data.push(sh_to_byte(splat.sh_rest(channel, k)));
+          }
+        }
+      }
+      writer.write_all(&sh_data).map_err(map_io_err)?;
+    }
+
+    Ok(())
+  }
+}
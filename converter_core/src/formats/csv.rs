@@ -35,36 +35,31 @@ impl Exporter for CsvExporter {
 
     // Write splat data
     for splat in &scene.splats {
-      let mut record = Vec::new();
-
-      record.push(splat.position[0].to_string());
-      record.push(splat.position[1].to_string());
-      record.push(splat.position[2].to_string());
-
-      record.push(splat.normal[0].to_string());
-      record.push(splat.normal[1].to_string());
-      record.push(splat.normal[2].to_string());
-
-      record.push(splat.spherical_harmonics_dc[0].to_string());
-      record.push(splat.spherical_harmonics_dc[1].to_string());
-      record.push(splat.spherical_harmonics_dc[2].to_string());
-
-      record.push(splat.opacity.to_string());
-
-      record.push(splat.scale[0].to_string());
-      record.push(splat.scale[1].to_string());
-      record.push(splat.scale[2].to_string());
-
-      record.push(splat.rotation[0].to_string());
-      record.push(splat.rotation[1].to_string());
-      record.push(splat.rotation[2].to_string());
-      record.push(splat.rotation[3].to_string());
-
-      // Pad SH Rest data to ensure consistent column count
-      let mut sh_rest_padded = splat.spherical_harmonics_rest.clone();
-      sh_rest_padded.resize(45, 0.0);
-      for val in sh_rest_padded {
-        record.push(val.to_string());
+      let mut record = vec![
+        splat.position[0].to_string(),
+        splat.position[1].to_string(),
+        splat.position[2].to_string(),
+        splat.normal[0].to_string(),
+        splat.normal[1].to_string(),
+        splat.normal[2].to_string(),
+        splat.spherical_harmonics_dc[0].to_string(),
+        splat.spherical_harmonics_dc[1].to_string(),
+        splat.spherical_harmonics_dc[2].to_string(),
+        splat.opacity.to_string(),
+        splat.scale[0].to_string(),
+        splat.scale[1].to_string(),
+        splat.scale[2].to_string(),
+        splat.rotation[0].to_string(),
+        splat.rotation[1].to_string(),
+        splat.rotation[2].to_string(),
+        splat.rotation[3].to_string(),
+      ];
+
+      // Pad SH Rest data of lower-degree splats to ensure consistent column count
+      for channel in 0..3 {
+        for i in 0..15 {
+          record.push(splat.sh_rest(channel, i).to_string());
+        }
       }
 
       wtr
@@ -153,6 +148,9 @@ impl Importer for CsvImporter {
       splats.push(splat);
     }
 
-    Ok(Scene { splats })
+    Ok(Scene {
+      splats,
+      cameras: Vec::new(),
+    })
   }
 }
@@ -0,0 +1,180 @@
+//! Recognises the format of a file from its first bytes, for files whose extension is missing or
+//! wrong. `.splat` files and COLMAP models have no header to recognise them by.
+
+use std::io::Read;
+
+use flate2::read::{DeflateDecoder, GzDecoder};
+
+/// How many bytes from the start of a file `detect_format` looks at.
+pub const DETECTION_BYTES: usize = 4096;
+
+const SPZ_MAGIC: u32 = 0x5053474e;
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const ZIP_LOCAL_HEADER_SIZE: usize = 30;
+
+/// A format recognised from the start of a file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectedFormat {
+  /// Extension of the format, which also names its importer (e.g. "ply", "glb").
+  pub format: &'static str,
+  /// Human-readable name, telling variants sharing an extension apart (e.g. "Compressed PLY").
+  pub name: &'static str,
+  /// Version or encoding stated in the header, if the format has one.
+  pub version: Option<String>,
+}
+
+impl DetectedFormat {
+  fn new(format: &'static str, name: &'static str, version: Option<String>) -> Self {
+    Self {
+      format,
+      name,
+      version,
+    }
+  }
+}
+
+/// Detects the format from the first `DETECTION_BYTES` bytes of a file (fewer if it is shorter).
+pub fn detect_format(header: &[u8]) -> Option<DetectedFormat> {
+  if header.starts_with(b"ply\n") || header.starts_with(b"ply\r\n") {
+    return Some(detect_ply(header));
+  }
+  if header.starts_with(&[0x1f, 0x8b]) {
+    return detect_spz(header);
+  }
+  if header.starts_with(b"glTF") && header.len() >= 8 {
+    let version = u32::from_le_bytes(header[4..8].try_into().unwrap());
+    return Some(DetectedFormat::new(
+      "glb",
+      "glTF binary",
+      Some(version.to_string()),
+    ));
+  }
+  if header.starts_with(b"LASF") && header.len() >= 26 {
+    return Some(DetectedFormat::new(
+      "las",
+      "LAS",
+      Some(format!("{}.{}", header[24], header[25])),
+    ));
+  }
+  if header.starts_with(ZIP_MAGIC) {
+    return detect_zip(header);
+  }
+  if let Some(version) = header.strip_prefix(b"#usda ") {
+    let version = String::from_utf8_lossy(version);
+    let version = version.split_whitespace().next().map(str::to_string);
+    return Some(DetectedFormat::new("usda", "OpenUSD text", version));
+  }
+  if header.starts_with(b"x,y,z,") {
+    return Some(DetectedFormat::new("csv", "CSV", None));
+  }
+
+  let text = String::from_utf8_lossy(header);
+  if text.trim_start().starts_with('{') && text.contains("\"asset\"") {
+    return Some(DetectedFormat::new("gltf", "glTF", json_version(&text)));
+  }
+  if is_ksplat(header) {
+    return Some(DetectedFormat::new(
+      "ksplat",
+      "KSPLAT",
+      Some(format!("{}.{}", header[0], header[1])),
+    ));
+  }
+  None
+}
+
+/// PLY files are told apart by their header, the same way `PlyImporter` does: compressed ones
+/// have a `chunk` element and packed vertices, plain point clouds lack opacity and colour SH.
+fn detect_ply(header: &[u8]) -> DetectedFormat {
+  let text = String::from_utf8_lossy(header);
+  let lines: Vec<&str> = text
+    .lines()
+    .map(str::trim)
+    .take_while(|line| *line != "end_header")
+    .collect();
+  let version = lines
+    .iter()
+    .find_map(|line| line.strip_prefix("format "))
+    .map(str::to_string);
+  let has_property = |name: &str| {
+    lines
+      .iter()
+      .any(|line| line.starts_with("property") && line.split_whitespace().last() == Some(name))
+  };
+
+  let name = if lines.iter().any(|line| line.starts_with("element chunk"))
+    && has_property("packed_position")
+  {
+    "Compressed PLY"
+  } else if !has_property("opacity") && !has_property("f_dc_0") {
+    "Point Cloud PLY"
+  } else {
+    "PLY"
+  };
+  DetectedFormat::new("ply", name, version)
+}
+
+/// SPZ files are gzipped, with a magic number and version at the start of the payload.
+fn detect_spz(header: &[u8]) -> Option<DetectedFormat> {
+  let mut payload = [0u8; 8];
+  GzDecoder::new(header).read_exact(&mut payload).ok()?;
+  let magic = u32::from_le_bytes(payload[0..4].try_into().unwrap());
+  let version = u32::from_le_bytes(payload[4..8].try_into().unwrap());
+  (magic == SPZ_MAGIC).then(|| DetectedFormat::new("spz", "SPZ", Some(version.to_string())))
+}
+
+/// `.usdz` packages and `.sog` bundles are both zip archives. USDZ starts with its root layer and
+/// SOG with its `meta.json`, whose version is read when it fits in the header.
+fn detect_zip(header: &[u8]) -> Option<DetectedFormat> {
+  let field = |offset: usize| {
+    header
+      .get(offset..offset + 2)
+      .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]) as usize)
+  };
+  let (method, name_length, extra_length) = (field(8)?, field(26)?, field(28)?);
+  let name_end = ZIP_LOCAL_HEADER_SIZE + name_length;
+  let name = String::from_utf8_lossy(header.get(ZIP_LOCAL_HEADER_SIZE..name_end)?).to_lowercase();
+
+  if name.ends_with(".usda") || name.ends_with(".usdc") || name.ends_with(".usd") {
+    return Some(DetectedFormat::new("usdz", "OpenUSD package", None));
+  }
+  if name != "meta.json" {
+    return None;
+  }
+
+  // Stored (0) or deflated (8); a truncated entry still decompresses up to the cut
+  let data = header.get(name_end + extra_length..).unwrap_or_default();
+  let mut meta = Vec::new();
+  match method {
+    0 => meta.extend_from_slice(data),
+    8 => {
+      let _ = DeflateDecoder::new(data).read_to_end(&mut meta);
+    }
+    _ => {}
+  }
+  Some(DetectedFormat::new(
+    "sog",
+    "SOG",
+    json_version(&String::from_utf8_lossy(&meta)),
+  ))
+}
+
+/// The value of the first `"version"` key of a JSON text, without parsing the (possibly
+/// truncated) rest of it.
+fn json_version(text: &str) -> Option<String> {
+  let after_key = &text[text.find("\"version\"")? + "\"version\"".len()..];
+  let value = after_key.trim_start().strip_prefix(':')?.trim_start();
+  let end = value.find([',', '}', '\n']).unwrap_or(value.len());
+  Some(value[..end].trim().trim_matches('"').to_string()).filter(|v| !v.is_empty())
+}
+
+/// KSPLAT has no magic number, but a version 0.x header with a small section count and a known
+/// compression level is distinctive enough.
+fn is_ksplat(header: &[u8]) -> bool {
+  if header.len() < 22 || header[0] != 0 || header[1] == 0 {
+    return false;
+  }
+  let read_u32 = |offset: usize| u32::from_le_bytes(header[offset..offset + 4].try_into().unwrap());
+  let (max_sections, sections) = (read_u32(4), read_u32(8));
+  let compression_level = u16::from_le_bytes([header[20], header[21]]);
+  (1..=max_sections).contains(&sections) && max_sections <= 1 << 16 && compression_level <= 2
+}
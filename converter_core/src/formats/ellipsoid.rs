@@ -0,0 +1,313 @@
+use crate::formats::point_cloud::point_color;
+use crate::formats::splat::SplatExporter;
+use crate::helpers::activation::sigmoid;
+use crate::helpers::rotation::quaternion_to_matrix;
+use crate::{ConversionError, Exporter, GaussianSplat, Scene};
+
+/// Writes every splat as a low-poly ellipsoid in a Wavefront OBJ mesh, with vertex colours
+/// (`v x y z r g b`) as read by Blender and MeshLab.
+pub struct EllipsoidObjExporter;
+/// Writes every splat as a low-poly ellipsoid in a binary PLY mesh, with RGBA vertex colours.
+pub struct EllipsoidPlyExporter;
+
+/// Options shared by the ellipsoid mesh exporters.
+#[derive(Debug, Clone)]
+pub struct EllipsoidExportOptions {
+  /// Radius of the ellipsoids along each axis, in standard deviations of the Gaussian.
+  pub sigma: f32,
+  /// Skip splats whose opacity (0..1) is below this value.
+  pub min_opacity: Option<f32>,
+  /// Maximum number of triangles to write. Coarser ellipsoids are used first, then the least
+  /// important splats (by volume and opacity) are dropped.
+  pub max_triangles: Option<usize>,
+}
+
+impl Default for EllipsoidExportOptions {
+  fn default() -> Self {
+    Self {
+      sigma: 2.0,
+      min_opacity: None,
+      max_triangles: None,
+    }
+  }
+}
+
+/// A unit sphere approximation that is instanced for every splat.
+struct SphereTemplate {
+  vertices: Vec<[f32; 3]>,
+  triangles: Vec<[u32; 3]>,
+}
+
+impl SphereTemplate {
+  /// Builds a template, winding every triangle counter-clockwise seen from outside.
+  fn new(vertices: Vec<[f32; 3]>, triangles: Vec<[u32; 3]>) -> Self {
+    let normalise = |v: [f32; 3]| {
+      let length = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+      v.map(|c| c / length)
+    };
+    let vertices: Vec<[f32; 3]> = vertices.into_iter().map(normalise).collect();
+    let triangles = triangles
+      .into_iter()
+      .map(|[a, b, c]| {
+        let [p, q, r] = [a, b, c].map(|i| vertices[i as usize]);
+        let u = [q[0] - p[0], q[1] - p[1], q[2] - p[2]];
+        let v = [r[0] - p[0], r[1] - p[1], r[2] - p[2]];
+        let normal = [
+          u[1] * v[2] - u[2] * v[1],
+          u[2] * v[0] - u[0] * v[2],
+          u[0] * v[1] - u[1] * v[0],
+        ];
+        // On a convex shape around the origin, outward normals point away from it
+        if normal[0] * p[0] + normal[1] * p[1] + normal[2] * p[2] < 0.0 {
+          [a, c, b]
+        } else {
+          [a, b, c]
+        }
+      })
+      .collect();
+    Self {
+      vertices,
+      triangles,
+    }
+  }
+
+  /// 6 vertices, 8 triangles.
+  fn octahedron() -> Self {
+    let vertices = vec![
+      [1.0, 0.0, 0.0],
+      [-1.0, 0.0, 0.0],
+      [0.0, 1.0, 0.0],
+      [0.0, -1.0, 0.0],
+      [0.0, 0.0, 1.0],
+      [0.0, 0.0, -1.0],
+    ];
+    let mut triangles = Vec::with_capacity(8);
+    for x in [0, 1] {
+      for y in [2, 3] {
+        for z in [4, 5] {
+          triangles.push([x, y, z]);
+        }
+      }
+    }
+    Self::new(vertices, triangles)
+  }
+
+  /// 12 vertices, 20 triangles.
+  fn icosahedron() -> Self {
+    let t = (1.0 + 5f32.sqrt()) / 2.0;
+    let vertices = vec![
+      [-1.0, t, 0.0],
+      [1.0, t, 0.0],
+      [-1.0, -t, 0.0],
+      [1.0, -t, 0.0],
+      [0.0, -1.0, t],
+      [0.0, 1.0, t],
+      [0.0, -1.0, -t],
+      [0.0, 1.0, -t],
+      [t, 0.0, -1.0],
+      [t, 0.0, 1.0],
+      [-t, 0.0, -1.0],
+      [-t, 0.0, 1.0],
+    ];
+    let triangles = vec![
+      [0, 11, 5],
+      [0, 5, 1],
+      [0, 1, 7],
+      [0, 7, 10],
+      [0, 10, 11],
+      [1, 5, 9],
+      [5, 11, 4],
+      [11, 10, 2],
+      [10, 7, 6],
+      [7, 1, 8],
+      [3, 9, 4],
+      [3, 4, 2],
+      [3, 2, 6],
+      [3, 6, 8],
+      [3, 8, 9],
+      [4, 9, 5],
+      [2, 4, 11],
+      [6, 2, 10],
+      [8, 6, 7],
+      [9, 8, 1],
+    ];
+    Self::new(vertices, triangles)
+  }
+}
+
+/// The splats to write and the sphere to instance, after the opacity threshold and the triangle
+/// budget have been applied.
+fn plan<'a>(
+  scene: &'a Scene,
+  options: &EllipsoidExportOptions,
+) -> (Vec<&'a GaussianSplat>, SphereTemplate) {
+  let mut splats: Vec<&GaussianSplat> = scene
+    .splats
+    .iter()
+    .filter(|splat| {
+      options
+        .min_opacity
+        .is_none_or(|min| sigmoid(splat.opacity) >= min)
+    })
+    .collect();
+
+  let Some(budget) = options.max_triangles else {
+    return (splats, SphereTemplate::icosahedron());
+  };
+
+  let icosahedron = SphereTemplate::icosahedron();
+  if splats.len() * icosahedron.triangles.len() <= budget {
+    return (splats, icosahedron);
+  }
+  let octahedron = SphereTemplate::octahedron();
+  let max_splats = budget / octahedron.triangles.len();
+  if splats.len() > max_splats {
+    // Stable, so ties keep their input order
+    splats.sort_by(|a, b| SplatExporter::importance(b).total_cmp(&SplatExporter::importance(a)));
+    splats.truncate(max_splats);
+  }
+  (splats, octahedron)
+}
+
+/// The template's vertices scaled to the splat's `sigma` extent, rotated and moved to its centre.
+fn ellipsoid_vertices<'a>(
+  splat: &GaussianSplat,
+  template: &'a SphereTemplate,
+  sigma: f32,
+) -> impl Iterator<Item = [f32; 3]> + 'a {
+  let matrix = quaternion_to_matrix(splat.rotation);
+  let radii = splat.scale.map(|s| s.exp() * sigma);
+  let position = splat.position;
+  template.vertices.iter().map(move |vertex| {
+    let local = [0, 1, 2].map(|axis| vertex[axis] * radii[axis]);
+    [0, 1, 2].map(|row| {
+      position[row]
+        + matrix[row][0] * local[0]
+        + matrix[row][1] * local[1]
+        + matrix[row][2] * local[2]
+    })
+  })
+}
+
+// --- OBJ ---
+
+impl Exporter for EllipsoidObjExporter {
+  fn export(scene: &Scene, writer: &mut impl std::io::Write) -> Result<(), ConversionError> {
+    Self::export_with_options(scene, writer, &EllipsoidExportOptions::default())
+  }
+}
+
+impl EllipsoidObjExporter {
+  pub fn export_with_options(
+    scene: &Scene,
+    writer: &mut impl std::io::Write,
+    options: &EllipsoidExportOptions,
+  ) -> Result<(), ConversionError> {
+    let map_io_err = |e: std::io::Error| ConversionError::WriteError {
+      format: "OBJ (Ellipsoids)".to_string(),
+      message: e.to_string(),
+    };
+
+    let (splats, template) = plan(scene, options);
+    writeln!(writer, "# {} Gaussian splats as ellipsoids", splats.len()).map_err(map_io_err)?;
+    writeln!(writer, "o splats").map_err(map_io_err)?;
+
+    for splat in &splats {
+      let [r, g, b, _] = point_color(splat).map(|c| c as f32 / 255.0);
+      for [x, y, z] in ellipsoid_vertices(splat, &template, options.sigma) {
+        writeln!(writer, "v {} {} {} {} {} {}", x, y, z, r, g, b).map_err(map_io_err)?;
+      }
+    }
+
+    // OBJ indices are 1-based
+    for i in 0..splats.len() {
+      let base = i * template.vertices.len() + 1;
+      for [a, b, c] in &template.triangles {
+        writeln!(
+          writer,
+          "f {} {} {}",
+          base + *a as usize,
+          base + *b as usize,
+          base + *c as usize
+        )
+        .map_err(map_io_err)?;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+// --- PLY ---
+
+impl Exporter for EllipsoidPlyExporter {
+  fn export(scene: &Scene, writer: &mut impl std::io::Write) -> Result<(), ConversionError> {
+    Self::export_with_options(scene, writer, &EllipsoidExportOptions::default())
+  }
+}
+
+impl EllipsoidPlyExporter {
+  pub fn export_with_options(
+    scene: &Scene,
+    writer: &mut impl std::io::Write,
+    options: &EllipsoidExportOptions,
+  ) -> Result<(), ConversionError> {
+    let map_io_err = |e: std::io::Error| ConversionError::WriteError {
+      format: "PLY (Ellipsoids)".to_string(),
+      message: e.to_string(),
+    };
+
+    let (splats, template) = plan(scene, options);
+    let vertex_count = splats.len() * template.vertices.len();
+    if vertex_count > u32::MAX as usize {
+      return Err(ConversionError::WriteError {
+        format: "PLY (Ellipsoids)".to_string(),
+        message: "Too many vertices for 32-bit indices, set a triangle budget".to_string(),
+      });
+    }
+
+    // Write header
+    writeln!(writer, "ply").map_err(map_io_err)?;
+    writeln!(writer, "format binary_little_endian 1.0").map_err(map_io_err)?;
+    writeln!(writer, "element vertex {}", vertex_count).map_err(map_io_err)?;
+    for axis in ["x", "y", "z"] {
+      writeln!(writer, "property float {}", axis).map_err(map_io_err)?;
+    }
+    for channel in ["red", "green", "blue", "alpha"] {
+      writeln!(writer, "property uchar {}", channel).map_err(map_io_err)?;
+    }
+    writeln!(
+      writer,
+      "element face {}",
+      splats.len() * template.triangles.len()
+    )
+    .map_err(map_io_err)?;
+    writeln!(writer, "property list uchar uint vertex_indices").map_err(map_io_err)?;
+    writeln!(writer, "end_header").map_err(map_io_err)?;
+
+    // Write body
+    for splat in &splats {
+      let color = point_color(splat);
+      for vertex in ellipsoid_vertices(splat, &template, options.sigma) {
+        for value in vertex {
+          writer.write_all(&value.to_le_bytes()).map_err(map_io_err)?;
+        }
+        writer.write_all(&color).map_err(map_io_err)?;
+      }
+    }
+
+    let mut face = [0u8; 13];
+    face[0] = 3;
+    for i in 0..splats.len() {
+      let base = (i * template.vertices.len()) as u32;
+      for triangle in &template.triangles {
+        for (corner, index) in triangle.iter().enumerate() {
+          face[1 + corner * 4..5 + corner * 4].copy_from_slice(&(base + index).to_le_bytes());
+        }
+        writer.write_all(&face).map_err(map_io_err)?;
+      }
+    }
+
+    Ok(())
+  }
+}
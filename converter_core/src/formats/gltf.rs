@@ -0,0 +1,933 @@
+use std::io::{Read, Write};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde_json::{Map, Value, json};
+
+use crate::helpers::activation::{inverse_sigmoid, sigmoid};
+use crate::helpers::meshopt::{decode_vertex_buffer, encode_vertex_buffer};
+use crate::helpers::rotation::{matrix_to_quaternion, quaternion_to_matrix};
+use crate::helpers::spherical_harmonics::SH_C0;
+use crate::{Camera, ConversionError, Exporter, GaussianSplat, Importer, Scene};
+
+/// Reads and writes glTF 2.0 point primitives with the Khronos `KHR_gaussian_splatting`
+/// extension, either as JSON (`.gltf`) or binary (`.glb`).
+///
+/// Rotations are stored as unit [x, y, z, w] quaternions, scales and opacities as linear values
+/// and the spherical harmonics as one `SH_DEGREE_l_COEF_n` RGB attribute per coefficient.
+/// A `COLOR_0` attribute is written as well, so viewers without the extension still show points.
+/// The scene's cameras become perspective camera nodes.
+pub struct GltfImporter;
+/// Writes `.gltf` files. The `Exporter` implementation embeds the buffer as a base64 data URI;
+/// use `export_with_external_buffer` for a separate `.bin` file.
+pub struct GltfExporter;
+/// Writes binary `.glb` files.
+pub struct GlbExporter;
+
+/// Options shared by the glTF exporters.
+#[derive(Debug, Clone, Default)]
+pub struct GltfExportOptions {
+  /// Compress the attribute buffers with `KHR_meshopt_compression`.
+  pub meshopt_compression: bool,
+}
+
+const EXTENSION: &str = "KHR_gaussian_splatting";
+const MESHOPT_EXTENSION: &str = "KHR_meshopt_compression";
+const EXT_MESHOPT_EXTENSION: &str = "EXT_meshopt_compression";
+const MAX_SH_DEGREE: usize = 3;
+
+const GLB_MAGIC: &[u8; 4] = b"glTF";
+const GLB_CHUNK_JSON: u32 = 0x4e4f_534a;
+const GLB_CHUNK_BIN: u32 = 0x004e_4942;
+
+const COMPONENT_BYTE: u64 = 5120;
+const COMPONENT_UNSIGNED_BYTE: u64 = 5121;
+const COMPONENT_SHORT: u64 = 5122;
+const COMPONENT_UNSIGNED_SHORT: u64 = 5123;
+const COMPONENT_FLOAT: u64 = 5126;
+const MODE_POINTS: u64 = 0;
+const TARGET_ARRAY_BUFFER: u64 = 34962;
+
+/// glTF cameras look along -Z with +Y up, scene cameras along +Z with +Y down.
+const CAMERA_AXIS_FLIP: [f32; 3] = [1.0, -1.0, -1.0];
+const CAMERA_ZNEAR: f32 = 0.01;
+/// Image height assumed for cameras without the intrinsics in their `extras`.
+const DEFAULT_IMAGE_HEIGHT: u32 = 1080;
+
+fn parse_error(message: impl Into<String>) -> ConversionError {
+  ConversionError::ParseError {
+    format: "glTF".to_string(),
+    message: message.into(),
+  }
+}
+
+fn write_error(message: impl Into<String>) -> ConversionError {
+  ConversionError::WriteError {
+    format: "glTF".to_string(),
+    message: message.into(),
+  }
+}
+
+fn attribute_name(name: &str) -> String {
+  format!("{}:{}", EXTENSION, name)
+}
+
+fn sh_attribute_name(degree: usize, coefficient: usize) -> String {
+  attribute_name(&format!("SH_DEGREE_{}_COEF_{}", degree, coefficient))
+}
+
+/// A vertex attribute of the splat primitive, as floats with `components` values per splat.
+struct Attribute {
+  name: String,
+  kind: &'static str,
+  components: usize,
+  values: Vec<f32>,
+}
+
+impl Attribute {
+  fn new(name: String, kind: &'static str, values: Vec<f32>) -> Self {
+    let components = match kind {
+      "SCALAR" => 1,
+      "VEC3" => 3,
+      _ => 4,
+    };
+    Self {
+      name,
+      kind,
+      components,
+      values,
+    }
+  }
+}
+
+fn scene_attributes(scene: &Scene) -> Vec<Attribute> {
+  let splats = &scene.splats;
+  let collect = |f: &dyn Fn(&GaussianSplat) -> Vec<f32>| splats.iter().flat_map(f).collect();
+
+  let mut attributes = vec![
+    Attribute::new(
+      "POSITION".to_string(),
+      "VEC3",
+      collect(&|s| s.position.to_vec()),
+    ),
+    Attribute::new(
+      "COLOR_0".to_string(),
+      "VEC4",
+      collect(&|s| {
+        let [r, g, b] = s
+          .spherical_harmonics_dc
+          .map(|dc| (0.5 + SH_C0 * dc).clamp(0.0, 1.0));
+        vec![r, g, b, sigmoid(s.opacity)]
+      }),
+    ),
+    Attribute::new(
+      attribute_name("ROTATION"),
+      "VEC4",
+      collect(&|s| {
+        let [w, x, y, z] = s.rotation;
+        let len = (w * w + x * x + y * y + z * z).sqrt();
+        if len > 0.0 {
+          vec![x / len, y / len, z / len, w / len]
+        } else {
+          vec![0.0, 0.0, 0.0, 1.0]
+        }
+      }),
+    ),
+    Attribute::new(
+      attribute_name("SCALE"),
+      "VEC3",
+      collect(&|s| s.scale.map(f32::exp).to_vec()),
+    ),
+    Attribute::new(
+      attribute_name("OPACITY"),
+      "SCALAR",
+      collect(&|s| vec![sigmoid(s.opacity)]),
+    ),
+    Attribute::new(
+      sh_attribute_name(0, 0),
+      "VEC3",
+      collect(&|s| s.spherical_harmonics_dc.to_vec()),
+    ),
+  ];
+
+  for degree in 1..=scene.sh_degree() as usize {
+    for coefficient in 0..2 * degree + 1 {
+      // Coefficient n of band l is at index l² + n, and the rest skips the DC term
+      let index = degree * degree + coefficient - 1;
+      attributes.push(Attribute::new(
+        sh_attribute_name(degree, coefficient),
+        "VEC3",
+        collect(&|s| (0..3).map(|c| s.sh_rest(c, index)).collect()),
+      ));
+    }
+  }
+
+  attributes
+}
+
+/// Builds the glTF JSON and its single binary buffer. `buffer_uri` is `None` for `.glb`.
+fn build_document(
+  scene: &Scene,
+  options: &GltfExportOptions,
+  buffer_uri: Option<String>,
+) -> (Value, Vec<u8>) {
+  let count = scene.splats.len();
+  let mut extensions_used = vec![EXTENSION];
+  let mut document = json!({
+    "asset": { "version": "2.0", "generator": "gs-flux" },
+  });
+
+  if count == 0 {
+    // Accessors can't be empty, so an empty scene has no mesh at all
+    document["extensionsUsed"] = json!(extensions_used);
+    add_nodes(&mut document, false, &scene.cameras);
+    return (document, Vec::new());
+  }
+
+  let mut binary = Vec::new();
+  let mut raw_length = 0;
+  let mut buffer_views = Vec::new();
+  let mut accessors = Vec::new();
+  let mut primitive_attributes = Map::new();
+
+  for attribute in scene_attributes(scene) {
+    let stride = attribute.components * 4;
+    let bytes: Vec<u8> = attribute
+      .values
+      .iter()
+      .flat_map(|v| v.to_le_bytes())
+      .collect();
+
+    let buffer_view = if options.meshopt_compression {
+      let compressed = encode_vertex_buffer(&bytes, stride);
+      let view = json!({
+        "buffer": 1,
+        "byteOffset": raw_length,
+        "byteLength": bytes.len(),
+        "byteStride": stride,
+        "target": TARGET_ARRAY_BUFFER,
+        "extensions": {
+          MESHOPT_EXTENSION: {
+            "buffer": 0,
+            "byteOffset": binary.len(),
+            "byteLength": compressed.len(),
+            "byteStride": stride,
+            "count": count,
+            "mode": "ATTRIBUTES",
+          }
+        }
+      });
+      binary.extend_from_slice(&compressed);
+      binary.resize(binary.len().next_multiple_of(4), 0);
+      view
+    } else {
+      let view = json!({
+        "buffer": 0,
+        "byteOffset": binary.len(),
+        "byteLength": bytes.len(),
+        "byteStride": stride,
+        "target": TARGET_ARRAY_BUFFER,
+      });
+      binary.extend_from_slice(&bytes);
+      view
+    };
+    raw_length += bytes.len();
+
+    let mut accessor = json!({
+      "bufferView": buffer_views.len(),
+      "componentType": COMPONENT_FLOAT,
+      "count": count,
+      "type": attribute.kind,
+    });
+    if attribute.name == "POSITION" {
+      // POSITION accessors must declare their bounds
+      let mut min = [f32::INFINITY; 3];
+      let mut max = [f32::NEG_INFINITY; 3];
+      for position in attribute.values.chunks_exact(3) {
+        for axis in 0..3 {
+          min[axis] = min[axis].min(position[axis]);
+          max[axis] = max[axis].max(position[axis]);
+        }
+      }
+      accessor["min"] = json!(min);
+      accessor["max"] = json!(max);
+    }
+
+    primitive_attributes.insert(attribute.name, json!(accessors.len()));
+    buffer_views.push(buffer_view);
+    accessors.push(accessor);
+  }
+
+  let mut buffer = json!({ "byteLength": binary.len() });
+  if let Some(uri) = buffer_uri {
+    buffer["uri"] = json!(uri);
+  }
+  let mut buffers = vec![buffer];
+  if options.meshopt_compression {
+    extensions_used.push(MESHOPT_EXTENSION);
+    document["extensionsRequired"] = json!([MESHOPT_EXTENSION]);
+    buffers.push(json!({
+      "byteLength": raw_length,
+      "extensions": { MESHOPT_EXTENSION: { "fallback": true } },
+    }));
+  }
+
+  document["extensionsUsed"] = json!(extensions_used);
+  add_nodes(&mut document, true, &scene.cameras);
+  document["meshes"] = json!([{
+    "primitives": [{
+      "mode": MODE_POINTS,
+      "attributes": primitive_attributes,
+      "extensions": {
+        EXTENSION: { "kernel": "ellipse", "colorSpace": "srgb_rec709_display" }
+      },
+    }]
+  }]);
+  document["buffers"] = json!(buffers);
+  document["bufferViews"] = json!(buffer_views);
+  document["accessors"] = json!(accessors);
+
+  (document, binary)
+}
+
+/// Sets the scene's nodes: the splat mesh, if any, followed by a node per camera. The exact
+/// intrinsics are kept in the cameras' `extras`, since glTF cameras only have a vertical field of
+/// view and an aspect ratio.
+fn add_nodes(document: &mut Value, has_mesh: bool, cameras: &[Camera]) {
+  let mut nodes = Vec::new();
+  if has_mesh {
+    nodes.push(json!({ "mesh": 0 }));
+  }
+
+  let mut gltf_cameras = Vec::new();
+  for camera in cameras {
+    // Camera-to-world rotation, with the camera axes flipped to the glTF convention
+    let matrix: [[f32; 3]; 3] = [0, 1, 2]
+      .map(|row| [0, 1, 2].map(|column| camera.rotation[column][row] * CAMERA_AXIS_FLIP[column]));
+    let [w, x, y, z] = matrix_to_quaternion(matrix);
+    nodes.push(json!({
+      "name": camera.name,
+      "camera": gltf_cameras.len(),
+      "translation": camera.position,
+      "rotation": [x, y, z, w],
+    }));
+    gltf_cameras.push(json!({
+      "name": camera.name,
+      "type": "perspective",
+      "perspective": {
+        "yfov": 2.0 * (camera.height as f32 / 2.0 / camera.focal[1]).atan(),
+        "aspectRatio": camera.width as f32 / camera.height as f32,
+        "znear": CAMERA_ZNEAR,
+      },
+      "extras": {
+        "width": camera.width,
+        "height": camera.height,
+        "focal": camera.focal,
+        "principalPoint": camera.principal_point,
+      },
+    }));
+  }
+
+  document["scene"] = json!(0);
+  document["scenes"] = json!([{ "nodes": (0..nodes.len()).collect::<Vec<_>>() }]);
+  if !nodes.is_empty() {
+    document["nodes"] = json!(nodes);
+  }
+  if !gltf_cameras.is_empty() {
+    document["cameras"] = json!(gltf_cameras);
+  }
+}
+
+fn f32_array<const N: usize>(value: &Value) -> Option<[f32; N]> {
+  let values = value.as_array()?;
+  if values.len() != N {
+    return None;
+  }
+  let mut array = [0.0; N];
+  for (item, value) in array.iter_mut().zip(values) {
+    *item = value.as_f64()? as f32;
+  }
+  Some(array)
+}
+
+/// Cameras of the nodes with a perspective camera. Like the splats, only the node's own transform
+/// is applied, not those of its parents.
+fn node_cameras(document: &Value) -> Vec<Camera> {
+  let nodes = document["nodes"].as_array().into_iter().flatten();
+  nodes
+    .filter_map(|node| {
+      let camera = &document["cameras"][node["camera"].as_u64()? as usize];
+      let perspective = &camera["perspective"];
+      let yfov = perspective["yfov"].as_f64()? as f32;
+      let extras = &camera["extras"];
+
+      let (width, height) = match (extras["width"].as_u64(), extras["height"].as_u64()) {
+        (Some(width), Some(height)) => (width as u32, height as u32),
+        _ => {
+          let aspect = perspective["aspectRatio"].as_f64().unwrap_or(1.0) as f32;
+          let width = (DEFAULT_IMAGE_HEIGHT as f32 * aspect).round() as u32;
+          (width.max(1), DEFAULT_IMAGE_HEIGHT)
+        }
+      };
+      let focal =
+        f32_array(&extras["focal"]).unwrap_or([height as f32 / 2.0 / (yfov / 2.0).tan(); 2]);
+      let principal_point =
+        f32_array(&extras["principalPoint"]).unwrap_or([width as f32 / 2.0, height as f32 / 2.0]);
+
+      // Camera-to-world rotation columns and translation, from a column-major matrix or TRS
+      let (matrix, position) = match f32_array::<16>(&node["matrix"]) {
+        Some(m) => {
+          let column = |c: usize| {
+            let length = (m[c * 4].powi(2) + m[c * 4 + 1].powi(2) + m[c * 4 + 2].powi(2)).sqrt();
+            [0, 1, 2].map(|row| m[c * 4 + row] / length)
+          };
+          let columns = [column(0), column(1), column(2)];
+          (
+            [0, 1, 2].map(|row| [0, 1, 2].map(|c| columns[c][row])),
+            [m[12], m[13], m[14]],
+          )
+        }
+        None => {
+          let [x, y, z, w] = f32_array(&node["rotation"]).unwrap_or([0.0, 0.0, 0.0, 1.0]);
+          (
+            quaternion_to_matrix([w, x, y, z]),
+            f32_array(&node["translation"]).unwrap_or([0.0; 3]),
+          )
+        }
+      };
+
+      Some(Camera {
+        name: node["name"]
+          .as_str()
+          .or(camera["name"].as_str())
+          .unwrap_or_default()
+          .to_string(),
+        position,
+        rotation: [0, 1, 2]
+          .map(|row| [0, 1, 2].map(|column| matrix[column][row] * CAMERA_AXIS_FLIP[row])),
+        width,
+        height,
+        focal,
+        principal_point,
+      })
+    })
+    .collect()
+}
+
+fn write_json(document: &Value, writer: &mut impl Write) -> Result<(), ConversionError> {
+  serde_json::to_writer(writer, document).map_err(|e| write_error(e.to_string()))
+}
+
+// --- IMPORTER ---
+
+fn usize_field(value: &Value, key: &str) -> Option<usize> {
+  value.get(key).and_then(Value::as_u64).map(|v| v as usize)
+}
+
+/// Splits a `.glb` container into its JSON and optional binary chunk.
+fn parse_glb(data: &[u8]) -> Result<(&[u8], Option<&[u8]>), ConversionError> {
+  let read_u32 = |offset: usize| {
+    data
+      .get(offset..offset + 4)
+      .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+      .ok_or_else(|| parse_error("Truncated GLB container"))
+  };
+
+  let version = read_u32(4)?;
+  if version != 2 {
+    return Err(parse_error(format!("Unsupported GLB version {}", version)));
+  }
+  let length = (read_u32(8)? as usize).min(data.len());
+
+  let mut json = None;
+  let mut binary = None;
+  let mut offset = 12;
+  while offset + 8 <= length {
+    let chunk_length = read_u32(offset)? as usize;
+    let chunk_type = read_u32(offset + 4)?;
+    let chunk = (offset + 8)
+      .checked_add(chunk_length)
+      .and_then(|end| data.get(offset + 8..end))
+      .ok_or_else(|| parse_error("Truncated GLB chunk"))?;
+    match chunk_type {
+      GLB_CHUNK_JSON if json.is_none() => json = Some(chunk),
+      GLB_CHUNK_BIN if binary.is_none() => binary = Some(chunk),
+      _ => {}
+    }
+    offset += 8 + chunk_length.next_multiple_of(4);
+  }
+
+  Ok((
+    json.ok_or_else(|| parse_error("Missing GLB JSON chunk"))?,
+    binary,
+  ))
+}
+
+fn decode_data_uri(uri: &str) -> Result<Vec<u8>, ConversionError> {
+  let (_, data) = uri
+    .split_once(";base64,")
+    .ok_or_else(|| parse_error("Only base64 data URIs are supported"))?;
+  BASE64
+    .decode(data)
+    .map_err(|e| parse_error(format!("Invalid base64 buffer: {}", e)))
+}
+
+/// Resolved buffers of a glTF document, used to read its accessors.
+struct GltfData<'a> {
+  document: &'a Value,
+  buffers: Vec<Vec<u8>>,
+}
+
+impl GltfData<'_> {
+  fn buffer_slice(
+    &self,
+    index: usize,
+    offset: usize,
+    length: usize,
+  ) -> Result<&[u8], ConversionError> {
+    self
+      .buffers
+      .get(index)
+      .zip(offset.checked_add(length))
+      .and_then(|(buffer, end)| buffer.get(offset..end))
+      .ok_or_else(|| parse_error(format!("Buffer view outside of buffer {}", index)))
+  }
+
+  /// Bytes of a buffer view, decompressing meshopt encoded views.
+  fn buffer_view(&self, index: usize) -> Result<Vec<u8>, ConversionError> {
+    let view = &self.document["bufferViews"][index];
+    if view.is_null() {
+      return Err(parse_error(format!("Missing buffer view {}", index)));
+    }
+
+    let meshopt = view.get("extensions").and_then(|ext| {
+      ext
+        .get(MESHOPT_EXTENSION)
+        .or_else(|| ext.get(EXT_MESHOPT_EXTENSION))
+    });
+    if let Some(meshopt) = meshopt {
+      let mode = meshopt["mode"].as_str().unwrap_or_default();
+      let filter = meshopt["filter"].as_str().unwrap_or("NONE");
+      if mode != "ATTRIBUTES" || filter != "NONE" {
+        return Err(parse_error(format!(
+          "Unsupported meshopt compression (mode {}, filter {})",
+          mode, filter
+        )));
+      }
+
+      let data = self.buffer_slice(
+        usize_field(meshopt, "buffer").unwrap_or(0),
+        usize_field(meshopt, "byteOffset").unwrap_or(0),
+        usize_field(meshopt, "byteLength").unwrap_or(0),
+      )?;
+      return decode_vertex_buffer(
+        data,
+        usize_field(meshopt, "count").unwrap_or(0),
+        usize_field(meshopt, "byteStride").unwrap_or(0),
+      )
+      .map_err(parse_error);
+    }
+
+    Ok(
+      self
+        .buffer_slice(
+          usize_field(view, "buffer").unwrap_or(0),
+          usize_field(view, "byteOffset").unwrap_or(0),
+          usize_field(view, "byteLength").unwrap_or(0),
+        )?
+        .to_vec(),
+    )
+  }
+
+  /// Reads an accessor as floats, `components` per element. Normalized integers are mapped to 0..1 (or -1..1).
+  /// Accessors without a buffer view read as zeros, at most `position_count` of them.
+  fn accessor(
+    &self,
+    index: usize,
+    components: usize,
+    position_count: Option<usize>,
+  ) -> Result<Vec<f32>, ConversionError> {
+    let accessor = &self.document["accessors"][index];
+    let count = usize_field(accessor, "count")
+      .ok_or_else(|| parse_error(format!("Missing accessor {}", index)))?;
+    let expected = match components {
+      1 => "SCALAR",
+      3 => "VEC3",
+      _ => "VEC4",
+    };
+    if accessor["type"].as_str() != Some(expected) {
+      return Err(parse_error(format!(
+        "Accessor {} should be of type {}",
+        index, expected
+      )));
+    }
+    if accessor.get("sparse").is_some() {
+      return Err(parse_error("Sparse accessors are not supported"));
+    }
+
+    let component_type = accessor["componentType"].as_u64().unwrap_or(0);
+    let normalized = accessor["normalized"].as_bool().unwrap_or(false);
+    let component_size = match component_type {
+      COMPONENT_BYTE | COMPONENT_UNSIGNED_BYTE => 1,
+      COMPONENT_SHORT | COMPONENT_UNSIGNED_SHORT => 2,
+      COMPONENT_FLOAT => 4,
+      other => {
+        return Err(parse_error(format!(
+          "Unsupported accessor component type {}",
+          other
+        )));
+      }
+    };
+
+    let Some(view_index) = usize_field(accessor, "bufferView") else {
+      return match position_count {
+        Some(position_count) if count <= position_count => Ok(vec![0.0; count * components]),
+        Some(_) => Err(parse_error(format!(
+          "Accessor {} counts more elements than POSITION",
+          index
+        ))),
+        None => Err(parse_error(format!(
+          "Accessor {} has no buffer view",
+          index
+        ))),
+      };
+    };
+    let data = self.buffer_view(view_index)?;
+    let element_size = components * component_size;
+    let stride =
+      usize_field(&self.document["bufferViews"][view_index], "byteStride").unwrap_or(element_size);
+    if stride < element_size {
+      return Err(parse_error(format!(
+        "Accessor {} has a stride of {} bytes for {} byte elements",
+        index, stride, element_size
+      )));
+    }
+    let offset = usize_field(accessor, "byteOffset").unwrap_or(0);
+
+    // The last element has to fit in the view before anything is allocated
+    let out_of_bounds = || parse_error(format!("Accessor {} is out of bounds", index));
+    if count > 0 {
+      let end = (count - 1)
+        .checked_mul(stride)
+        .and_then(|last| last.checked_add(offset))
+        .and_then(|last| last.checked_add(element_size))
+        .ok_or_else(out_of_bounds)?;
+      if end > data.len() {
+        return Err(out_of_bounds());
+      }
+    }
+
+    let mut values = Vec::with_capacity(count * components);
+    for i in 0..count {
+      for c in 0..components {
+        let start = offset + i * stride + c * component_size;
+        let bytes = data
+          .get(start..start + component_size)
+          .ok_or_else(out_of_bounds)?;
+        let value = match component_type {
+          COMPONENT_FLOAT => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+          COMPONENT_UNSIGNED_BYTE if normalized => bytes[0] as f32 / 255.0,
+          COMPONENT_UNSIGNED_BYTE => bytes[0] as f32,
+          COMPONENT_BYTE if normalized => (bytes[0] as i8 as f32 / 127.0).max(-1.0),
+          COMPONENT_BYTE => bytes[0] as i8 as f32,
+          COMPONENT_UNSIGNED_SHORT if normalized => {
+            u16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 65535.0
+          }
+          COMPONENT_UNSIGNED_SHORT => u16::from_le_bytes([bytes[0], bytes[1]]) as f32,
+          _ if normalized => (i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32767.0).max(-1.0),
+          _ => i16::from_le_bytes([bytes[0], bytes[1]]) as f32,
+        };
+        values.push(value);
+      }
+    }
+
+    Ok(values)
+  }
+
+  /// Reads the accessor of a primitive attribute, if the primitive has it.
+  fn attribute(
+    &self,
+    primitive: &Value,
+    name: &str,
+    components: usize,
+    position_count: Option<usize>,
+  ) -> Result<Option<Vec<f32>>, ConversionError> {
+    usize_field(&primitive["attributes"], name)
+      .map(|index| self.accessor(index, components, position_count))
+      .transpose()
+  }
+
+  fn primitive_splats(&self, primitive: &Value) -> Result<Vec<GaussianSplat>, ConversionError> {
+    let positions = self
+      .attribute(primitive, "POSITION", 3, None)?
+      .ok_or_else(|| parse_error("Splat primitive without POSITION"))?;
+    let count = positions.len() / 3;
+    let check = |values: Option<Vec<f32>>, components: usize, name: &str| match values {
+      Some(values) if values.len() != count * components => Err(parse_error(format!(
+        "Attribute {} has a different count than POSITION",
+        name
+      ))),
+      values => Ok(values),
+    };
+
+    let rotations = check(
+      self.attribute(primitive, &attribute_name("ROTATION"), 4, Some(count))?,
+      4,
+      "ROTATION",
+    )?;
+    let scales = check(
+      self.attribute(primitive, &attribute_name("SCALE"), 3, Some(count))?,
+      3,
+      "SCALE",
+    )?;
+    let opacities = check(
+      self.attribute(primitive, &attribute_name("OPACITY"), 1, Some(count))?,
+      1,
+      "OPACITY",
+    )?;
+    let colors = check(
+      self.attribute(primitive, "COLOR_0", 4, Some(count))?,
+      4,
+      "COLOR_0",
+    )?;
+    let dc = check(
+      self.attribute(primitive, &sh_attribute_name(0, 0), 3, Some(count))?,
+      3,
+      "SH_DEGREE_0_COEF_0",
+    )?;
+
+    // Each band counts only if all of its coefficients are present
+    let mut bands: Vec<Vec<Vec<f32>>> = Vec::new();
+    'bands: for degree in 1..=MAX_SH_DEGREE {
+      let mut band = Vec::new();
+      for coefficient in 0..2 * degree + 1 {
+        let name = sh_attribute_name(degree, coefficient);
+        match check(self.attribute(primitive, &name, 3, Some(count))?, 3, &name)? {
+          Some(values) => band.push(values),
+          None => break 'bands,
+        }
+      }
+      bands.push(band);
+    }
+    let per_channel: usize = bands.iter().map(Vec::len).sum();
+
+    let mut splats = Vec::with_capacity(count);
+    for i in 0..count {
+      let color = colors.as_ref().map(|c| &c[i * 4..i * 4 + 4]);
+
+      let spherical_harmonics_dc = match (&dc, color) {
+        (Some(dc), _) => [dc[i * 3], dc[i * 3 + 1], dc[i * 3 + 2]],
+        (None, Some(color)) => [0, 1, 2].map(|c| (color[c] - 0.5) / SH_C0),
+        (None, None) => [0.0; 3],
+      };
+      let opacity = match (&opacities, color) {
+        (Some(opacities), _) => inverse_sigmoid(opacities[i]),
+        (None, Some(color)) => inverse_sigmoid(color[3]),
+        (None, None) => inverse_sigmoid(1.0),
+      };
+      let rotation = rotations.as_ref().map_or([1.0, 0.0, 0.0, 0.0], |r| {
+        [r[i * 4 + 3], r[i * 4], r[i * 4 + 1], r[i * 4 + 2]]
+      });
+      let scale = scales.as_ref().map_or([0.0; 3], |s| {
+        [s[i * 3], s[i * 3 + 1], s[i * 3 + 2]].map(|v| v.max(f32::MIN_POSITIVE).ln())
+      });
+
+      let mut spherical_harmonics_rest = vec![0.0; 3 * per_channel];
+      for (index, values) in bands.iter().flatten().enumerate() {
+        for channel in 0..3 {
+          spherical_harmonics_rest[channel * per_channel + index] = values[i * 3 + channel];
+        }
+      }
+
+      splats.push(GaussianSplat {
+        position: [positions[i * 3], positions[i * 3 + 1], positions[i * 3 + 2]],
+        normal: [0.0, 0.0, 0.0],
+        spherical_harmonics_dc,
+        spherical_harmonics_rest,
+        opacity,
+        scale,
+        rotation,
+      });
+    }
+
+    Ok(splats)
+  }
+}
+
+impl Importer for GltfImporter {
+  fn import(reader: &mut impl Read) -> Result<Scene, ConversionError> {
+    Self::import_with_resolver(reader, |uri| {
+      Err(parse_error(format!(
+        "External buffer \"{}\" can't be resolved from a stream",
+        uri
+      )))
+    })
+  }
+}
+
+impl GltfImporter {
+  /// Imports a `.gltf` or `.glb` file, calling `resolve` with the URI of every buffer that is
+  /// neither embedded nor the GLB binary chunk (usually a `.bin` file next to the `.gltf`).
+  ///
+  /// All mesh primitives using `KHR_gaussian_splatting` are merged; node transforms are not applied.
+  pub fn import_with_resolver(
+    reader: &mut impl Read,
+    mut resolve: impl FnMut(&str) -> Result<Vec<u8>, ConversionError>,
+  ) -> Result<Scene, ConversionError> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    let (json_data, binary_chunk) = if data.starts_with(GLB_MAGIC) {
+      parse_glb(&data)?
+    } else {
+      (data.as_slice(), None)
+    };
+    let document: Value =
+      serde_json::from_slice(json_data).map_err(|e| parse_error(e.to_string()))?;
+
+    let version = document["asset"]["version"].as_str().unwrap_or_default();
+    if !version.starts_with("2.") {
+      return Err(parse_error(format!(
+        "Unsupported glTF version \"{}\"",
+        version
+      )));
+    }
+
+    let mut buffers = Vec::new();
+    for (index, buffer) in document["buffers"]
+      .as_array()
+      .into_iter()
+      .flatten()
+      .enumerate()
+    {
+      let data = match buffer["uri"].as_str() {
+        Some(uri) if uri.starts_with("data:") => decode_data_uri(uri)?,
+        Some(uri) => resolve(uri)?,
+        None if index == 0 => binary_chunk.unwrap_or_default().to_vec(),
+        // Fallback buffers of compressed views have no data
+        None => Vec::new(),
+      };
+      buffers.push(data);
+    }
+
+    let gltf = GltfData {
+      document: &document,
+      buffers,
+    };
+
+    let mut splats = Vec::new();
+    let mut meshes = 0;
+    for mesh in document["meshes"].as_array().into_iter().flatten() {
+      meshes += 1;
+      for primitive in mesh["primitives"].as_array().into_iter().flatten() {
+        let is_splat = primitive["extensions"].get(EXTENSION).is_some()
+          || primitive["attributes"]
+            .get(attribute_name("ROTATION"))
+            .is_some();
+        if is_splat {
+          splats.extend(gltf.primitive_splats(primitive)?);
+        }
+      }
+    }
+
+    if meshes > 0 && splats.is_empty() {
+      return Err(parse_error(format!("No {} primitives found", EXTENSION)));
+    }
+
+    Ok(Scene {
+      splats,
+      cameras: node_cameras(&document),
+    })
+  }
+}
+
+// --- EXPORTERS ---
+
+impl Exporter for GltfExporter {
+  fn export(scene: &Scene, writer: &mut impl Write) -> Result<(), ConversionError> {
+    Self::export_with_options(scene, writer, &GltfExportOptions::default())
+  }
+}
+
+impl GltfExporter {
+  /// Writes a self-contained `.gltf`, with the buffer embedded as a base64 data URI.
+  pub fn export_with_options(
+    scene: &Scene,
+    writer: &mut impl Write,
+    options: &GltfExportOptions,
+  ) -> Result<(), ConversionError> {
+    let (mut document, binary) = build_document(scene, options, None);
+    if let Some(buffer) = document["buffers"].get_mut(0) {
+      buffer["uri"] = json!(format!(
+        "data:application/octet-stream;base64,{}",
+        BASE64.encode(&binary)
+      ));
+    }
+    write_json(&document, writer)
+  }
+
+  /// Writes the `.gltf` JSON to `writer` and its buffer to `buffer_writer`, which the JSON
+  /// references as `buffer_uri` (relative to the `.gltf` file).
+  pub fn export_with_external_buffer(
+    scene: &Scene,
+    writer: &mut impl Write,
+    buffer_writer: &mut impl Write,
+    buffer_uri: &str,
+    options: &GltfExportOptions,
+  ) -> Result<(), ConversionError> {
+    let (document, binary) = build_document(scene, options, Some(buffer_uri.to_string()));
+    write_json(&document, writer)?;
+    buffer_writer
+      .write_all(&binary)
+      .map_err(|e| write_error(e.to_string()))
+  }
+}
+
+impl Exporter for GlbExporter {
+  fn export(scene: &Scene, writer: &mut impl Write) -> Result<(), ConversionError> {
+    Self::export_with_options(scene, writer, &GltfExportOptions::default())
+  }
+}
+
+impl GlbExporter {
+  pub fn export_with_options(
+    scene: &Scene,
+    writer: &mut impl Write,
+    options: &GltfExportOptions,
+  ) -> Result<(), ConversionError> {
+    let map_io_err = |e: std::io::Error| write_error(e.to_string());
+    let (document, mut binary) = build_document(scene, options, None);
+
+    // Chunks are 4-byte aligned, JSON padded with spaces and binary data with zeros
+    let mut json_chunk = serde_json::to_vec(&document).map_err(|e| write_error(e.to_string()))?;
+    json_chunk.resize(json_chunk.len().next_multiple_of(4), b' ');
+    binary.resize(binary.len().next_multiple_of(4), 0);
+
+    let mut length = 12 + 8 + json_chunk.len();
+    if !binary.is_empty() {
+      length += 8 + binary.len();
+    }
+
+    writer.write_all(GLB_MAGIC).map_err(map_io_err)?;
+    writer.write_all(&2u32.to_le_bytes()).map_err(map_io_err)?;
+    writer
+      .write_all(&(length as u32).to_le_bytes())
+      .map_err(map_io_err)?;
+
+    let mut chunks = vec![(GLB_CHUNK_JSON, json_chunk)];
+    if !binary.is_empty() {
+      chunks.push((GLB_CHUNK_BIN, binary));
+    }
+    for (chunk_type, data) in chunks {
+      writer
+        .write_all(&(data.len() as u32).to_le_bytes())
+        .map_err(map_io_err)?;
+      writer
+        .write_all(&chunk_type.to_le_bytes())
+        .map_err(map_io_err)?;
+      writer.write_all(&data).map_err(map_io_err)?;
+    }
+
+    Ok(())
+  }
+}
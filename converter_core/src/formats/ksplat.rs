@@ -0,0 +1,580 @@
+use std::collections::BTreeMap;
+
+use half::f16;
+
+use crate::helpers::activation::{inverse_sigmoid, sigmoid};
+use crate::helpers::spherical_harmonics::SH_C0;
+use crate::{
+  ConversionError, Exporter, GaussianSplat, Importer, Scene, sh_coefficients_per_channel,
+};
+
+/// Reads and writes mkkellogg's GaussianSplats3D `.ksplat` files (`SplatBuffer` version 0.1).
+pub struct KsplatImporter;
+pub struct KsplatExporter;
+
+const VERSION_MAJOR: u8 = 0;
+const VERSION_MINOR: u8 = 1;
+const HEADER_SIZE_BYTES: usize = 4096;
+const SECTION_HEADER_SIZE_BYTES: usize = 1024;
+const BUCKET_STORAGE_SIZE_BYTES: usize = 12;
+const BUCKET_SIZE: usize = 256;
+const BUCKET_BLOCK_SIZE: f32 = 5.0;
+const COMPRESSED_SCALE_RANGE: u32 = 32767;
+const DEFAULT_SH_RANGE: f32 = 1.5;
+const MAX_SH_DEGREE: u8 = 2;
+
+/// Options for `KsplatExporter::export_with_options`.
+#[derive(Debug, Clone, Copy)]
+pub struct KsplatExportOptions {
+  /// 0 stores full floats, 1 half floats with 16-bit bucketed positions, 2 additionally packs SH into bytes.
+  pub compression_level: u8,
+  /// SH degree to write (0-2). Defaults to the scene's degree, capped at the 2 supported by the format.
+  pub sh_degree: Option<u8>,
+}
+
+impl Default for KsplatExportOptions {
+  fn default() -> Self {
+    KsplatExportOptions {
+      compression_level: 1,
+      sh_degree: None,
+    }
+  }
+}
+
+/// Byte sizes of one splat's components at a compression level.
+struct Layout {
+  bytes_per_center: usize,
+  bytes_per_scale: usize,
+  bytes_per_rotation: usize,
+  bytes_per_sh_component: usize,
+}
+
+impl Layout {
+  fn for_level(compression_level: u16) -> Option<Layout> {
+    match compression_level {
+      0 => Some(Layout {
+        bytes_per_center: 12,
+        bytes_per_scale: 12,
+        bytes_per_rotation: 16,
+        bytes_per_sh_component: 4,
+      }),
+      1 | 2 => Some(Layout {
+        bytes_per_center: 6,
+        bytes_per_scale: 6,
+        bytes_per_rotation: 8,
+        bytes_per_sh_component: if compression_level == 1 { 2 } else { 1 },
+      }),
+      _ => None,
+    }
+  }
+
+  fn bytes_per_splat(&self, sh_degree: u8) -> usize {
+    self.bytes_per_center
+      + self.bytes_per_scale
+      + self.bytes_per_rotation
+      + 4
+      + 3 * sh_coefficients_per_channel(sh_degree) * self.bytes_per_sh_component
+  }
+}
+
+fn parse_error(message: impl Into<String>) -> ConversionError {
+  ConversionError::ParseError {
+    format: "KSPLAT".to_string(),
+    message: message.into(),
+  }
+}
+
+fn write_error(message: impl Into<String>) -> ConversionError {
+  ConversionError::WriteError {
+    format: "KSPLAT".to_string(),
+    message: message.into(),
+  }
+}
+
+fn read_bytes<const N: usize>(buffer: &[u8], offset: usize) -> Result<[u8; N], ConversionError> {
+  offset
+    .checked_add(N)
+    .and_then(|end| buffer.get(offset..end))
+    .map(|slice| slice.try_into().unwrap())
+    .ok_or_else(|| parse_error("Unexpected end of file"))
+}
+
+fn read_u16(buffer: &[u8], offset: usize) -> Result<u16, ConversionError> {
+  read_bytes(buffer, offset).map(u16::from_le_bytes)
+}
+
+fn read_u32(buffer: &[u8], offset: usize) -> Result<u32, ConversionError> {
+  read_bytes(buffer, offset).map(u32::from_le_bytes)
+}
+
+fn read_f32(buffer: &[u8], offset: usize) -> Result<f32, ConversionError> {
+  read_bytes(buffer, offset).map(f32::from_le_bytes)
+}
+
+fn read_f16(buffer: &[u8], offset: usize) -> Result<f32, ConversionError> {
+  read_bytes(buffer, offset).map(|bytes| f16::from_le_bytes(bytes).to_f32())
+}
+
+// --- IMPORTER ---
+
+impl Importer for KsplatImporter {
+  fn import(reader: &mut impl std::io::Read) -> Result<Scene, ConversionError> {
+    let mut buffer = Vec::new();
+    reader
+      .read_to_end(&mut buffer)
+      .map_err(|e| parse_error(e.to_string()))?;
+
+    let version_major = *buffer.first().ok_or_else(|| parse_error("Empty file"))?;
+    let version_minor = read_bytes::<1>(&buffer, 1)?[0];
+    if version_major != VERSION_MAJOR || version_minor < VERSION_MINOR {
+      return Err(parse_error(format!(
+        "Unsupported version {}.{}",
+        version_major, version_minor
+      )));
+    }
+
+    let max_section_count = read_u32(&buffer, 4)? as usize;
+    let splat_count = read_u32(&buffer, 16)? as usize;
+    let compression_level = read_u16(&buffer, 20)?;
+    let min_sh = read_f32(&buffer, 36)?;
+    let max_sh = read_f32(&buffer, 40)?;
+    let (min_sh, max_sh) = if min_sh == 0.0 && max_sh == 0.0 {
+      (-DEFAULT_SH_RANGE, DEFAULT_SH_RANGE)
+    } else {
+      (min_sh, max_sh)
+    };
+
+    let layout = Layout::for_level(compression_level).ok_or_else(|| {
+      parse_error(format!(
+        "Unsupported compression level {}",
+        compression_level
+      ))
+    })?;
+
+    // The counts of the header are untrusted, so they are checked against the file before anything
+    // is reserved for them
+    let section_headers_size = max_section_count
+      .checked_mul(SECTION_HEADER_SIZE_BYTES)
+      .and_then(|size| size.checked_add(HEADER_SIZE_BYTES));
+    if section_headers_size.is_none_or(|size| size > buffer.len()) {
+      return Err(parse_error("Section count exceeds the file size"));
+    }
+    if splat_count
+      .checked_mul(layout.bytes_per_splat(0))
+      .is_none_or(|size| size > buffer.len())
+    {
+      return Err(parse_error("Splat count exceeds the file size"));
+    }
+
+    let mut splats = Vec::with_capacity(splat_count);
+    let mut section_base = HEADER_SIZE_BYTES + max_section_count * SECTION_HEADER_SIZE_BYTES;
+
+    for section in 0..max_section_count {
+      let header = HEADER_SIZE_BYTES + section * SECTION_HEADER_SIZE_BYTES;
+      let section_splat_count = read_u32(&buffer, header)? as usize;
+      let max_splat_count = read_u32(&buffer, header + 4)? as usize;
+      let bucket_size = read_u32(&buffer, header + 8)? as usize;
+      let bucket_count = read_u32(&buffer, header + 12)? as usize;
+      let bucket_block_size = read_f32(&buffer, header + 16)?;
+      let bucket_storage_size = read_u16(&buffer, header + 20)? as usize;
+      let scale_range = match read_u32(&buffer, header + 24)? {
+        0 if compression_level == 0 => 1,
+        0 => COMPRESSED_SCALE_RANGE,
+        range => range,
+      } as f32;
+      let full_bucket_count = read_u32(&buffer, header + 32)? as usize;
+      let partial_bucket_count = read_u32(&buffer, header + 36)? as usize;
+      let sh_degree = read_u16(&buffer, header + 40)?.min(MAX_SH_DEGREE as u16) as u8;
+
+      let bytes_per_splat = layout.bytes_per_splat(sh_degree);
+      let truncated = || parse_error(format!("Section {} exceeds the file size", section));
+      let buckets_metadata_size = partial_bucket_count.checked_mul(4).ok_or_else(truncated)?;
+      let buckets_storage_size = bucket_storage_size
+        .checked_mul(bucket_count)
+        .and_then(|size| size.checked_add(buckets_metadata_size))
+        .ok_or_else(truncated)?;
+      let buckets_base = section_base
+        .checked_add(buckets_metadata_size)
+        .ok_or_else(truncated)?;
+      let data_base = section_base
+        .checked_add(buckets_storage_size)
+        .ok_or_else(truncated)?;
+      let data_end = section_splat_count
+        .checked_mul(bytes_per_splat)
+        .and_then(|size| size.checked_add(data_base));
+      if data_end.is_none_or(|end| end > buffer.len()) {
+        return Err(truncated());
+      }
+      // Every bucket stores its center in the file
+      let bucket_count_fits = full_bucket_count
+        .checked_add(partial_bucket_count)
+        .is_some_and(|count| count <= bucket_count)
+        && bucket_count
+          .checked_mul(BUCKET_STORAGE_SIZE_BYTES)
+          .is_some_and(|size| size <= buffer.len());
+      if compression_level >= 1 && !bucket_count_fits {
+        return Err(truncated());
+      }
+
+      // Every splat's bucket: full buckets first, then the partially filled ones in order
+      let mut bucket_of_splat = Vec::new();
+      if compression_level >= 1 {
+        bucket_of_splat.reserve(section_splat_count);
+        let full_buckets =
+          (0..full_bucket_count).flat_map(|bucket| std::iter::repeat_n(bucket, bucket_size));
+        bucket_of_splat.extend(full_buckets.take(section_splat_count));
+        for partial in 0..partial_bucket_count {
+          if bucket_of_splat.len() >= section_splat_count {
+            break;
+          }
+          let length = read_u32(&buffer, section_base + partial * 4)? as usize;
+          let remaining = section_splat_count - bucket_of_splat.len();
+          bucket_of_splat.extend(std::iter::repeat_n(
+            full_bucket_count + partial,
+            length.min(remaining),
+          ));
+        }
+        if bucket_of_splat.len() < section_splat_count {
+          return Err(parse_error("Bucket sizes do not cover every splat"));
+        }
+      }
+
+      let position_factor = bucket_block_size / 2.0 / scale_range;
+      let sh_per_channel = sh_coefficients_per_channel(sh_degree);
+
+      for i in 0..section_splat_count {
+        let base = data_base + i * bytes_per_splat;
+
+        let position = if compression_level == 0 {
+          [
+            read_f32(&buffer, base)?,
+            read_f32(&buffer, base + 4)?,
+            read_f32(&buffer, base + 8)?,
+          ]
+        } else {
+          let bucket = bucket_of_splat.get(i).copied().unwrap_or(0);
+          let center = buckets_base + bucket * BUCKET_STORAGE_SIZE_BYTES;
+          let mut position = [0.0f32; 3];
+          for (axis, value) in position.iter_mut().enumerate() {
+            let quantized = read_u16(&buffer, base + axis * 2)? as f32;
+            *value =
+              (quantized - scale_range) * position_factor + read_f32(&buffer, center + axis * 4)?;
+          }
+          position
+        };
+
+        let mut offset = base + layout.bytes_per_center;
+        let read_float = |offset: usize| {
+          if compression_level == 0 {
+            read_f32(&buffer, offset)
+          } else {
+            read_f16(&buffer, offset)
+          }
+        };
+        let float_size = layout.bytes_per_scale / 3;
+
+        // Scale - stored linear
+        let mut scale = [0.0f32; 3];
+        for value in scale.iter_mut() {
+          *value = read_float(offset)?.ln();
+          offset += float_size;
+        }
+
+        // Rotation - stored as [w, x, y, z]
+        let mut rotation = [0.0f32; 4];
+        for value in rotation.iter_mut() {
+          *value = read_float(offset)?;
+          offset += float_size;
+        }
+
+        let rgba: [u8; 4] = read_bytes(&buffer, offset)?;
+        offset += 4;
+        let spherical_harmonics_dc = [
+          (rgba[0] as f32 / 255.0 - 0.5) / SH_C0,
+          (rgba[1] as f32 / 255.0 - 0.5) / SH_C0,
+          (rgba[2] as f32 / 255.0 - 0.5) / SH_C0,
+        ];
+        let opacity = inverse_sigmoid(rgba[3] as f32 / 255.0);
+
+        // SH - interleaved per coefficient ([r, g, b] of band 1, ...), stored channel-major in the scene
+        let mut spherical_harmonics_rest = vec![0.0f32; sh_per_channel * 3];
+        for k in 0..sh_per_channel {
+          for channel in 0..3 {
+            let value = match compression_level {
+              0 => read_f32(&buffer, offset)?,
+              1 => read_f16(&buffer, offset)?,
+              _ => {
+                let byte = read_bytes::<1>(&buffer, offset)?[0];
+                byte as f32 / 255.0 * (max_sh - min_sh) + min_sh
+              }
+            };
+            spherical_harmonics_rest[channel * sh_per_channel + k] = value;
+            offset += layout.bytes_per_sh_component;
+          }
+        }
+
+        splats.push(GaussianSplat {
+          position,
+          normal: [0.0, 0.0, 0.0],
+          spherical_harmonics_dc,
+          spherical_harmonics_rest,
+          opacity,
+          scale,
+          rotation,
+        });
+      }
+
+      section_base = bytes_per_splat
+        .checked_mul(max_splat_count)
+        .and_then(|size| size.checked_add(buckets_storage_size))
+        .and_then(|size| size.checked_add(section_base))
+        .ok_or_else(truncated)?;
+    }
+
+    Ok(Scene {
+      splats,
+      cameras: Vec::new(),
+    })
+  }
+}
+
+// --- EXPORTER ---
+
+/// A cube of the bucket grid and the splats that fall into it.
+struct Bucket {
+  center: [f32; 3],
+  splats: Vec<usize>,
+}
+
+impl Exporter for KsplatExporter {
+  fn export(scene: &Scene, writer: &mut impl std::io::Write) -> Result<(), ConversionError> {
+    Self::export_with_options(scene, writer, &KsplatExportOptions::default())
+  }
+}
+
+impl KsplatExporter {
+  /// Writes `scene` as a single-section `.ksplat` file.
+  pub fn export_with_options(
+    scene: &Scene,
+    writer: &mut impl std::io::Write,
+    options: &KsplatExportOptions,
+  ) -> Result<(), ConversionError> {
+    let compression_level = options.compression_level as u16;
+    let layout = Layout::for_level(compression_level).ok_or_else(|| {
+      write_error(format!(
+        "Unsupported compression level {}, expected 0, 1 or 2",
+        compression_level
+      ))
+    })?;
+    let sh_degree = options
+      .sh_degree
+      .unwrap_or_else(|| scene.sh_degree())
+      .min(MAX_SH_DEGREE);
+    let sh_per_channel = sh_coefficients_per_channel(sh_degree);
+    let splat_count = scene.splats.len();
+
+    // Range used to pack SH into bytes at level 2
+    let (mut min_sh, mut max_sh) = (f32::INFINITY, f32::NEG_INFINITY);
+    for splat in &scene.splats {
+      for channel in 0..3 {
+        for k in 0..sh_per_channel {
+          let value = splat.sh_rest(channel, k);
+          min_sh = min_sh.min(value);
+          max_sh = max_sh.max(value);
+        }
+      }
+    }
+    if !(min_sh.is_finite() && max_sh.is_finite() && max_sh > min_sh) {
+      (min_sh, max_sh) = (-DEFAULT_SH_RANGE, DEFAULT_SH_RANGE);
+    }
+
+    // Levels 1 and 2 store positions relative to the center of their bucket
+    let (full_buckets, partial_buckets) = if compression_level >= 1 {
+      Self::compute_buckets(scene)
+    } else {
+      (Vec::new(), Vec::new())
+    };
+    let buckets: Vec<&Bucket> = full_buckets.iter().chain(&partial_buckets).collect();
+    let order: Vec<usize> = if compression_level >= 1 {
+      buckets
+        .iter()
+        .flat_map(|b| b.splats.iter().copied())
+        .collect()
+    } else {
+      (0..splat_count).collect()
+    };
+
+    let bytes_per_splat = layout.bytes_per_splat(sh_degree);
+    let buckets_storage_size =
+      buckets.len() * BUCKET_STORAGE_SIZE_BYTES + partial_buckets.len() * 4;
+    let storage_size = bytes_per_splat * splat_count + buckets_storage_size;
+
+    // Header
+    let mut header = vec![0u8; HEADER_SIZE_BYTES];
+    header[0] = VERSION_MAJOR;
+    header[1] = VERSION_MINOR;
+    header[4..8].copy_from_slice(&1u32.to_le_bytes()); // max section count
+    header[8..12].copy_from_slice(&1u32.to_le_bytes()); // section count
+    header[12..16].copy_from_slice(&(splat_count as u32).to_le_bytes());
+    header[16..20].copy_from_slice(&(splat_count as u32).to_le_bytes());
+    header[20..22].copy_from_slice(&compression_level.to_le_bytes());
+    let scene_center = Self::scene_center(scene);
+    for (axis, value) in scene_center.iter().enumerate() {
+      header[24 + axis * 4..28 + axis * 4].copy_from_slice(&value.to_le_bytes());
+    }
+    header[36..40].copy_from_slice(&min_sh.to_le_bytes());
+    header[40..44].copy_from_slice(&max_sh.to_le_bytes());
+
+    // Section header
+    let mut section = vec![0u8; SECTION_HEADER_SIZE_BYTES];
+    section[0..4].copy_from_slice(&(splat_count as u32).to_le_bytes());
+    section[4..8].copy_from_slice(&(splat_count as u32).to_le_bytes());
+    if compression_level >= 1 {
+      section[8..12].copy_from_slice(&(BUCKET_SIZE as u32).to_le_bytes());
+      section[12..16].copy_from_slice(&(buckets.len() as u32).to_le_bytes());
+      section[16..20].copy_from_slice(&BUCKET_BLOCK_SIZE.to_le_bytes());
+      section[20..22].copy_from_slice(&(BUCKET_STORAGE_SIZE_BYTES as u16).to_le_bytes());
+      section[24..28].copy_from_slice(&COMPRESSED_SCALE_RANGE.to_le_bytes());
+      section[32..36].copy_from_slice(&(full_buckets.len() as u32).to_le_bytes());
+      section[36..40].copy_from_slice(&(partial_buckets.len() as u32).to_le_bytes());
+    }
+    section[28..32].copy_from_slice(&(storage_size as u32).to_le_bytes());
+    section[40..42].copy_from_slice(&(sh_degree as u16).to_le_bytes());
+
+    // Section data: partially filled bucket lengths, bucket centers, then splats in bucket order
+    let mut data = Vec::with_capacity(storage_size);
+    for bucket in &partial_buckets {
+      data.extend_from_slice(&(bucket.splats.len() as u32).to_le_bytes());
+    }
+    for bucket in &buckets {
+      for value in bucket.center {
+        data.extend_from_slice(&value.to_le_bytes());
+      }
+    }
+
+    let position_factor = COMPRESSED_SCALE_RANGE as f32 / (BUCKET_BLOCK_SIZE / 2.0);
+    let mut bucket_centers = buckets
+      .iter()
+      .flat_map(|b| std::iter::repeat_n(b.center, b.splats.len()));
+
+    let push_float = |data: &mut Vec<u8>, value: f32| {
+      if compression_level == 0 {
+        data.extend_from_slice(&value.to_le_bytes());
+      } else {
+        data.extend_from_slice(&f16::from_f32(value).to_le_bytes());
+      }
+    };
+
+    for &index in &order {
+      let splat = &scene.splats[index];
+
+      if compression_level == 0 {
+        for value in splat.position {
+          data.extend_from_slice(&value.to_le_bytes());
+        }
+      } else {
+        let center = bucket_centers.next().unwrap_or([0.0; 3]);
+        for (value, center) in splat.position.iter().zip(center) {
+          let quantized =
+            ((value - center) * position_factor).round() + COMPRESSED_SCALE_RANGE as f32;
+          let quantized = quantized.clamp(0.0, (COMPRESSED_SCALE_RANGE * 2 + 1) as f32) as u16;
+          data.extend_from_slice(&quantized.to_le_bytes());
+        }
+      }
+
+      for value in splat.scale {
+        push_float(&mut data, value.exp());
+      }
+
+      let rotation = splat.rotation;
+      let len = rotation.iter().map(|v| v * v).sum::<f32>().sqrt();
+      for value in rotation {
+        push_float(&mut data, if len > 0.0 { value / len } else { value });
+      }
+
+      let to_u8 = |v: f32| (v * 255.0).floor().clamp(0.0, 255.0) as u8;
+      data.extend_from_slice(&[
+        to_u8(0.5 + SH_C0 * splat.spherical_harmonics_dc[0]),
+        to_u8(0.5 + SH_C0 * splat.spherical_harmonics_dc[1]),
+        to_u8(0.5 + SH_C0 * splat.spherical_harmonics_dc[2]),
+        to_u8(sigmoid(splat.opacity)),
+      ]);
+
+      for k in 0..sh_per_channel {
+        for channel in 0..3 {
+          let value = splat.sh_rest(channel, k);
+          match compression_level {
+            0 | 1 => push_float(&mut data, value),
+            _ => {
+              let normalized = (value.clamp(min_sh, max_sh) - min_sh) / (max_sh - min_sh);
+              data.push((normalized * 255.0).floor().clamp(0.0, 255.0) as u8);
+            }
+          }
+        }
+      }
+    }
+
+    let map_io_err = |e: std::io::Error| write_error(e.to_string());
+    writer.write_all(&header).map_err(map_io_err)?;
+    writer.write_all(&section).map_err(map_io_err)?;
+    writer.write_all(&data).map_err(map_io_err)?;
+
+    Ok(())
+  }
+
+  /// Groups splats into cubes of `BUCKET_BLOCK_SIZE`, splitting each cube into buckets of
+  /// at most `BUCKET_SIZE` splats. Returns the full buckets and the partially filled ones.
+  fn compute_buckets(scene: &Scene) -> (Vec<Bucket>, Vec<Bucket>) {
+    let mut min = [f32::INFINITY; 3];
+    for splat in &scene.splats {
+      for (min, value) in min.iter_mut().zip(splat.position) {
+        *min = min.min(value);
+      }
+    }
+
+    let half_block = BUCKET_BLOCK_SIZE / 2.0;
+    let mut full = Vec::new();
+    let mut partial: BTreeMap<[i64; 3], Bucket> = BTreeMap::new();
+
+    for (index, splat) in scene.splats.iter().enumerate() {
+      let block = [0, 1, 2].map(|axis| {
+        let offset = (splat.position[axis] - min[axis]) / BUCKET_BLOCK_SIZE;
+        if offset.is_finite() {
+          offset.floor() as i64
+        } else {
+          0
+        }
+      });
+
+      let bucket = partial.entry(block).or_insert_with(|| Bucket {
+        center: [0, 1, 2]
+          .map(|axis| block[axis] as f32 * BUCKET_BLOCK_SIZE + min[axis] + half_block),
+        splats: Vec::with_capacity(BUCKET_SIZE),
+      });
+      bucket.splats.push(index);
+
+      if bucket.splats.len() >= BUCKET_SIZE {
+        full.extend(partial.remove(&block));
+      }
+    }
+
+    (full, partial.into_values().collect())
+  }
+
+  /// Center of the scene's bounding box, used by the viewer for sorting.
+  fn scene_center(scene: &Scene) -> [f32; 3] {
+    let mut min = [f32::INFINITY; 3];
+    let mut max = [f32::NEG_INFINITY; 3];
+    for splat in &scene.splats {
+      for (axis, value) in splat.position.into_iter().enumerate() {
+        min[axis] = min[axis].min(value);
+        max[axis] = max[axis].max(value);
+      }
+    }
+
+    [0, 1, 2].map(|axis| {
+      let center = (min[axis] + max[axis]) / 2.0;
+      if center.is_finite() { center } else { 0.0 }
+    })
+  }
+}
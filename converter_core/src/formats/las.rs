@@ -0,0 +1,235 @@
+use crate::formats::point_cloud::{
+  PointCloudExportOptions, PointCloudImportOptions, point_color, scene_from_points,
+};
+use crate::{ConversionError, Exporter, Importer, Scene};
+
+/// Reads uncompressed ASPRS LAS point clouds (versions 1.0 to 1.4, any point format) and
+/// initialises a Gaussian per point.
+pub struct LasImporter;
+/// Writes the splat centres as an ASPRS LAS 1.4 point cloud, using point data record format 7
+/// (XYZ with 16-bit RGB). The opacity is stored as the point intensity.
+pub struct LasExporter;
+
+const HEADER_SIZE: u16 = 375;
+const POINT_FORMAT: u8 = 7;
+const POINT_RECORD_LENGTH: u16 = 36;
+/// Global encoding bit stating that any coordinate reference system is given as WKT,
+/// which LAS 1.4 requires for point formats 6 to 10.
+const GLOBAL_ENCODING_WKT: u16 = 1 << 4;
+/// Finest coordinate resolution; coarser when the scene is too large for 32-bit coordinates.
+const MIN_SCALE: f64 = 1e-4;
+
+fn parse_error(message: impl Into<String>) -> ConversionError {
+  ConversionError::ParseError {
+    format: "LAS".to_string(),
+    message: message.into(),
+  }
+}
+
+fn write_error(message: impl Into<String>) -> ConversionError {
+  ConversionError::WriteError {
+    format: "LAS".to_string(),
+    message: message.into(),
+  }
+}
+
+/// A fixed-size ASCII field, padded with zeros.
+fn ascii_field<const N: usize>(text: &str) -> [u8; N] {
+  let mut field = [0u8; N];
+  for (target, byte) in field.iter_mut().zip(text.bytes()) {
+    *target = byte;
+  }
+  field
+}
+
+/// Byte offset of the RGB values within a point record, for the point formats that have them.
+fn rgb_offset(point_format: u8) -> Option<usize> {
+  match point_format {
+    2 => Some(20),
+    3 | 5 => Some(28),
+    7 | 8 | 10 => Some(30),
+    _ => None,
+  }
+}
+
+// --- IMPORTER ---
+
+impl Importer for LasImporter {
+  fn import(reader: &mut impl std::io::Read) -> Result<Scene, ConversionError> {
+    Self::import_with_options(reader, &PointCloudImportOptions::default())
+  }
+}
+
+impl LasImporter {
+  pub fn import_with_options(
+    reader: &mut impl std::io::Read,
+    options: &PointCloudImportOptions,
+  ) -> Result<Scene, ConversionError> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    if !data.starts_with(b"LASF") || data.len() < 227 {
+      return Err(parse_error("Missing LASF signature"));
+    }
+
+    let u16_at = |offset: usize| u16::from_le_bytes([data[offset], data[offset + 1]]);
+    let u32_at = |offset: usize| u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+    let f64_at = |offset: usize| f64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+
+    let header_size = u16_at(94) as usize;
+    let point_offset = u32_at(96) as usize;
+    let point_format = data[104];
+    let record_length = u16_at(105) as usize;
+    if point_format & 0xc0 != 0 {
+      return Err(parse_error("Compressed LAZ files are not supported"));
+    }
+
+    // LAS 1.4 moved the point count to a 64-bit field, leaving the legacy one at 0
+    let mut count = u32_at(107) as u64;
+    if data[25] >= 4 && header_size >= HEADER_SIZE as usize && data.len() >= 255 {
+      count = count.max(u64::from_le_bytes(data[247..255].try_into().unwrap()));
+    }
+
+    let scale = [f64_at(131), f64_at(139), f64_at(147)];
+    let offset = [f64_at(155), f64_at(163), f64_at(171)];
+    let minimum_length = rgb_offset(point_format).map_or(12, |rgb| rgb + 6);
+    if record_length < minimum_length {
+      return Err(parse_error(format!(
+        "Point record length {} is too short for point format {}",
+        record_length, point_format
+      )));
+    }
+
+    // The count is untrusted, so the records it spans must be in the file before any is reserved
+    let records = usize::try_from(count)
+      .ok()
+      .and_then(|count| count.checked_mul(record_length))
+      .and_then(|length| length.checked_add(point_offset))
+      .and_then(|end| data.get(point_offset..end))
+      .ok_or_else(|| parse_error("Point data is truncated"))?;
+    let count = records.len() / record_length;
+
+    let mut positions = Vec::with_capacity(count);
+    let mut raw_colors = Vec::with_capacity(count);
+    for record in records.chunks_exact(record_length) {
+      positions.push([0, 1, 2].map(|axis| {
+        let value = i32::from_le_bytes(record[axis * 4..axis * 4 + 4].try_into().unwrap());
+        (value as f64 * scale[axis] + offset[axis]) as f32
+      }));
+      if let Some(rgb) = rgb_offset(point_format) {
+        raw_colors.push(
+          [0, 1, 2].map(|c| u16::from_le_bytes([record[rgb + c * 2], record[rgb + c * 2 + 1]])),
+        );
+      }
+    }
+
+    // Colours should be 16-bit, but some writers store 8-bit values
+    let max = raw_colors.iter().flatten().copied().max().unwrap_or(0);
+    let range = if max <= 255 { 255.0 } else { 65535.0 };
+    let colors = if raw_colors.is_empty() {
+      vec![[0.5; 3]; positions.len()]
+    } else {
+      raw_colors
+        .iter()
+        .map(|rgb| rgb.map(|c| c as f32 / range))
+        .collect()
+    };
+
+    Ok(scene_from_points(positions, colors, options))
+  }
+}
+
+// --- EXPORTER ---
+
+impl Exporter for LasExporter {
+  fn export(scene: &Scene, writer: &mut impl std::io::Write) -> Result<(), ConversionError> {
+    Self::export_with_options(scene, writer, &PointCloudExportOptions::default())
+  }
+}
+
+impl LasExporter {
+  pub fn export_with_options(
+    scene: &Scene,
+    writer: &mut impl std::io::Write,
+    options: &PointCloudExportOptions,
+  ) -> Result<(), ConversionError> {
+    let mut count = 0u64;
+    let mut min = [f64::INFINITY; 3];
+    let mut max = [f64::NEG_INFINITY; 3];
+    for splat in options.points(scene) {
+      count += 1;
+      for axis in 0..3 {
+        min[axis] = min[axis].min(splat.position[axis] as f64);
+        max[axis] = max[axis].max(splat.position[axis] as f64);
+      }
+    }
+    if count == 0 {
+      (min, max) = ([0.0; 3], [0.0; 3]);
+    }
+
+    // Coordinates are stored as integers relative to the minimum
+    let extent = (0..3).map(|axis| max[axis] - min[axis]).fold(0.0, f64::max);
+    let scale = (extent / i32::MAX as f64).max(MIN_SCALE);
+
+    let mut header = Vec::with_capacity(HEADER_SIZE as usize);
+    header.extend_from_slice(b"LASF");
+    header.extend_from_slice(&0u16.to_le_bytes()); // File source ID
+    header.extend_from_slice(&GLOBAL_ENCODING_WKT.to_le_bytes());
+    header.extend_from_slice(&[0; 16]); // Project ID (GUID)
+    header.extend_from_slice(&[1, 4]); // Version 1.4
+    header.extend_from_slice(&ascii_field::<32>("OTHER"));
+    header.extend_from_slice(&ascii_field::<32>("gs-flux"));
+    header.extend_from_slice(&0u16.to_le_bytes()); // Creation day of year
+    header.extend_from_slice(&0u16.to_le_bytes()); // Creation year
+    header.extend_from_slice(&HEADER_SIZE.to_le_bytes());
+    header.extend_from_slice(&(HEADER_SIZE as u32).to_le_bytes()); // Offset to point data
+    header.extend_from_slice(&0u32.to_le_bytes()); // Number of VLRs
+    header.push(POINT_FORMAT);
+    header.extend_from_slice(&POINT_RECORD_LENGTH.to_le_bytes());
+    // Legacy point counts must be 0 for point formats 6 and up
+    header.extend_from_slice(&[0; 4 + 5 * 4]);
+    for _ in 0..3 {
+      header.extend_from_slice(&scale.to_le_bytes());
+    }
+    for offset in min {
+      header.extend_from_slice(&offset.to_le_bytes());
+    }
+    for axis in 0..3 {
+      header.extend_from_slice(&max[axis].to_le_bytes());
+      header.extend_from_slice(&min[axis].to_le_bytes());
+    }
+    header.extend_from_slice(&0u64.to_le_bytes()); // Start of waveform data
+    header.extend_from_slice(&0u64.to_le_bytes()); // Start of first EVLR
+    header.extend_from_slice(&0u32.to_le_bytes()); // Number of EVLRs
+    header.extend_from_slice(&count.to_le_bytes());
+    // Points by return: every point is a single first return
+    header.extend_from_slice(&count.to_le_bytes());
+    header.extend_from_slice(&[0; 14 * 8]);
+    debug_assert_eq!(header.len(), HEADER_SIZE as usize);
+
+    writer
+      .write_all(&header)
+      .map_err(|e| write_error(e.to_string()))?;
+
+    let mut record = [0u8; POINT_RECORD_LENGTH as usize];
+    for splat in options.points(scene) {
+      for axis in 0..3 {
+        let value = ((splat.position[axis] as f64 - min[axis]) / scale).round() as i32;
+        record[axis * 4..axis * 4 + 4].copy_from_slice(&value.to_le_bytes());
+      }
+
+      let [r, g, b, alpha] = point_color(splat);
+      record[12..14].copy_from_slice(&(alpha as u16 * 257).to_le_bytes()); // Intensity
+      record[14] = 0x11; // Return 1 of 1
+      // Flags, classification, user data, scan angle, point source ID and GPS time stay 0
+      for (i, channel) in [r, g, b].into_iter().enumerate() {
+        record[30 + i * 2..32 + i * 2].copy_from_slice(&(channel as u16 * 257).to_le_bytes());
+      }
+
+      writer
+        .write_all(&record)
+        .map_err(|e| write_error(e.to_string()))?;
+    }
+
+    Ok(())
+  }
+}
@@ -0,0 +1,76 @@
+use crate::ConversionError;
+use crate::operations::mesh_extraction::TriangleMesh;
+
+/// Writes a `TriangleMesh` as Wavefront OBJ, with vertex colours (`v x y z r g b`).
+pub struct MeshObjExporter;
+/// Writes a `TriangleMesh` as binary PLY, with RGB vertex colours.
+pub struct MeshPlyExporter;
+
+fn to_byte(value: f32) -> u8 {
+  (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+impl MeshObjExporter {
+  pub fn export(
+    mesh: &TriangleMesh,
+    writer: &mut impl std::io::Write,
+  ) -> Result<(), ConversionError> {
+    let map_io_err = |e: std::io::Error| ConversionError::WriteError {
+      format: "OBJ".to_string(),
+      message: e.to_string(),
+    };
+
+    writeln!(writer, "o mesh").map_err(map_io_err)?;
+    for ([x, y, z], [r, g, b]) in mesh.vertices.iter().zip(&mesh.colors) {
+      writeln!(writer, "v {} {} {} {} {} {}", x, y, z, r, g, b).map_err(map_io_err)?;
+    }
+    // OBJ indices are 1-based
+    for [a, b, c] in &mesh.triangles {
+      writeln!(writer, "f {} {} {}", a + 1, b + 1, c + 1).map_err(map_io_err)?;
+    }
+
+    Ok(())
+  }
+}
+
+impl MeshPlyExporter {
+  pub fn export(
+    mesh: &TriangleMesh,
+    writer: &mut impl std::io::Write,
+  ) -> Result<(), ConversionError> {
+    let map_io_err = |e: std::io::Error| ConversionError::WriteError {
+      format: "PLY (Mesh)".to_string(),
+      message: e.to_string(),
+    };
+
+    // Write header
+    writeln!(writer, "ply").map_err(map_io_err)?;
+    writeln!(writer, "format binary_little_endian 1.0").map_err(map_io_err)?;
+    writeln!(writer, "element vertex {}", mesh.vertices.len()).map_err(map_io_err)?;
+    for axis in ["x", "y", "z"] {
+      writeln!(writer, "property float {}", axis).map_err(map_io_err)?;
+    }
+    for channel in ["red", "green", "blue"] {
+      writeln!(writer, "property uchar {}", channel).map_err(map_io_err)?;
+    }
+    writeln!(writer, "element face {}", mesh.triangles.len()).map_err(map_io_err)?;
+    writeln!(writer, "property list uchar uint vertex_indices").map_err(map_io_err)?;
+    writeln!(writer, "end_header").map_err(map_io_err)?;
+
+    // Write body
+    for (position, color) in mesh.vertices.iter().zip(&mesh.colors) {
+      for value in position {
+        writer.write_all(&value.to_le_bytes()).map_err(map_io_err)?;
+      }
+      writer.write_all(&color.map(to_byte)).map_err(map_io_err)?;
+    }
+    for triangle in &mesh.triangles {
+      writer.write_all(&[3]).map_err(map_io_err)?;
+      for index in triangle {
+        writer.write_all(&index.to_le_bytes()).map_err(map_io_err)?;
+      }
+    }
+
+    Ok(())
+  }
+}
@@ -1,4 +1,16 @@
+pub mod cameras_json;
+pub mod colmap;
+pub mod compressed_ply;
 pub mod csv;
+pub mod detect;
+pub mod ellipsoid;
+pub mod gltf;
+pub mod ksplat;
+pub mod las;
+pub mod mesh;
 pub mod ply;
+pub mod point_cloud;
+pub mod sog;
 pub mod splat;
 pub mod spz;
+pub mod usd;
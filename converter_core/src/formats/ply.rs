@@ -1,4 +1,10 @@
-use crate::{ConversionError, Exporter, GaussianSplat, Importer, Scene};
+use crate::formats::compressed_ply::{is_compressed_ply, scene_from_compressed_ply};
+use crate::formats::point_cloud::{
+  PointCloudImportOptions, is_point_cloud_ply, scene_from_point_cloud_ply,
+};
+use crate::{
+  ConversionError, Exporter, GaussianSplat, Importer, Scene, sh_coefficients_per_channel,
+};
 use ply_rs::parser::Parser;
 use ply_rs::ply;
 
@@ -6,28 +12,57 @@ pub struct PlyImporter;
 pub struct PlyASCIIExporter;
 pub struct PlyBinaryExporter;
 
-fn property_to_f32(prop: &ply::Property) -> f32 {
+/// Options for `PlyASCIIExporter::export_with_options` and `PlyBinaryExporter::export_with_options`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlyExportOptions {
+  /// SH degree to write (0-3), dropping higher bands. Defaults to 3, the 45 `f_rest_*` properties
+  /// of 3DGS training output, with zeros for the bands the scene doesn't have.
+  pub sh_degree: Option<u8>,
+}
+
+pub(crate) fn property_to_f32(prop: &ply::Property) -> f32 {
   match prop {
     ply::Property::Float(f) => *f,
     _ => 0.0, // fallback for unsupported property types
   }
 }
 
-fn get_spherical_harmonics_res(vert: &ply::DefaultElement) -> Vec<f32> {
+fn get_spherical_harmonics_res(vert: &ply::DefaultElement, count: usize) -> Vec<f32> {
   let base_key = "f_rest_";
-  let mut output: Vec<f32> = Vec::new();
+  let mut output: Vec<f32> = Vec::with_capacity(count);
 
-  for i in 0..45 {
+  for i in 0..count {
     let key = format!("{}{}", base_key, i);
-    let value = vert.get(&key).map_or(0.0, |prop| property_to_f32(prop));
+    let value = vert.get(&key).map_or(0.0, property_to_f32);
     output.push(value);
   }
 
   output
 }
 
+/// Number of `f_rest_*` properties written with `options`.
+fn sh_rest_count(options: &PlyExportOptions) -> usize {
+  3 * sh_coefficients_per_channel(options.sh_degree.unwrap_or(3).min(3))
+}
+
+/// The splat's `f_rest_*` values in PLY order, padded or truncated to `count` values.
+fn sh_rest_values(splat: &GaussianSplat, count: usize) -> impl Iterator<Item = f32> + '_ {
+  let per_channel = count / 3;
+  (0..3).flat_map(move |channel| (0..per_channel).map(move |i| splat.sh_rest(channel, i)))
+}
+
 impl Importer for PlyImporter {
   fn import(reader: &mut impl std::io::Read) -> Result<Scene, ConversionError> {
+    Self::import_with_options(reader, &PointCloudImportOptions::default())
+  }
+}
+
+impl PlyImporter {
+  /// Like `import`, with the options used when the file turns out to be a plain point cloud.
+  pub fn import_with_options(
+    reader: &mut impl std::io::Read,
+    point_cloud_options: &PointCloudImportOptions,
+  ) -> Result<Scene, ConversionError> {
     let parser = Parser::<ply::DefaultElement>::new();
     let ply = parser
       .read_ply(reader)
@@ -36,6 +71,16 @@ impl Importer for PlyImporter {
         message: e.to_string(),
       })?;
 
+    // PlayCanvas / SuperSplat files pack every splat into integers relative to chunk bounds
+    if is_compressed_ply(&ply) {
+      return scene_from_compressed_ply(&ply);
+    }
+
+    // Plain point clouds (e.g. from CloudCompare or MeshLab) get Gaussians initialised per point
+    if is_point_cloud_ply(&ply) {
+      return scene_from_point_cloud_ply(&ply, point_cloud_options);
+    }
+
     let verticies = ply
       .payload
       .get("vertex")
@@ -44,6 +89,15 @@ impl Importer for PlyImporter {
         message: "Missing \"vertex\" element in PLY file".to_string(),
       })?;
 
+    // 3DGS files with a lower SH degree simply have fewer `f_rest_*` properties
+    let sh_rest_len = ply.header.elements.get("vertex").map_or(0, |element| {
+      element
+        .properties
+        .keys()
+        .filter(|key| key.starts_with("f_rest_"))
+        .count()
+    });
+
     let mut splats: Vec<GaussianSplat> = Vec::with_capacity(verticies.len());
 
     for vert in verticies {
@@ -63,7 +117,7 @@ impl Importer for PlyImporter {
           property_to_f32(&vert["f_dc_1"]),
           property_to_f32(&vert["f_dc_2"]),
         ],
-        spherical_harmonics_rest: get_spherical_harmonics_res(vert),
+        spherical_harmonics_rest: get_spherical_harmonics_res(vert, sh_rest_len),
         opacity: property_to_f32(&vert["opacity"]),
         scale: [
           property_to_f32(&vert["scale_0"]),
@@ -80,12 +134,25 @@ impl Importer for PlyImporter {
       splats.push(splat);
     }
 
-    Ok(Scene { splats })
+    Ok(Scene {
+      splats,
+      cameras: Vec::new(),
+    })
   }
 }
 
 impl Exporter for PlyASCIIExporter {
   fn export(scene: &Scene, writer: &mut impl std::io::Write) -> Result<(), ConversionError> {
+    Self::export_with_options(scene, writer, &PlyExportOptions::default())
+  }
+}
+
+impl PlyASCIIExporter {
+  pub fn export_with_options(
+    scene: &Scene,
+    writer: &mut impl std::io::Write,
+    options: &PlyExportOptions,
+  ) -> Result<(), ConversionError> {
     // A helper closure to map I/O errors to custom ConversionError type.
     let map_io_err = |e: std::io::Error| ConversionError::WriteError {
       format: "PLY".to_string(),
@@ -105,7 +172,8 @@ impl Exporter for PlyASCIIExporter {
     writeln!(writer, "property float f_dc_0").map_err(map_io_err)?;
     writeln!(writer, "property float f_dc_1").map_err(map_io_err)?;
     writeln!(writer, "property float f_dc_2").map_err(map_io_err)?;
-    for i in 0..45 {
+    let sh_rest_len = sh_rest_count(options);
+    for i in 0..sh_rest_len {
       writeln!(writer, "property float f_rest_{}", i).map_err(map_io_err)?;
     }
     writeln!(writer, "property float opacity").map_err(map_io_err)?;
@@ -137,7 +205,7 @@ impl Exporter for PlyASCIIExporter {
       .map_err(map_io_err)?;
 
       // Rest of Spherical Harmonics
-      for val in &splat.spherical_harmonics_rest {
+      for val in sh_rest_values(splat, sh_rest_len) {
         write!(writer, "{} ", val).map_err(map_io_err)?;
       }
 
@@ -163,6 +231,16 @@ impl Exporter for PlyASCIIExporter {
 
 impl Exporter for PlyBinaryExporter {
   fn export(scene: &Scene, writer: &mut impl std::io::Write) -> Result<(), ConversionError> {
+    Self::export_with_options(scene, writer, &PlyExportOptions::default())
+  }
+}
+
+impl PlyBinaryExporter {
+  pub fn export_with_options(
+    scene: &Scene,
+    writer: &mut impl std::io::Write,
+    options: &PlyExportOptions,
+  ) -> Result<(), ConversionError> {
     let map_io_err = |e: std::io::Error| ConversionError::WriteError {
       format: "PLY (Binary)".to_string(),
       message: e.to_string(),
@@ -181,7 +259,8 @@ impl Exporter for PlyBinaryExporter {
     writeln!(writer, "property float f_dc_0").map_err(map_io_err)?;
     writeln!(writer, "property float f_dc_1").map_err(map_io_err)?;
     writeln!(writer, "property float f_dc_2").map_err(map_io_err)?;
-    for i in 0..45 {
+    let sh_rest_len = sh_rest_count(options);
+    for i in 0..sh_rest_len {
       writeln!(writer, "property float f_rest_{}", i).map_err(map_io_err)?;
     }
     writeln!(writer, "property float opacity").map_err(map_io_err)?;
@@ -200,7 +279,9 @@ impl Exporter for PlyBinaryExporter {
       Self::write_f32_slice(writer, &splat.position).map_err(map_io_err)?;
       Self::write_f32_slice(writer, &splat.normal).map_err(map_io_err)?;
       Self::write_f32_slice(writer, &splat.spherical_harmonics_dc).map_err(map_io_err)?;
-      Self::write_f32_slice(writer, &splat.spherical_harmonics_rest).map_err(map_io_err)?;
+      for val in sh_rest_values(splat, sh_rest_len) {
+        writer.write_all(&val.to_le_bytes()).map_err(map_io_err)?;
+      }
       writer
         .write_all(&splat.opacity.to_le_bytes())
         .map_err(map_io_err)?;
@@ -210,9 +291,7 @@ impl Exporter for PlyBinaryExporter {
 
     Ok(())
   }
-}
 
-impl PlyBinaryExporter {
   /// Helper function to write a slice of f32 values as little-endian bytes.
   /// This function is generic over the `Write` trait.
   fn write_f32_slice<W: std::io::Write>(writer: &mut W, data: &[f32]) -> std::io::Result<()> {
@@ -0,0 +1,219 @@
+use ply_rs::parser::Parser;
+use ply_rs::ply;
+
+use crate::helpers::activation::{inverse_sigmoid, sigmoid};
+use crate::helpers::nearest_neighbours::mean_squared_neighbour_distances;
+use crate::helpers::spherical_harmonics::SH_C0;
+use crate::{ConversionError, Exporter, GaussianSplat, Importer, Scene};
+
+/// Reads a plain (optionally coloured) PLY point cloud and initialises a Gaussian per point.
+pub struct PointCloudPlyImporter;
+/// Writes the splat centres as a plain coloured point cloud (`x y z red green blue alpha`),
+/// which point-cloud tools like CloudCompare and MeshLab display directly.
+pub struct PointCloudPlyExporter;
+
+/// Number of neighbours whose mean distance sets the initial size of a Gaussian, as in 3DGS.
+const NEIGHBOURS: usize = 3;
+/// Lower bound for the squared neighbour distance, so that duplicate points don't get a zero scale.
+const MIN_SQUARED_DISTANCE: f32 = 1e-7;
+
+/// Options for the importers that build Gaussians from point clouds (PLY, LAS and COLMAP).
+#[derive(Debug, Clone)]
+pub struct PointCloudImportOptions {
+  /// Opacity (0..1) given to every Gaussian.
+  pub initial_opacity: f32,
+}
+
+impl Default for PointCloudImportOptions {
+  fn default() -> Self {
+    Self {
+      initial_opacity: 0.1,
+    }
+  }
+}
+
+/// Initialises a scene the way 3DGS does from a sparse reconstruction: the colour becomes the SH
+/// DC term, the scale is isotropic with the mean distance to the nearest neighbours, and rotations
+/// are the identity. `colors` are linear 0..1 RGB, one per position.
+pub(crate) fn scene_from_points(
+  positions: Vec<[f32; 3]>,
+  colors: Vec<[f32; 3]>,
+  options: &PointCloudImportOptions,
+) -> Scene {
+  let distances = mean_squared_neighbour_distances(&positions, NEIGHBOURS);
+  let opacity = inverse_sigmoid(options.initial_opacity);
+
+  let splats = positions
+    .into_iter()
+    .zip(colors)
+    .zip(distances)
+    .map(|((position, color), distance)| {
+      let scale = distance.max(MIN_SQUARED_DISTANCE).sqrt().ln();
+      GaussianSplat {
+        position,
+        normal: [0.0, 0.0, 0.0],
+        spherical_harmonics_dc: color.map(|c| (c - 0.5) / SH_C0),
+        spherical_harmonics_rest: Vec::new(),
+        opacity,
+        scale: [scale; 3],
+        rotation: [1.0, 0.0, 0.0, 0.0],
+      }
+    })
+    .collect();
+
+  Scene {
+    splats,
+    cameras: Vec::new(),
+  }
+}
+
+fn property_to_f64(prop: &ply::Property) -> Option<f64> {
+  match prop {
+    ply::Property::Char(v) => Some(*v as f64),
+    ply::Property::UChar(v) => Some(*v as f64),
+    ply::Property::Short(v) => Some(*v as f64),
+    ply::Property::UShort(v) => Some(*v as f64),
+    ply::Property::Int(v) => Some(*v as f64),
+    ply::Property::UInt(v) => Some(*v as f64),
+    ply::Property::Float(v) => Some(*v as f64),
+    ply::Property::Double(v) => Some(*v),
+    _ => None,
+  }
+}
+
+/// Whether a parsed PLY is a plain point cloud rather than a Gaussian splat file.
+pub(crate) fn is_point_cloud_ply(ply: &ply::Ply<ply::DefaultElement>) -> bool {
+  ply.header.elements.get("vertex").is_some_and(|vertex| {
+    ["x", "y", "z"]
+      .iter()
+      .all(|key| vertex.properties.contains_key(*key))
+      && !vertex.properties.contains_key("opacity")
+      && !vertex.properties.contains_key("f_dc_0")
+  })
+}
+
+/// Builds Gaussians from an already parsed point-cloud PLY, shared with `PlyImporter` which
+/// delegates here. Integer colours are taken as 0..255 and floating point colours as 0..1.
+pub(crate) fn scene_from_point_cloud_ply(
+  ply: &ply::Ply<ply::DefaultElement>,
+  options: &PointCloudImportOptions,
+) -> Result<Scene, ConversionError> {
+  let vertices = ply
+    .payload
+    .get("vertex")
+    .ok_or_else(|| ConversionError::ParseError {
+      format: "PLY (Point Cloud)".to_string(),
+      message: "Missing \"vertex\" element in PLY file".to_string(),
+    })?;
+
+  let get = |vertex: &ply::DefaultElement, key: &str| vertex.get(key).and_then(property_to_f64);
+  let color = |vertex: &ply::DefaultElement| {
+    ["red", "green", "blue"].map(|key| match vertex.get(key) {
+      Some(ply::Property::Float(v)) => *v,
+      Some(ply::Property::Double(v)) => *v as f32,
+      Some(prop) => property_to_f64(prop).map_or(0.5, |v| (v / 255.0) as f32),
+      None => 0.5,
+    })
+  };
+
+  let positions = vertices
+    .iter()
+    .map(|vertex| ["x", "y", "z"].map(|key| get(vertex, key).unwrap_or(0.0) as f32))
+    .collect();
+  let colors = vertices.iter().map(color).collect();
+
+  Ok(scene_from_points(positions, colors, options))
+}
+
+impl Importer for PointCloudPlyImporter {
+  fn import(reader: &mut impl std::io::Read) -> Result<Scene, ConversionError> {
+    Self::import_with_options(reader, &PointCloudImportOptions::default())
+  }
+}
+
+impl PointCloudPlyImporter {
+  pub fn import_with_options(
+    reader: &mut impl std::io::Read,
+    options: &PointCloudImportOptions,
+  ) -> Result<Scene, ConversionError> {
+    let parser = Parser::<ply::DefaultElement>::new();
+    let ply = parser
+      .read_ply(reader)
+      .map_err(|e| ConversionError::ParseError {
+        format: "PLY (Point Cloud)".to_string(),
+        message: e.to_string(),
+      })?;
+
+    scene_from_point_cloud_ply(&ply, options)
+  }
+}
+
+/// Options shared by the point-cloud exporters (PLY and LAS).
+#[derive(Debug, Clone, Default)]
+pub struct PointCloudExportOptions {
+  /// Skip splats whose opacity (0..1) is below this value.
+  pub min_opacity: Option<f32>,
+}
+
+impl PointCloudExportOptions {
+  /// The splats to write as points, in scene order.
+  pub(crate) fn points<'a>(&self, scene: &'a Scene) -> impl Iterator<Item = &'a GaussianSplat> {
+    let min_opacity = self.min_opacity;
+    scene
+      .splats
+      .iter()
+      .filter(move |splat| min_opacity.is_none_or(|min| sigmoid(splat.opacity) >= min))
+  }
+}
+
+/// The base colour of a splat as sRGB bytes, with the opacity as alpha.
+pub(crate) fn point_color(splat: &GaussianSplat) -> [u8; 4] {
+  let to_byte = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+  let [r, g, b] = splat
+    .spherical_harmonics_dc
+    .map(|dc| to_byte(0.5 + SH_C0 * dc));
+  [r, g, b, to_byte(sigmoid(splat.opacity))]
+}
+
+impl Exporter for PointCloudPlyExporter {
+  fn export(scene: &Scene, writer: &mut impl std::io::Write) -> Result<(), ConversionError> {
+    Self::export_with_options(scene, writer, &PointCloudExportOptions::default())
+  }
+}
+
+impl PointCloudPlyExporter {
+  pub fn export_with_options(
+    scene: &Scene,
+    writer: &mut impl std::io::Write,
+    options: &PointCloudExportOptions,
+  ) -> Result<(), ConversionError> {
+    let map_io_err = |e: std::io::Error| ConversionError::WriteError {
+      format: "PLY (Point Cloud)".to_string(),
+      message: e.to_string(),
+    };
+
+    let count = options.points(scene).count();
+
+    // Write header
+    writeln!(writer, "ply").map_err(map_io_err)?;
+    writeln!(writer, "format binary_little_endian 1.0").map_err(map_io_err)?;
+    writeln!(writer, "element vertex {}", count).map_err(map_io_err)?;
+    for axis in ["x", "y", "z"] {
+      writeln!(writer, "property float {}", axis).map_err(map_io_err)?;
+    }
+    for channel in ["red", "green", "blue", "alpha"] {
+      writeln!(writer, "property uchar {}", channel).map_err(map_io_err)?;
+    }
+    writeln!(writer, "end_header").map_err(map_io_err)?;
+
+    // Write body
+    for splat in options.points(scene) {
+      for value in splat.position {
+        writer.write_all(&value.to_le_bytes()).map_err(map_io_err)?;
+      }
+      writer.write_all(&point_color(splat)).map_err(map_io_err)?;
+    }
+
+    Ok(())
+  }
+}
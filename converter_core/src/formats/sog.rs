@@ -0,0 +1,529 @@
+use std::io::{Cursor, Read, Write};
+
+use image::codecs::webp::WebPEncoder;
+use image::{ExtendedColorType, ImageFormat};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::helpers::activation::{inverse_sigmoid, sigmoid};
+use crate::helpers::kmeans::{kmeans, kmeans_1d, nearest_in_codebook};
+use crate::helpers::morton_order::morton_order;
+use crate::{
+  ConversionError, Exporter, GaussianSplat, Importer, Scene, sh_coefficients_per_channel,
+};
+
+/// Reads and writes PlayCanvas SOG (version 2): splat attributes are quantized into lossless
+/// WebP textures described by a `meta.json`, and bundled together in a single zip archive.
+/// Scales and colours go through 256-entry codebooks, and higher order SH through a k-means palette.
+pub struct SogImporter;
+pub struct SogExporter;
+
+const META_FILE: &str = "meta.json";
+const CODEBOOK_SIZE: usize = 256;
+/// Number of SH palette entries laid out per row of `shN_centroids.webp`.
+const CENTROIDS_PER_ROW: usize = 64;
+/// Upper bound for the default SH palette, which keeps k-means tractable on the CPU.
+const DEFAULT_MAX_PALETTE_SIZE: usize = 4096;
+
+/// Options specific to the SOG export.
+#[derive(Debug, Clone)]
+pub struct SogExportOptions {
+  /// Number of SH palette entries. Defaults to the splat count rounded down to a power of two,
+  /// kept between 1024 and 4096.
+  pub sh_palette_size: Option<usize>,
+  /// Number of k-means iterations used for the codebooks and the SH palette.
+  pub iterations: usize,
+}
+
+impl Default for SogExportOptions {
+  fn default() -> Self {
+    Self {
+      sh_palette_size: None,
+      iterations: 10,
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SogMeta {
+  version: u32,
+  count: usize,
+  means: MeansMeta,
+  scales: CodebookMeta,
+  quats: FilesMeta,
+  sh0: CodebookMeta,
+  #[serde(rename = "shN", default, skip_serializing_if = "Option::is_none")]
+  sh_n: Option<ShNMeta>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MeansMeta {
+  mins: [f32; 3],
+  maxs: [f32; 3],
+  files: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CodebookMeta {
+  codebook: Vec<f32>,
+  files: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FilesMeta {
+  files: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ShNMeta {
+  count: usize,
+  bands: u8,
+  codebook: Vec<f32>,
+  files: Vec<String>,
+}
+
+fn parse_error(message: impl Into<String>) -> ConversionError {
+  ConversionError::ParseError {
+    format: "SOG".to_string(),
+    message: message.into(),
+  }
+}
+
+fn write_error(message: impl Into<String>) -> ConversionError {
+  ConversionError::WriteError {
+    format: "SOG".to_string(),
+    message: message.into(),
+  }
+}
+
+/// Texture dimensions for `count` splats: roughly square, with both sides a multiple of 4.
+fn texture_size(count: usize) -> (usize, usize) {
+  let width = ((count as f64).sqrt() / 4.0).ceil().max(1.0) as usize * 4;
+  let height = count.div_ceil(width).div_ceil(4).max(1) * 4;
+  (width, height)
+}
+
+/// Positions are stored log-transformed so that dense centres keep more precision than outliers.
+fn log_transform(value: f32) -> f32 {
+  value.signum() * (value.abs() + 1.0).ln()
+}
+
+fn inverse_log_transform(value: f32) -> f32 {
+  value.signum() * (value.abs().exp() - 1.0)
+}
+
+fn unit_to_byte(value: f32) -> u8 {
+  (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn codebook_index(codebook: &[f32], value: f32) -> u8 {
+  nearest_in_codebook(codebook, value) as u8
+}
+
+/// Packs the three smallest components of a [w, x, y, z] quaternion into RGB, with the index of
+/// the largest (made positive and recovered from the unit norm) stored as `252 + index` in alpha.
+fn pack_rotation(rotation: [f32; 4]) -> [u8; 4] {
+  let len = rotation.iter().map(|v| v * v).sum::<f32>().sqrt();
+  let mut q = if len > 0.0 {
+    rotation.map(|v| v / len)
+  } else {
+    [1.0, 0.0, 0.0, 0.0]
+  };
+
+  let largest = (0..4)
+    .max_by(|&a, &b| q[a].abs().total_cmp(&q[b].abs()))
+    .unwrap_or(0);
+  if q[largest] < 0.0 {
+    q = q.map(|v| -v);
+  }
+
+  let mut pixel = [0, 0, 0, 252 + largest as u8];
+  for (target, i) in pixel.iter_mut().zip((0..4).filter(|&i| i != largest)) {
+    *target = unit_to_byte(q[i] * std::f32::consts::FRAC_1_SQRT_2 + 0.5);
+  }
+  pixel
+}
+
+fn unpack_rotation(pixel: &[u8]) -> [f32; 4] {
+  let largest = pixel[3].saturating_sub(252).min(3) as usize;
+  let components = [0, 1, 2].map(|i| (pixel[i] as f32 / 255.0 - 0.5) * std::f32::consts::SQRT_2);
+  let norm = components.iter().map(|v| v * v).sum::<f32>();
+
+  let mut q = [0.0; 4];
+  q[largest] = (1.0 - norm).max(0.0).sqrt();
+  for (i, value) in (0..4).filter(|&i| i != largest).zip(components) {
+    q[i] = value;
+  }
+  q
+}
+
+fn encode_webp(pixels: &[u8], width: usize, height: usize) -> Result<Vec<u8>, ConversionError> {
+  let mut encoded = Vec::new();
+  WebPEncoder::new_lossless(&mut encoded)
+    .encode(
+      pixels,
+      width as u32,
+      height as u32,
+      ExtendedColorType::Rgba8,
+    )
+    .map_err(|e| write_error(e.to_string()))?;
+  Ok(encoded)
+}
+
+/// A decoded RGBA texture.
+struct Texture {
+  width: usize,
+  pixels: Vec<u8>,
+}
+
+impl Texture {
+  fn pixel(&self, index: usize) -> Result<&[u8], ConversionError> {
+    self
+      .pixels
+      .get(index * 4..index * 4 + 4)
+      .ok_or_else(|| parse_error("Texture is smaller than the splat count"))
+  }
+}
+
+fn read_texture(
+  archive: &mut ZipArchive<Cursor<Vec<u8>>>,
+  files: &[String],
+) -> Result<Texture, ConversionError> {
+  let name = files
+    .first()
+    .ok_or_else(|| parse_error("Missing texture file name in meta.json"))?;
+  read_named_texture(archive, name)
+}
+
+fn read_named_texture(
+  archive: &mut ZipArchive<Cursor<Vec<u8>>>,
+  name: &str,
+) -> Result<Texture, ConversionError> {
+  let mut data = Vec::new();
+  archive
+    .by_name(name)
+    .map_err(|e| parse_error(format!("{}: {}", name, e)))?
+    .read_to_end(&mut data)?;
+
+  let image = image::load_from_memory_with_format(&data, ImageFormat::WebP)
+    .map_err(|e| parse_error(format!("{}: {}", name, e)))?
+    .into_rgba8();
+
+  Ok(Texture {
+    width: image.width() as usize,
+    pixels: image.into_raw(),
+  })
+}
+
+// --- IMPORTER ---
+
+impl Importer for SogImporter {
+  fn import(reader: &mut impl Read) -> Result<Scene, ConversionError> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    let mut archive = ZipArchive::new(Cursor::new(data)).map_err(|e| parse_error(e.to_string()))?;
+
+    let meta: SogMeta = {
+      let file = archive
+        .by_name(META_FILE)
+        .map_err(|e| parse_error(format!("{}: {}", META_FILE, e)))?;
+      serde_json::from_reader(file).map_err(|e| parse_error(e.to_string()))?
+    };
+    if meta.version != 2 {
+      return Err(parse_error(format!(
+        "Unsupported SOG version {}",
+        meta.version
+      )));
+    }
+    if meta.means.files.len() < 2 {
+      return Err(parse_error("Expected low and high byte position textures"));
+    }
+
+    let means_low = read_named_texture(&mut archive, &meta.means.files[0])?;
+    // meta.json is untrusted, so its count must fit the textures before splats are reserved for it
+    if meta.count > means_low.pixels.len() / 4 {
+      return Err(parse_error(format!(
+        "meta.json counts {} splats, but the position textures hold {}",
+        meta.count,
+        means_low.pixels.len() / 4
+      )));
+    }
+    let means_high = read_named_texture(&mut archive, &meta.means.files[1])?;
+    let scales = read_texture(&mut archive, &meta.scales.files)?;
+    let quats = read_texture(&mut archive, &meta.quats.files)?;
+    let sh0 = read_texture(&mut archive, &meta.sh0.files)?;
+
+    let sh_n = match &meta.sh_n {
+      Some(sh_n) if sh_n.bands > 3 => {
+        return Err(parse_error(format!(
+          "meta.json has {} SH bands, at most 3 are supported",
+          sh_n.bands
+        )));
+      }
+      Some(sh_n) if sh_n.files.len() >= 2 => Some((
+        sh_n,
+        read_named_texture(&mut archive, &sh_n.files[0])?,
+        read_named_texture(&mut archive, &sh_n.files[1])?,
+      )),
+      Some(_) => return Err(parse_error("Expected SH centroid and label textures")),
+      None => None,
+    };
+
+    let lookup = |codebook: &[f32], index: u8| codebook.get(index as usize).copied().unwrap_or(0.0);
+
+    let mut splats = Vec::with_capacity(meta.count);
+    for i in 0..meta.count {
+      let (low, high) = (means_low.pixel(i)?, means_high.pixel(i)?);
+      let position = [0, 1, 2].map(|axis| {
+        let quantized = (low[axis] as u32 | (high[axis] as u32) << 8) as f32 / 65535.0;
+        let (min, max) = (meta.means.mins[axis], meta.means.maxs[axis]);
+        inverse_log_transform(min + (max - min) * quantized)
+      });
+
+      let scale_pixel = scales.pixel(i)?;
+      let color_pixel = sh0.pixel(i)?;
+
+      let spherical_harmonics_rest = match &sh_n {
+        Some((meta, centroids, labels)) => {
+          let per_channel = sh_coefficients_per_channel(meta.bands);
+          let label_pixel = labels.pixel(i)?;
+          let label = label_pixel[0] as usize | (label_pixel[1] as usize) << 8;
+          let origin = (label / CENTROIDS_PER_ROW) * centroids.width
+            + (label % CENTROIDS_PER_ROW) * per_channel;
+
+          let mut rest = vec![0.0; 3 * per_channel];
+          for coefficient in 0..per_channel {
+            let pixel = centroids.pixel(origin + coefficient)?;
+            for channel in 0..3 {
+              rest[channel * per_channel + coefficient] = lookup(&meta.codebook, pixel[channel]);
+            }
+          }
+          rest
+        }
+        None => Vec::new(),
+      };
+
+      splats.push(GaussianSplat {
+        position,
+        normal: [0.0, 0.0, 0.0],
+        spherical_harmonics_dc: [0, 1, 2].map(|c| lookup(&meta.sh0.codebook, color_pixel[c])),
+        spherical_harmonics_rest,
+        opacity: inverse_sigmoid(color_pixel[3] as f32 / 255.0),
+        scale: [0, 1, 2].map(|axis| lookup(&meta.scales.codebook, scale_pixel[axis])),
+        rotation: unpack_rotation(quats.pixel(i)?),
+      });
+    }
+
+    Ok(Scene {
+      splats,
+      cameras: Vec::new(),
+    })
+  }
+}
+
+// --- EXPORTER ---
+
+impl Exporter for SogExporter {
+  fn export(scene: &Scene, writer: &mut impl Write) -> Result<(), ConversionError> {
+    Self::export_with_options(scene, writer, &SogExportOptions::default())
+  }
+}
+
+impl SogExporter {
+  pub fn export_with_options(
+    scene: &Scene,
+    writer: &mut impl Write,
+    options: &SogExportOptions,
+  ) -> Result<(), ConversionError> {
+    // Textures compress better when neighbouring pixels hold neighbouring splats
+    let order = morton_order(scene);
+    let splats: Vec<&GaussianSplat> = order.iter().map(|&i| &scene.splats[i]).collect();
+    let count = splats.len();
+    let (width, height) = texture_size(count);
+    let new_texture = || vec![0u8; width * height * 4];
+
+    // Positions
+    let transformed: Vec<[f32; 3]> = splats
+      .iter()
+      .map(|s| s.position.map(log_transform))
+      .collect();
+    let mut mins = [f32::INFINITY; 3];
+    let mut maxs = [f32::NEG_INFINITY; 3];
+    for position in &transformed {
+      for axis in 0..3 {
+        mins[axis] = mins[axis].min(position[axis]);
+        maxs[axis] = maxs[axis].max(position[axis]);
+      }
+    }
+    if count == 0 {
+      (mins, maxs) = ([0.0; 3], [0.0; 3]);
+    }
+
+    let mut means_low = new_texture();
+    let mut means_high = new_texture();
+    for (i, position) in transformed.iter().enumerate() {
+      for axis in 0..3 {
+        let range = maxs[axis] - mins[axis];
+        let t = if range > 0.0 {
+          (position[axis] - mins[axis]) / range
+        } else {
+          0.0
+        };
+        let quantized = (t.clamp(0.0, 1.0) * 65535.0).round() as u16;
+        means_low[i * 4 + axis] = (quantized & 0xff) as u8;
+        means_high[i * 4 + axis] = (quantized >> 8) as u8;
+      }
+      means_low[i * 4 + 3] = 255;
+      means_high[i * 4 + 3] = 255;
+    }
+
+    // Scales, colours and opacity
+    let scale_values: Vec<f32> = splats.iter().flat_map(|s| s.scale).collect();
+    let scale_codebook = kmeans_1d(&scale_values, CODEBOOK_SIZE, options.iterations);
+    let color_values: Vec<f32> = splats
+      .iter()
+      .flat_map(|s| s.spherical_harmonics_dc)
+      .collect();
+    let color_codebook = kmeans_1d(&color_values, CODEBOOK_SIZE, options.iterations);
+
+    let mut scales = new_texture();
+    let mut quats = new_texture();
+    let mut sh0 = new_texture();
+    for (i, splat) in splats.iter().enumerate() {
+      let pixel = i * 4..i * 4 + 4;
+      for axis in 0..3 {
+        scales[i * 4 + axis] = codebook_index(&scale_codebook, splat.scale[axis]);
+        sh0[i * 4 + axis] = codebook_index(&color_codebook, splat.spherical_harmonics_dc[axis]);
+      }
+      scales[i * 4 + 3] = 255;
+      sh0[i * 4 + 3] = unit_to_byte(sigmoid(splat.opacity));
+      quats[pixel].copy_from_slice(&pack_rotation(splat.rotation));
+    }
+
+    let mut files = vec![
+      ("means_l.webp", encode_webp(&means_low, width, height)?),
+      ("means_u.webp", encode_webp(&means_high, width, height)?),
+      ("scales.webp", encode_webp(&scales, width, height)?),
+      ("quats.webp", encode_webp(&quats, width, height)?),
+      ("sh0.webp", encode_webp(&sh0, width, height)?),
+    ];
+
+    // Higher order SH share a palette of k-means centroids, referenced by a 16-bit label
+    let sh_degree = scene.sh_degree();
+    let sh_n = if sh_degree > 0 && count > 0 {
+      let per_channel = sh_coefficients_per_channel(sh_degree);
+      let dim = 3 * per_channel;
+      let points: Vec<f32> = splats
+        .par_iter()
+        .flat_map_iter(|s| (0..3).flat_map(move |c| (0..per_channel).map(move |i| s.sh_rest(c, i))))
+        .collect();
+
+      let palette_size = options
+        .sh_palette_size
+        .unwrap_or_else(|| {
+          let blocks = (count / 1024).max(1);
+          ((1 << blocks.ilog2()) * 1024).min(DEFAULT_MAX_PALETTE_SIZE)
+        })
+        .clamp(1, 1 << 16);
+      let (centroids, labels) = kmeans(&points, dim, palette_size, options.iterations);
+      let palette_count = centroids.len() / dim;
+      let sh_codebook = kmeans_1d(&centroids, CODEBOOK_SIZE, options.iterations);
+
+      let centroids_width = CENTROIDS_PER_ROW * per_channel;
+      let centroids_height = palette_count.div_ceil(CENTROIDS_PER_ROW).max(1);
+      let mut centroid_pixels = vec![0u8; centroids_width * centroids_height * 4];
+      for (entry, centroid) in centroids.chunks_exact(dim).enumerate() {
+        let origin =
+          (entry / CENTROIDS_PER_ROW) * centroids_width + (entry % CENTROIDS_PER_ROW) * per_channel;
+        for coefficient in 0..per_channel {
+          let pixel = (origin + coefficient) * 4;
+          for channel in 0..3 {
+            centroid_pixels[pixel + channel] =
+              codebook_index(&sh_codebook, centroid[channel * per_channel + coefficient]);
+          }
+          centroid_pixels[pixel + 3] = 255;
+        }
+      }
+
+      let mut label_pixels = new_texture();
+      for (i, label) in labels.iter().enumerate() {
+        label_pixels[i * 4] = (label & 0xff) as u8;
+        label_pixels[i * 4 + 1] = (label >> 8) as u8;
+        label_pixels[i * 4 + 3] = 255;
+      }
+
+      files.push((
+        "shN_centroids.webp",
+        encode_webp(&centroid_pixels, centroids_width, centroids_height)?,
+      ));
+      files.push((
+        "shN_labels.webp",
+        encode_webp(&label_pixels, width, height)?,
+      ));
+
+      Some(ShNMeta {
+        count: palette_count,
+        bands: sh_degree,
+        codebook: sh_codebook,
+        files: vec![
+          "shN_centroids.webp".to_string(),
+          "shN_labels.webp".to_string(),
+        ],
+      })
+    } else {
+      None
+    };
+
+    let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+    let meta = SogMeta {
+      version: 2,
+      count,
+      means: MeansMeta {
+        mins,
+        maxs,
+        files: names(&["means_l.webp", "means_u.webp"]),
+      },
+      scales: CodebookMeta {
+        codebook: scale_codebook,
+        files: names(&["scales.webp"]),
+      },
+      quats: FilesMeta {
+        files: names(&["quats.webp"]),
+      },
+      sh0: CodebookMeta {
+        codebook: color_codebook,
+        files: names(&["sh0.webp"]),
+      },
+      sh_n,
+    };
+    let meta_json = serde_json::to_vec(&meta).map_err(|e| write_error(e.to_string()))?;
+
+    // The zip writer needs to seek, so build the archive in memory
+    let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+    let map_zip_err = |e: zip::result::ZipError| write_error(e.to_string());
+    archive
+      .start_file(
+        META_FILE,
+        SimpleFileOptions::default().compression_method(CompressionMethod::Deflated),
+      )
+      .map_err(map_zip_err)?;
+    archive.write_all(&meta_json)?;
+    for (name, data) in &files {
+      // WebP data is already compressed
+      archive
+        .start_file(
+          *name,
+          SimpleFileOptions::default().compression_method(CompressionMethod::Stored),
+        )
+        .map_err(map_zip_err)?;
+      archive.write_all(data)?;
+    }
+    let bundle = archive.finish().map_err(map_zip_err)?.into_inner();
+
+    writer
+      .write_all(&bundle)
+      .map_err(|e| write_error(e.to_string()))
+  }
+}
@@ -1,3 +1,5 @@
+use crate::helpers::activation::sigmoid;
+use crate::helpers::spherical_harmonics::SH_C0;
 use crate::{ConversionError, Exporter, GaussianSplat, Importer, Scene};
 use std::convert::TryInto;
 
@@ -5,7 +7,26 @@ pub struct SplatImporter;
 pub struct SplatExporter;
 
 const SPLAT_SIZE_BYTES: usize = 32;
-const SH_C0: f32 = 0.28209479177387814;
+
+/// The order in which `SplatExporter` writes splats.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SplatOrder {
+  /// Keep the order of the input scene.
+  #[default]
+  Input,
+  /// Most important splats first, as sorted by antimatter15's `convert.py`:
+  /// `exp(scale_0 + scale_1 + scale_2) * sigmoid(opacity)`, descending.
+  /// Progressive web viewers can then show a usable scene before the file has finished loading.
+  Importance,
+}
+
+/// Options for `SplatExporter::export_with_options`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SplatExportOptions {
+  pub order: SplatOrder,
+  /// Splats with an activated opacity (`0..1`) below this value are not written.
+  pub min_opacity: Option<f32>,
+}
 
 /// Helper function to read a little-endian f32 from a slice.
 fn read_f32_le(slice: &[u8]) -> f32 {
@@ -64,26 +85,24 @@ impl Importer for SplatImporter {
       let normalized_opacity = (color_rgba[3] as f32 / 255.0).clamp(1e-6, 1.0 - 1e-6);
       let opacity = (normalized_opacity / (1.0 - normalized_opacity)).ln();
 
-      // Rotation (Bytes 28-31) - u8 to normalized quaternion f32
+      // Rotation (Bytes 28-31) - u8 to normalized quaternion f32, stored as [w, x, y, z]
       let rot_u8 = &chunk[28..32];
-      let mut rot_f32 = [
-        (rot_u8[0] as f32 / 255.0) * 2.0 - 1.0, // x
-        (rot_u8[1] as f32 / 255.0) * 2.0 - 1.0, // y
-        (rot_u8[2] as f32 / 255.0) * 2.0 - 1.0, // z
-        (rot_u8[3] as f32 / 255.0) * 2.0 - 1.0, // w
+      let mut rotation = [
+        (rot_u8[0] as f32 - 128.0) / 128.0, // w
+        (rot_u8[1] as f32 - 128.0) / 128.0, // x
+        (rot_u8[2] as f32 - 128.0) / 128.0, // y
+        (rot_u8[3] as f32 - 128.0) / 128.0, // z
       ];
 
       let len =
-        (rot_f32[0].powi(2) + rot_f32[1].powi(2) + rot_f32[2].powi(2) + rot_f32[3].powi(2)).sqrt();
+        (rotation[0].powi(2) + rotation[1].powi(2) + rotation[2].powi(2) + rotation[3].powi(2))
+          .sqrt();
       if len > 0.0 {
-        for v in &mut rot_f32 {
+        for v in &mut rotation {
           *v /= len;
         }
       }
 
-      // Re-order from [x, y, z, w] to our struct's [w, x, y, z]
-      let rotation = [rot_f32[3], rot_f32[0], rot_f32[1], rot_f32[2]];
-
       splats.push(GaussianSplat {
         position,
         scale,
@@ -91,11 +110,14 @@ impl Importer for SplatImporter {
         spherical_harmonics_dc,
         opacity,
         normal: [0.0, 0.0, 0.0],
-        spherical_harmonics_rest: vec![0.0; 45],
+        spherical_harmonics_rest: Vec::new(),
       });
     }
 
-    Ok(Scene { splats })
+    Ok(Scene {
+      splats,
+      cameras: Vec::new(),
+    })
   }
 }
 
@@ -103,12 +125,36 @@ impl Importer for SplatImporter {
 
 impl Exporter for SplatExporter {
   fn export(scene: &Scene, writer: &mut impl std::io::Write) -> Result<(), ConversionError> {
+    Self::export_with_options(scene, writer, &SplatExportOptions::default())
+  }
+}
+
+impl SplatExporter {
+  /// Writes `scene` as `.splat`, quantizing exactly like antimatter15's reference `convert.py`
+  /// so that output is byte-for-byte identical to it when `SplatOrder::Importance` is used.
+  pub fn export_with_options(
+    scene: &Scene,
+    writer: &mut impl std::io::Write,
+    options: &SplatExportOptions,
+  ) -> Result<(), ConversionError> {
     let map_io_err = |e: std::io::Error| ConversionError::WriteError {
       format: "SPLAT".to_string(),
       message: e.to_string(),
     };
 
-    for splat in &scene.splats {
+    let mut indices: Vec<usize> = (0..scene.splats.len()).collect();
+
+    if let Some(min_opacity) = options.min_opacity {
+      indices.retain(|&i| sigmoid(scene.splats[i].opacity) >= min_opacity);
+    }
+
+    if options.order == SplatOrder::Importance {
+      let importance: Vec<f32> = scene.splats.iter().map(Self::importance).collect();
+      // Stable, so ties keep their input order
+      indices.sort_by(|&a, &b| importance[b].total_cmp(&importance[a]));
+    }
+
+    for splat in indices.into_iter().map(|i| &scene.splats[i]) {
       // Position
       for &p in &splat.position {
         writer.write_all(&p.to_le_bytes()).map_err(map_io_err)?;
@@ -121,40 +167,35 @@ impl Exporter for SplatExporter {
           .map_err(map_io_err)?;
       }
 
-      // Color - Convert from SH f32 to RGBA u8
-      let r = ((splat.spherical_harmonics_dc[0] * SH_C0 + 0.5) * 255.0)
-        .round()
-        .clamp(0.0, 255.0) as u8;
-      let g = ((splat.spherical_harmonics_dc[1] * SH_C0 + 0.5) * 255.0)
-        .round()
-        .clamp(0.0, 255.0) as u8;
-      let b = ((splat.spherical_harmonics_dc[2] * SH_C0 + 0.5) * 255.0)
-        .round()
-        .clamp(0.0, 255.0) as u8;
+      // Color - Convert from SH f32 to RGBA u8, in float32 and truncating like numpy's
+      // `astype(np.uint8)`, so that values at the edge of a byte land on the same side
+      let to_u8 = |v: f32| (v * 255.0).clamp(0.0, 255.0) as u8;
+      let r = to_u8(0.5 + SH_C0 * splat.spherical_harmonics_dc[0]);
+      let g = to_u8(0.5 + SH_C0 * splat.spherical_harmonics_dc[1]);
+      let b = to_u8(0.5 + SH_C0 * splat.spherical_harmonics_dc[2]);
 
       // Opacity - Apply sigmoid and convert to u8
-      let sigmoid = 1.0 / (1.0 + (-splat.opacity).exp());
-      let a = (sigmoid * 255.0).round().clamp(0.0, 255.0) as u8;
+      let a = to_u8(sigmoid(splat.opacity));
       writer.write_all(&[r, g, b, a]).map_err(map_io_err)?;
 
-      // Rotation - Convert from [w, x, y, z] f32 to quantized [x, y, z, w] u8
-      let mut rot = splat.rotation; // [w, x, y, z]
-      let len = (rot[1].powi(2) + rot[2].powi(2) + rot[3].powi(2) + rot[0].powi(2)).sqrt();
+      // Rotation - normalized [w, x, y, z] quantized to `q * 128 + 128`
+      let mut rot = splat.rotation;
+      let len = (rot[0].powi(2) + rot[1].powi(2) + rot[2].powi(2) + rot[3].powi(2)).sqrt();
       if len > 0.0 {
         for v in &mut rot {
           *v /= len;
         }
       }
 
-      let rot_u8 = [
-        (((rot[1] + 1.0) / 2.0) * 255.0).round().clamp(0.0, 255.0) as u8, // x
-        (((rot[2] + 1.0) / 2.0) * 255.0).round().clamp(0.0, 255.0) as u8, // y
-        (((rot[3] + 1.0) / 2.0) * 255.0).round().clamp(0.0, 255.0) as u8, // z
-        (((rot[0] + 1.0) / 2.0) * 255.0).round().clamp(0.0, 255.0) as u8, // w
-      ];
+      let rot_u8 = rot.map(|v| (v * 128.0 + 128.0).clamp(0.0, 255.0) as u8);
       writer.write_all(&rot_u8).map_err(map_io_err)?;
     }
 
     Ok(())
   }
+
+  /// Sort key used by `SplatOrder::Importance`: the splat's volume times its activated opacity.
+  pub(crate) fn importance(splat: &GaussianSplat) -> f32 {
+    (splat.scale[0] + splat.scale[1] + splat.scale[2]).exp() / (1.0 + (-splat.opacity).exp())
+  }
 }
@@ -1,19 +1,17 @@
-use std::f32::INFINITY;
-
-use crate::{ConversionError, Exporter, GaussianSplat, Importer, Scene};
+use crate::{
+  ConversionError, Exporter, GaussianSplat, Importer, Scene, sh_coefficients_per_channel,
+};
 use spz_rs::{UnpackedGaussian, load_packed_gaussians_from_spz_buffer};
 
 pub struct SpzImporter;
 pub struct SpzV2Exporter;
 
-fn unpack_sh_rest(unpacked: &UnpackedGaussian) -> Vec<f32> {
-  let mut sh_rest = vec![0.0f32; 45]; // preallocate all 45 slots
+fn unpack_sh_rest(unpacked: &UnpackedGaussian, per_channel: usize) -> Vec<f32> {
+  let mut sh_rest = Vec::with_capacity(per_channel * 3);
 
-  for i in 0..15 {
-    sh_rest[i] = unpacked.sh_r[i] as f32;
-    sh_rest[i + 15] = unpacked.sh_g[i] as f32;
-    sh_rest[i + 30] = unpacked.sh_b[i] as f32;
-  }
+  sh_rest.extend_from_slice(&unpacked.sh_r[..per_channel]);
+  sh_rest.extend_from_slice(&unpacked.sh_g[..per_channel]);
+  sh_rest.extend_from_slice(&unpacked.sh_b[..per_channel]);
 
   sh_rest
 }
@@ -22,6 +20,7 @@ impl Importer for SpzImporter {
   fn import(reader: &mut impl std::io::Read) -> Result<Scene, ConversionError> {
     let packed_gaussians = load_packed_gaussians_from_spz_buffer(reader)?;
 
+    let sh_per_channel = sh_coefficients_per_channel(packed_gaussians.sh_degree.min(3) as u8);
     let mut splats: Vec<GaussianSplat> = Vec::with_capacity(packed_gaussians.num_points);
 
     for i in 0..packed_gaussians.num_points {
@@ -29,7 +28,7 @@ impl Importer for SpzImporter {
 
       // Handle `infinity`
       let opacity = {
-        if unpacked_gaussian.alpha == INFINITY {
+        if unpacked_gaussian.alpha == f32::INFINITY {
           1.0
         } else {
           0.0
@@ -44,8 +43,8 @@ impl Importer for SpzImporter {
         ],
         normal: [0f32, 0f32, 0f32],
         spherical_harmonics_dc: unpacked_gaussian.color,
-        spherical_harmonics_rest: unpack_sh_rest(&unpacked_gaussian),
-        opacity: opacity,
+        spherical_harmonics_rest: unpack_sh_rest(&unpacked_gaussian, sh_per_channel),
+        opacity,
         scale: unpacked_gaussian.scale,
         rotation: unpacked_gaussian.rotation,
       };
@@ -53,7 +52,10 @@ impl Importer for SpzImporter {
       splats.push(splat);
     }
 
-    Ok(Scene { splats })
+    Ok(Scene {
+      splats,
+      cameras: Vec::new(),
+    })
   }
 }
 
@@ -69,7 +71,8 @@ impl Exporter for SpzV2Exporter {
     };
 
     // Header fields
-    let sh_degree: u8 = 3; // 15 coeffs/channel
+    let sh_degree: u8 = scene.sh_degree();
+    let sh_per_channel = sh_coefficients_per_channel(sh_degree);
     let version: u32 = 2; // fixed-point 3-byte coordinates
     let flags: u8 = 0;
 
@@ -133,7 +136,7 @@ impl Exporter for SpzV2Exporter {
     let mut colors: Vec<u8> = Vec::with_capacity(num_points * 3); // 3 bytes
     let mut scales: Vec<u8> = Vec::with_capacity(num_points * 3); // 3 bytes
     let mut rotations: Vec<u8> = Vec::with_capacity(num_points * 3); // 3 bytes (x,y,z only)
-    let mut sh: Vec<u8> = Vec::with_capacity(num_points * 45); // 15*3 bytes
+    let mut sh: Vec<u8> = Vec::with_capacity(num_points * sh_per_channel * 3);
 
     const COLOR_SCALE: f32 = 0.15;
 
@@ -176,12 +179,12 @@ impl Exporter for SpzV2Exporter {
       rotations.push(clamp_u8f((y + 1.0) * 127.5));
       rotations.push(clamp_u8f((z + 1.0) * 127.5));
 
-      // SH rest (15 per channel; interleaved r,g,b per coefficient)
+      // SH rest (up to 15 per channel; interleaved r,g,b per coefficient)
       // inverse of: unquantize_sh(x) = (x - 128) / 128
-      for i in 0..15 {
-        let r = *splat.spherical_harmonics_rest.get(i).unwrap_or(&0.0);
-        let g = *splat.spherical_harmonics_rest.get(i + 15).unwrap_or(&0.0);
-        let b = *splat.spherical_harmonics_rest.get(i + 30).unwrap_or(&0.0);
+      for i in 0..sh_per_channel {
+        let r = splat.sh_rest(0, i);
+        let g = splat.sh_rest(1, i);
+        let b = splat.sh_rest(2, i);
         sh.push(clamp_u8f(r * 128.0 + 128.0));
         sh.push(clamp_u8f(g * 128.0 + 128.0));
         sh.push(clamp_u8f(b * 128.0 + 128.0));
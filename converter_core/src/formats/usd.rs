@@ -0,0 +1,416 @@
+use std::io::{Cursor, Read, Write};
+
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::helpers::activation::{inverse_sigmoid, sigmoid};
+use crate::{
+  Camera, ConversionError, Exporter, GaussianSplat, Importer, Scene, sh_coefficients_per_channel,
+};
+
+/// Reads and writes OpenUSD scenes holding a single `ParticleField3DGaussianSplat` prim, as text
+/// (`.usda`) or as a `.usdz` package of that text layer.
+///
+/// Orientations are `quatf` (real part first, like the `Scene`), scales and opacities are linear
+/// and `radiance:sphericalHarmonicsCoefficients` holds `(degree + 1)²` RGB coefficients per
+/// particle, starting with the DC term. The scene's cameras are written as `Camera` prims next to
+/// it, but not read back.
+pub struct UsdImporter;
+pub struct UsdaExporter;
+pub struct UsdzExporter;
+
+const PRIM_TYPE: &str = "ParticleField3DGaussianSplat";
+const PRIM_NAME: &str = "GaussianSplats";
+/// Name of the layer inside a `.usdz` package; the first file is the package's root layer.
+const USDZ_ROOT_LAYER: &str = "scene.usda";
+/// Files in a `.usdz` package are stored uncompressed, with their data 64-byte aligned.
+const USDZ_ALIGNMENT: u16 = 64;
+/// USD cameras look along -Z with +Y up, scene cameras along +Z with +Y down.
+const CAMERA_AXIS_FLIP: [f32; 3] = [1.0, -1.0, -1.0];
+/// Film height of the exported cameras, in tenths of a scene unit like USD's defaults (mm).
+const CAMERA_VERTICAL_APERTURE: f32 = 24.0;
+
+fn parse_error(message: impl Into<String>) -> ConversionError {
+  ConversionError::ParseError {
+    format: "USD".to_string(),
+    message: message.into(),
+  }
+}
+
+fn write_error(message: impl Into<String>) -> ConversionError {
+  ConversionError::WriteError {
+    format: "USD".to_string(),
+    message: message.into(),
+  }
+}
+
+/// Writes `values` as a USD array of tuples, `components` values per tuple.
+fn write_tuples(
+  writer: &mut impl Write,
+  declaration: &str,
+  values: impl Iterator<Item = f32>,
+  components: usize,
+) -> std::io::Result<()> {
+  write!(writer, "    {} = [", declaration)?;
+  let mut empty = true;
+  for (i, value) in values.enumerate() {
+    match (i % components, i) {
+      (0, 0) => write!(writer, "(")?,
+      (0, _) => write!(writer, "), (")?,
+      _ => write!(writer, ", ")?,
+    }
+    write!(writer, "{}", value)?;
+    empty = false;
+  }
+  writeln!(writer, "{}]", if empty { "" } else { ")" })
+}
+
+fn write_usda(scene: &Scene, writer: &mut impl Write) -> std::io::Result<()> {
+  let splats = &scene.splats;
+  let sh_degree = scene.sh_degree();
+  let per_channel = sh_coefficients_per_channel(sh_degree);
+
+  writeln!(writer, "#usda 1.0")?;
+  writeln!(writer, "(")?;
+  writeln!(writer, "    defaultPrim = \"{}\"", PRIM_NAME)?;
+  writeln!(writer, "    metersPerUnit = 1")?;
+  writeln!(writer, "    upAxis = \"Y\"")?;
+  writeln!(writer, ")")?;
+  writeln!(writer)?;
+  writeln!(writer, "def {} \"{}\"", PRIM_TYPE, PRIM_NAME)?;
+  writeln!(writer, "{{")?;
+
+  if !splats.is_empty() {
+    let mut min = [f32::INFINITY; 3];
+    let mut max = [f32::NEG_INFINITY; 3];
+    for splat in splats {
+      for axis in 0..3 {
+        min[axis] = min[axis].min(splat.position[axis]);
+        max[axis] = max[axis].max(splat.position[axis]);
+      }
+    }
+    write_tuples(writer, "float3[] extent", min.into_iter().chain(max), 3)?;
+  }
+
+  write_tuples(
+    writer,
+    "point3f[] positions",
+    splats.iter().flat_map(|s| s.position),
+    3,
+  )?;
+  write_tuples(
+    writer,
+    "quatf[] orientations",
+    splats.iter().flat_map(|s| {
+      let len = s.rotation.iter().map(|v| v * v).sum::<f32>().sqrt();
+      if len > 0.0 {
+        s.rotation.map(|v| v / len)
+      } else {
+        [1.0, 0.0, 0.0, 0.0]
+      }
+    }),
+    4,
+  )?;
+  write_tuples(
+    writer,
+    "float3[] scales",
+    splats.iter().flat_map(|s| s.scale.map(f32::exp)),
+    3,
+  )?;
+
+  write!(writer, "    float[] opacities = [")?;
+  for (i, splat) in splats.iter().enumerate() {
+    let separator = if i == 0 { "" } else { ", " };
+    write!(writer, "{}{}", separator, sigmoid(splat.opacity))?;
+  }
+  writeln!(writer, "]")?;
+
+  writeln!(
+    writer,
+    "    uniform int radiance:sphericalHarmonicsDegree = {}",
+    sh_degree
+  )?;
+  write_tuples(
+    writer,
+    "float3[] radiance:sphericalHarmonicsCoefficients",
+    splats.iter().flat_map(|s| {
+      let dc = s.spherical_harmonics_dc;
+      dc.into_iter()
+        .chain((0..per_channel).flat_map(move |i| (0..3).map(move |c| s.sh_rest(c, i))))
+    }),
+    3,
+  )?;
+
+  writeln!(writer, "}}")?;
+
+  for (index, camera) in scene.cameras.iter().enumerate() {
+    write_camera(writer, index, camera)?;
+  }
+  Ok(())
+}
+
+/// Writes a `Camera` prim whose film back reproduces the camera's focal lengths and principal
+/// point in pixels.
+fn write_camera(writer: &mut impl Write, index: usize, camera: &Camera) -> std::io::Result<()> {
+  let (width, height) = (camera.width as f32, camera.height as f32);
+  let focal_length = camera.focal[1] * CAMERA_VERTICAL_APERTURE / height;
+  let horizontal_aperture = width * focal_length / camera.focal[0];
+  // USD matrices transform row vectors: the rows are the camera axes, then the translation
+  let rows = [0, 1, 2].map(|row| camera.rotation[row].map(|v| v * CAMERA_AXIS_FLIP[row]));
+
+  writeln!(writer)?;
+  writeln!(writer, "def Camera \"Camera_{}\"", index)?;
+  writeln!(writer, "{{")?;
+  if !camera.name.is_empty() {
+    writeln!(writer, "    string gsflux:imageName = {:?}", camera.name)?;
+  }
+  writeln!(writer, "    float focalLength = {}", focal_length)?;
+  writeln!(
+    writer,
+    "    float horizontalAperture = {}",
+    horizontal_aperture
+  )?;
+  writeln!(
+    writer,
+    "    float horizontalApertureOffset = {}",
+    (width / 2.0 - camera.principal_point[0]) * horizontal_aperture / width
+  )?;
+  writeln!(
+    writer,
+    "    float verticalAperture = {}",
+    CAMERA_VERTICAL_APERTURE
+  )?;
+  writeln!(
+    writer,
+    "    float verticalApertureOffset = {}",
+    (camera.principal_point[1] - height / 2.0) * CAMERA_VERTICAL_APERTURE / height
+  )?;
+  let [x, y, z] = camera.position;
+  writeln!(
+    writer,
+    "    matrix4d xformOp:transform = ( ({}, {}, {}, 0), ({}, {}, {}, 0), ({}, {}, {}, 0), ({}, {}, {}, 1) )",
+    rows[0][0],
+    rows[0][1],
+    rows[0][2],
+    rows[1][0],
+    rows[1][1],
+    rows[1][2],
+    rows[2][0],
+    rows[2][1],
+    rows[2][2],
+    x,
+    y,
+    z
+  )?;
+  writeln!(
+    writer,
+    "    uniform token[] xformOpOrder = [\"xformOp:transform\"]"
+  )?;
+  writeln!(writer, "}}")
+}
+
+// --- IMPORTER ---
+
+/// Body of the first `ParticleField3DGaussianSplat` prim, between its braces.
+fn prim_body(layer: &str) -> Result<&str, ConversionError> {
+  let start = layer
+    .find(&format!("def {}", PRIM_TYPE))
+    .ok_or_else(|| parse_error(format!("No {} prim found", PRIM_TYPE)))?;
+  let open = start
+    + layer[start..]
+      .find('{')
+      .ok_or_else(|| parse_error("Missing prim body"))?;
+
+  let mut depth = 0;
+  for (offset, c) in layer[open..].char_indices() {
+    match c {
+      '{' => depth += 1,
+      '}' => {
+        depth -= 1;
+        if depth == 0 {
+          return Ok(&layer[open + 1..open + offset]);
+        }
+      }
+      _ => {}
+    }
+  }
+  Err(parse_error("Unterminated prim body"))
+}
+
+/// The value text of attribute `name` (everything after `=` up to the end of its value).
+fn attribute_value<'a>(body: &'a str, name: &str) -> Option<&'a str> {
+  let mut search = 0;
+  while let Some(found) = body[search..].find(name) {
+    let start = search + found;
+    let end = start + name.len();
+    search = end;
+
+    // Only whole attribute names, so "orientations" doesn't match "orientationsh"
+    let before = body[..start].chars().next_back();
+    let rest = body[end..].trim_start();
+    if before.is_some_and(|c| !c.is_whitespace()) || !rest.starts_with('=') {
+      continue;
+    }
+
+    let value = rest[1..].trim_start();
+    let length = if value.starts_with('[') {
+      value.find(']').map_or(value.len(), |i| i + 1)
+    } else {
+      value.find('\n').unwrap_or(value.len())
+    };
+    return Some(&value[..length]);
+  }
+  None
+}
+
+/// Parses every number of an attribute value, flattening tuples.
+fn attribute_numbers(body: &str, name: &str) -> Result<Option<Vec<f32>>, ConversionError> {
+  let Some(value) = attribute_value(body, name) else {
+    return Ok(None);
+  };
+
+  value
+    .split(|c: char| c.is_whitespace() || "[](),".contains(c))
+    .filter(|token| !token.is_empty())
+    .map(|token| {
+      token
+        .parse::<f32>()
+        .map_err(|_| parse_error(format!("Invalid number \"{}\" in {}", token, name)))
+    })
+    .collect::<Result<Vec<_>, _>>()
+    .map(Some)
+}
+
+fn scene_from_usda(layer: &str) -> Result<Scene, ConversionError> {
+  if !layer.starts_with("#usda") {
+    return Err(parse_error(
+      "Only text layers (#usda) are supported, not binary crate files",
+    ));
+  }
+  let body = prim_body(layer)?;
+
+  let positions = attribute_numbers(body, "positions")?
+    .ok_or_else(|| parse_error("Missing positions attribute"))?;
+  let count = positions.len() / 3;
+  let check = |values: Option<Vec<f32>>, components: usize, name: &str| match values {
+    Some(values) if values.len() != count * components => Err(parse_error(format!(
+      "{} has a different count than positions",
+      name
+    ))),
+    values => Ok(values),
+  };
+
+  let orientations = check(attribute_numbers(body, "orientations")?, 4, "orientations")?;
+  let scales = check(attribute_numbers(body, "scales")?, 3, "scales")?;
+  let opacities = check(attribute_numbers(body, "opacities")?, 1, "opacities")?;
+
+  let sh_degree = attribute_numbers(body, "radiance:sphericalHarmonicsDegree")?
+    .and_then(|values| values.first().copied())
+    .unwrap_or(0.0) as u8;
+  if sh_degree > 3 {
+    return Err(parse_error(format!(
+      "Unsupported spherical harmonics degree {}",
+      sh_degree
+    )));
+  }
+  let per_channel = sh_coefficients_per_channel(sh_degree);
+  let coefficients = check(
+    attribute_numbers(body, "radiance:sphericalHarmonicsCoefficients")?,
+    3 * (per_channel + 1),
+    "radiance:sphericalHarmonicsCoefficients",
+  )?;
+
+  let splats = (0..count)
+    .map(|i| {
+      let sh = coefficients
+        .as_ref()
+        .map(|c| &c[i * 3 * (per_channel + 1)..(i + 1) * 3 * (per_channel + 1)]);
+      let mut spherical_harmonics_rest = vec![0.0; 3 * per_channel];
+      if let Some(sh) = sh {
+        for index in 0..per_channel {
+          for channel in 0..3 {
+            spherical_harmonics_rest[channel * per_channel + index] = sh[(index + 1) * 3 + channel];
+          }
+        }
+      }
+
+      GaussianSplat {
+        position: [positions[i * 3], positions[i * 3 + 1], positions[i * 3 + 2]],
+        normal: [0.0, 0.0, 0.0],
+        spherical_harmonics_dc: sh.map_or([0.0; 3], |sh| [sh[0], sh[1], sh[2]]),
+        spherical_harmonics_rest,
+        opacity: inverse_sigmoid(opacities.as_ref().map_or(1.0, |o| o[i])),
+        scale: scales.as_ref().map_or([0.0; 3], |s| {
+          [s[i * 3], s[i * 3 + 1], s[i * 3 + 2]].map(|v| v.max(f32::MIN_POSITIVE).ln())
+        }),
+        rotation: orientations.as_ref().map_or([1.0, 0.0, 0.0, 0.0], |q| {
+          [q[i * 4], q[i * 4 + 1], q[i * 4 + 2], q[i * 4 + 3]]
+        }),
+      }
+    })
+    .collect();
+
+  Ok(Scene {
+    splats,
+    cameras: Vec::new(),
+  })
+}
+
+impl Importer for UsdImporter {
+  fn import(reader: &mut impl Read) -> Result<Scene, ConversionError> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    // A .usdz package is a zip archive whose first file is the root layer
+    let layer = if data.starts_with(b"PK\x03\x04") {
+      let mut archive =
+        ZipArchive::new(Cursor::new(data)).map_err(|e| parse_error(e.to_string()))?;
+      let mut root = archive
+        .by_index(0)
+        .map_err(|e| parse_error(e.to_string()))?;
+      let mut layer = String::new();
+      root
+        .read_to_string(&mut layer)
+        .map_err(|_| parse_error(format!("Root layer {} is not a text layer", root.name())))?;
+      layer
+    } else {
+      String::from_utf8(data).map_err(|_| {
+        parse_error("Only text layers (#usda) are supported, not binary crate files")
+      })?
+    };
+
+    scene_from_usda(&layer)
+  }
+}
+
+// --- EXPORTERS ---
+
+impl Exporter for UsdaExporter {
+  fn export(scene: &Scene, writer: &mut impl Write) -> Result<(), ConversionError> {
+    write_usda(scene, writer).map_err(|e| write_error(e.to_string()))
+  }
+}
+
+impl Exporter for UsdzExporter {
+  fn export(scene: &Scene, writer: &mut impl Write) -> Result<(), ConversionError> {
+    let mut layer = Vec::new();
+    UsdaExporter::export(scene, &mut layer)?;
+
+    let map_zip_err = |e: zip::result::ZipError| write_error(e.to_string());
+    let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+    archive
+      .start_file(
+        USDZ_ROOT_LAYER,
+        SimpleFileOptions::default()
+          .compression_method(CompressionMethod::Stored)
+          .with_alignment(USDZ_ALIGNMENT),
+      )
+      .map_err(map_zip_err)?;
+    archive.write_all(&layer)?;
+    let package = archive.finish().map_err(map_zip_err)?.into_inner();
+
+    writer
+      .write_all(&package)
+      .map_err(|e| write_error(e.to_string()))
+  }
+}
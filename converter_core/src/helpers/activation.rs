@@ -0,0 +1,10 @@
+/// Logistic sigmoid, maps a stored (logit) opacity to the activated range `0..1`.
+pub fn sigmoid(x: f32) -> f32 {
+  1.0 / (1.0 + (-x).exp())
+}
+
+/// Inverse of [`sigmoid`], clamped so that `0` and `1` map to finite logits.
+pub fn inverse_sigmoid(y: f32) -> f32 {
+  let y = y.clamp(1e-6, 1.0 - 1e-6);
+  (y / (1.0 - y)).ln()
+}
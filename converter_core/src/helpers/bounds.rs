@@ -0,0 +1,26 @@
+use crate::Scene;
+
+/// Axis-aligned bounds of the splat centres ignoring the `fraction` (0..0.5) of splats at either
+/// end of each axis, so that a few far-away floaters don't dominate. Non-finite positions are
+/// skipped; `None` for a scene without any finite position.
+pub fn robust_bounds(scene: &Scene, fraction: f32) -> Option<([f32; 3], [f32; 3])> {
+  let mut min = [0.0f32; 3];
+  let mut max = [0.0f32; 3];
+  for axis in 0..3 {
+    let mut values: Vec<f32> = scene
+      .splats
+      .iter()
+      .map(|splat| splat.position[axis])
+      .filter(|value| value.is_finite())
+      .collect();
+    if values.is_empty() {
+      return None;
+    }
+    values.sort_unstable_by(f32::total_cmp);
+
+    let skip = ((values.len() - 1) as f32 * fraction.clamp(0.0, 0.5)).round() as usize;
+    min[axis] = values[skip];
+    max[axis] = values[values.len() - 1 - skip];
+  }
+  Some((min, max))
+}
@@ -0,0 +1,136 @@
+use rayon::prelude::*;
+
+/// Clusters `values` into a sorted codebook of exactly `k` entries with 1D k-means
+/// (entries repeat when there are fewer distinct values than `k`).
+pub fn kmeans_1d(values: &[f32], k: usize, iterations: usize) -> Vec<f32> {
+  let mut sorted: Vec<f32> = values.iter().copied().filter(|v| v.is_finite()).collect();
+  sorted.sort_by(f32::total_cmp);
+
+  if sorted.is_empty() || k == 0 {
+    return vec![0.0; k];
+  }
+
+  let mut distinct = sorted.clone();
+  distinct.dedup();
+  if distinct.len() <= k {
+    let last = *distinct.last().unwrap();
+    distinct.resize(k, last);
+    return distinct;
+  }
+
+  // Start from quantiles, then run Lloyd's iterations. With sorted values and centroids,
+  // every cluster is a contiguous run split at the midpoints between centroids.
+  let mut codebook: Vec<f32> = (0..k)
+    .map(|i| sorted[((i as f64 + 0.5) / k as f64 * sorted.len() as f64) as usize])
+    .collect();
+
+  for _ in 0..iterations {
+    let mut sums = vec![0.0f64; k];
+    let mut counts = vec![0usize; k];
+    let mut cluster = 0;
+    for &v in &sorted {
+      while cluster + 1 < k && v > (codebook[cluster] + codebook[cluster + 1]) / 2.0 {
+        cluster += 1;
+      }
+      sums[cluster] += v as f64;
+      counts[cluster] += 1;
+    }
+
+    let mut changed = false;
+    for i in 0..k {
+      if counts[i] > 0 {
+        let mean = (sums[i] / counts[i] as f64) as f32;
+        changed |= mean != codebook[i];
+        codebook[i] = mean;
+      }
+    }
+    codebook.sort_by(f32::total_cmp);
+
+    if !changed {
+      break;
+    }
+  }
+
+  codebook
+}
+
+/// Index of the entry of a sorted `codebook` nearest to `value`.
+pub fn nearest_in_codebook(codebook: &[f32], value: f32) -> usize {
+  let upper = codebook.partition_point(|&c| c < value);
+  if upper == 0 {
+    0
+  } else if upper == codebook.len() {
+    codebook.len() - 1
+  } else if value - codebook[upper - 1] <= codebook[upper] - value {
+    upper - 1
+  } else {
+    upper
+  }
+}
+
+fn squared_distance(a: &[f32], b: &[f32]) -> f32 {
+  a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+fn nearest_centroid(centroids: &[f32], dim: usize, point: &[f32]) -> usize {
+  centroids
+    .chunks_exact(dim)
+    .map(|centroid| squared_distance(centroid, point))
+    .enumerate()
+    .min_by(|a, b| a.1.total_cmp(&b.1))
+    .map_or(0, |(i, _)| i)
+}
+
+/// Lloyd's k-means over `points`, stored row-major with `dim` values per point.
+/// Centroids are trained on an evenly strided sample of at most `16 * k` points and then every
+/// point is assigned to its nearest one. Returns `k` (or fewer, for small inputs) centroids
+/// and a label per point.
+pub fn kmeans(points: &[f32], dim: usize, k: usize, iterations: usize) -> (Vec<f32>, Vec<u32>) {
+  let count = points.len().checked_div(dim).unwrap_or(0);
+  let k = k.min(count);
+  if k == 0 {
+    return (Vec::new(), vec![0; count]);
+  }
+
+  let point = |i: usize| &points[i * dim..(i + 1) * dim];
+  let sample_size = count.min(16 * k);
+  let sample: Vec<usize> = (0..sample_size).map(|i| i * count / sample_size).collect();
+
+  let mut centroids: Vec<f32> = (0..k)
+    .flat_map(|i| point(sample[i * sample_size / k]).to_vec())
+    .collect();
+
+  for _ in 0..iterations {
+    let labels: Vec<usize> = sample
+      .par_iter()
+      .map(|&i| nearest_centroid(&centroids, dim, point(i)))
+      .collect();
+
+    let mut sums = vec![0.0f64; k * dim];
+    let mut counts = vec![0usize; k];
+    for (&i, &label) in sample.iter().zip(&labels) {
+      counts[label] += 1;
+      for (sum, &v) in sums[label * dim..(label + 1) * dim]
+        .iter_mut()
+        .zip(point(i))
+      {
+        *sum += v as f64;
+      }
+    }
+
+    for (c, &n) in counts.iter().enumerate() {
+      if n > 0 {
+        for d in 0..dim {
+          centroids[c * dim + d] = (sums[c * dim + d] / n as f64) as f32;
+        }
+      }
+    }
+  }
+
+  let labels = (0..count)
+    .into_par_iter()
+    .map(|i| nearest_centroid(&centroids, dim, point(i)) as u32)
+    .collect();
+
+  (centroids, labels)
+}
@@ -0,0 +1,160 @@
+use std::sync::OnceLock;
+
+/// The 12 edges of a cell as `(corner, corner)` pairs. Corner `c` sits at offset
+/// `(c & 1, (c >> 1) & 1, (c >> 2) & 1)`, and the second corner is one step from the first along
+/// axis `edge / 4`.
+pub const EDGES: [(usize, usize); 12] = [
+  (0, 1),
+  (2, 3),
+  (4, 5),
+  (6, 7),
+  (0, 2),
+  (1, 3),
+  (4, 6),
+  (5, 7),
+  (0, 4),
+  (1, 5),
+  (2, 6),
+  (3, 7),
+];
+
+/// Offset of cell corner `corner` from the cell's lowest corner.
+pub fn corner_offset(corner: usize) -> [usize; 3] {
+  [corner & 1, (corner >> 1) & 1, (corner >> 2) & 1]
+}
+
+/// Triangles, as cell edge indices, for a cell whose corners inside the surface are the set bits
+/// of `config`. Triangles wind counter-clockwise seen from the outside.
+pub fn triangles(config: u8) -> &'static [[u8; 3]] {
+  static TABLE: OnceLock<Vec<Vec<[u8; 3]>>> = OnceLock::new();
+  &TABLE.get_or_init(|| (0..=255).map(cell_triangles).collect())[config as usize]
+}
+
+fn edge_between(a: usize, b: usize) -> usize {
+  EDGES
+    .iter()
+    .position(|&(p, q)| (p, q) == (a, b) || (p, q) == (b, a))
+    .unwrap()
+}
+
+/// Whether two edges lie on a common face of the cell.
+fn share_face(a: usize, b: usize) -> bool {
+  let corners = [EDGES[a].0, EDGES[a].1, EDGES[b].0, EDGES[b].1];
+  (0..3).any(|axis| {
+    corners
+      .iter()
+      .all(|&corner| (corner >> axis) & 1 == (corners[0] >> axis) & 1)
+  })
+}
+
+fn edge_midpoint(edge: usize) -> [f32; 3] {
+  let (a, b) = EDGES[edge];
+  let [a, b] = [corner_offset(a), corner_offset(b)];
+  [0, 1, 2].map(|axis| (a[axis] + b[axis]) as f32 / 2.0)
+}
+
+/// Builds the triangles of one configuration instead of relying on a hand-written table.
+/// The contour is traced on each of the 6 faces, every face segment oriented so that the inside
+/// lies on a fixed side, and the segments are chained into loops that are fanned into triangles.
+/// Every loop is known to have a vertex to fan from without a diagonal along a face.
+/// Faces with two diagonal inside corners always separate them, so neighbouring cells agree on
+/// their shared face and the mesh has no holes.
+fn cell_triangles(config: usize) -> Vec<[u8; 3]> {
+  let inside = |corner: usize| config & (1 << corner) != 0;
+  let mut next = [usize::MAX; 12];
+
+  for axis in 0..3 {
+    let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+    for side in 0..2 {
+      let corners =
+        [(0, 0), (1, 0), (1, 1), (0, 1)].map(|(du, dv)| (side << axis) | (du << u) | (dv << v));
+      let mut normal = [0.0f32; 3];
+      normal[axis] = if side == 0 { -1.0 } else { 1.0 };
+
+      // Each segment cuts off the inside region around `reference`
+      let mut add_segment = |first: usize, second: usize, reference: [f32; 3]| {
+        let (p, q) = (edge_midpoint(first), edge_midpoint(second));
+        let pq = [q[0] - p[0], q[1] - p[1], q[2] - p[2]];
+        let pr = [
+          reference[0] - p[0],
+          reference[1] - p[1],
+          reference[2] - p[2],
+        ];
+        let cross = [
+          pq[1] * pr[2] - pq[2] * pr[1],
+          pq[2] * pr[0] - pq[0] * pr[2],
+          pq[0] * pr[1] - pq[1] * pr[0],
+        ];
+        let side = cross[0] * normal[0] + cross[1] * normal[1] + cross[2] * normal[2];
+        if side < 0.0 {
+          next[first] = second;
+        } else {
+          next[second] = first;
+        }
+      };
+
+      let cut_edges: Vec<usize> = (0..4)
+        .filter(|&i| inside(corners[i]) != inside(corners[(i + 1) % 4]))
+        .map(|i| edge_between(corners[i], corners[(i + 1) % 4]))
+        .collect();
+
+      match cut_edges.len() {
+        2 => {
+          let inside_corners: Vec<[usize; 3]> = corners
+            .iter()
+            .filter(|&&c| inside(c))
+            .map(|&c| corner_offset(c))
+            .collect();
+          let reference = [0, 1, 2].map(|axis| {
+            inside_corners.iter().map(|c| c[axis] as f32).sum::<f32>() / inside_corners.len() as f32
+          });
+          add_segment(cut_edges[0], cut_edges[1], reference);
+        }
+        4 => {
+          for i in (0..4).filter(|&i| inside(corners[i])) {
+            let before = edge_between(corners[(i + 3) % 4], corners[i]);
+            let after = edge_between(corners[i], corners[(i + 1) % 4]);
+            add_segment(before, after, corner_offset(corners[i]).map(|c| c as f32));
+          }
+        }
+        _ => {}
+      }
+    }
+  }
+
+  let mut triangles = Vec::new();
+  let mut visited = [false; 12];
+  for start in 0..12 {
+    if next[start] == usize::MAX || visited[start] {
+      continue;
+    }
+    let mut polygon = Vec::new();
+    let mut edge = start;
+    while !visited[edge] {
+      visited[edge] = true;
+      polygon.push(edge as u8);
+      edge = next[edge];
+    }
+    // Fan from a vertex whose diagonals all cross the cell's interior. A diagonal lying in a face
+    // could also be produced by the neighbouring cell and break the mesh there.
+    let length = polygon.len();
+    let apex = (0..length)
+      .find(|&apex| {
+        (2..length - 1).all(|i| {
+          !share_face(
+            polygon[apex] as usize,
+            polygon[(apex + i) % length] as usize,
+          )
+        })
+      })
+      .unwrap_or(0);
+    for i in 1..length - 1 {
+      triangles.push([
+        polygon[apex],
+        polygon[(apex + i) % length],
+        polygon[(apex + i + 1) % length],
+      ]);
+    }
+  }
+  triangles
+}
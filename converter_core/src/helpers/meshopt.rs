@@ -0,0 +1,205 @@
+//! The meshoptimizer vertex codec (version 0), as used by the glTF `KHR_meshopt_compression` and
+//! `EXT_meshopt_compression` extensions in `ATTRIBUTES` mode.
+//!
+//! Vertices are processed in blocks; within a block every byte of the vertex is delta encoded
+//! against the previous vertex, zigzagged and stored in groups of 16 using 0, 2, 4 or 8 bits per value.
+
+const VERTEX_HEADER: u8 = 0xa0;
+const BYTE_GROUP_SIZE: usize = 16;
+const VERTEX_BLOCK_SIZE_BYTES: usize = 8192;
+const VERTEX_BLOCK_MAX_SIZE: usize = 256;
+const TAIL_MAX_SIZE: usize = 32;
+/// Vertex bytes per encoded byte when every group of 16 values takes 0 bits.
+const MAX_COMPRESSION_RATIO: usize = 4 * BYTE_GROUP_SIZE;
+
+fn vertex_block_size(vertex_size: usize) -> usize {
+  ((VERTEX_BLOCK_SIZE_BYTES / vertex_size) & !(BYTE_GROUP_SIZE - 1)).min(VERTEX_BLOCK_MAX_SIZE)
+}
+
+fn zigzag(delta: u8) -> u8 {
+  (delta << 1) ^ ((delta as i8 >> 7) as u8)
+}
+
+fn unzigzag(value: u8) -> u8 {
+  (value >> 1) ^ (value & 1).wrapping_neg()
+}
+
+/// Encoded size of a group of 16 values with `bits` per value, including escaped bytes.
+fn group_size(group: &[u8], bits: u32) -> usize {
+  match bits {
+    0 if group.iter().all(|&v| v == 0) => 0,
+    0 => usize::MAX,
+    8 => BYTE_GROUP_SIZE,
+    _ => {
+      let sentinel = (1u8 << bits) - 1;
+      BYTE_GROUP_SIZE * bits as usize / 8 + group.iter().filter(|&&v| v >= sentinel).count()
+    }
+  }
+}
+
+fn encode_bytes(output: &mut Vec<u8>, buffer: &[u8]) {
+  let groups = buffer.len() / BYTE_GROUP_SIZE;
+  let header_start = output.len();
+  output.resize(header_start + groups.div_ceil(4), 0);
+
+  for (index, group) in buffer.chunks_exact(BYTE_GROUP_SIZE).enumerate() {
+    let (mode, bits) = [(0u8, 0u32), (1, 2), (2, 4), (3, 8)]
+      .into_iter()
+      .min_by_key(|&(_, bits)| group_size(group, bits))
+      .unwrap();
+    output[header_start + index / 4] |= mode << ((index % 4) * 2);
+
+    match bits {
+      0 => {}
+      8 => output.extend_from_slice(group),
+      _ => {
+        let sentinel = (1u8 << bits) - 1;
+        let per_byte = 8 / bits as usize;
+        let mut packed = vec![0u8; BYTE_GROUP_SIZE / per_byte];
+        for (i, &value) in group.iter().enumerate() {
+          let shift = 8 - bits as usize * (i % per_byte + 1);
+          packed[i / per_byte] |= value.min(sentinel) << shift;
+        }
+        output.extend_from_slice(&packed);
+        output.extend(group.iter().filter(|&&v| v >= sentinel));
+      }
+    }
+  }
+}
+
+fn decode_bytes(data: &[u8], position: &mut usize, buffer: &mut [u8]) -> Result<(), String> {
+  let truncated = || "Truncated meshopt vertex data".to_string();
+  let groups = buffer.len() / BYTE_GROUP_SIZE;
+  let header = data
+    .get(*position..*position + groups.div_ceil(4))
+    .ok_or_else(truncated)?;
+  *position += header.len();
+
+  for (index, group) in buffer.chunks_exact_mut(BYTE_GROUP_SIZE).enumerate() {
+    let bits = match (header[index / 4] >> ((index % 4) * 2)) & 3 {
+      0 => 0,
+      1 => 2,
+      2 => 4,
+      _ => 8,
+    };
+
+    match bits {
+      0 => group.fill(0),
+      8 => {
+        group.copy_from_slice(data.get(*position..*position + 16).ok_or_else(truncated)?);
+        *position += 16;
+      }
+      _ => {
+        let sentinel = (1u8 << bits) - 1;
+        let per_byte = 8 / bits;
+        let packed = data
+          .get(*position..*position + BYTE_GROUP_SIZE / per_byte)
+          .ok_or_else(truncated)?;
+        let mut escaped = *position + packed.len();
+        for (i, value) in group.iter_mut().enumerate() {
+          let shift = 8 - bits * (i % per_byte + 1);
+          *value = (packed[i / per_byte] >> shift) & sentinel;
+          if *value == sentinel {
+            *value = *data.get(escaped).ok_or_else(truncated)?;
+            escaped += 1;
+          }
+        }
+        *position = escaped;
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// Encodes `vertices`, made of `vertex_size` byte elements (a multiple of 4, at most 256).
+pub fn encode_vertex_buffer(vertices: &[u8], vertex_size: usize) -> Vec<u8> {
+  debug_assert!(vertex_size > 0 && vertex_size <= 256 && vertex_size.is_multiple_of(4));
+
+  let mut output = vec![VERTEX_HEADER];
+  let first_vertex = vertices
+    .get(..vertex_size)
+    .map_or_else(|| vec![0; vertex_size], <[u8]>::to_vec);
+  let mut last_vertex = first_vertex.clone();
+
+  for block in vertices.chunks(vertex_block_size(vertex_size) * vertex_size) {
+    let count = block.len() / vertex_size;
+    let mut buffer = vec![0u8; count.next_multiple_of(BYTE_GROUP_SIZE)];
+
+    for k in 0..vertex_size {
+      let mut previous = last_vertex[k];
+      for (i, value) in buffer.iter_mut().take(count).enumerate() {
+        let current = block[i * vertex_size + k];
+        *value = zigzag(current.wrapping_sub(previous));
+        previous = current;
+      }
+      encode_bytes(&mut output, &buffer);
+    }
+
+    last_vertex.copy_from_slice(&block[(count - 1) * vertex_size..count * vertex_size]);
+  }
+
+  // The first vertex goes at the end, padded to a minimum tail size
+  output.resize(output.len() + TAIL_MAX_SIZE.saturating_sub(vertex_size), 0);
+  output.extend_from_slice(&first_vertex);
+  output
+}
+
+/// Decodes `count` vertices of `vertex_size` bytes from data written by [`encode_vertex_buffer`]
+/// or by meshoptimizer's `meshopt_encodeVertexBuffer`.
+pub fn decode_vertex_buffer(
+  data: &[u8],
+  count: usize,
+  vertex_size: usize,
+) -> Result<Vec<u8>, String> {
+  if vertex_size == 0 || vertex_size > 256 || !vertex_size.is_multiple_of(4) {
+    return Err(format!("Invalid meshopt vertex size {}", vertex_size));
+  }
+  match data.first() {
+    Some(&header) if header == VERTEX_HEADER => {}
+    Some(&header) if header & 0xf0 == VERTEX_HEADER => {
+      return Err(format!(
+        "Unsupported meshopt vertex codec version {}",
+        header & 0x0f
+      ));
+    }
+    _ => return Err("Missing meshopt vertex codec header".to_string()),
+  }
+
+  let tail_size = TAIL_MAX_SIZE.max(vertex_size);
+  if data.len() < 1 + tail_size {
+    return Err("Truncated meshopt vertex data".to_string());
+  }
+  let mut last_vertex = data[data.len() - vertex_size..].to_vec();
+
+  // Bound the output by what the data can hold before allocating it
+  let size = count
+    .checked_mul(vertex_size)
+    .filter(|&size| size / MAX_COMPRESSION_RATIO <= data.len())
+    .ok_or_else(|| format!("Meshopt vertex data is too short for {} vertices", count))?;
+  let mut vertices = vec![0u8; size];
+  let mut position = 1;
+  let block_size = vertex_block_size(vertex_size);
+
+  for block in vertices.chunks_mut(block_size * vertex_size) {
+    let block_count = block.len() / vertex_size;
+    let mut buffer = vec![0u8; block_count.next_multiple_of(BYTE_GROUP_SIZE)];
+
+    for k in 0..vertex_size {
+      decode_bytes(data, &mut position, &mut buffer)?;
+      let mut previous = last_vertex[k];
+      for (i, &value) in buffer.iter().take(block_count).enumerate() {
+        previous = previous.wrapping_add(unzigzag(value));
+        block[i * vertex_size + k] = previous;
+      }
+    }
+
+    last_vertex.copy_from_slice(&block[(block_count - 1) * vertex_size..]);
+  }
+
+  if data.len() - position != tail_size {
+    return Err("Unexpected trailing meshopt vertex data".to_string());
+  }
+
+  Ok(vertices)
+}
@@ -1 +1,10 @@
+pub mod activation;
+pub mod bounds;
+pub mod kmeans;
+pub mod marching_cubes;
+pub mod meshopt;
+pub mod morton_order;
+pub mod nearest_neighbours;
 pub mod quaternion_multiplication;
+pub mod rotation;
+pub mod spherical_harmonics;
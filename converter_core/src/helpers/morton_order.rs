@@ -0,0 +1,46 @@
+use crate::Scene;
+
+/// Spreads the low 10 bits of `v` so that there are two zero bits between each.
+fn part_1_by_2(v: u32) -> u32 {
+  let mut x = v & 0x3ff;
+  x = (x | (x << 16)) & 0x030000ff;
+  x = (x | (x << 8)) & 0x0300f00f;
+  x = (x | (x << 4)) & 0x030c30c3;
+  x = (x | (x << 2)) & 0x09249249;
+  x
+}
+
+/// Splat indices sorted along a 3D Morton (Z-order) curve over the scene's bounding box,
+/// so that neighbouring indices are close in space.
+pub fn morton_order(scene: &Scene) -> Vec<usize> {
+  let mut min = [f32::INFINITY; 3];
+  let mut max = [f32::NEG_INFINITY; 3];
+  for splat in &scene.splats {
+    for (axis, value) in splat.position.into_iter().enumerate() {
+      min[axis] = min[axis].min(value);
+      max[axis] = max[axis].max(value);
+    }
+  }
+
+  let codes: Vec<u32> = scene
+    .splats
+    .iter()
+    .map(|splat| {
+      let q = [0, 1, 2].map(|axis| {
+        let extent = max[axis] - min[axis];
+        let t = if extent > 0.0 {
+          (splat.position[axis] - min[axis]) / extent
+        } else {
+          0.0
+        };
+        // NaN saturates to 0 when cast
+        (t * 1023.0).clamp(0.0, 1023.0) as u32
+      });
+      part_1_by_2(q[0]) | (part_1_by_2(q[1]) << 1) | (part_1_by_2(q[2]) << 2)
+    })
+    .collect();
+
+  let mut order: Vec<usize> = (0..scene.splats.len()).collect();
+  order.sort_by_key(|&i| codes[i]);
+  order
+}
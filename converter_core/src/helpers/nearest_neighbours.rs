@@ -0,0 +1,106 @@
+use rayon::prelude::*;
+
+/// A static k-d tree over 3D points, stored implicitly: every range of `indices` is split at its
+/// middle element along the axis `depth % 3`.
+struct KdTree<'a> {
+  points: &'a [[f32; 3]],
+  indices: Vec<usize>,
+}
+
+impl<'a> KdTree<'a> {
+  fn new(points: &'a [[f32; 3]]) -> Self {
+    let mut indices: Vec<usize> = (0..points.len()).collect();
+    Self::build(points, &mut indices, 0);
+    Self { points, indices }
+  }
+
+  fn build(points: &[[f32; 3]], indices: &mut [usize], depth: usize) {
+    if indices.len() <= 1 {
+      return;
+    }
+    let axis = depth % 3;
+    let mid = indices.len() / 2;
+    indices.select_nth_unstable_by(mid, |&a, &b| points[a][axis].total_cmp(&points[b][axis]));
+    let (left, right) = indices.split_at_mut(mid);
+    Self::build(points, left, depth + 1);
+    Self::build(points, &mut right[1..], depth + 1);
+  }
+
+  /// Squared distances and indices of the `k` nearest points other than `exclude`, in ascending
+  /// order of distance.
+  fn nearest(&self, query: [f32; 3], exclude: usize, k: usize) -> Vec<(f32, usize)> {
+    let mut best = Vec::with_capacity(k + 1);
+    self.search(&self.indices, 0, query, exclude, k, &mut best);
+    best
+  }
+
+  fn search(
+    &self,
+    indices: &[usize],
+    depth: usize,
+    query: [f32; 3],
+    exclude: usize,
+    k: usize,
+    best: &mut Vec<(f32, usize)>,
+  ) {
+    if indices.is_empty() {
+      return;
+    }
+    let mid = indices.len() / 2;
+    let index = indices[mid];
+    let point = self.points[index];
+
+    if index != exclude {
+      let distance: f32 = (0..3).map(|axis| (point[axis] - query[axis]).powi(2)).sum();
+      if best.len() < k || distance < best[best.len() - 1].0 {
+        let position = best.partition_point(|&(d, _)| d <= distance);
+        best.insert(position, (distance, index));
+        best.truncate(k);
+      }
+    }
+
+    let axis = depth % 3;
+    let delta = query[axis] - point[axis];
+    let (near, far) = if delta < 0.0 {
+      (&indices[..mid], &indices[mid + 1..])
+    } else {
+      (&indices[mid + 1..], &indices[..mid])
+    };
+
+    self.search(near, depth + 1, query, exclude, k, best);
+    if best.len() < k || delta * delta < best[best.len() - 1].0 {
+      self.search(far, depth + 1, query, exclude, k, best);
+    }
+  }
+}
+
+/// For every point, the mean squared distance to its `k` nearest neighbours (0 for a lone point).
+pub fn mean_squared_neighbour_distances(points: &[[f32; 3]], k: usize) -> Vec<f32> {
+  let tree = KdTree::new(points);
+  points
+    .par_iter()
+    .enumerate()
+    .map(|(i, &point)| {
+      let distances = tree.nearest(point, i, k);
+      if distances.is_empty() {
+        0.0
+      } else {
+        distances.iter().map(|(d, _)| d).sum::<f32>() / distances.len() as f32
+      }
+    })
+    .collect()
+}
+
+/// For every query, the index of the nearest of `points` (`None` when `points` is empty).
+pub fn nearest_indices(points: &[[f32; 3]], queries: &[[f32; 3]]) -> Vec<Option<usize>> {
+  let tree = KdTree::new(points);
+  queries
+    .par_iter()
+    .map(|&query| {
+      tree
+        .nearest(query, usize::MAX, 1)
+        .first()
+        .map(|&(_, index)| index)
+    })
+    .collect()
+}
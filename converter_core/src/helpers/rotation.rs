@@ -0,0 +1,69 @@
+/// Rotation matrix (row-major) of a `[w, x, y, z]` quaternion, which is normalised first.
+/// A zero quaternion gives the identity.
+pub fn quaternion_to_matrix(rotation: [f32; 4]) -> [[f32; 3]; 3] {
+  let length = rotation.iter().map(|v| v * v).sum::<f32>().sqrt();
+  if length == 0.0 || !length.is_finite() {
+    return [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+  }
+  let [w, x, y, z] = rotation.map(|v| v / length);
+
+  [
+    [
+      1.0 - 2.0 * (y * y + z * z),
+      2.0 * (x * y - w * z),
+      2.0 * (x * z + w * y),
+    ],
+    [
+      2.0 * (x * y + w * z),
+      1.0 - 2.0 * (x * x + z * z),
+      2.0 * (y * z - w * x),
+    ],
+    [
+      2.0 * (x * z - w * y),
+      2.0 * (y * z + w * x),
+      1.0 - 2.0 * (x * x + y * y),
+    ],
+  ]
+}
+
+/// `[w, x, y, z]` unit quaternion of a rotation matrix (row-major), with `w >= 0`.
+pub fn matrix_to_quaternion(m: [[f32; 3]; 3]) -> [f32; 4] {
+  let trace = m[0][0] + m[1][1] + m[2][2];
+  // Shepperd's method: divide by the largest of the four candidates for stability
+  let q = if trace > 0.0 {
+    let s = (trace + 1.0).sqrt() * 2.0;
+    [
+      s / 4.0,
+      (m[2][1] - m[1][2]) / s,
+      (m[0][2] - m[2][0]) / s,
+      (m[1][0] - m[0][1]) / s,
+    ]
+  } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+    let s = (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt() * 2.0;
+    [
+      (m[2][1] - m[1][2]) / s,
+      s / 4.0,
+      (m[0][1] + m[1][0]) / s,
+      (m[0][2] + m[2][0]) / s,
+    ]
+  } else if m[1][1] > m[2][2] {
+    let s = (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt() * 2.0;
+    [
+      (m[0][2] - m[2][0]) / s,
+      (m[0][1] + m[1][0]) / s,
+      s / 4.0,
+      (m[1][2] + m[2][1]) / s,
+    ]
+  } else {
+    let s = (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt() * 2.0;
+    [
+      (m[1][0] - m[0][1]) / s,
+      (m[0][2] + m[2][0]) / s,
+      (m[1][2] + m[2][1]) / s,
+      s / 4.0,
+    ]
+  };
+  let length = q.iter().map(|v| v * v).sum::<f32>().sqrt();
+  let sign = if q[0] < 0.0 { -1.0 } else { 1.0 };
+  q.map(|v| v * sign / length)
+}
@@ -0,0 +1,87 @@
+use crate::GaussianSplat;
+
+pub const SH_C0: f32 = 0.282_094_8;
+const SH_C1: f32 = 0.488_602_52;
+const SH_C2: [f32; 5] = [
+  1.092_548_4,
+  -1.092_548_4,
+  0.315_391_57,
+  -1.092_548_4,
+  0.546_274_2,
+];
+const SH_C3: [f32; 7] = [
+  -0.590_043_6,
+  2.890_611_4,
+  -0.457_045_8,
+  0.373_176_34,
+  -0.457_045_8,
+  1.445_305_7,
+  -0.590_043_6,
+];
+
+/// Evaluates the splat's colour seen along `dir` (camera -> splat, normalized),
+/// following the reference 3DGS renderer: the result is offset by 0.5 and clamped at 0.
+pub fn evaluate_sh_color(splat: &GaussianSplat, dir: [f32; 3]) -> [f32; 3] {
+  let degree = splat.sh_degree();
+  let [x, y, z] = dir;
+  let (xx, yy, zz) = (x * x, y * y, z * z);
+  let (xy, yz, xz) = (x * y, y * z, x * z);
+
+  let mut color = [0.0f32; 3];
+  for (channel, value) in color.iter_mut().enumerate() {
+    let sh = |k: usize| splat.sh_rest(channel, k - 1);
+    let mut result = SH_C0 * splat.spherical_harmonics_dc[channel];
+
+    if degree > 0 {
+      result += -SH_C1 * y * sh(1) + SH_C1 * z * sh(2) - SH_C1 * x * sh(3);
+
+      if degree > 1 {
+        result += SH_C2[0] * xy * sh(4)
+          + SH_C2[1] * yz * sh(5)
+          + SH_C2[2] * (2.0 * zz - xx - yy) * sh(6)
+          + SH_C2[3] * xz * sh(7)
+          + SH_C2[4] * (xx - yy) * sh(8);
+
+        if degree > 2 {
+          result += SH_C3[0] * y * (3.0 * xx - yy) * sh(9)
+            + SH_C3[1] * xy * z * sh(10)
+            + SH_C3[2] * y * (4.0 * zz - xx - yy) * sh(11)
+            + SH_C3[3] * z * (2.0 * zz - 3.0 * xx - 3.0 * yy) * sh(12)
+            + SH_C3[4] * x * (4.0 * zz - xx - yy) * sh(13)
+            + SH_C3[5] * z * (xx - yy) * sh(14)
+            + SH_C3[6] * x * (xx - 3.0 * yy) * sh(15);
+        }
+      }
+    }
+
+    *value = (result + 0.5).max(0.0);
+  }
+
+  color
+}
+
+/// Values of the 15 higher-order SH basis functions (degrees 1-3) along `dir`, in the order and
+/// with the signs `evaluate_sh_color` uses for the coefficients.
+pub fn sh_basis(dir: [f32; 3]) -> [f32; 15] {
+  let [x, y, z] = dir;
+  let (xx, yy, zz) = (x * x, y * y, z * z);
+  let (xy, yz, xz) = (x * y, y * z, x * z);
+
+  [
+    -SH_C1 * y,
+    SH_C1 * z,
+    -SH_C1 * x,
+    SH_C2[0] * xy,
+    SH_C2[1] * yz,
+    SH_C2[2] * (2.0 * zz - xx - yy),
+    SH_C2[3] * xz,
+    SH_C2[4] * (xx - yy),
+    SH_C3[0] * y * (3.0 * xx - yy),
+    SH_C3[1] * xy * z,
+    SH_C3[2] * y * (4.0 * zz - xx - yy),
+    SH_C3[3] * z * (2.0 * zz - 3.0 * xx - 3.0 * yy),
+    SH_C3[4] * x * (4.0 * zz - xx - yy),
+    SH_C3[5] * z * (xx - yy),
+    SH_C3[6] * x * (xx - 3.0 * yy),
+  ]
+}
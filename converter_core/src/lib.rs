@@ -1,7 +1,13 @@
+pub mod camera;
 pub mod error;
 pub mod formats;
 pub mod helpers;
+pub mod metrics;
+pub mod operations;
+pub mod render;
+pub mod stats;
 
+pub use camera::Camera;
 pub use error::ConversionError;
 
 #[derive(Debug, Clone)]
@@ -15,9 +21,52 @@ pub struct GaussianSplat {
   pub rotation: [f32; 4], // Quaternion (w, x, y, z)
 }
 
+/// Number of higher-order spherical harmonics coefficients stored per colour channel for an SH `degree`.
+pub fn sh_coefficients_per_channel(degree: u8) -> usize {
+  (degree as usize + 1).pow(2) - 1
+}
+
+impl GaussianSplat {
+  /// Higher-order SH coefficient `index` of `channel` (0 = R, 1 = G, 2 = B).
+  /// `spherical_harmonics_rest` is stored channel-major like the `f_rest_*` PLY properties,
+  /// so coefficients above the splat's degree read as 0.
+  pub fn sh_rest(&self, channel: usize, index: usize) -> f32 {
+    let per_channel = self.spherical_harmonics_rest.len() / 3;
+    if index < per_channel {
+      self.spherical_harmonics_rest[channel * per_channel + index]
+    } else {
+      0.0
+    }
+  }
+
+  /// The SH degree (0-3) implied by the length of `spherical_harmonics_rest`.
+  pub fn sh_degree(&self) -> u8 {
+    match self.spherical_harmonics_rest.len() / 3 {
+      0 => 0,
+      1..=3 => 1,
+      4..=8 => 2,
+      _ => 3,
+    }
+  }
+}
+
 #[derive(Debug, Clone)]
 pub struct Scene {
   pub splats: Vec<GaussianSplat>,
+  /// Capture or viewing cameras stored with the scene, if the source format has them.
+  pub cameras: Vec<Camera>,
+}
+
+impl Scene {
+  /// The highest SH degree of any splat in the scene.
+  pub fn sh_degree(&self) -> u8 {
+    self
+      .splats
+      .iter()
+      .map(GaussianSplat::sh_degree)
+      .max()
+      .unwrap_or(0)
+  }
 }
 
 /// A trait for any object that can read a byte stream and produce a `Scene`.
@@ -0,0 +1,220 @@
+//! Fidelity metrics for comparing a scene with a converted copy of it: image metrics on renders of
+//! both scenes and numeric errors of the splat attributes.
+
+use image::RgbaImage;
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::helpers::activation::sigmoid;
+use crate::helpers::nearest_neighbours::nearest_indices;
+use crate::helpers::spherical_harmonics::SH_C0;
+use crate::{GaussianSplat, Scene};
+
+const SSIM_WINDOW_RADIUS: usize = 5;
+const SSIM_SIGMA: f64 = 1.5;
+const SSIM_C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+const SSIM_C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+
+/// Peak signal-to-noise ratio in dB over the RGB channels of two images of the same size
+/// (alpha is ignored). Identical images give infinity.
+pub fn psnr(a: &RgbaImage, b: &RgbaImage) -> f64 {
+  assert_eq!(a.dimensions(), b.dimensions());
+  let (sum, count) = a
+    .pixels()
+    .zip(b.pixels())
+    .flat_map(|(p, q)| (0..3).map(move |c| (p[c] as f64 - q[c] as f64).powi(2)))
+    .fold((0.0, 0usize), |(sum, count), e| (sum + e, count + 1));
+  let mse = sum / count.max(1) as f64;
+  if mse == 0.0 {
+    f64::INFINITY
+  } else {
+    10.0 * (255.0 * 255.0 / mse).log10()
+  }
+}
+
+/// Structural similarity (Wang et al. 2004) of two images of the same size, with the usual 11x11
+/// Gaussian window (sigma 1.5), averaged over the RGB channels and all pixels whose window lies
+/// inside the image. Images smaller than the window are compared as one window.
+pub fn ssim(a: &RgbaImage, b: &RgbaImage) -> f64 {
+  assert_eq!(a.dimensions(), b.dimensions());
+  let (width, height) = (a.width() as usize, a.height() as usize);
+  if width == 0 || height == 0 {
+    return 1.0;
+  }
+  let radius = SSIM_WINDOW_RADIUS.min((width.min(height) - 1) / 2);
+  let weights: Vec<f64> = (0..=2 * radius)
+    .map(|i| (-((i as f64 - radius as f64).powi(2)) / (2.0 * SSIM_SIGMA * SSIM_SIGMA)).exp())
+    .collect();
+  let total: f64 = weights.iter().sum();
+  let weights: Vec<f64> = weights.iter().map(|w| w / total).collect();
+
+  let channels: Vec<f64> = (0..3)
+    .into_par_iter()
+    .map(|c| {
+      let x: Vec<f64> = a.pixels().map(|p| p[c] as f64).collect();
+      let y: Vec<f64> = b.pixels().map(|p| p[c] as f64).collect();
+      let product = |p: &[f64], q: &[f64]| p.iter().zip(q).map(|(p, q)| p * q).collect::<Vec<_>>();
+
+      let blur = |image: &[f64]| filter(image, width, height, &weights);
+      let (mu_x, mu_y) = (blur(&x), blur(&y));
+      let (xx, yy, xy) = (
+        blur(&product(&x, &x)),
+        blur(&product(&y, &y)),
+        blur(&product(&x, &y)),
+      );
+
+      let values: Vec<f64> = (0..mu_x.len())
+        .map(|i| {
+          let (mx, my) = (mu_x[i], mu_y[i]);
+          let var_x = xx[i] - mx * mx;
+          let var_y = yy[i] - my * my;
+          let covariance = xy[i] - mx * my;
+          ((2.0 * mx * my + SSIM_C1) * (2.0 * covariance + SSIM_C2))
+            / ((mx * mx + my * my + SSIM_C1) * (var_x + var_y + SSIM_C2))
+        })
+        .collect();
+      values.iter().sum::<f64>() / values.len() as f64
+    })
+    .collect();
+  channels.iter().sum::<f64>() / 3.0
+}
+
+/// Separable filter keeping only the "valid" region where the window fits in the image.
+fn filter(image: &[f64], width: usize, height: usize, weights: &[f64]) -> Vec<f64> {
+  let size = weights.len();
+  let (out_width, out_height) = (width + 1 - size, height + 1 - size);
+
+  let mut rows = vec![0.0; out_width * height];
+  for y in 0..height {
+    for x in 0..out_width {
+      rows[y * out_width + x] = (0..size)
+        .map(|k| weights[k] * image[y * width + x + k])
+        .sum();
+    }
+  }
+  let mut out = vec![0.0; out_width * out_height];
+  for y in 0..out_height {
+    for x in 0..out_width {
+      out[y * out_width + x] = (0..size)
+        .map(|k| weights[k] * rows[(y + k) * out_width + x])
+        .sum();
+    }
+  }
+  out
+}
+
+/// Summary of the absolute errors of one attribute over all matched splats.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ErrorStats {
+  pub mean: f64,
+  pub rmse: f64,
+  pub max: f64,
+}
+
+impl ErrorStats {
+  fn from_errors(errors: &[f64]) -> Self {
+    if errors.is_empty() {
+      return Self::default();
+    }
+    let count = errors.len() as f64;
+    Self {
+      mean: errors.iter().sum::<f64>() / count,
+      rmse: (errors.iter().map(|e| e * e).sum::<f64>() / count).sqrt(),
+      max: errors.iter().cloned().fold(0.0, f64::max),
+    }
+  }
+}
+
+/// Per-attribute errors of a converted scene against its reference.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AttributeErrors {
+  /// Number of splats in the reference and the converted scene.
+  pub reference_count: usize,
+  pub test_count: usize,
+  /// Distance between the splat centres.
+  pub position: ErrorStats,
+  /// Base colour (0..1) difference, per channel.
+  pub color: ErrorStats,
+  /// Activated opacity (0..1) difference.
+  pub opacity: ErrorStats,
+  /// Difference of the log scales, per axis.
+  pub log_scale: ErrorStats,
+  /// Angle between the rotations, in degrees.
+  pub rotation_degrees: ErrorStats,
+  /// Higher-order SH coefficient difference, over the coefficients of the reference.
+  pub spherical_harmonics: ErrorStats,
+}
+
+fn base_color(splat: &GaussianSplat) -> [f32; 3] {
+  splat.spherical_harmonics_dc.map(|dc| 0.5 + SH_C0 * dc)
+}
+
+fn rotation_angle_degrees(a: [f32; 4], b: [f32; 4]) -> f64 {
+  let norm = |q: [f32; 4]| q.iter().map(|v| (*v as f64).powi(2)).sum::<f64>().sqrt();
+  let (na, nb) = (norm(a), norm(b));
+  if na == 0.0 || nb == 0.0 {
+    return 0.0;
+  }
+  let dot: f64 = a.iter().zip(&b).map(|(p, q)| *p as f64 * *q as f64).sum();
+  // q and -q are the same rotation
+  2.0 * (dot.abs() / (na * nb)).min(1.0).acos().to_degrees()
+}
+
+/// Compares every splat of `reference` with the splat of `test` nearest to it. Lossy formats
+/// reorder splats, so they are matched by position rather than index.
+pub fn attribute_errors(reference: &Scene, test: &Scene) -> AttributeErrors {
+  let mut errors = AttributeErrors {
+    reference_count: reference.splats.len(),
+    test_count: test.splats.len(),
+    ..Default::default()
+  };
+  let test_positions: Vec<[f32; 3]> = test.splats.iter().map(|s| s.position).collect();
+  let reference_positions: Vec<[f32; 3]> = reference.splats.iter().map(|s| s.position).collect();
+  let matches = nearest_indices(&test_positions, &reference_positions);
+
+  let pairs: Vec<(&GaussianSplat, &GaussianSplat)> = reference
+    .splats
+    .iter()
+    .zip(matches)
+    .filter_map(|(splat, index)| Some((splat, &test.splats[index?])))
+    .collect();
+  let collect = |error: &(dyn Fn(&GaussianSplat, &GaussianSplat) -> Vec<f64> + Sync)| {
+    let values: Vec<f64> = pairs.par_iter().flat_map(|(a, b)| error(a, b)).collect();
+    ErrorStats::from_errors(&values)
+  };
+
+  errors.position = collect(&|a, b| {
+    vec![
+      (0..3)
+        .map(|i| (a.position[i] as f64 - b.position[i] as f64).powi(2))
+        .sum::<f64>()
+        .sqrt(),
+    ]
+  });
+  errors.color = collect(&|a, b| {
+    let (ca, cb) = (base_color(a), base_color(b));
+    (0..3)
+      .map(|i| (ca[i] as f64 - cb[i] as f64).abs())
+      .collect()
+  });
+  errors.opacity =
+    collect(&|a, b| vec![(sigmoid(a.opacity) as f64 - sigmoid(b.opacity) as f64).abs()]);
+  errors.log_scale = collect(&|a, b| {
+    (0..3)
+      .map(|i| (a.scale[i] as f64 - b.scale[i] as f64).abs())
+      .collect()
+  });
+  errors.rotation_degrees = collect(&|a, b| vec![rotation_angle_degrees(a.rotation, b.rotation)]);
+  errors.spherical_harmonics = collect(&|a, b| {
+    let per_channel = a.spherical_harmonics_rest.len() / 3;
+    (0..3)
+      .flat_map(|channel| {
+        (0..per_channel).map(move |index| {
+          (a.sh_rest(channel, index) as f64 - b.sh_rest(channel, index) as f64).abs()
+        })
+      })
+      .collect()
+  });
+
+  errors
+}
@@ -0,0 +1,698 @@
+//! A small expression language over splat attributes, e.g.
+//! `opacity > 0.1 and max_scale < 0.5 and z > 0`. Expressions are compiled once into a tree with
+//! the attribute names resolved, then evaluated per splat.
+//!
+//! Numbers combine with `+ - * / % ^` and the functions `abs`, `sqrt`, `exp`, `log`, `min` and
+//! `max`. Comparisons (`< <= > >= == !=`, chained like `-1 < x < 1`) give conditions, which
+//! combine with `and`, `or` and `not` (or `&&`, `||` and `!`).
+
+use std::fmt;
+use std::str::FromStr;
+
+use rayon::prelude::*;
+
+use crate::helpers::activation::sigmoid;
+use crate::helpers::spherical_harmonics::SH_C0;
+use crate::{ConversionError, GaussianSplat, Scene};
+
+/// Attributes an expression can use, with their meaning, for help texts.
+pub const ATTRIBUTES: [(&str, &str); 12] = [
+  ("x, y, z", "position"),
+  ("nx, ny, nz", "normal"),
+  ("opacity", "activated opacity, 0..1"),
+  ("scale_x, scale_y, scale_z", "linear scale along each axis"),
+  ("max_scale, min_scale", "largest and smallest linear scale"),
+  ("r, g, b", "base colour, 0..1"),
+  ("distance", "distance from the origin"),
+  ("sh_degree", "spherical harmonics degree, 0-3"),
+  ("f_dc_0..2", "stored base colour coefficients"),
+  (
+    "f_rest_0..44",
+    "stored higher-order SH coefficients in PLY order, 0 past the last",
+  ),
+  ("scale_0..2", "stored (log) scales"),
+  ("rot_0..3", "stored rotation quaternion, w first"),
+];
+
+/// A compiled condition over splat attributes.
+#[derive(Debug, Clone)]
+pub struct Expression {
+  source: String,
+  root: Condition,
+}
+
+impl Expression {
+  /// Parses `source` and resolves its attributes.
+  pub fn compile(source: &str) -> Result<Self, ConversionError> {
+    let tokens = tokenize(source)?;
+    let mut parser = Parser {
+      tokens,
+      index: 0,
+      depth: 0,
+    };
+    let term = parser.or()?;
+    let (token, column) = parser.peek();
+    if *token != Token::End {
+      return Err(parse_error(column, format!("unexpected {}", token)));
+    }
+    match term {
+      Term::Condition(root) => Ok(Self {
+        source: source.trim().to_string(),
+        root,
+      }),
+      Term::Number(_) => Err(parse_error(
+        0,
+        "the expression must be a condition, e.g. opacity > 0.1".to_string(),
+      )),
+    }
+  }
+
+  /// The text the expression was compiled from.
+  pub fn source(&self) -> &str {
+    &self.source
+  }
+
+  /// Whether the condition holds for `splat`.
+  pub fn matches(&self, splat: &GaussianSplat) -> bool {
+    self.root.evaluate(splat)
+  }
+
+  /// Evaluates the condition for every splat of `scene` in parallel.
+  pub fn evaluate(&self, scene: &Scene) -> Vec<bool> {
+    scene
+      .splats
+      .par_iter()
+      .map(|splat| self.matches(splat))
+      .collect()
+  }
+}
+
+impl FromStr for Expression {
+  type Err = ConversionError;
+
+  fn from_str(source: &str) -> Result<Self, Self::Err> {
+    Self::compile(source)
+  }
+}
+
+/// Expressions are equal when they were compiled from the same text.
+impl PartialEq for Expression {
+  fn eq(&self, other: &Self) -> bool {
+    self.source == other.source
+  }
+}
+
+impl fmt::Display for Expression {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.source)
+  }
+}
+
+fn parse_error(column: usize, message: String) -> ConversionError {
+  ConversionError::ParseError {
+    format: "Expression".to_string(),
+    message: format!("{} (at column {})", message, column + 1),
+  }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Attribute {
+  Position(usize),
+  Normal(usize),
+  Opacity,
+  Scale(usize),
+  MaxScale,
+  MinScale,
+  Color(usize),
+  Distance,
+  ShDegree,
+  Dc(usize),
+  Rest(usize),
+  LogScale(usize),
+  Rotation(usize),
+}
+
+impl Attribute {
+  fn from_name(name: &str) -> Option<Self> {
+    let attribute = match name {
+      "x" => Attribute::Position(0),
+      "y" => Attribute::Position(1),
+      "z" => Attribute::Position(2),
+      "nx" => Attribute::Normal(0),
+      "ny" => Attribute::Normal(1),
+      "nz" => Attribute::Normal(2),
+      "opacity" => Attribute::Opacity,
+      "scale_x" => Attribute::Scale(0),
+      "scale_y" => Attribute::Scale(1),
+      "scale_z" => Attribute::Scale(2),
+      "max_scale" => Attribute::MaxScale,
+      "min_scale" => Attribute::MinScale,
+      "r" => Attribute::Color(0),
+      "g" => Attribute::Color(1),
+      "b" => Attribute::Color(2),
+      "distance" => Attribute::Distance,
+      "sh_degree" => Attribute::ShDegree,
+      _ => {
+        // Stored values under their PLY property names
+        let indexed = |prefix: &str, count: usize| {
+          name
+            .strip_prefix(prefix)
+            .and_then(|index| index.parse::<usize>().ok())
+            .filter(|&index| index < count)
+        };
+        if let Some(index) = indexed("f_dc_", 3) {
+          Attribute::Dc(index)
+        } else if let Some(index) = indexed("f_rest_", 45) {
+          Attribute::Rest(index)
+        } else if let Some(index) = indexed("scale_", 3) {
+          Attribute::LogScale(index)
+        } else if let Some(index) = indexed("rot_", 4) {
+          Attribute::Rotation(index)
+        } else {
+          return None;
+        }
+      }
+    };
+    Some(attribute)
+  }
+
+  fn value(self, splat: &GaussianSplat) -> f32 {
+    match self {
+      Attribute::Position(axis) => splat.position[axis],
+      Attribute::Normal(axis) => splat.normal[axis],
+      Attribute::Opacity => sigmoid(splat.opacity),
+      Attribute::Scale(axis) => splat.scale[axis].exp(),
+      Attribute::MaxScale => splat
+        .scale
+        .iter()
+        .copied()
+        .fold(f32::NEG_INFINITY, f32::max)
+        .exp(),
+      Attribute::MinScale => splat
+        .scale
+        .iter()
+        .copied()
+        .fold(f32::INFINITY, f32::min)
+        .exp(),
+      Attribute::Color(channel) => 0.5 + SH_C0 * splat.spherical_harmonics_dc[channel],
+      Attribute::Distance => splat.position.iter().map(|v| v * v).sum::<f32>().sqrt(),
+      Attribute::ShDegree => splat.sh_degree() as f32,
+      Attribute::Dc(channel) => splat.spherical_harmonics_dc[channel],
+      Attribute::Rest(index) => splat
+        .spherical_harmonics_rest
+        .get(index)
+        .copied()
+        .unwrap_or(0.0),
+      Attribute::LogScale(axis) => splat.scale[axis],
+      Attribute::Rotation(index) => splat.rotation[index],
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Function {
+  Abs,
+  Sqrt,
+  Exp,
+  Log,
+  Min,
+  Max,
+}
+
+impl Function {
+  fn from_name(name: &str) -> Option<Self> {
+    Some(match name {
+      "abs" => Function::Abs,
+      "sqrt" => Function::Sqrt,
+      "exp" => Function::Exp,
+      "log" => Function::Log,
+      "min" => Function::Min,
+      "max" => Function::Max,
+      _ => return None,
+    })
+  }
+
+  fn takes(self, arguments: usize) -> bool {
+    match self {
+      Function::Min | Function::Max => arguments >= 2,
+      _ => arguments == 1,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Arithmetic {
+  Add,
+  Subtract,
+  Multiply,
+  Divide,
+  Remainder,
+  Power,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Comparison {
+  Less,
+  LessOrEqual,
+  Greater,
+  GreaterOrEqual,
+  Equal,
+  NotEqual,
+}
+
+#[derive(Debug, Clone)]
+enum Number {
+  Constant(f32),
+  Attribute(Attribute),
+  Negate(Box<Number>),
+  Binary(Arithmetic, Box<Number>, Box<Number>),
+  Call(Function, Vec<Number>),
+}
+
+impl Number {
+  fn evaluate(&self, splat: &GaussianSplat) -> f32 {
+    match self {
+      Number::Constant(value) => *value,
+      Number::Attribute(attribute) => attribute.value(splat),
+      Number::Negate(value) => -value.evaluate(splat),
+      Number::Binary(operator, left, right) => {
+        let (a, b) = (left.evaluate(splat), right.evaluate(splat));
+        match operator {
+          Arithmetic::Add => a + b,
+          Arithmetic::Subtract => a - b,
+          Arithmetic::Multiply => a * b,
+          Arithmetic::Divide => a / b,
+          Arithmetic::Remainder => a % b,
+          Arithmetic::Power => a.powf(b),
+        }
+      }
+      Number::Call(function, arguments) => {
+        let mut values = arguments.iter().map(|argument| argument.evaluate(splat));
+        match function {
+          Function::Abs => values.next().unwrap().abs(),
+          Function::Sqrt => values.next().unwrap().sqrt(),
+          Function::Exp => values.next().unwrap().exp(),
+          Function::Log => values.next().unwrap().ln(),
+          Function::Min => values.fold(f32::INFINITY, f32::min),
+          Function::Max => values.fold(f32::NEG_INFINITY, f32::max),
+        }
+      }
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+enum Condition {
+  Constant(bool),
+  Compare(Comparison, Number, Number),
+  And(Box<Condition>, Box<Condition>),
+  Or(Box<Condition>, Box<Condition>),
+  Not(Box<Condition>),
+}
+
+impl Condition {
+  fn evaluate(&self, splat: &GaussianSplat) -> bool {
+    match self {
+      Condition::Constant(value) => *value,
+      Condition::Compare(operator, left, right) => {
+        let (a, b) = (left.evaluate(splat), right.evaluate(splat));
+        match operator {
+          Comparison::Less => a < b,
+          Comparison::LessOrEqual => a <= b,
+          Comparison::Greater => a > b,
+          Comparison::GreaterOrEqual => a >= b,
+          Comparison::Equal => a == b,
+          Comparison::NotEqual => a != b,
+        }
+      }
+      Condition::And(left, right) => left.evaluate(splat) && right.evaluate(splat),
+      Condition::Or(left, right) => left.evaluate(splat) || right.evaluate(splat),
+      Condition::Not(value) => !value.evaluate(splat),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+  Number(f32),
+  Name(String),
+  Symbol(&'static str),
+  End,
+}
+
+impl fmt::Display for Token {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Token::Number(value) => write!(f, "number {}", value),
+      Token::Name(name) => write!(f, "'{}'", name),
+      Token::Symbol(symbol) => write!(f, "'{}'", symbol),
+      Token::End => write!(f, "end of expression"),
+    }
+  }
+}
+
+/// Longest first, so that `<=` isn't read as `<` and `=`.
+const SYMBOLS: [&str; 19] = [
+  "<=", ">=", "==", "!=", "&&", "||", "<", ">", "!", "+", "-", "*", "/", "%", "^", "(", ")", ",",
+  "=",
+];
+
+/// The tokens of `source` with the column each starts at, ending with `Token::End`.
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, ConversionError> {
+  let mut tokens = Vec::new();
+  let mut rest = source;
+  loop {
+    let trimmed = rest.trim_start();
+    let column = source.len() - trimmed.len();
+    rest = trimmed;
+    let Some(first) = rest.chars().next() else {
+      tokens.push((Token::End, column));
+      return Ok(tokens);
+    };
+
+    if first.is_ascii_digit() || first == '.' {
+      let mut end = 0;
+      let bytes = rest.as_bytes();
+      while end < bytes.len() {
+        let c = bytes[end];
+        let exponent_sign =
+          (c == b'+' || c == b'-') && end > 0 && matches!(bytes[end - 1], b'e' | b'E');
+        if c.is_ascii_digit() || c == b'.' || c == b'e' || c == b'E' || exponent_sign {
+          end += 1;
+        } else {
+          break;
+        }
+      }
+      let text = &rest[..end];
+      let value = text
+        .parse::<f32>()
+        .map_err(|_| parse_error(column, format!("invalid number '{}'", text)))?;
+      tokens.push((Token::Number(value), column));
+      rest = &rest[end..];
+    } else if first.is_alphabetic() || first == '_' {
+      let end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+      tokens.push((Token::Name(rest[..end].to_string()), column));
+      rest = &rest[end..];
+    } else if let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol)) {
+      if *symbol == "=" {
+        return Err(parse_error(column, "use '==' to compare".to_string()));
+      }
+      tokens.push((Token::Symbol(symbol), column));
+      rest = &rest[symbol.len()..];
+    } else {
+      return Err(parse_error(
+        column,
+        format!("unexpected character '{}'", first),
+      ));
+    }
+  }
+}
+
+/// A parsed subexpression, typed so that numbers and conditions can't be mixed up.
+enum Term {
+  Number(Number),
+  Condition(Condition),
+}
+
+/// How deep the terms of an expression may nest, so that neither parsing nor evaluating them runs
+/// out of stack.
+const MAX_DEPTH: usize = 64;
+
+/// Recursive descent over the tokens, from the loosest binding operator to the tightest.
+struct Parser {
+  tokens: Vec<(Token, usize)>,
+  index: usize,
+  /// Depth of the term being parsed, counting operands and chained operators alike.
+  depth: usize,
+}
+
+impl Parser {
+  /// Goes one level deeper, failing past `MAX_DEPTH`. Callers restore the depth they started at.
+  fn descend(&mut self, column: usize) -> Result<(), ConversionError> {
+    self.depth += 1;
+    if self.depth > MAX_DEPTH {
+      return Err(parse_error(
+        column,
+        format!("the expression nests deeper than {} levels", MAX_DEPTH),
+      ));
+    }
+    Ok(())
+  }
+
+  fn peek(&self) -> (&Token, usize) {
+    let (token, column) = &self.tokens[self.index];
+    (token, *column)
+  }
+
+  fn next(&mut self) -> (Token, usize) {
+    let token = self.tokens[self.index].clone();
+    if token.0 != Token::End {
+      self.index += 1;
+    }
+    token
+  }
+
+  /// Consumes the next token if it is one of `symbols` (or the word of the same meaning).
+  fn accept(&mut self, symbols: &[&str]) -> Option<(&'static str, usize)> {
+    let (token, column) = self.peek();
+    let symbol = match token {
+      Token::Symbol(symbol) if symbols.contains(symbol) => *symbol,
+      Token::Name(word) => {
+        let symbol = match word.as_str() {
+          "and" => "&&",
+          "or" => "||",
+          "not" => "!",
+          _ => return None,
+        };
+        if !symbols.contains(&symbol) {
+          return None;
+        }
+        symbol
+      }
+      _ => return None,
+    };
+    self.index += 1;
+    Some((symbol, column))
+  }
+
+  fn or(&mut self) -> Result<Term, ConversionError> {
+    let depth = self.depth;
+    let mut left = self.and()?;
+    while let Some((_, column)) = self.accept(&["||"]) {
+      self.descend(column)?;
+      let right = self.and()?;
+      left = Term::Condition(Condition::Or(
+        Box::new(condition(left, column, "or")?),
+        Box::new(condition(right, column, "or")?),
+      ));
+    }
+    self.depth = depth;
+    Ok(left)
+  }
+
+  fn and(&mut self) -> Result<Term, ConversionError> {
+    let depth = self.depth;
+    let mut left = self.not()?;
+    while let Some((_, column)) = self.accept(&["&&"]) {
+      self.descend(column)?;
+      let right = self.not()?;
+      left = Term::Condition(Condition::And(
+        Box::new(condition(left, column, "and")?),
+        Box::new(condition(right, column, "and")?),
+      ));
+    }
+    self.depth = depth;
+    Ok(left)
+  }
+
+  fn not(&mut self) -> Result<Term, ConversionError> {
+    if let Some((_, column)) = self.accept(&["!"]) {
+      self.descend(column)?;
+      let value = self.not()?;
+      self.depth -= 1;
+      return Ok(Term::Condition(Condition::Not(Box::new(condition(
+        value, column, "not",
+      )?))));
+    }
+    self.comparison()
+  }
+
+  /// A sum, or a chain of comparisons between sums where `a < b < c` means `a < b and b < c`.
+  fn comparison(&mut self) -> Result<Term, ConversionError> {
+    let depth = self.depth;
+    let first = self.sum()?;
+    let mut result: Option<Condition> = None;
+    let mut left = first;
+    while let Some((symbol, column)) = self.accept(&["<", "<=", ">", ">=", "==", "!="]) {
+      self.descend(column)?;
+      let operator = match symbol {
+        "<" => Comparison::Less,
+        "<=" => Comparison::LessOrEqual,
+        ">" => Comparison::Greater,
+        ">=" => Comparison::GreaterOrEqual,
+        "==" => Comparison::Equal,
+        _ => Comparison::NotEqual,
+      };
+      let a = number(left, column, symbol)?;
+      let b = number(self.sum()?, column, symbol)?;
+      let compare = Condition::Compare(operator, a, b.clone());
+      result = Some(match result {
+        Some(previous) => Condition::And(Box::new(previous), Box::new(compare)),
+        None => compare,
+      });
+      left = Term::Number(b);
+    }
+    self.depth = depth;
+    Ok(match result {
+      Some(condition) => Term::Condition(condition),
+      None => left,
+    })
+  }
+
+  fn sum(&mut self) -> Result<Term, ConversionError> {
+    let depth = self.depth;
+    let mut left = self.product()?;
+    while let Some((symbol, column)) = self.accept(&["+", "-"]) {
+      self.descend(column)?;
+      let operator = if symbol == "+" {
+        Arithmetic::Add
+      } else {
+        Arithmetic::Subtract
+      };
+      let right = self.product()?;
+      left = binary(operator, left, right, column, symbol)?;
+    }
+    self.depth = depth;
+    Ok(left)
+  }
+
+  fn product(&mut self) -> Result<Term, ConversionError> {
+    let depth = self.depth;
+    let mut left = self.unary()?;
+    while let Some((symbol, column)) = self.accept(&["*", "/", "%"]) {
+      self.descend(column)?;
+      let operator = match symbol {
+        "*" => Arithmetic::Multiply,
+        "/" => Arithmetic::Divide,
+        _ => Arithmetic::Remainder,
+      };
+      let right = self.unary()?;
+      left = binary(operator, left, right, column, symbol)?;
+    }
+    self.depth = depth;
+    Ok(left)
+  }
+
+  fn unary(&mut self) -> Result<Term, ConversionError> {
+    if let Some((_, column)) = self.accept(&["-"]) {
+      self.descend(column)?;
+      let value = number(self.unary()?, column, "-")?;
+      self.depth -= 1;
+      return Ok(Term::Number(Number::Negate(Box::new(value))));
+    }
+    self.power()
+  }
+
+  /// `^` binds tighter than a leading minus and to the right, so `-2^2` is -4 and `2^-1` is 0.5.
+  fn power(&mut self) -> Result<Term, ConversionError> {
+    let base = self.primary()?;
+    if let Some((symbol, column)) = self.accept(&["^"]) {
+      self.descend(column)?;
+      let exponent = self.unary()?;
+      self.depth -= 1;
+      return binary(Arithmetic::Power, base, exponent, column, symbol);
+    }
+    Ok(base)
+  }
+
+  fn primary(&mut self) -> Result<Term, ConversionError> {
+    let (token, column) = self.next();
+    match token {
+      Token::Number(value) => Ok(Term::Number(Number::Constant(value))),
+      Token::Symbol("(") => {
+        self.descend(column)?;
+        let term = self.or()?;
+        self.depth -= 1;
+        self.expect(")")?;
+        Ok(term)
+      }
+      Token::Name(name) if name == "true" => Ok(Term::Condition(Condition::Constant(true))),
+      Token::Name(name) if name == "false" => Ok(Term::Condition(Condition::Constant(false))),
+      Token::Name(name) if self.accept(&["("]).is_some() => {
+        let function = Function::from_name(&name)
+          .ok_or_else(|| parse_error(column, format!("unknown function '{}'", name)))?;
+        let mut arguments = Vec::new();
+        self.descend(column)?;
+        if self.accept(&[")"]).is_none() {
+          loop {
+            let argument = self.or()?;
+            arguments.push(number(argument, column, &name)?);
+            if self.accept(&[","]).is_none() {
+              self.expect(")")?;
+              break;
+            }
+          }
+        }
+        self.depth -= 1;
+        if !function.takes(arguments.len()) {
+          return Err(parse_error(
+            column,
+            format!("wrong number of arguments for {}", name),
+          ));
+        }
+        Ok(Term::Number(Number::Call(function, arguments)))
+      }
+      Token::Name(name) => Attribute::from_name(&name)
+        .map(|attribute| Term::Number(Number::Attribute(attribute)))
+        .ok_or_else(|| parse_error(column, format!("unknown attribute '{}'", name))),
+      token => Err(parse_error(column, format!("unexpected {}", token))),
+    }
+  }
+
+  fn expect(&mut self, symbol: &'static str) -> Result<(), ConversionError> {
+    if self.accept(&[symbol]).is_some() {
+      return Ok(());
+    }
+    let (token, column) = self.peek();
+    Err(parse_error(
+      column,
+      format!("expected '{}', found {}", symbol, token),
+    ))
+  }
+}
+
+fn number(term: Term, column: usize, operator: &str) -> Result<Number, ConversionError> {
+  match term {
+    Term::Number(number) => Ok(number),
+    Term::Condition(_) => Err(parse_error(
+      column,
+      format!("'{}' needs numbers, not conditions", operator),
+    )),
+  }
+}
+
+fn condition(term: Term, column: usize, operator: &str) -> Result<Condition, ConversionError> {
+  match term {
+    Term::Condition(condition) => Ok(condition),
+    Term::Number(_) => Err(parse_error(
+      column,
+      format!("'{}' needs conditions, not numbers", operator),
+    )),
+  }
+}
+
+fn binary(
+  operator: Arithmetic,
+  left: Term,
+  right: Term,
+  column: usize,
+  symbol: &str,
+) -> Result<Term, ConversionError> {
+  Ok(Term::Number(Number::Binary(
+    operator,
+    Box::new(number(left, column, symbol)?),
+    Box::new(number(right, column, symbol)?),
+  )))
+}
@@ -0,0 +1,65 @@
+use crate::helpers::activation::sigmoid;
+use crate::operations::expression::Expression;
+use crate::{GaussianSplat, Scene};
+
+/// A region of space splats are kept in (or removed from) by `crop`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CropRegion {
+  /// Axis-aligned box between two corners.
+  Box { min: [f32; 3], max: [f32; 3] },
+  /// Sphere around `center`.
+  Sphere { center: [f32; 3], radius: f32 },
+}
+
+impl CropRegion {
+  pub fn contains(&self, point: [f32; 3]) -> bool {
+    match self {
+      CropRegion::Box { min, max } => (0..3).all(|axis| {
+        let (low, high) = (min[axis].min(max[axis]), min[axis].max(max[axis]));
+        point[axis] >= low && point[axis] <= high
+      }),
+      CropRegion::Sphere { center, radius } => {
+        let distance_squared: f32 = (0..3)
+          .map(|axis| (point[axis] - center[axis]).powi(2))
+          .sum();
+        distance_squared <= radius * radius
+      }
+    }
+  }
+}
+
+/// Keeps the splats whose centre lies inside `region`, or outside it when `invert` is set.
+pub fn crop(scene: &mut Scene, region: &CropRegion, invert: bool) {
+  scene
+    .splats
+    .retain(|splat| region.contains(splat.position) != invert);
+}
+
+/// Drops splats whose activated opacity (0..1) is below `min_opacity`.
+pub fn filter_min_opacity(scene: &mut Scene, min_opacity: f32) {
+  scene
+    .splats
+    .retain(|splat| sigmoid(splat.opacity) >= min_opacity);
+}
+
+/// Drops splats whose largest linear scale (standard deviation along an axis) is above
+/// `max_scale`, e.g. the huge floaters around a capture.
+pub fn filter_max_scale(scene: &mut Scene, max_scale: f32) {
+  scene
+    .splats
+    .retain(|splat| largest_scale(splat) <= max_scale);
+}
+
+fn largest_scale(splat: &GaussianSplat) -> f32 {
+  splat
+    .scale
+    .iter()
+    .fold(f32::NEG_INFINITY, |a, &b| a.max(b))
+    .exp()
+}
+
+/// Keeps the splats `expression` holds for, evaluating it over the splats in parallel.
+pub fn filter_expression(scene: &mut Scene, expression: &Expression) {
+  let mut keep = expression.evaluate(scene).into_iter();
+  scene.splats.retain(|_| keep.next().unwrap_or(false));
+}
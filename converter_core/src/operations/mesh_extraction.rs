@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+
+use rayon::prelude::*;
+
+use crate::Scene;
+use crate::helpers::activation::sigmoid;
+use crate::helpers::marching_cubes::{EDGES, corner_offset, triangles};
+use crate::helpers::rotation::quaternion_to_matrix;
+use crate::helpers::spherical_harmonics::SH_C0;
+
+/// Cells along each side of a block of the sparse grid.
+const BLOCK_SIZE: usize = 16;
+/// Gaussians are evaluated out to this many standard deviations.
+const CUTOFF_SIGMA: f32 = 3.0;
+/// Splats less opaque than this are skipped, as in 3DGS rasterisation.
+const MIN_ALPHA: f32 = 1.0 / 255.0;
+/// Highest opacity a single Gaussian reaches at its centre, as in 3DGS rasterisation.
+const MAX_ALPHA: f32 = 0.99;
+
+/// Options for `extract_mesh`.
+#[derive(Debug, Clone)]
+pub struct MeshExtractionOptions {
+  /// Number of grid cells along the longest side of the scene bounds.
+  pub resolution: usize,
+  /// Opacity (0..1, exclusive) at which the surface is placed.
+  pub iso_level: f32,
+}
+
+impl Default for MeshExtractionOptions {
+  fn default() -> Self {
+    Self {
+      resolution: 256,
+      iso_level: 0.5,
+    }
+  }
+}
+
+/// An indexed triangle mesh with vertex colours.
+#[derive(Debug, Clone, Default)]
+pub struct TriangleMesh {
+  pub vertices: Vec<[f32; 3]>,
+  /// Linear 0..1 RGB, one per vertex.
+  pub colors: Vec<[f32; 3]>,
+  /// Vertex indices, counter-clockwise seen from outside.
+  pub triangles: Vec<[u32; 3]>,
+}
+
+/// A splat prepared for evaluating its opacity at arbitrary points.
+struct Kernel {
+  mean: [f32; 3],
+  inverse_covariance: [[f32; 3]; 3],
+  alpha: f32,
+  color: [f32; 3],
+  min: [f32; 3],
+  max: [f32; 3],
+}
+
+impl Kernel {
+  fn new(splat: &crate::GaussianSplat) -> Option<Self> {
+    let alpha = sigmoid(splat.opacity);
+    if alpha < MIN_ALPHA || !splat.position.iter().all(|v| v.is_finite()) {
+      return None;
+    }
+
+    // Σ = R S² Rᵀ, so Σ⁻¹ = R S⁻² Rᵀ
+    let rotation = quaternion_to_matrix(splat.rotation);
+    let scale = splat.scale.map(|s| s.exp().max(1e-6));
+    let mut inverse_covariance = [[0.0f32; 3]; 3];
+    let mut extent = [0.0f32; 3];
+    for i in 0..3 {
+      for j in 0..3 {
+        inverse_covariance[i][j] = (0..3)
+          .map(|k| rotation[i][k] * rotation[j][k] / (scale[k] * scale[k]))
+          .sum();
+      }
+      extent[i] = CUTOFF_SIGMA
+        * (0..3)
+          .map(|k| (rotation[i][k] * scale[k]).powi(2))
+          .sum::<f32>()
+          .sqrt();
+    }
+
+    Some(Self {
+      mean: splat.position,
+      inverse_covariance,
+      alpha,
+      color: splat
+        .spherical_harmonics_dc
+        .map(|dc| (0.5 + SH_C0 * dc).clamp(0.0, 1.0)),
+      min: [0, 1, 2].map(|axis| splat.position[axis] - extent[axis]),
+      max: [0, 1, 2].map(|axis| splat.position[axis] + extent[axis]),
+    })
+  }
+
+  /// Opacity contributed at `point`, or `None` beyond the cutoff.
+  fn alpha_at(&self, point: [f32; 3]) -> Option<f32> {
+    let d = [0, 1, 2].map(|axis| point[axis] - self.mean[axis]);
+    let m = &self.inverse_covariance;
+    let power = 0.5
+      * (d[0] * (m[0][0] * d[0] + m[0][1] * d[1] + m[0][2] * d[2])
+        + d[1] * (m[1][0] * d[0] + m[1][1] * d[1] + m[1][2] * d[2])
+        + d[2] * (m[2][0] * d[0] + m[2][1] * d[1] + m[2][2] * d[2]));
+    (power <= 0.5 * CUTOFF_SIGMA * CUTOFF_SIGMA)
+      .then(|| (self.alpha * (-power).exp()).min(MAX_ALPHA))
+  }
+}
+
+/// The lattice of grid samples: `samples[axis]` points `cell` apart, starting at `origin`.
+struct Grid {
+  origin: [f32; 3],
+  cell: f32,
+  samples: [usize; 3],
+}
+
+impl Grid {
+  fn position(&self, index: [usize; 3]) -> [f32; 3] {
+    [0, 1, 2].map(|axis| self.origin[axis] + index[axis] as f32 * self.cell)
+  }
+
+  /// The sample indices within `min..=max` along `axis`, if any.
+  fn sample_range(&self, min: f32, max: f32, axis: usize) -> Option<(usize, usize)> {
+    let first = ((min - self.origin[axis]) / self.cell).ceil().max(0.0) as usize;
+    let last = ((max - self.origin[axis]) / self.cell).floor();
+    if last < 0.0 {
+      return None;
+    }
+    let last = (last as usize).min(self.samples[axis] - 1);
+    (first <= last).then_some((first, last))
+  }
+}
+
+/// A mesh vertex before welding: the global edge it lies on, its position and colour.
+type EdgeVertex = (u64, [f32; 3], [f32; 3]);
+
+/// Extracts a surface from the scene's opacity field, `1 - Π(1 - αᵢ·Gᵢ(x))`, with marching
+/// cubes at `options.iso_level`.
+///
+/// The field is sampled on a sparse grid: space is split into blocks of cells, and only the blocks
+/// that some Gaussian reaches are evaluated, each against just the Gaussians overlapping it. Blocks
+/// are processed in parallel. The grid is padded by one cell so that surfaces close at its border,
+/// and vertices are shared between neighbouring cells and blocks.
+pub fn extract_mesh(scene: &Scene, options: &MeshExtractionOptions) -> TriangleMesh {
+  let kernels: Vec<Kernel> = scene.splats.par_iter().filter_map(Kernel::new).collect();
+  if kernels.is_empty() {
+    return TriangleMesh::default();
+  }
+
+  let mut min = [f32::INFINITY; 3];
+  let mut max = [f32::NEG_INFINITY; 3];
+  for kernel in &kernels {
+    for axis in 0..3 {
+      min[axis] = min[axis].min(kernel.min[axis]);
+      max[axis] = max[axis].max(kernel.max[axis]);
+    }
+  }
+  let longest = (0..3).map(|axis| max[axis] - min[axis]).fold(0.0, f32::max);
+  let cell = (longest / options.resolution.max(1) as f32).max(f32::MIN_POSITIVE);
+  let grid = Grid {
+    origin: min.map(|v| v - cell),
+    cell,
+    samples: [0, 1, 2].map(|axis| ((max[axis] - min[axis]) / cell).ceil() as usize + 3),
+  };
+  let blocks = grid.samples.map(|n| (n - 1).div_ceil(BLOCK_SIZE));
+
+  // Gaussians overlapping each occupied block. A sample on a block border belongs to both blocks.
+  let mut block_kernels: HashMap<usize, Vec<u32>> = HashMap::new();
+  for (index, kernel) in kernels.iter().enumerate() {
+    let ranges: Option<Vec<(usize, usize)>> = (0..3)
+      .map(|axis| grid.sample_range(kernel.min[axis], kernel.max[axis], axis))
+      .collect();
+    let Some(ranges) = ranges else {
+      continue;
+    };
+    let block_range = |axis: usize| {
+      let (first, last) = ranges[axis];
+      first.saturating_sub(1) / BLOCK_SIZE..=(last / BLOCK_SIZE).min(blocks[axis] - 1)
+    };
+    for z in block_range(2) {
+      for y in block_range(1) {
+        for x in block_range(0) {
+          block_kernels
+            .entry((z * blocks[1] + y) * blocks[0] + x)
+            .or_default()
+            .push(index as u32);
+        }
+      }
+    }
+  }
+
+  let mut occupied: Vec<(usize, Vec<u32>)> = block_kernels.into_iter().collect();
+  occupied.sort_unstable_by_key(|(block, _)| *block);
+
+  let iso_level = options.iso_level.clamp(1e-6, 1.0 - 1e-6);
+  let soups: Vec<Vec<[EdgeVertex; 3]>> = occupied
+    .par_iter()
+    .map(|(block, members)| {
+      let block = [
+        block % blocks[0],
+        (block / blocks[0]) % blocks[1],
+        block / (blocks[0] * blocks[1]),
+      ];
+      march_block(&grid, &kernels, members, block, iso_level)
+    })
+    .collect();
+
+  // Weld vertices on shared edges, in block order so that the output is deterministic
+  let mut mesh = TriangleMesh::default();
+  let mut vertex_indices: HashMap<u64, u32> = HashMap::new();
+  for triangle in soups.into_iter().flatten() {
+    let indices = triangle.map(|(key, position, color)| {
+      *vertex_indices.entry(key).or_insert_with(|| {
+        mesh.vertices.push(position);
+        mesh.colors.push(color);
+        (mesh.vertices.len() - 1) as u32
+      })
+    });
+    mesh.triangles.push(indices);
+  }
+  mesh
+}
+
+/// Samples the field over one block and runs marching cubes on its cells.
+fn march_block(
+  grid: &Grid,
+  kernels: &[Kernel],
+  members: &[u32],
+  block: [usize; 3],
+  iso_level: f32,
+) -> Vec<[EdgeVertex; 3]> {
+  let base = block.map(|b| b * BLOCK_SIZE);
+  let size = [0, 1, 2].map(|axis| (grid.samples[axis] - base[axis]).min(BLOCK_SIZE + 1));
+  let local = |x: usize, y: usize, z: usize| (z * size[1] + y) * size[0] + x;
+
+  let count = size[0] * size[1] * size[2];
+  let mut transmittance = vec![1.0f32; count];
+  let mut weights = vec![0.0f32; count];
+  let mut colors = vec![[0.0f32; 3]; count];
+
+  for &member in members {
+    let kernel = &kernels[member as usize];
+    let ranges: Option<Vec<(usize, usize)>> = (0..3)
+      .map(|axis| {
+        let (first, last) = grid.sample_range(kernel.min[axis], kernel.max[axis], axis)?;
+        let first = first.max(base[axis]);
+        let last = last.min(base[axis] + size[axis] - 1);
+        (first <= last).then_some((first - base[axis], last - base[axis]))
+      })
+      .collect();
+    let Some(ranges) = ranges else {
+      continue;
+    };
+
+    for z in ranges[2].0..=ranges[2].1 {
+      for y in ranges[1].0..=ranges[1].1 {
+        for x in ranges[0].0..=ranges[0].1 {
+          let point = grid.position([base[0] + x, base[1] + y, base[2] + z]);
+          if let Some(alpha) = kernel.alpha_at(point) {
+            let i = local(x, y, z);
+            transmittance[i] *= 1.0 - alpha;
+            weights[i] += alpha;
+            for (sum, channel) in colors[i].iter_mut().zip(kernel.color) {
+              *sum += alpha * channel;
+            }
+          }
+        }
+      }
+    }
+  }
+
+  let field: Vec<f32> = transmittance.iter().map(|t| 1.0 - t).collect();
+  let edge_key = |index: [usize; 3], axis: usize| {
+    (((index[2] * grid.samples[1] + index[1]) * grid.samples[0] + index[0]) * 3 + axis) as u64
+  };
+
+  let mut soup = Vec::new();
+  for z in 0..size[2] - 1 {
+    for y in 0..size[1] - 1 {
+      for x in 0..size[0] - 1 {
+        let corner_index = |corner: usize| {
+          let [dx, dy, dz] = corner_offset(corner);
+          local(x + dx, y + dy, z + dz)
+        };
+        let config = (0..8).fold(0u8, |config, corner| {
+          if field[corner_index(corner)] >= iso_level {
+            config | (1 << corner)
+          } else {
+            config
+          }
+        });
+
+        for triangle in triangles(config) {
+          soup.push(triangle.map(|edge| {
+            let (a, b) = EDGES[edge as usize];
+            let (ia, ib) = (corner_index(a), corner_index(b));
+            let t = ((iso_level - field[ia]) / (field[ib] - field[ia])).clamp(0.0, 1.0);
+
+            let [dx, dy, dz] = corner_offset(a);
+            let index = [base[0] + x + dx, base[1] + y + dy, base[2] + z + dz];
+            let axis = edge as usize / 4;
+            let mut position = grid.position(index);
+            position[axis] += t * grid.cell;
+
+            let weight = weights[ia] * (1.0 - t) + weights[ib] * t;
+            let color = if weight > 0.0 {
+              [0, 1, 2].map(|c| (colors[ia][c] * (1.0 - t) + colors[ib][c] * t) / weight)
+            } else {
+              [0.5; 3]
+            };
+
+            (edge_key(index, axis), position, color)
+          }));
+        }
+      }
+    }
+  }
+  soup
+}
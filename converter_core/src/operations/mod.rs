@@ -0,0 +1,7 @@
+pub mod expression;
+pub mod filter;
+pub mod mesh_extraction;
+pub mod pipeline;
+pub mod sort;
+pub mod spherical_harmonics;
+pub mod transform;
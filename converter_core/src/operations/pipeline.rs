@@ -0,0 +1,436 @@
+//! Processing steps applied in order to a scene between import and export. Each step is written
+//! as `name:parameter:...`, where parameters are `key=value` (lists are comma-separated) or a
+//! single value for the step's main parameter, e.g. `crop:box=-1,-1,-1,1,1,1:invert` or
+//! `min-opacity:0.05`. `where` takes the rest of the step as an expression, e.g.
+//! `where:opacity > 0.1 and z > 0`. Pipeline files list the same steps, or tables of their
+//! parameters.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::operations::expression::Expression;
+use crate::operations::filter::{
+  CropRegion, crop, filter_expression, filter_max_scale, filter_min_opacity,
+};
+use crate::operations::sort::{SortOrder, sort};
+use crate::operations::spherical_harmonics::{
+  BakeDirections, bake_view_dependent_color, reduce_sh_degree,
+};
+use crate::operations::transform::{Transform, rotation_from_euler_degrees, transform};
+use crate::{ConversionError, Scene};
+
+/// Names of all operations with their parameters, for help texts.
+pub const OPERATIONS: [(&str, &str); 8] = [
+  (
+    "crop",
+    "box=x0,y0,z0,x1,y1,z1 or sphere=x,y,z,radius, keeps the splats inside (outside with :invert)",
+  ),
+  ("min-opacity", "<0..1>, drops splats less opaque"),
+  ("max-scale", "<size>, drops splats larger along any axis"),
+  (
+    "where",
+    "<expression>, keeps the splats it holds for, e.g. where:opacity > 0.1 and z > 0",
+  ),
+  ("sh-degree", "<0-3>, drops higher spherical harmonics bands"),
+  (
+    "bake-sh",
+    "bakes the view-dependent colour into the base colour (SH degree 0)",
+  ),
+  (
+    "transform",
+    "translate=x,y,z, rotate=x,y,z (degrees) or quaternion=w,x,y,z, scale=factor",
+  ),
+  ("sort", "morton or importance"),
+];
+
+/// One processing step.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "OperationSpec")]
+pub enum Operation {
+  /// Keeps the splats inside the region, or outside it when inverted.
+  Crop {
+    region: CropRegion,
+    invert: bool,
+  },
+  /// Drops splats whose opacity (0..1) is below the value.
+  MinOpacity(f32),
+  /// Drops splats whose largest linear scale is above the value.
+  MaxScale(f32),
+  /// Keeps the splats the expression holds for.
+  Where(Expression),
+  /// Drops spherical harmonics bands above the degree.
+  ShDegree(u8),
+  /// Bakes the average view-dependent colour into the base colour, seen from the scene's cameras
+  /// when it has any, and drops the higher bands.
+  BakeSh,
+  Transform(Transform),
+  Sort(SortOrder),
+}
+
+impl Operation {
+  /// Applies the step to `scene`.
+  pub fn apply(&self, scene: &mut Scene) {
+    match self {
+      Operation::Crop { region, invert } => crop(scene, region, *invert),
+      Operation::MinOpacity(min_opacity) => filter_min_opacity(scene, *min_opacity),
+      Operation::MaxScale(max_scale) => filter_max_scale(scene, *max_scale),
+      Operation::Where(expression) => filter_expression(scene, expression),
+      Operation::ShDegree(degree) => reduce_sh_degree(scene, *degree),
+      Operation::BakeSh => {
+        let directions = if scene.cameras.is_empty() {
+          BakeDirections::default()
+        } else {
+          BakeDirections::Cameras(scene.cameras.iter().map(|camera| camera.position).collect())
+        };
+        bake_view_dependent_color(scene, &directions)
+      }
+      Operation::Transform(parameters) => transform(scene, parameters),
+      Operation::Sort(order) => sort(scene, *order),
+    }
+  }
+}
+
+/// Applies `operations` to `scene` in order.
+pub fn apply_operations(scene: &mut Scene, operations: &[Operation]) {
+  for operation in operations {
+    operation.apply(scene);
+  }
+}
+
+/// The steps of a pipeline file, e.g. in JSON
+/// `{"operations": ["min-opacity:0.05", {"op": "crop", "box": [-1, -1, -1, 1, 1, 1]}]}`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Pipeline {
+  pub operations: Vec<Operation>,
+}
+
+fn parse_error(message: String) -> ConversionError {
+  ConversionError::ParseError {
+    format: "Operation".to_string(),
+    message,
+  }
+}
+
+/// The parameters of one step, with the unnamed one stored under "".
+struct Parameters {
+  operation: String,
+  values: Vec<(String, String)>,
+}
+
+impl Parameters {
+  fn parse(operation: &str, text: &str) -> Self {
+    let values = text
+      .split(':')
+      .filter(|part| !part.trim().is_empty())
+      .map(|part| match part.split_once('=') {
+        Some((key, value)) => (key.trim().to_string(), value.trim().to_string()),
+        None => (String::new(), part.trim().to_string()),
+      })
+      .collect();
+    Self {
+      operation: operation.to_string(),
+      values,
+    }
+  }
+
+  fn take(&mut self, key: &str) -> Option<String> {
+    let index = self.values.iter().position(|(k, _)| k == key)?;
+    Some(self.values.remove(index).1)
+  }
+
+  /// The step's main parameter, named or not.
+  fn take_main(&mut self, key: &str) -> Option<String> {
+    self.take(key).or_else(|| self.take(""))
+  }
+
+  /// Whether the flag is given, as `name` or `name=true`.
+  fn flag(&mut self, name: &str) -> bool {
+    match self
+      .values
+      .iter()
+      .position(|(k, v)| k.is_empty() && v == name)
+    {
+      Some(index) => {
+        self.values.remove(index);
+        true
+      }
+      None => self.take(name).is_some_and(|value| value == "true"),
+    }
+  }
+
+  fn number(&self, key: &str, value: &str) -> Result<f32, ConversionError> {
+    value
+      .parse::<f32>()
+      .ok()
+      .filter(|v| v.is_finite())
+      .ok_or_else(|| {
+        parse_error(format!(
+          "{} of {} must be a number, not '{}'",
+          key, self.operation, value
+        ))
+      })
+  }
+
+  fn numbers<const N: usize>(&self, key: &str, value: &str) -> Result<[f32; N], ConversionError> {
+    let numbers = value
+      .split(',')
+      .map(|v| self.number(key, v.trim()))
+      .collect::<Result<Vec<_>, _>>()?;
+    numbers.try_into().map_err(|_| {
+      parse_error(format!(
+        "{} of {} must be {} comma-separated numbers",
+        key, self.operation, N
+      ))
+    })
+  }
+
+  /// Fails on parameters the step doesn't have.
+  fn finish(self) -> Result<(), ConversionError> {
+    match self.values.first() {
+      Some((key, value)) if key.is_empty() => Err(parse_error(format!(
+        "Unexpected parameter '{}' for {}",
+        value, self.operation
+      ))),
+      Some((key, _)) => Err(parse_error(format!(
+        "Unknown parameter '{}' for {}",
+        key, self.operation
+      ))),
+      None => Ok(()),
+    }
+  }
+}
+
+impl FromStr for Operation {
+  type Err = ConversionError;
+
+  fn from_str(text: &str) -> Result<Self, Self::Err> {
+    let (name, rest) = text.trim().split_once(':').unwrap_or((text.trim(), ""));
+    if name == "where" {
+      // Expressions use `=` and `,` themselves, so they aren't split into parameters
+      let source = rest.trim();
+      let source = source.strip_prefix("expr=").unwrap_or(source);
+      return Ok(Operation::Where(source.parse()?));
+    }
+    let mut parameters = Parameters::parse(name, rest);
+
+    let operation = match name {
+      "crop" => {
+        let invert = parameters.flag("invert");
+        let region = match (parameters.take("box"), parameters.take("sphere")) {
+          (Some(corners), None) => {
+            let [x0, y0, z0, x1, y1, z1] = parameters.numbers("box", &corners)?;
+            CropRegion::Box {
+              min: [x0, y0, z0],
+              max: [x1, y1, z1],
+            }
+          }
+          (None, Some(sphere)) => {
+            let [x, y, z, radius] = parameters.numbers("sphere", &sphere)?;
+            if radius <= 0.0 {
+              return Err(parse_error(
+                "The crop sphere radius must be above 0".to_string(),
+              ));
+            }
+            CropRegion::Sphere {
+              center: [x, y, z],
+              radius,
+            }
+          }
+          _ => {
+            return Err(parse_error(
+              "crop needs box=x0,y0,z0,x1,y1,z1 or sphere=x,y,z,radius".to_string(),
+            ));
+          }
+        };
+        Operation::Crop { region, invert }
+      }
+      "min-opacity" => {
+        let value = parameters.take_main("value").unwrap_or_default();
+        let min_opacity = parameters.number("value", &value)?;
+        if !(0.0..=1.0).contains(&min_opacity) {
+          return Err(parse_error(
+            "min-opacity must be between 0 and 1".to_string(),
+          ));
+        }
+        Operation::MinOpacity(min_opacity)
+      }
+      "max-scale" => {
+        let value = parameters.take_main("value").unwrap_or_default();
+        let max_scale = parameters.number("value", &value)?;
+        if max_scale <= 0.0 {
+          return Err(parse_error("max-scale must be above 0".to_string()));
+        }
+        Operation::MaxScale(max_scale)
+      }
+      "sh-degree" => {
+        let value = parameters.take_main("degree").unwrap_or_default();
+        match value.parse::<u8>() {
+          Ok(degree) if degree <= 3 => Operation::ShDegree(degree),
+          _ => {
+            return Err(parse_error("sh-degree must be 0, 1, 2 or 3".to_string()));
+          }
+        }
+      }
+      "bake-sh" => Operation::BakeSh,
+      "transform" => {
+        let mut similarity = Transform::default();
+        if let Some(value) = parameters.take("translate") {
+          similarity.translation = parameters.numbers("translate", &value)?;
+        }
+        match (parameters.take("rotate"), parameters.take("quaternion")) {
+          (Some(_), Some(_)) => {
+            return Err(parse_error(
+              "transform takes either rotate or quaternion, not both".to_string(),
+            ));
+          }
+          (Some(angles), None) => {
+            similarity.rotation =
+              rotation_from_euler_degrees(parameters.numbers("rotate", &angles)?);
+          }
+          (None, Some(quaternion)) => {
+            similarity.rotation = parameters.numbers("quaternion", &quaternion)?;
+          }
+          (None, None) => {}
+        }
+        if let Some(value) = parameters.take("scale") {
+          similarity.scale = parameters.number("scale", &value)?;
+          if similarity.scale <= 0.0 {
+            return Err(parse_error(
+              "The transform scale must be above 0".to_string(),
+            ));
+          }
+        }
+        Operation::Transform(similarity)
+      }
+      "sort" => match parameters.take_main("order").as_deref() {
+        Some("morton") => Operation::Sort(SortOrder::Morton),
+        Some("importance") => Operation::Sort(SortOrder::Importance),
+        _ => {
+          return Err(parse_error(
+            "sort must be sort:morton or sort:importance".to_string(),
+          ));
+        }
+      },
+      _ => {
+        return Err(parse_error(format!(
+          "Unknown operation '{}', expected one of: {}",
+          name,
+          OPERATIONS
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(", ")
+        )));
+      }
+    };
+
+    parameters.finish()?;
+    Ok(operation)
+  }
+}
+
+fn join(values: &[f32]) -> String {
+  values
+    .iter()
+    .map(|v| v.to_string())
+    .collect::<Vec<_>>()
+    .join(",")
+}
+
+/// Writes the step the way `from_str` reads it.
+impl fmt::Display for Operation {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Operation::Crop { region, invert } => {
+        match region {
+          CropRegion::Box { min, max } => write!(f, "crop:box={},{}", join(min), join(max))?,
+          CropRegion::Sphere { center, radius } => {
+            write!(f, "crop:sphere={},{}", join(center), radius)?
+          }
+        }
+        if *invert {
+          write!(f, ":invert")?;
+        }
+        Ok(())
+      }
+      Operation::MinOpacity(value) => write!(f, "min-opacity:{}", value),
+      Operation::MaxScale(value) => write!(f, "max-scale:{}", value),
+      Operation::Where(expression) => write!(f, "where:{}", expression),
+      Operation::ShDegree(degree) => write!(f, "sh-degree:{}", degree),
+      Operation::BakeSh => write!(f, "bake-sh"),
+      Operation::Transform(parameters) => {
+        // Only the parts that change anything
+        write!(f, "transform")?;
+        if parameters.translation != [0.0; 3] {
+          write!(f, ":translate={}", join(&parameters.translation))?;
+        }
+        if parameters.rotation != [1.0, 0.0, 0.0, 0.0] {
+          write!(f, ":quaternion={}", join(&parameters.rotation))?;
+        }
+        if parameters.scale != 1.0 {
+          write!(f, ":scale={}", parameters.scale)?;
+        }
+        Ok(())
+      }
+      Operation::Sort(SortOrder::Morton) => write!(f, "sort:morton"),
+      Operation::Sort(SortOrder::Importance) => write!(f, "sort:importance"),
+    }
+  }
+}
+
+/// A parameter value in a pipeline file table.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ParameterValue {
+  Flag(bool),
+  Number(f64),
+  Numbers(Vec<f64>),
+  Text(String),
+}
+
+/// A step in a pipeline file: its text, or a table of its parameters with the step's name as `op`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OperationSpec {
+  Text(String),
+  Table(BTreeMap<String, ParameterValue>),
+}
+
+impl TryFrom<OperationSpec> for Operation {
+  type Error = ConversionError;
+
+  fn try_from(spec: OperationSpec) -> Result<Self, Self::Error> {
+    let mut table = match spec {
+      OperationSpec::Text(text) => return text.parse(),
+      OperationSpec::Table(table) => table,
+    };
+    let Some(ParameterValue::Text(name)) = table.remove("op") else {
+      return Err(parse_error(
+        "Every operation table needs its name as \"op\"".to_string(),
+      ));
+    };
+
+    let mut text = name;
+    for (key, value) in table {
+      match value {
+        ParameterValue::Flag(true) => text.push_str(&format!(":{}", key)),
+        ParameterValue::Flag(false) => {}
+        ParameterValue::Number(number) => text.push_str(&format!(":{}={}", key, number)),
+        ParameterValue::Numbers(numbers) => text.push_str(&format!(
+          ":{}={}",
+          key,
+          numbers
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+        )),
+        ParameterValue::Text(value) => text.push_str(&format!(":{}={}", key, value)),
+      }
+    }
+    text.parse()
+  }
+}
@@ -0,0 +1,32 @@
+use crate::formats::splat::SplatExporter;
+use crate::helpers::morton_order::morton_order;
+use crate::{GaussianSplat, Scene};
+
+/// Order `sort` puts the splats in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortOrder {
+  /// Along a Morton (Z-order) curve, so that neighbouring splats are close in space.
+  Morton,
+  /// Largest and most opaque first, like `SplatOrder::Importance`.
+  Importance,
+}
+
+/// Reorders the splats of the scene.
+pub fn sort(scene: &mut Scene, order: SortOrder) {
+  let indices = match order {
+    SortOrder::Morton => morton_order(scene),
+    SortOrder::Importance => {
+      let importance: Vec<f32> = scene.splats.iter().map(SplatExporter::importance).collect();
+      let mut indices: Vec<usize> = (0..scene.splats.len()).collect();
+      // Stable, so ties keep their input order
+      indices.sort_by(|&a, &b| importance[b].total_cmp(&importance[a]));
+      indices
+    }
+  };
+
+  let mut splats: Vec<Option<GaussianSplat>> = scene.splats.drain(..).map(Some).collect();
+  scene.splats = indices
+    .into_iter()
+    .filter_map(|index| splats[index].take())
+    .collect();
+}
@@ -0,0 +1,103 @@
+use std::borrow::Cow;
+
+use rayon::prelude::*;
+
+use crate::helpers::spherical_harmonics::{SH_C0, evaluate_sh_color};
+use crate::{Scene, sh_coefficients_per_channel};
+
+/// Where to look at each splat from when baking its view-dependent colour.
+#[derive(Debug, Clone)]
+pub enum BakeDirections {
+  /// Average over this many directions spread evenly over the whole sphere.
+  Sphere(usize),
+  /// Average over the given world-space view directions (camera -> scene).
+  Given(Vec<[f32; 3]>),
+  /// Average over the directions from these camera centres to each splat, e.g. the positions of
+  /// the scene's cameras.
+  Cameras(Vec<[f32; 3]>),
+}
+
+impl Default for BakeDirections {
+  fn default() -> Self {
+    BakeDirections::Sphere(64)
+  }
+}
+
+/// Drops every SH band above `degree` (0-3). Truncating is the least-squares best
+/// approximation since the SH bands are orthonormal. Splats already at or below `degree` are untouched.
+pub fn reduce_sh_degree(scene: &mut Scene, degree: u8) {
+  let per_channel = sh_coefficients_per_channel(degree.min(3));
+
+  scene.splats.par_iter_mut().for_each(|splat| {
+    let current = splat.spherical_harmonics_rest.len() / 3;
+    if current <= per_channel {
+      return;
+    }
+
+    let rest = &splat.spherical_harmonics_rest;
+    splat.spherical_harmonics_rest = (0..3)
+      .flat_map(|channel| rest[channel * current..channel * current + per_channel].to_vec())
+      .collect();
+  });
+}
+
+/// Reduces the scene to SH degree 0, replacing `spherical_harmonics_dc` with the average
+/// colour the splat shows over `directions`. Unlike plain truncation this keeps the tint that
+/// view-dependent colour adds once negative colours are clamped by the renderer.
+pub fn bake_view_dependent_color(scene: &mut Scene, directions: &BakeDirections) {
+  let fixed_dirs: Vec<[f32; 3]> = match directions {
+    BakeDirections::Sphere(samples) => fibonacci_sphere(*samples),
+    BakeDirections::Given(dirs) => dirs.iter().filter_map(|&d| normalize(d)).collect(),
+    BakeDirections::Cameras(_) => Vec::new(),
+  };
+
+  scene.splats.par_iter_mut().for_each(|splat| {
+    if splat.spherical_harmonics_rest.is_empty() {
+      return;
+    }
+
+    let dirs: Cow<[[f32; 3]]> = match directions {
+      BakeDirections::Cameras(centres) => centres
+        .iter()
+        .filter_map(|centre| normalize([0, 1, 2].map(|axis| splat.position[axis] - centre[axis])))
+        .collect(),
+      _ => Cow::Borrowed(&fixed_dirs),
+    };
+    if dirs.is_empty() {
+      return;
+    }
+
+    let mut sum = [0.0f32; 3];
+    for &dir in dirs.iter() {
+      let color = evaluate_sh_color(splat, dir);
+      for (total, value) in sum.iter_mut().zip(color) {
+        *total += value;
+      }
+    }
+
+    for (dc, total) in splat.spherical_harmonics_dc.iter_mut().zip(sum) {
+      let average = total / dirs.len() as f32;
+      *dc = (average - 0.5) / SH_C0;
+    }
+  });
+
+  reduce_sh_degree(scene, 0);
+}
+
+fn normalize(v: [f32; 3]) -> Option<[f32; 3]> {
+  let len = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+  (len > 0.0).then(|| [v[0] / len, v[1] / len, v[2] / len])
+}
+
+/// `n` nearly uniformly distributed unit vectors.
+pub(crate) fn fibonacci_sphere(n: usize) -> Vec<[f32; 3]> {
+  let golden_angle = std::f32::consts::PI * (3.0 - 5f32.sqrt());
+  (0..n)
+    .map(|i| {
+      let y = 1.0 - 2.0 * (i as f32 + 0.5) / n as f32;
+      let r = (1.0 - y * y).sqrt();
+      let phi = golden_angle * i as f32;
+      [phi.cos() * r, y, phi.sin() * r]
+    })
+    .collect()
+}
@@ -0,0 +1,165 @@
+use rayon::prelude::*;
+
+use crate::helpers::quaternion_multiplication::multiply_quaternions;
+use crate::helpers::rotation::quaternion_to_matrix;
+use crate::helpers::spherical_harmonics::sh_basis;
+use crate::operations::spherical_harmonics::fibonacci_sphere;
+use crate::{GaussianSplat, Scene};
+
+/// Coefficient ranges of SH degrees 1, 2 and 3 within each channel of `spherical_harmonics_rest`.
+const SH_BANDS: [(usize, usize); 3] = [(0, 3), (3, 8), (8, 15)];
+/// Directions the rotated SH bands are fitted over.
+const SH_SAMPLES: usize = 64;
+
+/// A similarity transform of the whole scene: positions are scaled, then rotated, then
+/// translated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transform {
+  pub translation: [f32; 3],
+  /// `[w, x, y, z]` quaternion, normalised before use.
+  pub rotation: [f32; 4],
+  /// Uniform scale factor.
+  pub scale: f32,
+}
+
+impl Default for Transform {
+  fn default() -> Self {
+    Self {
+      translation: [0.0; 3],
+      rotation: [1.0, 0.0, 0.0, 0.0],
+      scale: 1.0,
+    }
+  }
+}
+
+/// `[w, x, y, z]` quaternion rotating around X, then Y, then Z by the given angles in degrees.
+pub fn rotation_from_euler_degrees(angles: [f32; 3]) -> [f32; 4] {
+  let [x, y, z] = angles.map(|angle| angle.to_radians() / 2.0);
+  let qx = [x.cos(), x.sin(), 0.0, 0.0];
+  let qy = [y.cos(), 0.0, y.sin(), 0.0];
+  let qz = [z.cos(), 0.0, 0.0, z.sin()];
+  multiply_quaternions(qz, multiply_quaternions(qy, qx))
+}
+
+/// Moves, rotates and scales the scene. Splat orientations, sizes and normals follow, the
+/// view-dependent colour is rotated with the splats, and cameras keep their view of the scene.
+pub fn transform(scene: &mut Scene, transform: &Transform) {
+  let length = transform.rotation.iter().map(|v| v * v).sum::<f32>().sqrt();
+  let rotation = if length > 0.0 && length.is_finite() {
+    transform.rotation.map(|v| v / length)
+  } else {
+    [1.0, 0.0, 0.0, 0.0]
+  };
+  let matrix = quaternion_to_matrix(rotation);
+  let log_scale = transform.scale.ln();
+  let sh_rotations = sh_band_rotations(&matrix);
+  let apply = |point: [f32; 3]| {
+    let rotated = rotate(&matrix, point);
+    [0, 1, 2].map(|axis| transform.scale * rotated[axis] + transform.translation[axis])
+  };
+
+  scene.splats.par_iter_mut().for_each(|splat| {
+    splat.position = apply(splat.position);
+    splat.normal = rotate(&matrix, splat.normal);
+    // Splat rotations are local to world, so the scene rotation comes first
+    splat.rotation = multiply_quaternions(rotation, splat.rotation);
+    splat.scale = splat.scale.map(|scale| scale + log_scale);
+    rotate_sh(splat, &sh_rotations);
+  });
+
+  for camera in &mut scene.cameras {
+    camera.position = apply(camera.position);
+    // World-to-camera rotation, undoing the scene rotation first
+    camera.rotation = [0, 1, 2].map(|row| {
+      [0, 1, 2].map(|column| {
+        (0..3)
+          .map(|k| camera.rotation[row][k] * matrix[column][k])
+          .sum()
+      })
+    });
+  }
+}
+
+fn rotate(matrix: &[[f32; 3]; 3], v: [f32; 3]) -> [f32; 3] {
+  matrix.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+}
+
+fn rotate_inverse(matrix: &[[f32; 3]; 3], v: [f32; 3]) -> [f32; 3] {
+  [0, 1, 2].map(|column| (0..3).map(|row| matrix[row][column] * v[row]).sum())
+}
+
+/// For each SH band, the matrix taking its coefficients to those of the rotated scene. It is
+/// fitted by least squares so that the rotated splat shows, along every rotated direction, the
+/// colour it showed along the original one.
+fn sh_band_rotations(matrix: &[[f32; 3]; 3]) -> Vec<Vec<Vec<f64>>> {
+  let directions = fibonacci_sphere(SH_SAMPLES);
+  let rotated: Vec<[f32; 15]> = directions.iter().map(|&d| sh_basis(d)).collect();
+  let original: Vec<[f32; 15]> = directions
+    .iter()
+    .map(|&d| sh_basis(rotate_inverse(matrix, d)))
+    .collect();
+
+  SH_BANDS
+    .iter()
+    .map(|&(start, end)| {
+      let n = end - start;
+      // Normal equations BᵀB D = BᵀA
+      let mut btb = vec![vec![0.0f64; n]; n];
+      let mut bta = vec![vec![0.0f64; n]; n];
+      for (b, a) in rotated.iter().zip(&original) {
+        for i in 0..n {
+          for j in 0..n {
+            btb[i][j] += b[start + i] as f64 * b[start + j] as f64;
+            bta[i][j] += b[start + i] as f64 * a[start + j] as f64;
+          }
+        }
+      }
+      solve(btb, bta)
+    })
+    .collect()
+}
+
+/// Solves `a x = b` for a square `x` by Gaussian elimination with partial pivoting.
+fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<Vec<f64>>) -> Vec<Vec<f64>> {
+  let n = a.len();
+  for column in 0..n {
+    let pivot = (column..n)
+      .max_by(|&i, &j| a[i][column].abs().total_cmp(&a[j][column].abs()))
+      .unwrap();
+    a.swap(column, pivot);
+    b.swap(column, pivot);
+    for row in 0..n {
+      if row == column || a[column][column] == 0.0 {
+        continue;
+      }
+      let factor = a[row][column] / a[column][column];
+      for k in 0..n {
+        a[row][k] -= factor * a[column][k];
+        b[row][k] -= factor * b[column][k];
+      }
+    }
+  }
+  (0..n)
+    .map(|row| b[row].iter().map(|value| value / a[row][row]).collect())
+    .collect()
+}
+
+fn rotate_sh(splat: &mut GaussianSplat, band_rotations: &[Vec<Vec<f64>>]) {
+  let per_channel = splat.spherical_harmonics_rest.len() / 3;
+  for channel in 0..3 {
+    let coefficients = &mut splat.spherical_harmonics_rest[channel * per_channel..][..per_channel];
+    for (&(start, end), rotation) in SH_BANDS.iter().zip(band_rotations) {
+      if end > per_channel {
+        break;
+      }
+      let band: Vec<f64> = coefficients[start..end].iter().map(|&c| c as f64).collect();
+      for (i, coefficient) in coefficients[start..end].iter_mut().enumerate() {
+        *coefficient = rotation[i]
+          .iter()
+          .zip(&band)
+          .map(|(r, c)| r * c)
+          .sum::<f64>() as f32;
+      }
+    }
+  }
+}
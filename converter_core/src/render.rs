@@ -0,0 +1,254 @@
+//! A CPU implementation of the 3D Gaussian splatting rasteriser, for previews and thumbnails.
+//!
+//! It follows the reference renderer: every splat's 3D covariance is projected to a 2D screen-space
+//! Gaussian, splats are sorted by depth and binned into 16x16 pixel tiles, and each tile is alpha
+//! blended front to back with the view-dependent colour from the spherical harmonics.
+
+use image::codecs::png::PngEncoder;
+use image::{ImageEncoder, RgbaImage};
+use rayon::prelude::*;
+
+use crate::camera::{dot, normalize, sub};
+use crate::helpers::activation::sigmoid;
+use crate::helpers::rotation::quaternion_to_matrix;
+use crate::helpers::spherical_harmonics::evaluate_sh_color;
+use crate::{Camera, ConversionError, GaussianSplat, Scene};
+
+const TILE_SIZE: u32 = 16;
+/// Splats closer to the camera than this are culled, as in the reference renderer.
+const NEAR_PLANE: f32 = 0.2;
+const MIN_ALPHA: f32 = 1.0 / 255.0;
+const MAX_ALPHA: f32 = 0.99;
+/// Blending stops once this little light gets through.
+const MIN_TRANSMITTANCE: f32 = 1e-4;
+/// Added to the 2D covariance so every splat covers at least about a pixel.
+const LOW_PASS: f32 = 0.3;
+
+/// Options for `render`.
+#[derive(Debug, Clone, Default)]
+pub struct RenderOptions {
+  /// Straight RGBA (0..1) behind the splats. Transparent by default.
+  pub background: [f32; 4],
+}
+
+/// A splat projected to the image.
+struct Projected {
+  mean: [f32; 2],
+  /// Inverse of the 2D covariance as (xx, xy, yy).
+  conic: [f32; 3],
+  depth: f32,
+  color: [f32; 3],
+  opacity: f32,
+  /// Tiles covered, as `min_x, min_y, max_x, max_y` with exclusive maxima.
+  tiles: [u32; 4],
+}
+
+fn project(splat: &GaussianSplat, camera: &Camera, tiles: [u32; 2]) -> Option<Projected> {
+  let opacity = sigmoid(splat.opacity);
+  let [x, y, z] = camera.to_camera(splat.position);
+  if opacity < MIN_ALPHA || z.is_nan() || z <= NEAR_PLANE || !x.is_finite() || !y.is_finite() {
+    return None;
+  }
+
+  // 3D covariance M Mᵀ with M = R S, in camera space
+  let rotation = quaternion_to_matrix(splat.rotation);
+  let scale = splat.scale.map(f32::exp);
+  let m: [[f32; 3]; 3] = [0, 1, 2].map(|row| {
+    [0, 1, 2].map(|column| {
+      (0..3)
+        .map(|k| camera.rotation[row][k] * rotation[k][column] * scale[column])
+        .sum()
+    })
+  });
+
+  // Jacobian of the perspective projection, with the position clamped like the reference
+  // renderer so that splats far outside the view don't blow up
+  let [fx, fy] = camera.focal;
+  let limit_x = 1.3 * camera.width as f32 / (2.0 * fx);
+  let limit_y = 1.3 * camera.height as f32 / (2.0 * fy);
+  let tx = (x / z).clamp(-limit_x, limit_x) * z;
+  let ty = (y / z).clamp(-limit_y, limit_y) * z;
+  let j = [
+    [fx / z, 0.0, -fx * tx / (z * z)],
+    [0.0, fy / z, -fy * ty / (z * z)],
+  ];
+
+  // 2D covariance J M Mᵀ Jᵀ
+  let jm: [[f32; 3]; 2] =
+    [0, 1].map(|row| [0, 1, 2].map(|column| (0..3).map(|k| j[row][k] * m[k][column]).sum()));
+  let a = dot(jm[0], jm[0]) + LOW_PASS;
+  let b = dot(jm[0], jm[1]);
+  let c = dot(jm[1], jm[1]) + LOW_PASS;
+  let det = a * c - b * b;
+  if det.is_nan() || det <= 0.0 {
+    return None;
+  }
+
+  let mid = (a + c) / 2.0;
+  let largest_eigenvalue = mid + (mid * mid - det).max(0.1).sqrt();
+  let radius = (3.0 * largest_eigenvalue.sqrt()).ceil();
+  let mean = [
+    fx * x / z + camera.principal_point[0],
+    fy * y / z + camera.principal_point[1],
+  ];
+
+  let tile_range = |center: f32, count: u32| {
+    let first = ((center - radius) / TILE_SIZE as f32).floor().max(0.0) as u32;
+    let last = ((center + radius) / TILE_SIZE as f32)
+      .floor()
+      .clamp(-1.0, count as f32 - 1.0);
+    (first.min(count), (last + 1.0) as u32)
+  };
+  let (min_x, max_x) = tile_range(mean[0], tiles[0]);
+  let (min_y, max_y) = tile_range(mean[1], tiles[1]);
+  if min_x >= max_x || min_y >= max_y {
+    return None;
+  }
+
+  let direction = normalize(sub(splat.position, camera.position)).unwrap_or([0.0, 0.0, 1.0]);
+  Some(Projected {
+    mean,
+    conic: [c / det, -b / det, a / det],
+    depth: z,
+    color: evaluate_sh_color(splat, direction),
+    opacity,
+    tiles: [min_x, min_y, max_x, max_y],
+  })
+}
+
+/// Renders the scene from `camera` into a straight-alpha RGBA image.
+pub fn render(scene: &Scene, camera: &Camera, options: &RenderOptions) -> RgbaImage {
+  let tiles = [
+    camera.width.div_ceil(TILE_SIZE),
+    camera.height.div_ceil(TILE_SIZE),
+  ];
+  let projected: Vec<Option<Projected>> = scene
+    .splats
+    .par_iter()
+    .map(|splat| project(splat, camera, tiles))
+    .collect();
+
+  let mut order: Vec<(f32, u32)> = projected
+    .iter()
+    .enumerate()
+    .filter_map(|(index, splat)| splat.as_ref().map(|splat| (splat.depth, index as u32)))
+    .collect();
+  order.par_sort_by(|a, b| a.0.total_cmp(&b.0));
+
+  // Binning in depth order leaves every tile's list sorted front to back
+  let mut tile_splats: Vec<Vec<u32>> = vec![Vec::new(); (tiles[0] * tiles[1]) as usize];
+  for (_, index) in order {
+    let [min_x, min_y, max_x, max_y] = projected[index as usize].as_ref().unwrap().tiles;
+    for tile_y in min_y..max_y {
+      for tile_x in min_x..max_x {
+        tile_splats[(tile_y * tiles[0] + tile_x) as usize].push(index);
+      }
+    }
+  }
+
+  let tile_pixels: Vec<Vec<[u8; 4]>> = tile_splats
+    .par_iter()
+    .enumerate()
+    .map(|(tile, splats)| {
+      let origin = [
+        tile as u32 % tiles[0] * TILE_SIZE,
+        tile as u32 / tiles[0] * TILE_SIZE,
+      ];
+      blend_tile(&projected, splats, origin, camera, &options.background)
+    })
+    .collect();
+
+  let mut image = RgbaImage::new(camera.width, camera.height);
+  for (tile, pixels) in tile_pixels.iter().enumerate() {
+    let origin = [
+      tile as u32 % tiles[0] * TILE_SIZE,
+      tile as u32 / tiles[0] * TILE_SIZE,
+    ];
+    let width = (camera.width - origin[0]).min(TILE_SIZE);
+    for (i, pixel) in pixels.iter().enumerate() {
+      let (x, y) = (i as u32 % width, i as u32 / width);
+      image.put_pixel(origin[0] + x, origin[1] + y, image::Rgba(*pixel));
+    }
+  }
+  image
+}
+
+/// Alpha blends the tile's splats front to back for each of its pixels.
+fn blend_tile(
+  projected: &[Option<Projected>],
+  splats: &[u32],
+  origin: [u32; 2],
+  camera: &Camera,
+  background: &[f32; 4],
+) -> Vec<[u8; 4]> {
+  let width = (camera.width - origin[0]).min(TILE_SIZE);
+  let height = (camera.height - origin[1]).min(TILE_SIZE);
+  let to_byte = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+  let mut pixels = Vec::with_capacity((width * height) as usize);
+  for y in 0..height {
+    for x in 0..width {
+      let pixel = [(origin[0] + x) as f32 + 0.5, (origin[1] + y) as f32 + 0.5];
+      let mut transmittance = 1.0f32;
+      let mut color = [0.0f32; 3];
+
+      for &index in splats {
+        let splat = projected[index as usize].as_ref().unwrap();
+        let d = [splat.mean[0] - pixel[0], splat.mean[1] - pixel[1]];
+        let power = -0.5 * (splat.conic[0] * d[0] * d[0] + splat.conic[2] * d[1] * d[1])
+          - splat.conic[1] * d[0] * d[1];
+        if power > 0.0 {
+          continue;
+        }
+        let alpha = (splat.opacity * power.exp()).min(MAX_ALPHA);
+        if alpha < MIN_ALPHA {
+          continue;
+        }
+        let next = transmittance * (1.0 - alpha);
+        if next < MIN_TRANSMITTANCE {
+          break;
+        }
+        for (channel, value) in color.iter_mut().zip(splat.color) {
+          *channel += value * alpha * transmittance;
+        }
+        transmittance = next;
+      }
+
+      // Composite over the background and convert back to straight alpha
+      let alpha = 1.0 - transmittance + transmittance * background[3];
+      let rgb = [0, 1, 2].map(|channel| {
+        let premultiplied = color[channel] + transmittance * background[3] * background[channel];
+        if alpha > 0.0 {
+          premultiplied / alpha
+        } else {
+          0.0
+        }
+      });
+      pixels.push([
+        to_byte(rgb[0]),
+        to_byte(rgb[1]),
+        to_byte(rgb[2]),
+        to_byte(alpha),
+      ]);
+    }
+  }
+  pixels
+}
+
+/// Encodes a rendered image as PNG.
+pub fn write_png(
+  image: &RgbaImage,
+  writer: &mut impl std::io::Write,
+) -> Result<(), ConversionError> {
+  PngEncoder::new(writer)
+    .write_image(
+      image.as_raw(),
+      image.width(),
+      image.height(),
+      image::ExtendedColorType::Rgba8,
+    )
+    .map_err(|e| ConversionError::WriteError {
+      format: "PNG".to_string(),
+      message: e.to_string(),
+    })
+}
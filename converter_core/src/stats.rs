@@ -0,0 +1,244 @@
+//! Summary statistics of a scene, to inspect a file without converting it: splat count, bounds,
+//! the distribution of every attribute, and how large the scene would be in each output format.
+
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::formats::{
+  compressed_ply::CompressedPlyExporter,
+  csv::CsvExporter,
+  gltf::GlbExporter,
+  ksplat::KsplatExporter,
+  las::LasExporter,
+  ply::PlyBinaryExporter,
+  sog::{SogExportOptions, SogExporter},
+  splat::SplatExporter,
+  spz::SpzV2Exporter,
+  usd::{UsdaExporter, UsdzExporter},
+};
+use crate::helpers::activation::sigmoid;
+use crate::helpers::bounds::robust_bounds;
+use crate::helpers::spherical_harmonics::SH_C0;
+use crate::{ConversionError, Exporter, GaussianSplat, Scene};
+
+/// Percentiles reported for every attribute.
+pub const PERCENTILES: [f64; 5] = [1.0, 25.0, 50.0, 75.0, 99.0];
+/// Share of splats ignored at each end of every axis for the robust bounds.
+const ROBUST_BOUNDS_OUTLIERS: f32 = 0.01;
+/// Size estimates of larger scenes export evenly spaced samples of this many splats and half as
+/// many.
+const SIZE_SAMPLE: usize = 10_000;
+
+/// Distribution of one attribute over all splats. Non-finite values are counted, not included.
+#[derive(Debug, Clone, Serialize)]
+pub struct AttributeStats {
+  pub name: &'static str,
+  pub min: f64,
+  pub max: f64,
+  pub mean: f64,
+  /// Values at `PERCENTILES`.
+  pub percentiles: [f64; 5],
+  /// Number of NaN or infinite values.
+  pub non_finite: usize,
+}
+
+/// Overview of a scene.
+#[derive(Debug, Clone, Serialize)]
+pub struct SceneStats {
+  pub splat_count: usize,
+  pub sh_degree: u8,
+  pub camera_count: usize,
+  /// Axis-aligned bounds (min, max) of all finite splat centres.
+  pub bounds: Option<([f32; 3], [f32; 3])>,
+  /// Bounds ignoring the outermost 1% of splats along each axis.
+  pub robust_bounds: Option<([f32; 3], [f32; 3])>,
+  /// Number of splats with a NaN or infinite value in any attribute.
+  pub non_finite_splats: usize,
+  /// Positions, log scales, activated opacity, base colour (0..1), rotation quaternion length and
+  /// higher-order SH coefficients.
+  pub attributes: Vec<AttributeStats>,
+}
+
+fn base_color(splat: &GaussianSplat) -> [f32; 3] {
+  splat.spherical_harmonics_dc.map(|dc| 0.5 + SH_C0 * dc)
+}
+
+type Extractor = fn(&GaussianSplat) -> Vec<f32>;
+
+const ATTRIBUTES: [(&str, Extractor); 12] = [
+  ("position x", |s| vec![s.position[0]]),
+  ("position y", |s| vec![s.position[1]]),
+  ("position z", |s| vec![s.position[2]]),
+  ("log scale x", |s| vec![s.scale[0]]),
+  ("log scale y", |s| vec![s.scale[1]]),
+  ("log scale z", |s| vec![s.scale[2]]),
+  ("opacity", |s| vec![sigmoid(s.opacity)]),
+  ("color r", |s| vec![base_color(s)[0]]),
+  ("color g", |s| vec![base_color(s)[1]]),
+  ("color b", |s| vec![base_color(s)[2]]),
+  ("rotation length", |s| {
+    vec![s.rotation.iter().map(|v| v * v).sum::<f32>().sqrt()]
+  }),
+  ("sh coefficients", |s| s.spherical_harmonics_rest.clone()),
+];
+
+fn attribute_stats(name: &'static str, values: Vec<f32>) -> AttributeStats {
+  let total = values.len();
+  let mut values: Vec<f64> = values
+    .into_iter()
+    .filter(|v| v.is_finite())
+    .map(f64::from)
+    .collect();
+  values.par_sort_unstable_by(f64::total_cmp);
+
+  // Linear interpolation between the closest ranks
+  let percentile = |p: f64| {
+    let rank = p / 100.0 * (values.len() - 1) as f64;
+    let (low, high) = (rank.floor() as usize, rank.ceil() as usize);
+    values[low] + (values[high] - values[low]) * (rank - low as f64)
+  };
+  let (min, max, mean, percentiles) = if values.is_empty() {
+    (f64::NAN, f64::NAN, f64::NAN, [f64::NAN; 5])
+  } else {
+    (
+      values[0],
+      values[values.len() - 1],
+      values.iter().sum::<f64>() / values.len() as f64,
+      PERCENTILES.map(percentile),
+    )
+  };
+  AttributeStats {
+    name,
+    min,
+    max,
+    mean,
+    percentiles,
+    non_finite: total - values.len(),
+  }
+}
+
+/// Computes the statistics of every attribute of the scene, in parallel.
+pub fn scene_stats(scene: &Scene) -> SceneStats {
+  let attributes = ATTRIBUTES
+    .par_iter()
+    .map(|(name, extract)| attribute_stats(name, scene.splats.iter().flat_map(extract).collect()))
+    .collect();
+  let non_finite_splats = scene
+    .splats
+    .par_iter()
+    .filter(|splat| {
+      !ATTRIBUTES
+        .iter()
+        .all(|(_, extract)| extract(splat).iter().all(|v| v.is_finite()))
+    })
+    .count();
+
+  SceneStats {
+    splat_count: scene.splats.len(),
+    sh_degree: scene.sh_degree(),
+    camera_count: scene.cameras.len(),
+    bounds: robust_bounds(scene, 0.0),
+    robust_bounds: robust_bounds(scene, ROBUST_BOUNDS_OUTLIERS),
+    non_finite_splats,
+    attributes,
+  }
+}
+
+/// Estimated size of the scene in one output format.
+#[derive(Debug, Clone, Serialize)]
+pub struct SizeEstimate {
+  /// Format name, e.g. "SPZ" or "PLY (binary)".
+  pub format: &'static str,
+  pub extension: &'static str,
+  /// Estimated file size, or `None` if the format cannot hold this scene.
+  pub bytes: Option<u64>,
+}
+
+/// Counts the bytes written to it.
+struct ByteCounter(u64);
+
+impl std::io::Write for ByteCounter {
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    self.0 += buf.len() as u64;
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> std::io::Result<()> {
+    Ok(())
+  }
+}
+
+type SizeOf = fn(&Scene) -> Result<u64, ConversionError>;
+
+fn size_of<E: Exporter>(scene: &Scene) -> Result<u64, ConversionError> {
+  let mut counter = ByteCounter(0);
+  E::export(scene, &mut counter)?;
+  Ok(counter.0)
+}
+
+/// The k-means iterations barely change the size of the quantized data, so one is enough.
+fn sog_size_of(scene: &Scene) -> Result<u64, ConversionError> {
+  let options = SogExportOptions {
+    iterations: 1,
+    ..SogExportOptions::default()
+  };
+  let mut counter = ByteCounter(0);
+  SogExporter::export_with_options(scene, &mut counter, &options)?;
+  Ok(counter.0)
+}
+
+const SIZE_FORMATS: [(&str, &str, SizeOf); 11] = [
+  ("PLY (binary)", "ply", size_of::<PlyBinaryExporter>),
+  ("Compressed PLY", "ply", size_of::<CompressedPlyExporter>),
+  ("SPLAT", "splat", size_of::<SplatExporter>),
+  ("SPZ", "spz", size_of::<SpzV2Exporter>),
+  ("KSPLAT", "ksplat", size_of::<KsplatExporter>),
+  ("SOG", "sog", sog_size_of),
+  ("glTF binary", "glb", size_of::<GlbExporter>),
+  ("OpenUSD text", "usda", size_of::<UsdaExporter>),
+  ("OpenUSD package", "usdz", size_of::<UsdzExporter>),
+  ("CSV", "csv", size_of::<CsvExporter>),
+  ("LAS", "las", size_of::<LasExporter>),
+];
+
+/// Estimates the file size of the scene in every output format with default options. Scenes of up
+/// to `SIZE_SAMPLE` splats are exported in full. Larger ones export two evenly spaced samples, and
+/// the size is extrapolated along the line through both: exact for formats with fixed-size
+/// records, while compressed formats assume the rest compresses like the samples.
+pub fn estimate_sizes(scene: &Scene) -> Vec<SizeEstimate> {
+  let count = scene.splats.len();
+  let sample = |size: usize| Scene {
+    splats: scene
+      .splats
+      .iter()
+      .step_by(count / size)
+      .take(size)
+      .cloned()
+      .collect(),
+    cameras: scene.cameras.clone(),
+  };
+  let samples = (count > SIZE_SAMPLE).then(|| (sample(SIZE_SAMPLE / 2), sample(SIZE_SAMPLE)));
+
+  SIZE_FORMATS
+    .par_iter()
+    .map(|(format, extension, size_of)| {
+      let bytes = match &samples {
+        None => size_of(scene).ok(),
+        Some((small, large)) => match (size_of(small), size_of(large)) {
+          (Ok(small_bytes), Ok(large_bytes)) => {
+            let per_splat = (large_bytes as f64 - small_bytes as f64)
+              / (large.splats.len() - small.splats.len()) as f64;
+            let estimate = large_bytes as f64 + per_splat * (count - large.splats.len()) as f64;
+            Some(estimate.max(0.0).round() as u64)
+          }
+          _ => None,
+        },
+      };
+      SizeEstimate {
+        format,
+        extension,
+        bytes,
+      }
+    })
+    .collect()
+}
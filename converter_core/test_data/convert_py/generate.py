@@ -0,0 +1,124 @@
+"""Regenerates the fixtures `splat_test.rs` compares `SplatExporter` against: `input.ply`, a
+3DGS scene with colours and opacities right at the edges of the byte they quantize to, and
+`expected.splat`, what antimatter15's reference `convert.py` makes of it.
+
+    pip install numpy plyfile
+    python generate.py
+"""
+
+import math
+import struct
+from io import BytesIO
+from pathlib import Path
+
+HERE = Path(__file__).parent
+
+PROPERTIES = (
+    ["x", "y", "z", "nx", "ny", "nz", "f_dc_0", "f_dc_1", "f_dc_2"]
+    + [f"f_rest_{i}" for i in range(45)]
+    + ["opacity", "scale_0", "scale_1", "scale_2", "rot_0", "rot_1", "rot_2", "rot_3"]
+)
+
+SH_C0 = 0.28209479177387814
+
+
+def f32(value):
+    return struct.unpack("<f", struct.pack("<f", value))[0]
+
+
+def boundary_dc(byte):
+    """The largest float32 SH coefficient whose colour is still below `byte`, as computed in
+    float32, so that computing it in float64 can land on the next byte."""
+    dc = f32((byte / 255 - 0.5) / SH_C0)
+    step = math.ulp(dc) if dc != 0 else 1e-7
+    return f32(dc - 4 * step)
+
+
+def boundary_opacity(byte):
+    """A logit right at the edge of the alpha `byte`."""
+    alpha = byte / 255
+    return f32(math.log(alpha / (1 - alpha)))
+
+
+def splats():
+    rows = []
+    for i in range(48):
+        t = i + 1
+        position = [f32(math.sin(t) * 3), f32(math.cos(t * 0.7) * 2), f32(t * 0.125 - 3)]
+        if i % 3 == 0:
+            dc = [boundary_dc(64 + i), boundary_dc(128 + i), boundary_dc(200 - i)]
+        elif i % 3 == 1:
+            dc = [f32(2.5 - i * 0.11), f32(-3.0 + i * 0.07), f32(0.0)]
+        else:
+            dc = [f32(math.sin(t * 1.3)), f32(math.cos(t * 0.4)), f32(-0.5 + i * 0.02)]
+        rest = [f32(math.sin(t + k) * 0.1) for k in range(45)]
+        opacity = boundary_opacity(10 + i * 5) if i % 2 == 0 else f32(-4.0 + i * 0.19)
+        scale = [f32(-5.5 + i * 0.07), f32(-4.25 + (i % 7) * 0.3), f32(-6.0 + (i % 5) * 0.45)]
+        # Small integers, so that the norm is exact whatever order it is summed in
+        rotation = [float(1 + i % 4), float(i % 3 - 1), float((i * 5) % 7 - 3), float(i % 2 * 2)]
+        rows.append(position + [0.0, 0.0, 0.0] + dc + rest + [opacity] + scale + rotation)
+    return rows
+
+
+def write_ply(path, rows):
+    header = "ply\nformat binary_little_endian 1.0\nelement vertex {}\n".format(len(rows))
+    header += "".join(f"property float {name}\n" for name in PROPERTIES)
+    header += "end_header\n"
+    with open(path, "wb") as file:
+        file.write(header.encode("ascii"))
+        for row in rows:
+            file.write(struct.pack(f"<{len(PROPERTIES)}f", *row))
+
+
+# --- From https://github.com/antimatter15/splat/blob/main/convert.py (MIT) ---
+
+
+def process_ply_to_splat(ply_file_path):
+    import numpy as np
+    from plyfile import PlyData
+
+    plydata = PlyData.read(ply_file_path)
+    vert = plydata["vertex"]
+    sorted_indices = np.argsort(
+        -np.exp(vert["scale_0"] + vert["scale_1"] + vert["scale_2"])
+        / (1 + np.exp(-vert["opacity"]))
+    )
+    buffer = BytesIO()
+    for idx in sorted_indices:
+        v = plydata["vertex"][idx]
+        position = np.array([v["x"], v["y"], v["z"]], dtype=np.float32)
+        scales = np.exp(
+            np.array(
+                [v["scale_0"], v["scale_1"], v["scale_2"]],
+                dtype=np.float32,
+            )
+        )
+        rot = np.array(
+            [v["rot_0"], v["rot_1"], v["rot_2"], v["rot_3"]],
+            dtype=np.float32,
+        )
+        SH_C0 = 0.28209479177387814
+        color = np.array(
+            [
+                0.5 + SH_C0 * v["f_dc_0"],
+                0.5 + SH_C0 * v["f_dc_1"],
+                0.5 + SH_C0 * v["f_dc_2"],
+                1 / (1 + np.exp(-v["opacity"])),
+            ]
+        )
+        buffer.write(position.tobytes())
+        buffer.write(scales.tobytes())
+        buffer.write((color * 255).clip(0, 255).astype(np.uint8).tobytes())
+        buffer.write(
+            ((rot / np.linalg.norm(rot)) * 128 + 128)
+            .clip(0, 255)
+            .astype(np.uint8)
+            .tobytes()
+        )
+
+    return buffer.getvalue()
+
+
+if __name__ == "__main__":
+    write_ply(HERE / "input.ply", splats())
+    (HERE / "expected.splat").write_bytes(process_ply_to_splat(HERE / "input.ply"))
@@ -0,0 +1,153 @@
+use converter_core::{
+  Camera, Exporter, GaussianSplat, Importer, Scene,
+  formats::{
+    cameras_json::{CamerasJsonExporter, CamerasJsonImporter},
+    gltf::{GlbExporter, GltfImporter},
+    usd::{UsdImporter, UsdaExporter},
+  },
+  operations::spherical_harmonics::{BakeDirections, bake_view_dependent_color},
+};
+
+fn splat(position: [f32; 3]) -> GaussianSplat {
+  GaussianSplat {
+    position,
+    normal: [0.0, 0.0, 0.0],
+    spherical_harmonics_dc: [0.1, 0.2, 0.3],
+    spherical_harmonics_rest: Vec::new(),
+    opacity: 1.0,
+    scale: [-2.0, -2.0, -2.0],
+    rotation: [1.0, 0.0, 0.0, 0.0],
+  }
+}
+
+/// Two cameras: one looking down +Z with an off-centre principal point, one looking at the
+/// origin from the side.
+fn sample_cameras() -> Vec<Camera> {
+  vec![
+    Camera {
+      name: "frame_0001.jpg".to_string(),
+      position: [0.0, 0.0, -4.0],
+      rotation: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+      width: 800,
+      height: 600,
+      focal: [700.0, 710.0],
+      principal_point: [390.0, 310.0],
+    },
+    Camera::look_at(
+      [4.0, 1.0, 0.0],
+      [0.0, 0.0, 0.0],
+      [0.0, -1.0, 0.0],
+      640,
+      480,
+      60.0,
+    ),
+  ]
+}
+
+fn assert_same_cameras(original: &[Camera], imported: &[Camera], check_principal_point: bool) {
+  assert_eq!(imported.len(), original.len());
+  for (a, b) in original.iter().zip(imported) {
+    assert_eq!((a.width, a.height), (b.width, b.height));
+    for axis in 0..3 {
+      assert!(
+        (a.position[axis] - b.position[axis]).abs() < 1e-4,
+        "{:?}",
+        b
+      );
+      for column in 0..3 {
+        assert!(
+          (a.rotation[axis][column] - b.rotation[axis][column]).abs() < 1e-4,
+          "{:?}",
+          b
+        );
+      }
+    }
+    for i in 0..2 {
+      assert!((a.focal[i] - b.focal[i]).abs() < 1e-2, "{:?}", b);
+      if check_principal_point {
+        assert!((a.principal_point[i] - b.principal_point[i]).abs() < 1e-2);
+      }
+    }
+  }
+}
+
+#[test]
+fn cameras_json_round_trip() {
+  let cameras = sample_cameras();
+  let mut json = Vec::new();
+  CamerasJsonExporter::export(&cameras, &mut json).unwrap();
+  let imported = CamerasJsonImporter::import(&mut json.as_slice()).unwrap();
+
+  // cameras.json has no principal point, it is always the image centre
+  assert_same_cameras(&cameras, &imported, false);
+  assert_eq!(imported[0].name, "frame_0001.jpg");
+  assert_eq!(imported[0].principal_point, [400.0, 300.0]);
+
+  // The rotation is stored camera-to-world
+  let entries: serde_json::Value = serde_json::from_slice(&json).unwrap();
+  let rotation = &entries[1]["rotation"];
+  for row in 0..3 {
+    for column in 0..3 {
+      let stored = rotation[row][column].as_f64().unwrap() as f32;
+      assert!((stored - cameras[1].rotation[column][row]).abs() < 1e-6);
+    }
+  }
+}
+
+#[test]
+fn gltf_cameras_round_trip() {
+  for splats in [vec![splat([0.0; 3]), splat([1.0, 0.0, 0.0])], Vec::new()] {
+    let scene = Scene {
+      splats,
+      cameras: sample_cameras(),
+    };
+    let mut glb = Vec::new();
+    GlbExporter::export(&scene, &mut glb).unwrap();
+    let imported = GltfImporter::import(&mut glb.as_slice()).unwrap();
+
+    assert_eq!(imported.splats.len(), scene.splats.len());
+    assert_same_cameras(&scene.cameras, &imported.cameras, true);
+    assert_eq!(imported.cameras[0].name, "frame_0001.jpg");
+  }
+}
+
+#[test]
+fn usd_writes_camera_prims() {
+  let scene = Scene {
+    splats: vec![splat([0.0; 3])],
+    cameras: sample_cameras(),
+  };
+  let mut usda = Vec::new();
+  UsdaExporter::export(&scene, &mut usda).unwrap();
+  let text = String::from_utf8(usda.clone()).unwrap();
+
+  assert!(text.contains("def Camera \"Camera_0\""));
+  assert!(text.contains("def Camera \"Camera_1\""));
+  assert!(text.contains("string gsflux:imageName = \"frame_0001.jpg\""));
+  // The camera prims don't get in the way of reading the splats back
+  let imported = UsdImporter::import(&mut usda.as_slice()).unwrap();
+  assert_eq!(imported.splats.len(), 1);
+}
+
+#[test]
+fn bake_from_cameras_uses_directions_towards_each_splat() {
+  // A degree 1 coefficient on the Z basis function: looking along +Z the red is brighter
+  let mut scene = Scene {
+    splats: vec![splat([0.0, 0.0, 0.0]), splat([0.0, 0.0, 10.0])],
+    cameras: Vec::new(),
+  };
+  for splat in &mut scene.splats {
+    splat.spherical_harmonics_rest = vec![0.0; 9];
+    splat.spherical_harmonics_rest[1] = 1.0;
+  }
+  // A camera between the two splats sees them from opposite sides
+  bake_view_dependent_color(&mut scene, &BakeDirections::Cameras(vec![[0.0, 0.0, 5.0]]));
+
+  let (behind, ahead) = (&scene.splats[0], &scene.splats[1]);
+  assert!(behind.spherical_harmonics_rest.is_empty());
+  assert!(ahead.spherical_harmonics_dc[0] - behind.spherical_harmonics_dc[0] > 0.5);
+  assert_eq!(
+    behind.spherical_harmonics_dc[1],
+    ahead.spherical_harmonics_dc[1]
+  );
+}
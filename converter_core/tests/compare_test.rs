@@ -0,0 +1,160 @@
+use converter_core::{
+  GaussianSplat, Scene,
+  formats::colmap::ColmapCamerasImporter,
+  metrics::{attribute_errors, psnr, ssim},
+};
+use image::{Rgba, RgbaImage};
+use std::f64::consts::FRAC_1_SQRT_2;
+
+fn splat(position: [f32; 3], opacity: f32) -> GaussianSplat {
+  GaussianSplat {
+    position,
+    normal: [0.0, 0.0, 0.0],
+    spherical_harmonics_dc: [0.1, 0.2, 0.3],
+    spherical_harmonics_rest: vec![0.5; 9],
+    opacity,
+    scale: [-2.0, -1.0, -3.0],
+    rotation: [1.0, 0.0, 0.0, 0.0],
+  }
+}
+
+fn pattern(width: u32, height: u32) -> RgbaImage {
+  RgbaImage::from_fn(width, height, |x, y| {
+    Rgba([
+      (x * 7 % 256) as u8,
+      (y * 13 % 256) as u8,
+      ((x + y) * 3 % 256) as u8,
+      255,
+    ])
+  })
+}
+
+#[test]
+fn image_metrics() {
+  let a = pattern(40, 30);
+  assert_eq!(psnr(&a, &a), f64::INFINITY);
+  assert!((ssim(&a, &a) - 1.0).abs() < 1e-9);
+
+  // Every channel off by 5 gives an MSE of 25
+  let mut b = a.clone();
+  for pixel in b.pixels_mut() {
+    for c in 0..3 {
+      pixel[c] = if pixel[c] > 250 {
+        pixel[c] - 5
+      } else {
+        pixel[c] + 5
+      };
+    }
+  }
+  assert!((psnr(&a, &b) - 10.0 * (255.0f64 * 255.0 / 25.0).log10()).abs() < 1e-9);
+  assert!(ssim(&a, &b) > 0.9 && ssim(&a, &b) < 1.0);
+
+  // Unrelated images are far less similar
+  let flat = RgbaImage::from_pixel(40, 30, Rgba([128, 128, 128, 255]));
+  assert!(ssim(&a, &flat) < 0.5);
+  assert!(psnr(&a, &flat) < psnr(&a, &b));
+}
+
+#[test]
+fn attribute_errors_match_reordered_splats() {
+  let reference = Scene {
+    splats: vec![splat([0.0, 0.0, 0.0], 0.0), splat([5.0, 0.0, 0.0], 2.0)],
+    cameras: Vec::new(),
+  };
+  let mut reordered = Scene {
+    splats: reference.splats.iter().rev().cloned().collect(),
+    cameras: Vec::new(),
+  };
+  let errors = attribute_errors(&reference, &reordered);
+  assert_eq!(errors.position.max, 0.0);
+  assert_eq!(errors.opacity.max, 0.0);
+  assert_eq!(errors.spherical_harmonics.max, 0.0);
+
+  reordered.splats[0].position[1] = 0.5;
+  reordered.splats[1].opacity = 100.0;
+  // A rotation of 90 degrees around X, written with the opposite sign
+  reordered.splats[0].rotation = [-0.70710677, -0.70710677, 0.0, 0.0];
+  let errors = attribute_errors(&reference, &reordered);
+  assert!((errors.position.max - 0.5).abs() < 1e-6);
+  assert!((errors.position.mean - 0.25).abs() < 1e-6);
+  assert!((errors.opacity.max - 0.5).abs() < 1e-6);
+  assert!((errors.rotation_degrees.max - 90.0).abs() < 1e-3);
+  assert_eq!(errors.color.max, 0.0);
+  assert_eq!(errors.reference_count, 2);
+}
+
+#[test]
+fn colmap_cameras_text_and_binary_agree() {
+  let cameras_text =
+    "# Camera list\n1 PINHOLE 800 600 700 710 400 300\n2 SIMPLE_RADIAL 640 480 500 320 240 0.01\n";
+  // Second image: 90 degrees around Y, then translated
+  let images_text = "# Image list\n\
+    2 0.70710678 0 0.70710678 0 1 2 3 2 b.jpg\n\
+    \n\
+    1 1 0 0 0 0 0 5 1 a.jpg\n\
+    100.0 200.0 -1 101.0 201.0 7\n";
+
+  let mut cameras_binary = Vec::new();
+  cameras_binary.extend_from_slice(&2u64.to_le_bytes());
+  for (id, model, width, height, params) in [
+    (1u32, 1u32, 800u64, 600u64, vec![700.0, 710.0, 400.0, 300.0]),
+    (2, 2, 640, 480, vec![500.0, 320.0, 240.0, 0.01]),
+  ] {
+    cameras_binary.extend_from_slice(&id.to_le_bytes());
+    cameras_binary.extend_from_slice(&model.to_le_bytes());
+    cameras_binary.extend_from_slice(&width.to_le_bytes());
+    cameras_binary.extend_from_slice(&height.to_le_bytes());
+    for param in params {
+      cameras_binary.extend_from_slice(&f64::to_le_bytes(param));
+    }
+  }
+  let mut images_binary = Vec::new();
+  images_binary.extend_from_slice(&2u64.to_le_bytes());
+  for (id, pose, camera, name, observations) in [
+    (
+      2u32,
+      [FRAC_1_SQRT_2, 0.0, FRAC_1_SQRT_2, 0.0, 1.0, 2.0, 3.0],
+      2u32,
+      "b.jpg",
+      0u64,
+    ),
+    (1, [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 5.0], 1, "a.jpg", 2),
+  ] {
+    images_binary.extend_from_slice(&id.to_le_bytes());
+    for value in pose {
+      images_binary.extend_from_slice(&f64::to_le_bytes(value));
+    }
+    images_binary.extend_from_slice(&camera.to_le_bytes());
+    images_binary.extend_from_slice(name.as_bytes());
+    images_binary.push(0);
+    images_binary.extend_from_slice(&observations.to_le_bytes());
+    images_binary.extend(std::iter::repeat_n(0u8, observations as usize * 24));
+  }
+
+  let from_text =
+    ColmapCamerasImporter::import(&mut cameras_text.as_bytes(), &mut images_text.as_bytes())
+      .unwrap();
+  let from_binary = ColmapCamerasImporter::import(
+    &mut cameras_binary.as_slice(),
+    &mut images_binary.as_slice(),
+  )
+  .unwrap();
+
+  for cameras in [&from_text, &from_binary] {
+    assert_eq!(cameras.len(), 2);
+    // Sorted by image name
+    let (a, b) = (&cameras[0], &cameras[1]);
+    assert_eq!((a.width, a.height), (800, 600));
+    assert_eq!(a.focal, [700.0, 710.0]);
+    assert_eq!(a.principal_point, [400.0, 300.0]);
+    assert_eq!(a.position, [0.0, 0.0, -5.0]);
+
+    assert_eq!((b.width, b.height), (640, 480));
+    assert_eq!(b.focal, [500.0, 500.0]);
+    // Centre is -Rᵀ t with R mapping world X to camera -Z and world Z to camera X
+    let expected = [3.0, -2.0, -1.0];
+    for (actual, expected) in b.position.iter().zip(expected) {
+      assert!((actual - expected).abs() < 1e-5, "{:?}", b.position);
+    }
+  }
+}
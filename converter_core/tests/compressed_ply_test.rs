@@ -0,0 +1,94 @@
+use converter_core::{
+  Exporter, GaussianSplat, Importer, Scene,
+  formats::{
+    compressed_ply::{CompressedPlyExporter, CompressedPlyImporter},
+    ply::PlyImporter,
+  },
+};
+
+fn sample_scene() -> Scene {
+  let splats = (0..300)
+    .map(|i| {
+      let t = i as f32;
+      let (s, c) = (t * 0.1).sin_cos();
+      GaussianSplat {
+        position: [s * 2.0, c * 2.0, t * 0.01],
+        normal: [0.0, 0.0, 0.0],
+        spherical_harmonics_dc: [s, c, 0.5],
+        spherical_harmonics_rest: (0..9).map(|k| (k as f32 - 4.0) * 0.1).collect(),
+        opacity: c * 3.0,
+        scale: [-4.0 + s, -3.0, -5.0 + c],
+        rotation: [c, s * 0.5, 0.2, -0.3],
+      }
+    })
+    .collect();
+
+  Scene {
+    splats,
+    cameras: Vec::new(),
+  }
+}
+
+fn normalized(q: [f32; 4]) -> [f32; 4] {
+  let len = q.iter().map(|v| v * v).sum::<f32>().sqrt();
+  q.map(|v| v / len)
+}
+
+#[test]
+fn compressed_ply_round_trip() {
+  let scene = sample_scene();
+  let mut output = Vec::new();
+  CompressedPlyExporter::export(&scene, &mut output).unwrap();
+
+  let header = String::from_utf8_lossy(&output[..1024]).to_string();
+  assert!(header.contains("element chunk 2"));
+  assert!(header.contains("property uint packed_position"));
+  assert!(header.contains("property uchar f_rest_8"));
+
+  let imported = CompressedPlyImporter::import(&mut output.as_slice()).unwrap();
+  assert_eq!(imported.splats.len(), 300);
+  assert_eq!(imported.sh_degree(), 1);
+
+  // Splats are reordered along a Morton curve, so match them up by their Z coordinate
+  for original in &scene.splats {
+    let decoded = imported
+      .splats
+      .iter()
+      .min_by(|a, b| {
+        let da = (a.position[2] - original.position[2]).abs();
+        let db = (b.position[2] - original.position[2]).abs();
+        da.total_cmp(&db)
+      })
+      .unwrap();
+
+    for axis in 0..3 {
+      assert!((decoded.position[axis] - original.position[axis]).abs() < 0.01);
+      assert!((decoded.scale[axis] - original.scale[axis]).abs() < 0.01);
+    }
+
+    let (q, r) = (normalized(original.rotation), decoded.rotation);
+    let dot: f32 = q.iter().zip(r).map(|(a, b)| a * b).sum();
+    assert!(
+      dot.abs() > 0.999,
+      "Rotation should survive 2-10-10-10 packing"
+    );
+
+    assert!((decoded.sh_rest(0, 1) - original.sh_rest(0, 1)).abs() < 0.04);
+  }
+}
+
+#[test]
+fn ply_importer_detects_compressed_layout() {
+  let mut output = Vec::new();
+  CompressedPlyExporter::export(&sample_scene(), &mut output).unwrap();
+
+  let imported = PlyImporter::import(&mut output.as_slice()).unwrap();
+  assert_eq!(imported.splats.len(), 300);
+}
+
+#[test]
+fn compressed_ply_importer_rejects_plain_ply() {
+  let data = "ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\nend_header\n1\n";
+  let result = CompressedPlyImporter::import(&mut data.as_bytes());
+  assert!(result.is_err());
+}
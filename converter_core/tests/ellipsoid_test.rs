@@ -0,0 +1,161 @@
+use converter_core::{
+  Exporter, GaussianSplat, Scene,
+  formats::ellipsoid::{EllipsoidExportOptions, EllipsoidObjExporter, EllipsoidPlyExporter},
+};
+
+fn splat(position: [f32; 3], scale: [f32; 3], opacity: f32) -> GaussianSplat {
+  GaussianSplat {
+    position,
+    normal: [0.0, 0.0, 0.0],
+    // 0.5 + SH_C0 * dc gives pure green
+    spherical_harmonics_dc: [-1.7724539, 1.7724539, -1.7724539],
+    spherical_harmonics_rest: Vec::new(),
+    opacity,
+    scale,
+    // 90 degrees about Z
+    rotation: [
+      std::f32::consts::FRAC_1_SQRT_2,
+      0.0,
+      0.0,
+      std::f32::consts::FRAC_1_SQRT_2,
+    ],
+  }
+}
+
+fn obj_vertices(obj: &str) -> Vec<[f32; 6]> {
+  obj
+    .lines()
+    .filter_map(|line| line.strip_prefix("v "))
+    .map(|line| {
+      let values: Vec<f32> = line.split(' ').map(|v| v.parse().unwrap()).collect();
+      values.try_into().unwrap()
+    })
+    .collect()
+}
+
+fn obj_faces(obj: &str) -> Vec<[usize; 3]> {
+  obj
+    .lines()
+    .filter_map(|line| line.strip_prefix("f "))
+    .map(|line| {
+      let values: Vec<usize> = line.split(' ').map(|v| v.parse().unwrap()).collect();
+      values.try_into().unwrap()
+    })
+    .collect()
+}
+
+#[test]
+fn obj_ellipsoid_is_scaled_rotated_and_coloured() {
+  let scene = Scene {
+    splats: vec![splat([1.0, 2.0, 3.0], [0.0, -1.0, -2.0], 2.0)],
+    cameras: Vec::new(),
+  };
+  let mut output = Vec::new();
+  EllipsoidObjExporter::export(&scene, &mut output).unwrap();
+  let obj = String::from_utf8(output).unwrap();
+
+  let vertices = obj_vertices(&obj);
+  let faces = obj_faces(&obj);
+  assert_eq!(vertices.len(), 12);
+  assert_eq!(faces.len(), 20);
+
+  // The local X axis (radius 2 sigma * e^0) is rotated onto world Y
+  let extent = |axis: usize| {
+    vertices
+      .iter()
+      .map(|v| (v[axis] - [1.0, 2.0, 3.0][axis]).abs())
+      .fold(0.0f32, f32::max)
+  };
+  assert!(extent(1) <= 2.0 + 1e-5 && extent(1) > 1.7);
+  assert!(extent(0) <= 2.0 * (-1.0f32).exp() + 1e-5);
+  assert!(extent(2) <= 2.0 * (-2.0f32).exp() + 1e-5);
+
+  assert_eq!(vertices[0][3..], [0.0, 1.0, 0.0]);
+
+  // Triangles face away from the centre
+  for [a, b, c] in faces {
+    let [p, q, r] = [a, b, c].map(|i| vertices[i - 1]);
+    let u = [q[0] - p[0], q[1] - p[1], q[2] - p[2]];
+    let v = [r[0] - p[0], r[1] - p[1], r[2] - p[2]];
+    let normal = [
+      u[1] * v[2] - u[2] * v[1],
+      u[2] * v[0] - u[0] * v[2],
+      u[0] * v[1] - u[1] * v[0],
+    ];
+    let outward = (0..3)
+      .map(|i| normal[i] * (p[i] - [1.0, 2.0, 3.0][i]))
+      .sum::<f32>();
+    assert!(outward > 0.0);
+  }
+}
+
+#[test]
+fn ply_mesh_layout() {
+  let scene = Scene {
+    splats: vec![
+      splat([0.0; 3], [0.0; 3], 5.0),
+      splat([1.0; 3], [0.0; 3], 5.0),
+    ],
+    cameras: Vec::new(),
+  };
+  let mut output = Vec::new();
+  EllipsoidPlyExporter::export(&scene, &mut output).unwrap();
+
+  let header_end = output
+    .windows(11)
+    .position(|w| w == b"end_header\n")
+    .unwrap()
+    + 11;
+  let header = String::from_utf8_lossy(&output[..header_end]).to_string();
+  assert!(header.contains("element vertex 24"));
+  assert!(header.contains("element face 40"));
+  assert!(header.contains("property list uchar uint vertex_indices"));
+  assert_eq!(output.len() - header_end, 24 * 16 + 40 * 13);
+
+  // The last face belongs to the second ellipsoid
+  let face = &output[output.len() - 13..];
+  assert_eq!(face[0], 3);
+  let index = u32::from_le_bytes(face[1..5].try_into().unwrap());
+  assert!((12..24).contains(&index));
+}
+
+#[test]
+fn triangle_budget_and_opacity_threshold() {
+  // Larger splats come later, so the budget has to reorder them
+  let splats = (0..10)
+    .map(|i| splat([i as f32, 0.0, 0.0], [i as f32 * 0.1; 3], 2.0))
+    .chain(std::iter::once(splat([0.0; 3], [5.0; 3], -5.0)))
+    .collect();
+  let scene = Scene {
+    splats,
+    cameras: Vec::new(),
+  };
+
+  let export = |options: EllipsoidExportOptions| {
+    let mut output = Vec::new();
+    EllipsoidObjExporter::export_with_options(&scene, &mut output, &options).unwrap();
+    String::from_utf8(output).unwrap()
+  };
+  let with = |max_triangles: Option<usize>| EllipsoidExportOptions {
+    min_opacity: Some(0.5),
+    max_triangles,
+    ..EllipsoidExportOptions::default()
+  };
+
+  // The transparent splat is dropped
+  assert_eq!(obj_faces(&export(with(None))).len(), 10 * 20);
+  assert_eq!(obj_faces(&export(with(Some(200)))).len(), 10 * 20);
+
+  // Octahedra once icosahedra no longer fit
+  let obj = export(with(Some(199)));
+  assert_eq!(obj_faces(&obj).len(), 10 * 8);
+  assert_eq!(obj_vertices(&obj).len(), 10 * 6);
+
+  // Then only the most important splats
+  let obj = export(with(Some(40)));
+  assert_eq!(obj_faces(&obj).len(), 5 * 8);
+  for octahedron in obj_vertices(&obj).chunks(6) {
+    let centre_x = octahedron.iter().map(|v| v[0]).sum::<f32>() / 6.0;
+    assert!(centre_x > 4.5);
+  }
+}
@@ -0,0 +1,18 @@
+use std::io;
+
+use converter_core::{ConversionError, Importer, formats::csv::CsvImporter};
+
+#[test]
+fn errors_name_their_kind_and_format() {
+  let error = CsvImporter::import(&mut "x,y\n1".as_bytes()).unwrap_err();
+  assert_eq!(error.kind(), "parse");
+  assert_eq!(error.format(), Some("CSV"));
+
+  let error = ConversionError::from(io::Error::new(io::ErrorKind::NotFound, "missing"));
+  assert_eq!(error.kind(), "io");
+  assert_eq!(error.format(), None);
+  assert_eq!(
+    ConversionError::UnsupportedFormat.kind(),
+    "unsupported_format"
+  );
+}
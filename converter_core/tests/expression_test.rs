@@ -0,0 +1,140 @@
+use converter_core::{
+  GaussianSplat, Scene, helpers::activation::inverse_sigmoid, operations::expression::Expression,
+  operations::filter::filter_expression, operations::pipeline::Operation,
+};
+
+fn splat_at(position: [f32; 3], opacity: f32, scale: f32) -> GaussianSplat {
+  GaussianSplat {
+    position,
+    normal: [0.0, 0.0, 0.0],
+    spherical_harmonics_dc: [0.0; 3],
+    spherical_harmonics_rest: Vec::new(),
+    opacity: inverse_sigmoid(opacity),
+    scale: [scale.ln(); 3],
+    rotation: [1.0, 0.0, 0.0, 0.0],
+  }
+}
+
+fn holds(source: &str, splat: &GaussianSplat) -> bool {
+  Expression::compile(source).unwrap().matches(splat)
+}
+
+#[test]
+fn evaluates_attributes_arithmetic_and_logic() {
+  let mut splat = splat_at([1.0, -2.0, 3.0], 0.8, 0.5);
+  splat.scale[2] = 2.0f32.ln();
+  splat.spherical_harmonics_dc = [1.0, 0.0, -1.0];
+
+  assert!(holds("opacity > 0.79 and opacity < 0.81", &splat));
+  assert!(holds("max_scale > 1.9 && min_scale < 0.6", &splat));
+  assert!(holds("abs(scale_z - 4 * scale_x) < 1e-5", &splat));
+  assert!(holds("x + y * 2 == -3", &splat));
+  assert!(holds("(x + y) * 2 == -2", &splat));
+  assert!(holds("-2^2 == -4 and 2^-1 == 0.5 and 7 % 4 == 3", &splat));
+  assert!(holds("abs(distance - sqrt(14)) < 1e-5", &splat));
+  assert!(holds("max(x, y, z) == 3 and min(x, y) == -2", &splat));
+  assert!(holds("r > 0.5 and g == 0.5 and b < 0.5", &splat));
+  assert!(holds(
+    "f_dc_0 == 1 and f_rest_0 == 0 and rot_0 == 1",
+    &splat
+  ));
+  assert!(holds("not (z < 0) or false", &splat));
+  assert!(holds("!(x > 5) || y > 0", &splat));
+  assert!(!holds("x > 0 and y > 0", &splat));
+}
+
+#[test]
+fn comparisons_chain() {
+  let inside = splat_at([0.5, 0.0, 0.0], 0.5, 1.0);
+  let outside = splat_at([1.5, 0.0, 0.0], 0.5, 1.0);
+
+  assert!(holds("-1 < x < 1", &inside));
+  assert!(!holds("-1 < x < 1", &outside));
+}
+
+#[test]
+fn rejects_invalid_expressions() {
+  for source in [
+    "",
+    "opacity",
+    "opacity > ",
+    "opacity = 0.1",
+    "brightness > 1",
+    "f_rest_45 > 0",
+    "sqrt(x, y) > 1",
+    "shout(x) > 1",
+    "(x > 0) + 1 > 0",
+    "x and y",
+    "x > 0 z",
+    "x > $",
+  ] {
+    assert!(Expression::compile(source).is_err(), "{}", source);
+  }
+}
+
+#[test]
+fn rejects_expressions_nested_too_deeply() {
+  let splat = splat_at([1.0, 2.0, 3.0], 0.8, 1.0);
+  assert!(holds(&format!("{}x > 0", "not not ".repeat(30)), &splat));
+  assert!(holds(
+    &format!("{}x{} > 0", "(".repeat(60), ")".repeat(60)),
+    &splat
+  ));
+
+  for source in [
+    format!("{}x > 0", "not ".repeat(30000)),
+    format!("{}x{} > 0", "(".repeat(30000), ")".repeat(30000)),
+    format!("{}x > 0", "-".repeat(30000)),
+    format!("{}x{} > 0", "abs(".repeat(30000), ")".repeat(30000)),
+    format!("x > 0{}", " and x > 0".repeat(30000)),
+    format!("x{} > 0", " + 1".repeat(30000)),
+  ] {
+    let message = Expression::compile(&source).unwrap_err().to_string();
+    assert!(message.contains("deeper than 64"), "{}", message);
+  }
+}
+
+#[test]
+fn errors_point_at_the_problem() {
+  let message = Expression::compile("opacity > 0.1 and brightness > 1")
+    .unwrap_err()
+    .to_string();
+  assert!(message.contains("brightness"));
+  assert!(message.contains("column 19"));
+}
+
+#[test]
+fn filters_scenes_keeping_their_order() {
+  let mut scene = Scene {
+    splats: vec![
+      splat_at([0.0, 0.0, 1.0], 0.9, 0.1),
+      splat_at([0.0, 0.0, -1.0], 0.9, 0.1),
+      splat_at([0.0, 0.0, 2.0], 0.05, 0.1),
+      splat_at([0.0, 0.0, 3.0], 0.9, 0.8),
+      splat_at([0.0, 0.0, 4.0], 0.5, 0.2),
+    ],
+    cameras: Vec::new(),
+  };
+  let expression = Expression::compile("opacity > 0.1 and max_scale < 0.5 and z > 0").unwrap();
+
+  filter_expression(&mut scene, &expression);
+
+  let zs: Vec<f32> = scene.splats.iter().map(|s| s.position[2]).collect();
+  assert_eq!(zs, vec![1.0, 4.0]);
+}
+
+#[test]
+fn where_is_a_pipeline_step() {
+  let operation: Operation = "where:opacity >= 0.5 and max(x, y) != 1".parse().unwrap();
+  assert_eq!(
+    operation,
+    Operation::Where(Expression::compile("opacity >= 0.5 and max(x, y) != 1").unwrap())
+  );
+  assert_eq!(
+    operation.to_string().parse::<Operation>().unwrap(),
+    operation
+  );
+
+  let from_table: Operation = serde_json::from_str(r#"{"op": "where", "expr": "z > 0"}"#).unwrap();
+  assert_eq!(from_table, "where:z > 0".parse().unwrap());
+}
@@ -0,0 +1,224 @@
+use converter_core::{
+  Exporter, GaussianSplat, Importer, Scene,
+  formats::gltf::{GlbExporter, GltfExportOptions, GltfExporter, GltfImporter},
+  helpers::meshopt::{decode_vertex_buffer, encode_vertex_buffer},
+};
+
+fn sample_scene() -> Scene {
+  let splats = (0..400)
+    .map(|i| {
+      let t = i as f32;
+      let (s, c) = (t * 0.1).sin_cos();
+      GaussianSplat {
+        position: [s * 2.0, c * 2.0, t * 0.01],
+        normal: [0.0, 0.0, 0.0],
+        spherical_harmonics_dc: [s, c, 0.5],
+        spherical_harmonics_rest: (0..24).map(|k| (k as f32 - 12.0) * 0.01 * s).collect(),
+        opacity: c * 3.0,
+        scale: [-4.0 + s, -3.0, -5.0 + c],
+        rotation: [0.5, 0.5, -0.5, 0.5],
+      }
+    })
+    .collect();
+
+  Scene {
+    splats,
+    cameras: Vec::new(),
+  }
+}
+
+fn assert_same_scene(original: &Scene, imported: &Scene) {
+  assert_eq!(imported.splats.len(), original.splats.len());
+  assert_eq!(imported.sh_degree(), original.sh_degree());
+
+  for (a, b) in original.splats.iter().zip(&imported.splats) {
+    assert_eq!(a.position, b.position);
+    assert_eq!(a.spherical_harmonics_dc, b.spherical_harmonics_dc);
+    assert_eq!(a.spherical_harmonics_rest, b.spherical_harmonics_rest);
+    assert!((a.opacity - b.opacity).abs() < 1e-3);
+    for axis in 0..3 {
+      assert!((a.scale[axis] - b.scale[axis]).abs() < 1e-5);
+    }
+    for (x, y) in a.rotation.iter().zip(b.rotation) {
+      assert!((x - y).abs() < 1e-6);
+    }
+  }
+}
+
+#[test]
+fn glb_round_trip() {
+  let scene = sample_scene();
+  let mut output = Vec::new();
+  GlbExporter::export(&scene, &mut output).unwrap();
+
+  assert_eq!(output[0..4], *b"glTF");
+  assert_eq!(
+    u32::from_le_bytes(output[8..12].try_into().unwrap()) as usize,
+    output.len()
+  );
+
+  let imported = GltfImporter::import(&mut output.as_slice()).unwrap();
+  assert_same_scene(&scene, &imported);
+}
+
+#[test]
+fn gltf_embedded_round_trip() {
+  let scene = sample_scene();
+  let mut output = Vec::new();
+  GltfExporter::export(&scene, &mut output).unwrap();
+
+  let json = String::from_utf8(output.clone()).unwrap();
+  assert!(json.contains("KHR_gaussian_splatting:SH_DEGREE_2_COEF_4"));
+  assert!(json.contains("data:application/octet-stream;base64,"));
+
+  let imported = GltfImporter::import(&mut output.as_slice()).unwrap();
+  assert_same_scene(&scene, &imported);
+}
+
+#[test]
+fn gltf_external_buffer_round_trip() {
+  let scene = sample_scene();
+  let (mut json, mut bin) = (Vec::new(), Vec::new());
+  GltfExporter::export_with_external_buffer(
+    &scene,
+    &mut json,
+    &mut bin,
+    "scene.bin",
+    &GltfExportOptions::default(),
+  )
+  .unwrap();
+
+  assert!(GltfImporter::import(&mut json.as_slice()).is_err());
+
+  let imported = GltfImporter::import_with_resolver(&mut json.as_slice(), |uri| {
+    assert_eq!(uri, "scene.bin");
+    Ok(bin.clone())
+  })
+  .unwrap();
+  assert_same_scene(&scene, &imported);
+}
+
+#[test]
+fn glb_meshopt_round_trip() {
+  let scene = sample_scene();
+  let options = GltfExportOptions {
+    meshopt_compression: true,
+  };
+  let (mut plain, mut compressed) = (Vec::new(), Vec::new());
+  GlbExporter::export(&scene, &mut plain).unwrap();
+  GlbExporter::export_with_options(&scene, &mut compressed, &options).unwrap();
+
+  assert!(compressed.len() < plain.len());
+  assert!(
+    String::from_utf8_lossy(&compressed)
+      .contains("\"extensionsRequired\":[\"KHR_meshopt_compression\"]")
+  );
+
+  let imported = GltfImporter::import(&mut compressed.as_slice()).unwrap();
+  assert_same_scene(&scene, &imported);
+}
+
+#[test]
+fn gltf_empty_scene() {
+  let mut output = Vec::new();
+  GlbExporter::export(
+    &Scene {
+      splats: Vec::new(),
+      cameras: Vec::new(),
+    },
+    &mut output,
+  )
+  .unwrap();
+  let imported = GltfImporter::import(&mut output.as_slice()).unwrap();
+  assert!(imported.splats.is_empty());
+}
+
+#[test]
+fn gltf_importer_failure_on_bad_data() {
+  assert!(GltfImporter::import(&mut "not a gltf file".as_bytes()).is_err());
+  assert!(GltfImporter::import(&mut "glTF\x02\0\0\0".as_bytes()).is_err());
+}
+
+/// A `.gltf` whose first primitive has `attributes`, over 12 zero bytes in buffer view 0.
+fn gltf_with_attributes(attributes: &str, accessors: &str, view: &str) -> String {
+  format!(
+    r#"{{"asset": {{"version": "2.0"}},
+      "buffers": [{{"uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAA", "byteLength": 12}}],
+      "bufferViews": [{view}],
+      "accessors": [{accessors}],
+      "meshes": [{{"primitives": [{{"attributes": {attributes},
+        "extensions": {{"KHR_gaussian_splatting": {{}}}}}}]}}]}}"#
+  )
+}
+
+#[test]
+fn gltf_importer_rejects_counts_beyond_the_data() {
+  let import = |gltf: String| GltfImporter::import(&mut gltf.as_bytes());
+  let view = r#"{"buffer": 0, "byteLength": 12}"#;
+  let position = r#"{"bufferView": 0, "componentType": 5126, "count": 1, "type": "VEC3"}"#;
+  let huge = r#"{"componentType": 5126, "count": 4000000000000, "type": "VEC4"}"#;
+
+  // Without a buffer view, positions can't be read and other attributes can't outnumber them
+  let unbacked = r#"{"componentType": 5126, "count": 4000000000000, "type": "VEC3"}"#;
+  assert!(import(gltf_with_attributes(r#"{"POSITION": 0}"#, unbacked, view)).is_err());
+  let attributes = r#"{"POSITION": 0, "KHR_gaussian_splatting:ROTATION": 1}"#;
+  assert!(
+    import(gltf_with_attributes(
+      attributes,
+      &format!("{position}, {huge}"),
+      view
+    ))
+    .is_err()
+  );
+  let one = r#"{"componentType": 5126, "count": 1, "type": "VEC4"}"#;
+  let scene = import(gltf_with_attributes(
+    attributes,
+    &format!("{position}, {one}"),
+    view,
+  ))
+  .unwrap();
+  assert_eq!(scene.splats.len(), 1);
+
+  // Counts, offsets and strides past the end of the view
+  let beyond =
+    r#"{"bufferView": 0, "componentType": 5126, "count": 4000000000000, "type": "VEC3"}"#;
+  assert!(import(gltf_with_attributes(r#"{"POSITION": 0}"#, beyond, view)).is_err());
+  let offset = r#"{"bufferView": 0, "byteOffset": 18446744073709551615, "componentType": 5126, "count": 1, "type": "VEC3"}"#;
+  assert!(import(gltf_with_attributes(r#"{"POSITION": 0}"#, offset, view)).is_err());
+  let strided = r#"{"buffer": 0, "byteLength": 12, "byteStride": 18446744073709551615}"#;
+  let two = r#"{"bufferView": 0, "componentType": 5126, "count": 2, "type": "VEC3"}"#;
+  assert!(import(gltf_with_attributes(r#"{"POSITION": 0}"#, two, strided)).is_err());
+  let shifted = r#"{"buffer": 0, "byteOffset": 18446744073709551615, "byteLength": 12}"#;
+  assert!(
+    import(gltf_with_attributes(
+      r#"{"POSITION": 0}"#,
+      position,
+      shifted
+    ))
+    .is_err()
+  );
+}
+
+#[test]
+fn meshopt_vertex_codec_round_trip() {
+  let vertices: Vec<u8> = (0..1000u32)
+    .flat_map(|i| {
+      [
+        (i as f32 * 0.01).sin().to_le_bytes(),
+        i.to_le_bytes(),
+        [7; 4],
+      ]
+    })
+    .flatten()
+    .collect();
+  let encoded = encode_vertex_buffer(&vertices, 12);
+  assert_eq!(encoded[0], 0xa0);
+  assert!(encoded.len() < vertices.len());
+
+  let decoded = decode_vertex_buffer(&encoded, 1000, 12).unwrap();
+  assert_eq!(decoded, vertices);
+  assert!(decode_vertex_buffer(&encoded[..encoded.len() - 1], 1000, 12).is_err());
+  // Counts the data can't hold are refused before anything is allocated
+  assert!(decode_vertex_buffer(&encoded, 1 << 40, 12).is_err());
+  assert!(decode_vertex_buffer(&encoded, usize::MAX / 4, 12).is_err());
+}
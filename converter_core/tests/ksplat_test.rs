@@ -0,0 +1,136 @@
+use converter_core::{
+  Exporter, GaussianSplat, Importer, Scene,
+  formats::ksplat::{KsplatExportOptions, KsplatExporter, KsplatImporter},
+};
+
+/// 600 splats spread over several 5-unit bucket blocks, with full and partially filled buckets.
+fn sample_scene() -> Scene {
+  let splats = (0..600)
+    .map(|i| {
+      let t = i as f32;
+      GaussianSplat {
+        position: [(t * 0.37) % 12.0 - 6.0, (t * 0.11) % 3.0, -(t * 0.05) % 7.0],
+        normal: [0.0, 0.0, 0.0],
+        spherical_harmonics_dc: [0.5, -0.25, 1.0],
+        spherical_harmonics_rest: (0..45).map(|k| ((k as f32) * 0.05 - 1.0) * 0.5).collect(),
+        opacity: 1.5,
+        scale: [-4.0, -3.5, -3.0],
+        rotation: [0.5, 0.5, 0.5, 0.5],
+      }
+    })
+    .collect();
+
+  Scene {
+    splats,
+    cameras: Vec::new(),
+  }
+}
+
+fn round_trip(options: &KsplatExportOptions) -> (Scene, Vec<u8>) {
+  let scene = sample_scene();
+  let mut output = Vec::new();
+  KsplatExporter::export_with_options(&scene, &mut output, options).unwrap();
+  let imported = KsplatImporter::import(&mut output.as_slice()).unwrap();
+  (imported, output)
+}
+
+/// Sorted positions, since compressed files are written in bucket order.
+fn sorted_positions(scene: &Scene) -> Vec<[f32; 3]> {
+  let mut positions: Vec<[f32; 3]> = scene.splats.iter().map(|s| s.position).collect();
+  positions.sort_by(|a, b| a.partial_cmp(b).unwrap());
+  positions
+}
+
+#[test]
+fn ksplat_round_trip_uncompressed() {
+  let options = KsplatExportOptions {
+    compression_level: 0,
+    sh_degree: Some(2),
+  };
+  let (imported, output) = round_trip(&options);
+  let original = sample_scene();
+
+  assert_eq!(output[0..2], [0, 1], "Version 0.1");
+  assert_eq!(output.len(), 4096 + 1024 + 600 * 140);
+  assert_eq!(imported.splats.len(), 600);
+  assert_eq!(imported.sh_degree(), 2);
+
+  for (a, b) in original.splats.iter().zip(&imported.splats) {
+    assert_eq!(a.position, b.position);
+    assert!((a.scale[0] - b.scale[0]).abs() < 1e-5);
+    assert_eq!(a.sh_rest(1, 7), b.sh_rest(1, 7));
+  }
+}
+
+#[test]
+fn ksplat_round_trip_compressed_levels() {
+  for level in [1, 2] {
+    let options = KsplatExportOptions {
+      compression_level: level,
+      sh_degree: Some(1),
+    };
+    let (imported, _) = round_trip(&options);
+    let original = sample_scene();
+
+    assert_eq!(imported.splats.len(), 600);
+    assert_eq!(imported.sh_degree(), 1);
+
+    for (a, b) in sorted_positions(&original)
+      .iter()
+      .zip(sorted_positions(&imported))
+    {
+      for axis in 0..3 {
+        assert!((a[axis] - b[axis]).abs() < 1e-3, "level {}", level);
+      }
+    }
+
+    let splat = &imported.splats[0];
+    assert!((splat.opacity - 1.5).abs() < 0.05);
+    assert!((splat.rotation[0] - 0.5).abs() < 1e-3);
+    assert!((splat.sh_rest(2, 2) - sample_scene().splats[0].sh_rest(2, 2)).abs() < 0.02);
+  }
+}
+
+#[test]
+fn ksplat_rejects_unknown_compression_level() {
+  let options = KsplatExportOptions {
+    compression_level: 3,
+    sh_degree: None,
+  };
+  let result = KsplatExporter::export_with_options(&sample_scene(), &mut Vec::new(), &options);
+  assert!(result.is_err());
+}
+
+#[test]
+fn ksplat_importer_failure_on_bad_data() {
+  let result = KsplatImporter::import(&mut "not a ksplat file".as_bytes());
+  assert!(result.is_err());
+}
+
+#[test]
+fn ksplat_default_export_is_compressed() {
+  let mut output = Vec::new();
+  KsplatExporter::export(&sample_scene(), &mut output).unwrap();
+  assert_eq!(u16::from_le_bytes([output[20], output[21]]), 1);
+}
+
+#[test]
+fn ksplat_importer_rejects_counts_beyond_the_file() {
+  let (_, output) = round_trip(&KsplatExportOptions::default());
+  let patched = |offset: usize, value: u32| {
+    let mut data = output.clone();
+    data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    KsplatImporter::import(&mut data.as_slice())
+  };
+
+  // Splat and section counts of the file header
+  assert!(patched(16, u32::MAX).is_err());
+  assert!(patched(4, u32::MAX).is_err());
+  // Splat, full bucket and partial bucket counts of the first section header
+  assert!(patched(4096, u32::MAX).is_err());
+  assert!(patched(4096 + 32, u32::MAX).is_err());
+  assert!(patched(4096 + 36, u32::MAX).is_err());
+
+  let truncated = &output[..output.len() / 2];
+  assert!(KsplatImporter::import(&mut &truncated[..]).is_err());
+}
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use converter_core::{
+  GaussianSplat, Scene,
+  formats::mesh::{MeshObjExporter, MeshPlyExporter},
+  helpers::marching_cubes::{EDGES, corner_offset, triangles},
+  operations::mesh_extraction::{MeshExtractionOptions, TriangleMesh, extract_mesh},
+};
+
+fn gaussian(position: [f32; 3], scale: [f32; 3], rotation: [f32; 4]) -> GaussianSplat {
+  GaussianSplat {
+    position,
+    normal: [0.0, 0.0, 0.0],
+    // 0.5 + SH_C0 * dc gives pure blue
+    spherical_harmonics_dc: [-1.7724539, -1.7724539, 1.7724539],
+    spherical_harmonics_rest: Vec::new(),
+    opacity: 10.0,
+    scale: scale.map(f32::ln),
+    rotation,
+  }
+}
+
+/// Every directed edge has exactly one reverse twin, so the mesh is closed and consistently wound.
+fn assert_closed(mesh: &TriangleMesh) {
+  let mut edges: HashMap<(u32, u32), usize> = HashMap::new();
+  for &[a, b, c] in &mesh.triangles {
+    for edge in [(a, b), (b, c), (c, a)] {
+      *edges.entry(edge).or_default() += 1;
+    }
+  }
+  for (&(a, b), &count) in &edges {
+    assert_eq!(count, 1, "Edge {}-{} is used twice", a, b);
+    assert_eq!(edges.get(&(b, a)), Some(&1), "Edge {}-{} is open", a, b);
+  }
+}
+
+/// Volume enclosed by the mesh, positive when its triangles face outwards.
+fn signed_volume(mesh: &TriangleMesh) -> f32 {
+  mesh
+    .triangles
+    .iter()
+    .map(|&[a, b, c]| {
+      let [p, q, r] = [a, b, c].map(|i| mesh.vertices[i as usize]);
+      (p[0] * (q[1] * r[2] - q[2] * r[1]) - p[1] * (q[0] * r[2] - q[2] * r[0])
+        + p[2] * (q[0] * r[1] - q[1] * r[0]))
+        / 6.0
+    })
+    .sum()
+}
+
+#[test]
+fn marching_cubes_table_is_watertight() {
+  // Random inside/outside lattices hit every configuration, including the ambiguous ones
+  let n = 8;
+  let mut state = 7u32;
+  for _ in 0..100 {
+    let mut inside = vec![false; n * n * n];
+    for z in 1..n - 1 {
+      for y in 1..n - 1 {
+        for x in 1..n - 1 {
+          state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+          inside[(z * n + y) * n + x] = (state >> 16).is_multiple_of(2);
+        }
+      }
+    }
+
+    let mut mesh = TriangleMesh::default();
+    let mut vertex_indices: HashMap<usize, u32> = HashMap::new();
+    for z in 0..n - 1 {
+      for y in 0..n - 1 {
+        for x in 0..n - 1 {
+          let config = (0..8).fold(0u8, |config, corner| {
+            let [dx, dy, dz] = corner_offset(corner);
+            config | ((inside[((z + dz) * n + y + dy) * n + x + dx] as u8) << corner)
+          });
+          for triangle in triangles(config) {
+            mesh.triangles.push(triangle.map(|edge| {
+              let [dx, dy, dz] = corner_offset(EDGES[edge as usize].0);
+              let key = (((z + dz) * n + y + dy) * n + x + dx) * 3 + edge as usize / 4;
+              let next = vertex_indices.len() as u32;
+              *vertex_indices.entry(key).or_insert(next)
+            }));
+          }
+        }
+      }
+    }
+    assert_closed(&mesh);
+  }
+}
+
+#[test]
+fn single_gaussian_gives_closed_sphere() {
+  let scene = Scene {
+    splats: vec![gaussian([1.0, -2.0, 0.5], [1.0; 3], [1.0, 0.0, 0.0, 0.0])],
+    cameras: Vec::new(),
+  };
+  let mesh = extract_mesh(
+    &scene,
+    &MeshExtractionOptions {
+      resolution: 48,
+      iso_level: 0.5,
+    },
+  );
+
+  assert!(!mesh.triangles.is_empty());
+  assert_closed(&mesh);
+
+  // 0.99 * exp(-r² / 2) = 0.5
+  let radius = (2.0 * (0.99f32 / 0.5).ln()).sqrt();
+  for vertex in &mesh.vertices {
+    let distance =
+      ((vertex[0] - 1.0).powi(2) + (vertex[1] + 2.0).powi(2) + (vertex[2] - 0.5).powi(2)).sqrt();
+    assert!((distance - radius).abs() < 0.05, "Distance {}", distance);
+  }
+  let volume = 4.0 / 3.0 * std::f32::consts::PI * radius.powi(3);
+  assert!((signed_volume(&mesh) - volume).abs() < volume * 0.05);
+
+  for color in &mesh.colors {
+    assert!((color[2] - 1.0).abs() < 1e-4 && color[0].abs() < 1e-4);
+  }
+}
+
+#[test]
+fn cluster_across_blocks_is_closed() {
+  // A deterministic jumble of anisotropic, rotated Gaussians spanning several grid blocks
+  let mut state = 12345u32;
+  let mut random = move || {
+    state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+    (state >> 8) as f32 / (1 << 24) as f32
+  };
+  let splats = (0..60)
+    .map(|_| {
+      let position = [random() * 6.0, random() * 3.0, random() * 3.0];
+      let scale = [
+        0.1 + random() * 0.5,
+        0.1 + random() * 0.3,
+        0.1 + random() * 0.4,
+      ];
+      let rotation = [
+        random() - 0.5,
+        random() - 0.5,
+        random() - 0.5,
+        random() - 0.5,
+      ];
+      gaussian(position, scale, rotation)
+    })
+    .collect();
+  let scene = Scene {
+    splats,
+    cameras: Vec::new(),
+  };
+
+  let mesh = extract_mesh(
+    &scene,
+    &MeshExtractionOptions {
+      resolution: 80,
+      iso_level: 0.3,
+    },
+  );
+  assert!(mesh.triangles.len() > 1000);
+  assert_closed(&mesh);
+  assert!(signed_volume(&mesh) > 0.0);
+}
+
+#[test]
+fn transparent_or_empty_scenes_give_empty_mesh() {
+  let mut splat = gaussian([0.0; 3], [1.0; 3], [1.0, 0.0, 0.0, 0.0]);
+  splat.opacity = -10.0;
+  let mesh = extract_mesh(
+    &Scene {
+      splats: vec![splat],
+      cameras: Vec::new(),
+    },
+    &MeshExtractionOptions::default(),
+  );
+  assert!(mesh.triangles.is_empty());
+
+  let mesh = extract_mesh(
+    &Scene {
+      splats: vec![],
+      cameras: Vec::new(),
+    },
+    &MeshExtractionOptions::default(),
+  );
+  assert!(mesh.vertices.is_empty());
+}
+
+#[test]
+fn mesh_writers() {
+  let mesh = TriangleMesh {
+    vertices: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+    colors: vec![[1.0, 0.0, 0.0]; 3],
+    triangles: vec![[0, 1, 2]],
+  };
+
+  let mut obj = Vec::new();
+  MeshObjExporter::export(&mesh, &mut obj).unwrap();
+  let obj = String::from_utf8(obj).unwrap();
+  assert!(obj.contains("v 1 0 0 1 0 0\n"));
+  assert!(obj.contains("f 1 2 3\n"));
+
+  let mut ply = Vec::new();
+  MeshPlyExporter::export(&mesh, &mut ply).unwrap();
+  let header_end = ply.windows(11).position(|w| w == b"end_header\n").unwrap() + 11;
+  let header = String::from_utf8_lossy(&ply[..header_end]).to_string();
+  assert!(header.contains("element vertex 3"));
+  assert!(header.contains("element face 1"));
+  assert_eq!(ply.len() - header_end, 3 * 15 + 13);
+  assert_eq!(ply[header_end + 12..header_end + 15], [255, 0, 0]);
+}
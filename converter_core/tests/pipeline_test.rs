@@ -0,0 +1,188 @@
+use converter_core::{
+  GaussianSplat, Scene,
+  helpers::activation::inverse_sigmoid,
+  helpers::spherical_harmonics::evaluate_sh_color,
+  operations::filter::CropRegion,
+  operations::pipeline::{Operation, Pipeline, apply_operations},
+  operations::sort::SortOrder,
+  operations::transform::{Transform, rotation_from_euler_degrees, transform},
+};
+
+fn splat_at(position: [f32; 3], opacity: f32, scale: f32) -> GaussianSplat {
+  GaussianSplat {
+    position,
+    normal: [0.0, 0.0, 0.0],
+    spherical_harmonics_dc: [0.0; 3],
+    spherical_harmonics_rest: Vec::new(),
+    opacity: inverse_sigmoid(opacity),
+    scale: [scale.ln(); 3],
+    rotation: [1.0, 0.0, 0.0, 0.0],
+  }
+}
+
+fn scene_of(splats: Vec<GaussianSplat>) -> Scene {
+  Scene {
+    splats,
+    cameras: Vec::new(),
+  }
+}
+
+#[test]
+fn parses_operations_with_main_and_named_parameters() {
+  assert_eq!(
+    "crop:box=-1,-1,-1,1,1,1:invert"
+      .parse::<Operation>()
+      .unwrap(),
+    Operation::Crop {
+      region: CropRegion::Box {
+        min: [-1.0; 3],
+        max: [1.0; 3],
+      },
+      invert: true,
+    }
+  );
+  assert_eq!(
+    "min-opacity:0.05".parse::<Operation>().unwrap(),
+    Operation::MinOpacity(0.05)
+  );
+  assert_eq!(
+    "min-opacity:value=0.05".parse::<Operation>().unwrap(),
+    Operation::MinOpacity(0.05)
+  );
+  assert_eq!(
+    "sh-degree:1".parse::<Operation>().unwrap(),
+    Operation::ShDegree(1)
+  );
+  assert_eq!(
+    "sort:morton".parse::<Operation>().unwrap(),
+    Operation::Sort(SortOrder::Morton)
+  );
+}
+
+#[test]
+fn rejects_unknown_operations_and_parameters() {
+  assert!("explode".parse::<Operation>().is_err());
+  assert!("crop".parse::<Operation>().is_err());
+  assert!("crop:box=1,2,3".parse::<Operation>().is_err());
+  assert!("min-opacity:0.1:radius=3".parse::<Operation>().is_err());
+  assert!("sh-degree:4".parse::<Operation>().is_err());
+}
+
+#[test]
+fn operations_round_trip_through_their_text() {
+  for text in [
+    "crop:sphere=0,1,2,3:invert",
+    "max-scale:0.5",
+    "bake-sh",
+    "transform:translate=1,2,3:rotate=0,90,0:scale=2",
+    "sort:importance",
+  ] {
+    let operation: Operation = text.parse().unwrap();
+    assert_eq!(
+      operation.to_string().parse::<Operation>().unwrap(),
+      operation
+    );
+  }
+}
+
+#[test]
+fn pipeline_files_mix_text_and_tables() {
+  let pipeline: Pipeline = serde_json::from_str(
+    r#"{"operations": [
+      "min-opacity:0.05",
+      {"op": "crop", "box": [-1, -1, -1, 1, 1, 1], "invert": false},
+      {"op": "sh-degree", "degree": 0}
+    ]}"#,
+  )
+  .unwrap();
+
+  assert_eq!(
+    pipeline.operations,
+    vec![
+      Operation::MinOpacity(0.05),
+      Operation::Crop {
+        region: CropRegion::Box {
+          min: [-1.0; 3],
+          max: [1.0; 3],
+        },
+        invert: false,
+      },
+      Operation::ShDegree(0),
+    ]
+  );
+}
+
+#[test]
+fn operations_run_in_order() {
+  let mut scene = scene_of(vec![
+    splat_at([0.0, 0.0, 0.0], 0.9, 0.1),
+    splat_at([5.0, 0.0, 0.0], 0.9, 0.1),
+    splat_at([0.5, 0.0, 0.0], 0.01, 0.1),
+    splat_at([-0.5, 0.0, 0.0], 0.9, 2.0),
+  ]);
+  let operations: Vec<Operation> = ["crop:box=-1,-1,-1,1,1,1", "min-opacity:0.05", "max-scale:1"]
+    .iter()
+    .map(|text| text.parse().unwrap())
+    .collect();
+
+  apply_operations(&mut scene, &operations);
+
+  assert_eq!(scene.splats.len(), 1);
+  assert_eq!(scene.splats[0].position, [0.0, 0.0, 0.0]);
+}
+
+#[test]
+fn importance_sort_puts_large_opaque_splats_first() {
+  let mut scene = scene_of(vec![
+    splat_at([0.0; 3], 0.5, 0.1),
+    splat_at([1.0; 3], 0.9, 1.0),
+    splat_at([2.0; 3], 0.9, 0.5),
+  ]);
+
+  apply_operations(&mut scene, &[Operation::Sort(SortOrder::Importance)]);
+
+  let xs: Vec<f32> = scene.splats.iter().map(|s| s.position[0]).collect();
+  assert_eq!(xs, vec![1.0, 2.0, 0.0]);
+}
+
+#[test]
+fn transform_moves_splats_and_rotates_their_view_dependent_colour() {
+  let rest: Vec<f32> = (0..45)
+    .map(|i| ((i * 7) % 11) as f32 / 10.0 - 0.5)
+    .collect();
+  let mut splat = splat_at([1.0, 0.0, 0.0], 0.9, 0.1);
+  splat.spherical_harmonics_rest = rest;
+  let original = splat.clone();
+  let mut scene = scene_of(vec![splat]);
+  let rotation = rotation_from_euler_degrees([30.0, 60.0, -45.0]);
+
+  transform(
+    &mut scene,
+    &Transform {
+      translation: [0.0, 0.0, 1.0],
+      rotation,
+      scale: 2.0,
+    },
+  );
+
+  let moved = &scene.splats[0];
+  let matrix = converter_core::helpers::rotation::quaternion_to_matrix(rotation);
+  let rotate = |v: [f32; 3]| matrix.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2]);
+  let expected = rotate([2.0, 0.0, 0.0]);
+  let expected = [expected[0], expected[1], expected[2] + 1.0];
+  for (position, target) in moved.position.iter().zip(expected) {
+    assert!((position - target).abs() < 1e-5);
+  }
+  for scale in moved.scale {
+    assert!((scale - 0.2f32.ln()).abs() < 1e-5);
+  }
+
+  // The colour seen along a rotated direction is the colour seen before along the original one
+  for dir in [[0.0, 0.0, 1.0], [0.6, 0.0, 0.8], [-0.48, 0.6, 0.64]] {
+    let before = evaluate_sh_color(&original, dir);
+    let after = evaluate_sh_color(moved, rotate(dir));
+    for channel in 0..3 {
+      assert!((before[channel] - after[channel]).abs() < 1e-3);
+    }
+  }
+}
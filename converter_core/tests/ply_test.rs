@@ -1,6 +1,9 @@
 use converter_core::{
   Exporter,
-  formats::ply::{PlyASCIIExporter, PlyImporter},
+  formats::{
+    ply::{PlyASCIIExporter, PlyBinaryExporter, PlyExportOptions, PlyImporter},
+    splat::SplatImporter,
+  },
 };
 #[cfg(test)]
 use converter_core::{GaussianSplat, Importer, Scene};
@@ -119,6 +122,7 @@ fn test_ply_ascii_exporter_success() {
       scale: [0.01, 0.02, 0.03],
       rotation: [0.9, 0.1, 0.2, 0.3],
     }],
+    cameras: Vec::new(),
   };
 
   let mut writer = Cursor::new(Vec::<u8>::new());
@@ -136,3 +140,34 @@ fn test_ply_ascii_exporter_success() {
   assert!(output_string.contains("1 2 3 0.1 0.2 0.3 0.4 0.5 0.6"));
   assert!(output_string.contains("0.9 0.01 0.02 0.03 0.9 0.1 0.2 0.3"));
 }
+
+/// The f_rest_* properties of a .ply exported from a scene without higher SH bands, as imported
+/// from a .splat.
+fn sh_rest_properties(options: &PlyExportOptions) -> Vec<String> {
+  let splat = [0u8; 32];
+  let scene = SplatImporter::import(&mut &splat[..]).unwrap();
+  let mut output = Vec::new();
+  PlyBinaryExporter::export_with_options(&scene, &mut output, options).unwrap();
+
+  let end = output
+    .windows(10)
+    .position(|window| window == b"end_header")
+    .unwrap();
+  String::from_utf8_lossy(&output[..end])
+    .lines()
+    .filter_map(|line| line.strip_prefix("property float "))
+    .filter(|name| name.starts_with("f_rest_"))
+    .map(str::to_string)
+    .collect()
+}
+
+#[test]
+fn ply_exporter_writes_all_sh_bands_unless_reduced() {
+  let expected: Vec<String> = (0..45).map(|i| format!("f_rest_{}", i)).collect();
+  assert_eq!(sh_rest_properties(&PlyExportOptions::default()), expected);
+
+  let reduced = PlyExportOptions { sh_degree: Some(1) };
+  assert_eq!(sh_rest_properties(&reduced), expected[..9]);
+  let reduced = PlyExportOptions { sh_degree: Some(0) };
+  assert!(sh_rest_properties(&reduced).is_empty());
+}
@@ -0,0 +1,225 @@
+use converter_core::{
+  Exporter, GaussianSplat, Importer, Scene,
+  formats::{
+    colmap::ColmapPointsImporter,
+    las::{LasExporter, LasImporter},
+    ply::PlyImporter,
+    point_cloud::{PointCloudExportOptions, PointCloudImportOptions, PointCloudPlyExporter},
+  },
+};
+
+fn splat(position: [f32; 3], opacity: f32) -> GaussianSplat {
+  GaussianSplat {
+    position,
+    normal: [0.0, 0.0, 0.0],
+    // 0.5 + SH_C0 * dc gives pure red
+    spherical_harmonics_dc: [1.7724539, -1.7724539, -1.7724539],
+    spherical_harmonics_rest: vec![0.1; 45],
+    opacity,
+    scale: [-3.0, -3.0, -3.0],
+    rotation: [1.0, 0.0, 0.0, 0.0],
+  }
+}
+
+fn sample_scene() -> Scene {
+  Scene {
+    splats: vec![
+      splat([1.0, 2.0, 3.0], 5.0),
+      splat([-1.0, 0.5, 10.0], -5.0),
+      splat([0.0, 0.0, 0.0], 0.0),
+    ],
+    cameras: Vec::new(),
+  }
+}
+
+fn read_f64(data: &[u8], offset: usize) -> f64 {
+  f64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn read_i32(data: &[u8], offset: usize) -> i32 {
+  i32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+#[test]
+fn point_cloud_ply_layout() {
+  let mut output = Vec::new();
+  PointCloudPlyExporter::export(&sample_scene(), &mut output).unwrap();
+
+  let header_end = output
+    .windows(11)
+    .position(|w| w == b"end_header\n")
+    .unwrap()
+    + 11;
+  let header = String::from_utf8_lossy(&output[..header_end]).to_string();
+  assert!(header.contains("element vertex 3"));
+  assert!(header.contains(
+    "property uchar red\nproperty uchar green\nproperty uchar blue\nproperty uchar alpha"
+  ));
+  assert!(!header.contains("f_rest"));
+
+  let body = &output[header_end..];
+  assert_eq!(body.len(), 3 * 16);
+  assert_eq!(f32::from_le_bytes(body[4..8].try_into().unwrap()), 2.0);
+  assert_eq!(body[12..16], [255, 0, 0, 253]);
+}
+
+#[test]
+fn point_cloud_ply_opacity_threshold() {
+  let options = PointCloudExportOptions {
+    min_opacity: Some(0.4),
+  };
+  let mut output = Vec::new();
+  PointCloudPlyExporter::export_with_options(&sample_scene(), &mut output, &options).unwrap();
+
+  let text = String::from_utf8_lossy(&output).to_string();
+  assert!(text.contains("element vertex 2"));
+}
+
+#[test]
+fn las_header_and_points() {
+  let mut output = Vec::new();
+  LasExporter::export(&sample_scene(), &mut output).unwrap();
+
+  assert_eq!(output[0..4], *b"LASF");
+  assert_eq!(output[24..26], [1, 4], "Version 1.4");
+  assert_eq!(u16::from_le_bytes([output[94], output[95]]), 375);
+  assert_eq!(output[104], 7, "Point data record format");
+  assert_eq!(u16::from_le_bytes([output[105], output[106]]), 36);
+  assert_eq!(u64::from_le_bytes(output[247..255].try_into().unwrap()), 3);
+  assert_eq!(output.len(), 375 + 3 * 36);
+
+  let scale = read_f64(&output, 131);
+  let offset_x = read_f64(&output, 155);
+  assert_eq!(read_f64(&output, 179), 1.0, "Max X");
+  assert_eq!(read_f64(&output, 187), -1.0, "Min X");
+  assert_eq!(read_f64(&output, 211), 10.0, "Max Z");
+
+  let point = &output[375..375 + 36];
+  let x = read_i32(point, 0) as f64 * scale + offset_x;
+  assert!((x - 1.0).abs() < 1e-3);
+  assert_eq!(point[14], 0x11);
+  assert_eq!(u16::from_le_bytes([point[30], point[31]]), 65535, "Red");
+  assert_eq!(u16::from_le_bytes([point[32], point[33]]), 0, "Green");
+}
+
+#[test]
+fn las_opacity_threshold() {
+  let options = PointCloudExportOptions {
+    min_opacity: Some(0.9),
+  };
+  let mut output = Vec::new();
+  LasExporter::export_with_options(&sample_scene(), &mut output, &options).unwrap();
+  assert_eq!(output.len(), 375 + 36);
+}
+
+#[test]
+fn las_round_trip_initialises_gaussians() {
+  let mut output = Vec::new();
+  LasExporter::export(&sample_scene(), &mut output).unwrap();
+  let scene = LasImporter::import(&mut output.as_slice()).unwrap();
+
+  assert_eq!(scene.splats.len(), 3);
+  let splat = &scene.splats[1];
+  for axis in 0..3 {
+    assert!((splat.position[axis] - [-1.0, 0.5, 10.0][axis]).abs() < 1e-3);
+  }
+  assert!((0.5 + 0.2820948 * splat.spherical_harmonics_dc[0] - 1.0).abs() < 1e-4);
+  assert!((0.5 + 0.2820948 * splat.spherical_harmonics_dc[1]).abs() < 1e-4);
+  assert_eq!(splat.rotation, [1.0, 0.0, 0.0, 0.0]);
+  assert!(splat.spherical_harmonics_rest.is_empty());
+  // Default initial opacity of 0.1
+  assert!((splat.opacity - (0.1f32 / 0.9).ln()).abs() < 1e-5);
+}
+
+#[test]
+fn las_rejects_point_counts_beyond_the_file() {
+  let mut output = Vec::new();
+  LasExporter::export(&sample_scene(), &mut output).unwrap();
+  // The 64-bit point count of LAS 1.4
+  output[247..255].copy_from_slice(&u64::MAX.to_le_bytes());
+  let error = LasImporter::import(&mut output.as_slice()).unwrap_err();
+  assert!(error.to_string().contains("truncated"));
+}
+
+#[test]
+fn ply_point_cloud_scales_from_nearest_neighbours() {
+  // Points one unit apart on a line: the 3 nearest neighbours of the first point are at 1, 2 and 3
+  let mut ply = String::from(
+    "ply\nformat ascii 1.0\nelement vertex 5\nproperty float x\nproperty float y\n\
+     property float z\nproperty uchar red\nproperty uchar green\nproperty uchar blue\nend_header\n",
+  );
+  for i in 0..5 {
+    ply.push_str(&format!("{} 0 0 255 0 128\n", i));
+  }
+  let options = PointCloudImportOptions {
+    initial_opacity: 0.5,
+  };
+  let scene = PlyImporter::import_with_options(&mut ply.as_bytes(), &options).unwrap();
+
+  assert_eq!(scene.splats.len(), 5);
+  let expected = ((1.0f32 + 4.0 + 9.0) / 3.0).sqrt().ln();
+  assert!((scene.splats[0].scale[0] - expected).abs() < 1e-5);
+  assert_eq!(scene.splats[0].scale[0], scene.splats[0].scale[2]);
+  // The middle point has two neighbours at 1 and one at 2
+  let expected = ((1.0f32 + 1.0 + 4.0) / 3.0).sqrt().ln();
+  assert!((scene.splats[2].scale[1] - expected).abs() < 1e-5);
+  assert!(scene.splats[0].opacity.abs() < 1e-6);
+  assert!(
+    (0.5 + 0.2820948 * scene.splats[0].spherical_harmonics_dc[2] - 128.0 / 255.0).abs() < 1e-4
+  );
+}
+
+#[test]
+fn colmap_text_points() {
+  let text = "# 3D point list with one line of data per point:
+#   POINT3D_ID, X, Y, Z, R, G, B, ERROR, TRACK[] as (IMAGE_ID, POINT2D_IDX)
+# Number of points: 2, mean track length: 2
+1 0.5 1.5 -2 255 0 0 0.8 1 10 2 20
+7 1 1 1 0 255 0 1.2 3 4 5 6
+";
+  let scene = ColmapPointsImporter::import(&mut text.as_bytes()).unwrap();
+  assert_eq!(scene.splats.len(), 2);
+  assert_eq!(scene.splats[0].position, [0.5, 1.5, -2.0]);
+  assert!((0.5 + 0.2820948 * scene.splats[1].spherical_harmonics_dc[1] - 1.0).abs() < 1e-4);
+}
+
+#[test]
+fn colmap_binary_points() {
+  let mut data = Vec::new();
+  data.extend_from_slice(&2u64.to_le_bytes());
+  for (id, position, track_length) in [(1u64, [1.0f64, 2.0, 3.0], 2u64), (2, [4.0, 5.0, 6.0], 0)] {
+    data.extend_from_slice(&id.to_le_bytes());
+    for value in position {
+      data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(&[0, 0, 255]);
+    data.extend_from_slice(&0.5f64.to_le_bytes());
+    data.extend_from_slice(&track_length.to_le_bytes());
+    data.extend(std::iter::repeat_n(0u8, track_length as usize * 8));
+  }
+
+  let scene = ColmapPointsImporter::import(&mut data.as_slice()).unwrap();
+  assert_eq!(scene.splats.len(), 2);
+  assert_eq!(scene.splats[1].position, [4.0, 5.0, 6.0]);
+  // Two lone points are each other's only neighbour
+  let expected = 27.0f32.sqrt().ln();
+  assert!((scene.splats[0].scale[0] - expected).abs() < 1e-5);
+
+  assert!(ColmapPointsImporter::import(&mut &data[..data.len() - 4]).is_err());
+}
+
+#[test]
+fn colmap_binary_points_with_corrupt_track_lengths() {
+  for track_length in [0x1fff_ffff_ffff_ffffu64, u64::MAX] {
+    let mut data = Vec::new();
+    data.extend_from_slice(&1u64.to_le_bytes());
+    data.extend_from_slice(&1u64.to_le_bytes());
+    data.extend_from_slice(&[0; 24]);
+    data.extend_from_slice(&[0, 0, 255]);
+    data.extend_from_slice(&0.5f64.to_le_bytes());
+    data.extend_from_slice(&track_length.to_le_bytes());
+    data.resize(76, 0);
+
+    assert!(ColmapPointsImporter::import(&mut data.as_slice()).is_err());
+  }
+}
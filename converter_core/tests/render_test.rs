@@ -0,0 +1,129 @@
+use converter_core::{
+  Camera, GaussianSplat, Scene,
+  camera::FramingOptions,
+  render::{RenderOptions, render, write_png},
+};
+
+fn gaussian(position: [f32; 3], scale: f32, color: [f32; 3]) -> GaussianSplat {
+  GaussianSplat {
+    position,
+    normal: [0.0, 0.0, 0.0],
+    // 0.5 + SH_C0 * dc gives `color`
+    spherical_harmonics_dc: color.map(|c| (c - 0.5) / 0.2820948),
+    spherical_harmonics_rest: Vec::new(),
+    opacity: 10.0,
+    scale: [scale.ln(); 3],
+    rotation: [1.0, 0.0, 0.0, 0.0],
+  }
+}
+
+/// Identity camera at the origin looking along +Z.
+fn camera(width: u32, height: u32) -> Camera {
+  Camera::look_at(
+    [0.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0],
+    [0.0, -1.0, 0.0],
+    width,
+    height,
+    60.0,
+  )
+}
+
+#[test]
+fn single_gaussian_is_drawn_at_its_projection() {
+  // Right of and below the optical axis in the OpenCV convention
+  let scene = Scene {
+    splats: vec![gaussian([1.0, 0.5, 5.0], 0.2, [1.0, 0.0, 0.0])],
+    cameras: Vec::new(),
+  };
+  let camera = camera(64, 48);
+  let image = render(&scene, &camera, &RenderOptions::default());
+  assert_eq!(image.dimensions(), (64, 48));
+
+  let u = camera.focal[0] * 1.0 / 5.0 + 32.0;
+  let v = camera.focal[1] * 0.5 / 5.0 + 24.0;
+  let center = image.get_pixel(u as u32, v as u32);
+  assert!(
+    center[0] > 240 && center[1] < 10 && center[2] < 10,
+    "{:?}",
+    center
+  );
+  assert!(center[3] > 240);
+
+  // Transparent far away from the splat
+  assert_eq!(image.get_pixel(0, 0)[3], 0);
+  assert_eq!(image.get_pixel(63, 0)[3], 0);
+}
+
+#[test]
+fn nearer_gaussian_occludes_regardless_of_scene_order() {
+  let near = gaussian([0.0, 0.0, 3.0], 0.3, [1.0, 0.0, 0.0]);
+  let far = gaussian([0.0, 0.0, 6.0], 1.0, [0.0, 0.0, 1.0]);
+  for splats in [vec![near.clone(), far.clone()], vec![far, near]] {
+    let image = render(
+      &Scene {
+        splats,
+        cameras: Vec::new(),
+      },
+      &camera(32, 32),
+      &RenderOptions::default(),
+    );
+    let center = image.get_pixel(16, 16);
+    assert!(center[0] > 240 && center[2] < 10, "{:?}", center);
+  }
+}
+
+#[test]
+fn splats_behind_the_camera_are_culled() {
+  let scene = Scene {
+    splats: vec![gaussian([0.0, 0.0, -5.0], 1.0, [1.0, 1.0, 1.0])],
+    cameras: Vec::new(),
+  };
+  let options = RenderOptions {
+    background: [0.0, 1.0, 0.0, 1.0],
+  };
+  let image = render(&scene, &camera(16, 16), &options);
+  assert!(image.pixels().all(|pixel| pixel.0 == [0, 255, 0, 255]));
+}
+
+#[test]
+fn view_dependent_colour_follows_spherical_harmonics() {
+  // A degree 1 coefficient on the Z basis function tints red towards one side
+  let mut splat = gaussian([0.0, 0.0, 0.0], 0.3, [0.5, 0.5, 0.5]);
+  splat.spherical_harmonics_rest = vec![0.0; 9];
+  splat.spherical_harmonics_rest[1] = 1.0;
+  let scene = Scene {
+    splats: vec![splat],
+    cameras: Vec::new(),
+  };
+
+  let red_at = |eye: [f32; 3]| {
+    let camera = Camera::look_at(eye, [0.0; 3], [0.0, -1.0, 0.0], 16, 16, 60.0);
+    render(&scene, &camera, &RenderOptions::default()).get_pixel(8, 8)[0]
+  };
+  assert!(red_at([0.0, 0.0, -4.0]) > red_at([0.0, 0.0, 4.0]) + 50);
+}
+
+#[test]
+fn framing_centres_the_scene_and_writes_png() {
+  let splats = (0..50)
+    .map(|i| gaussian([10.0 + (i % 5) as f32, 3.0, (i / 5) as f32], 0.3, [1.0; 3]))
+    .collect();
+  let scene = Scene {
+    splats,
+    cameras: Vec::new(),
+  };
+  let camera = Camera::framing(&scene, 80, 60, &FramingOptions::default());
+  let image = render(&scene, &camera, &RenderOptions::default());
+
+  // Something is drawn near the centre and nothing touches the border
+  assert!(image.get_pixel(40, 30)[3] > 200);
+  for x in 0..80 {
+    assert!(image.get_pixel(x, 0)[3] < 50 && image.get_pixel(x, 59)[3] < 50);
+  }
+
+  let mut png = Vec::new();
+  write_png(&image, &mut png).unwrap();
+  let decoded = image::load_from_memory(&png).unwrap().to_rgba8();
+  assert_eq!(decoded, image);
+}
@@ -0,0 +1,177 @@
+use std::io::{Cursor, Read, Write};
+
+use converter_core::{
+  Exporter, GaussianSplat, Importer, Scene,
+  formats::sog::{SogExportOptions, SogExporter, SogImporter},
+  helpers::kmeans::{kmeans, kmeans_1d, nearest_in_codebook},
+};
+
+fn sample_scene(sh_rest_len: usize) -> Scene {
+  let splats = (0..500)
+    .map(|i| {
+      let t = i as f32;
+      let (s, c) = (t * 0.1).sin_cos();
+      GaussianSplat {
+        position: [s * 3.0, c * 3.0, t * 0.02 - 5.0],
+        normal: [0.0, 0.0, 0.0],
+        spherical_harmonics_dc: [s, c, 0.5],
+        spherical_harmonics_rest: (0..sh_rest_len)
+          .map(|k| if i % 2 == 0 { k as f32 * 0.01 } else { -0.2 })
+          .collect(),
+        opacity: c * 3.0,
+        scale: [-4.0 + s, -3.0, -5.0 + c],
+        rotation: [c, s * 0.5, 0.2, -0.3],
+      }
+    })
+    .collect();
+
+  Scene {
+    splats,
+    cameras: Vec::new(),
+  }
+}
+
+fn normalized(q: [f32; 4]) -> [f32; 4] {
+  let len = q.iter().map(|v| v * v).sum::<f32>().sqrt();
+  q.map(|v| v / len)
+}
+
+/// Splats are written in Morton order, so match them up by their Z coordinate.
+fn find_by_z(scene: &Scene, z: f32) -> &GaussianSplat {
+  scene
+    .splats
+    .iter()
+    .min_by(|a, b| {
+      (a.position[2] - z)
+        .abs()
+        .total_cmp(&(b.position[2] - z).abs())
+    })
+    .unwrap()
+}
+
+#[test]
+fn sog_round_trip() {
+  let scene = sample_scene(24);
+  let mut output = Vec::new();
+  SogExporter::export(&scene, &mut output).unwrap();
+
+  assert_eq!(output[0..4], *b"PK\x03\x04", "SOG bundles are zip archives");
+
+  let imported = SogImporter::import(&mut output.as_slice()).unwrap();
+  assert_eq!(imported.splats.len(), 500);
+  assert_eq!(imported.sh_degree(), 2);
+
+  for original in &scene.splats {
+    let decoded = find_by_z(&imported, original.position[2]);
+
+    for axis in 0..3 {
+      assert!((decoded.position[axis] - original.position[axis]).abs() < 1e-3);
+      assert!((decoded.scale[axis] - original.scale[axis]).abs() < 0.02);
+      assert!(
+        (decoded.spherical_harmonics_dc[axis] - original.spherical_harmonics_dc[axis]).abs() < 0.02
+      );
+    }
+
+    let dot: f32 = normalized(original.rotation)
+      .iter()
+      .zip(decoded.rotation)
+      .map(|(a, b)| a * b)
+      .sum();
+    assert!(dot.abs() > 0.999, "Rotation should survive 8-bit packing");
+
+    let alpha = |opacity: f32| 1.0 / (1.0 + (-opacity).exp());
+    assert!((alpha(decoded.opacity) - alpha(original.opacity)).abs() < 0.01);
+
+    // Two distinct SH vectors fit comfortably in the palette
+    assert!((decoded.sh_rest(1, 5) - original.sh_rest(1, 5)).abs() < 0.01);
+  }
+}
+
+#[test]
+fn sog_without_higher_order_sh() {
+  let mut output = Vec::new();
+  let options = SogExportOptions {
+    iterations: 2,
+    ..SogExportOptions::default()
+  };
+  SogExporter::export_with_options(&sample_scene(0), &mut output, &options).unwrap();
+
+  let imported = SogImporter::import(&mut output.as_slice()).unwrap();
+  assert_eq!(imported.splats.len(), 500);
+  assert_eq!(imported.sh_degree(), 0);
+}
+
+#[test]
+fn sog_importer_failure_on_bad_data() {
+  let result = SogImporter::import(&mut "not a sog bundle".as_bytes());
+  assert!(result.is_err());
+}
+
+/// Copies a SOG bundle of `scene` with `patch` applied to its meta.json.
+fn patched_bundle(scene: &Scene, patch: impl Fn(&mut serde_json::Value)) -> Vec<u8> {
+  let mut output = Vec::new();
+  SogExporter::export(scene, &mut output).unwrap();
+
+  let mut archive = zip::ZipArchive::new(Cursor::new(output)).unwrap();
+  let mut patched = zip::ZipWriter::new(Cursor::new(Vec::new()));
+  for index in 0..archive.len() {
+    let mut file = archive.by_index(index).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+    if file.name() == "meta.json" {
+      let mut meta: serde_json::Value = serde_json::from_slice(&data).unwrap();
+      patch(&mut meta);
+      data = serde_json::to_vec(&meta).unwrap();
+    }
+    patched
+      .start_file(file.name(), zip::write::SimpleFileOptions::default())
+      .unwrap();
+    patched.write_all(&data).unwrap();
+  }
+  patched.finish().unwrap().into_inner()
+}
+
+#[test]
+fn sog_importer_rejects_counts_beyond_the_textures() {
+  // The splat count of meta.json raised far past the texture size
+  let patched = patched_bundle(&sample_scene(0), |meta| {
+    meta["count"] = serde_json::json!(u32::MAX)
+  });
+
+  let error = SogImporter::import(&mut patched.as_slice()).unwrap_err();
+  assert!(error.to_string().contains("position textures"));
+}
+
+#[test]
+fn sog_importer_rejects_more_than_three_sh_bands() {
+  let patched = patched_bundle(&sample_scene(9), |meta| {
+    meta["shN"]["bands"] = serde_json::json!(200)
+  });
+
+  let error = SogImporter::import(&mut patched.as_slice()).unwrap_err();
+  assert!(error.to_string().contains("200 SH bands"));
+}
+
+#[test]
+fn kmeans_1d_builds_sorted_codebook() {
+  let values: Vec<f32> = (0..1000).map(|i| (i % 4) as f32 * 10.0).collect();
+  let codebook = kmeans_1d(&values, 4, 10);
+  assert_eq!(codebook, vec![0.0, 10.0, 20.0, 30.0]);
+  assert_eq!(nearest_in_codebook(&codebook, 14.0), 1);
+  assert_eq!(nearest_in_codebook(&codebook, 100.0), 3);
+
+  let padded = kmeans_1d(&[1.0, 2.0], 256, 10);
+  assert_eq!(padded.len(), 256);
+}
+
+#[test]
+fn kmeans_separates_clusters() {
+  let points: Vec<f32> = (0..200)
+    .flat_map(|i| if i % 2 == 0 { [0.0, 0.0] } else { [5.0, 5.0] })
+    .collect();
+  let (centroids, labels) = kmeans(&points, 2, 2, 5);
+
+  assert_eq!(centroids.len(), 4);
+  assert_ne!(labels[0], labels[1]);
+  assert!(labels.iter().step_by(2).all(|&label| label == labels[0]));
+}
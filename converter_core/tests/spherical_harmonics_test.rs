@@ -0,0 +1,94 @@
+use converter_core::{
+  GaussianSplat, Importer, Scene,
+  formats::ply::{PlyBinaryExporter, PlyExportOptions, PlyImporter},
+  helpers::spherical_harmonics::{SH_C0, evaluate_sh_color},
+  operations::spherical_harmonics::{BakeDirections, bake_view_dependent_color, reduce_sh_degree},
+};
+
+fn splat_with_sh(dc: [f32; 3], rest: Vec<f32>) -> GaussianSplat {
+  GaussianSplat {
+    position: [0.0, 0.0, 0.0],
+    normal: [0.0, 0.0, 0.0],
+    spherical_harmonics_dc: dc,
+    spherical_harmonics_rest: rest,
+    opacity: 0.0,
+    scale: [0.0, 0.0, 0.0],
+    rotation: [1.0, 0.0, 0.0, 0.0],
+  }
+}
+
+#[test]
+fn reduce_sh_degree_keeps_lowest_bands_per_channel() {
+  let rest: Vec<f32> = (0..45).map(|i| i as f32).collect();
+  let mut scene = Scene {
+    splats: vec![splat_with_sh([0.0; 3], rest)],
+    cameras: Vec::new(),
+  };
+
+  reduce_sh_degree(&mut scene, 1);
+
+  assert_eq!(scene.sh_degree(), 1);
+  assert_eq!(
+    scene.splats[0].spherical_harmonics_rest,
+    vec![0.0, 1.0, 2.0, 15.0, 16.0, 17.0, 30.0, 31.0, 32.0]
+  );
+}
+
+#[test]
+fn reduced_scene_round_trips_through_ply() {
+  let mut scene = Scene {
+    splats: vec![splat_with_sh([0.1, 0.2, 0.3], vec![0.5; 45])],
+    cameras: Vec::new(),
+  };
+  reduce_sh_degree(&mut scene, 0);
+
+  // The default options pad to degree 3, so the degree is asked for
+  let mut output = Vec::new();
+  let options = PlyExportOptions { sh_degree: Some(0) };
+  PlyBinaryExporter::export_with_options(&scene, &mut output, &options).unwrap();
+  let header = String::from_utf8_lossy(&output[..output.len().min(1024)]).to_string();
+  assert!(!header.contains("f_rest_0"));
+
+  let imported = PlyImporter::import(&mut output.as_slice()).unwrap();
+  assert_eq!(imported.sh_degree(), 0);
+  assert_eq!(imported.splats[0].spherical_harmonics_dc, [0.1, 0.2, 0.3]);
+}
+
+#[test]
+fn bake_keeps_clamped_view_dependent_tint() {
+  // Black base colour with a strong first-order band: half the sphere sees a brighter colour,
+  // the other half is clamped to black, so the average is brighter than the DC alone.
+  let black = -0.5 / SH_C0;
+  let mut rest = vec![0.0; 9];
+  rest[0] = 1.0;
+  rest[3] = 1.0;
+  rest[6] = 1.0;
+  let mut scene = Scene {
+    splats: vec![splat_with_sh([black; 3], rest)],
+    cameras: Vec::new(),
+  };
+
+  bake_view_dependent_color(&mut scene, &BakeDirections::Sphere(256));
+
+  let splat = &scene.splats[0];
+  assert!(splat.spherical_harmonics_rest.is_empty());
+  assert!(splat.spherical_harmonics_dc[0] > black);
+}
+
+#[test]
+fn bake_from_single_direction_matches_that_view() {
+  let splat = splat_with_sh([0.2, 0.0, -0.2], vec![0.3; 45]);
+  let dir = [0.0, 0.0, 1.0];
+  let expected = evaluate_sh_color(&splat, dir);
+
+  let mut scene = Scene {
+    splats: vec![splat],
+    cameras: Vec::new(),
+  };
+  bake_view_dependent_color(&mut scene, &BakeDirections::Given(vec![[0.0, 0.0, 2.0]]));
+
+  let baked = evaluate_sh_color(&scene.splats[0], dir);
+  for c in 0..3 {
+    assert!((baked[c] - expected[c]).abs() < 1e-5);
+  }
+}
@@ -1,8 +1,11 @@
 use std::{fs::File, io::BufReader};
 
+use converter_core::formats::{
+  ply::PlyImporter,
+  splat::{SplatExportOptions, SplatExporter, SplatImporter, SplatOrder},
+};
 #[cfg(test)]
-use converter_core::Importer;
-use converter_core::formats::splat::SplatImporter;
+use converter_core::{Exporter, GaussianSplat, Importer, Scene};
 
 #[test]
 fn splat_importer_success() {
@@ -16,3 +19,84 @@ fn splat_importer_success() {
     "SplatImporter should successfully parse valid SPLAT data"
   );
 }
+
+fn splat_with(position: [f32; 3], scale: [f32; 3], opacity: f32) -> GaussianSplat {
+  GaussianSplat {
+    position,
+    normal: [0.0, 0.0, 0.0],
+    spherical_harmonics_dc: [0.0, 0.0, 0.0],
+    spherical_harmonics_rest: vec![0.0; 45],
+    opacity,
+    scale,
+    rotation: [1.0, 0.0, 0.0, 0.0],
+  }
+}
+
+#[test]
+fn splat_exporter_matches_reference_layout() {
+  let scene = Scene {
+    splats: vec![splat_with([1.0, 2.0, 3.0], [0.0, 0.0, 0.0], 0.0)],
+    cameras: Vec::new(),
+  };
+
+  let mut output = Vec::new();
+  SplatExporter::export(&scene, &mut output).unwrap();
+
+  let mut expected = Vec::new();
+  for v in [1.0f32, 2.0, 3.0, 1.0, 1.0, 1.0] {
+    expected.extend_from_slice(&v.to_le_bytes());
+  }
+  // RGBA truncated like numpy, then the quaternion as [w, x, y, z] * 128 + 128
+  expected.extend_from_slice(&[127, 127, 127, 127, 255, 128, 128, 128]);
+
+  assert_eq!(output, expected);
+}
+
+/// `expected.splat` is what antimatter15's `convert.py` writes for `input.ply`, see
+/// `test_data/convert_py/generate.py`.
+#[test]
+fn splat_exporter_matches_convert_py() {
+  let file = File::open("./test_data/convert_py/input.ply").unwrap();
+  let scene = PlyImporter::import(&mut BufReader::new(file)).unwrap();
+  let expected = std::fs::read("./test_data/convert_py/expected.splat").unwrap();
+
+  let options = SplatExportOptions {
+    order: SplatOrder::Importance,
+    min_opacity: None,
+  };
+  let mut output = Vec::new();
+  SplatExporter::export_with_options(&scene, &mut output, &options).unwrap();
+
+  assert_eq!(output.len(), expected.len());
+  for (index, (splat, reference)) in output.chunks(32).zip(expected.chunks(32)).enumerate() {
+    assert_eq!(splat, reference, "splat {} differs from convert.py", index);
+  }
+}
+
+#[test]
+fn splat_exporter_importance_order_and_min_opacity() {
+  let scene = Scene {
+    splats: vec![
+      splat_with([0.0, 0.0, 0.0], [0.0, 0.0, 0.0], 0.0),
+      splat_with([1.0, 0.0, 0.0], [1.0, 0.0, 0.0], 0.0),
+      splat_with([2.0, 0.0, 0.0], [2.0, 2.0, 2.0], -10.0),
+    ],
+    cameras: Vec::new(),
+  };
+
+  let options = SplatExportOptions {
+    order: SplatOrder::Importance,
+    min_opacity: Some(0.01),
+  };
+  let mut output = Vec::new();
+  SplatExporter::export_with_options(&scene, &mut output, &options).unwrap();
+
+  let imported = SplatImporter::import(&mut output.as_slice()).unwrap();
+  let xs: Vec<f32> = imported.splats.iter().map(|s| s.position[0]).collect();
+  assert_eq!(
+    xs,
+    vec![1.0, 0.0],
+    "Largest splat first, transparent one dropped"
+  );
+  assert_eq!(imported.splats[0].rotation, [1.0, 0.0, 0.0, 0.0]);
+}
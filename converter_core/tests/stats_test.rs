@@ -0,0 +1,182 @@
+use converter_core::{
+  Exporter, GaussianSplat, Scene,
+  formats::{
+    compressed_ply::CompressedPlyExporter,
+    csv::CsvExporter,
+    detect::detect_format,
+    gltf::{GlbExporter, GltfExporter},
+    ksplat::KsplatExporter,
+    las::LasExporter,
+    ply::{PlyASCIIExporter, PlyBinaryExporter},
+    point_cloud::PointCloudPlyExporter,
+    sog::SogExporter,
+    splat::SplatExporter,
+    spz::SpzV2Exporter,
+    usd::{UsdaExporter, UsdzExporter},
+  },
+  stats::{estimate_sizes, scene_stats},
+};
+
+fn sample_scene(count: usize) -> Scene {
+  let splats = (0..count)
+    .map(|i| GaussianSplat {
+      position: [i as f32, (i % 7) as f32, -(i as f32) / 2.0],
+      normal: [0.0, 0.0, 0.0],
+      spherical_harmonics_dc: [0.0, 0.5, -0.5],
+      spherical_harmonics_rest: vec![0.01 * (i % 5) as f32; 9],
+      opacity: 0.0,
+      scale: [-3.0, -2.0, -1.0],
+      rotation: [1.0, 0.0, 0.0, 0.0],
+    })
+    .collect();
+  Scene {
+    splats,
+    cameras: Vec::new(),
+  }
+}
+
+fn export<E: Exporter>(scene: &Scene) -> Vec<u8> {
+  let mut bytes = Vec::new();
+  E::export(scene, &mut bytes).unwrap();
+  bytes
+}
+
+#[test]
+fn scene_stats_summarise_attributes() {
+  let mut scene = sample_scene(101);
+  scene.splats[3].position[0] = f32::NAN;
+  scene.splats[4].opacity = f32::INFINITY;
+
+  let stats = scene_stats(&scene);
+  assert_eq!(stats.splat_count, 101);
+  assert_eq!(stats.sh_degree, 1);
+  assert_eq!(stats.non_finite_splats, 1);
+
+  let attribute = |name: &str| {
+    stats
+      .attributes
+      .iter()
+      .find(|a| a.name == name)
+      .unwrap()
+      .clone()
+  };
+  let x = attribute("position x");
+  assert_eq!(x.non_finite, 1);
+  assert_eq!((x.min, x.max), (0.0, 100.0));
+  // 100 finite values from 0 to 100 without 3
+  assert!((x.percentiles[2] - 50.5).abs() < 1e-9, "{:?}", x);
+
+  // Activated opacity: sigmoid(0) everywhere but the infinite logit, which activates to 1
+  let opacity = attribute("opacity");
+  assert_eq!((opacity.min, opacity.max), (0.5, 1.0));
+  assert_eq!(opacity.non_finite, 0);
+  assert_eq!(attribute("sh coefficients").max, 0.04f32 as f64);
+
+  let (min, max) = stats.bounds.unwrap();
+  assert_eq!((min[0], max[0]), (0.0, 100.0));
+  assert_eq!((min[2], max[2]), (-50.0, 0.0));
+  let (robust_min, robust_max) = stats.robust_bounds.unwrap();
+  assert!(robust_min[0] > 0.0 && robust_max[0] < 100.0);
+
+  let empty = scene_stats(&Scene {
+    splats: Vec::new(),
+    cameras: Vec::new(),
+  });
+  assert_eq!(empty.splat_count, 0);
+  assert!(empty.bounds.is_none());
+  assert!(empty.attributes.iter().all(|a| a.min.is_nan()));
+}
+
+#[test]
+fn size_estimates_match_exports() {
+  // Small scenes are exported in full, so every estimate is exact
+  let scene = sample_scene(500);
+  let sizes = estimate_sizes(&scene);
+  let size = |format: &str| {
+    sizes
+      .iter()
+      .find(|s| s.format == format)
+      .unwrap()
+      .bytes
+      .unwrap() as usize
+  };
+  assert_eq!(
+    size("PLY (binary)"),
+    export::<PlyBinaryExporter>(&scene).len()
+  );
+  assert_eq!(size("SPLAT"), export::<SplatExporter>(&scene).len());
+  assert_eq!(size("SPZ"), export::<SpzV2Exporter>(&scene).len());
+  assert_eq!(size("CSV"), export::<CsvExporter>(&scene).len());
+
+  // Larger ones are extrapolated from samples, exactly for fixed-size records. Without SH to
+  // cluster, the SOG estimate stays quick
+  let mut scene = sample_scene(25_000);
+  for splat in &mut scene.splats {
+    splat.spherical_harmonics_rest.clear();
+  }
+  let splat_size = estimate_sizes(&scene)
+    .into_iter()
+    .find(|s| s.format == "SPLAT")
+    .unwrap()
+    .bytes;
+  assert_eq!(splat_size, Some(25_000 * 32));
+}
+
+#[test]
+fn formats_are_detected_from_their_header() {
+  let scene = sample_scene(50);
+  let detect = |bytes: Vec<u8>| {
+    let detected = detect_format(&bytes[..bytes.len().min(4096)]).unwrap();
+    (detected.format, detected.name, detected.version)
+  };
+
+  assert_eq!(
+    detect(export::<PlyBinaryExporter>(&scene)),
+    ("ply", "PLY", Some("binary_little_endian 1.0".to_string()))
+  );
+  assert_eq!(
+    detect(export::<PlyASCIIExporter>(&scene)).2,
+    Some("ascii 1.0".to_string())
+  );
+  assert_eq!(
+    detect(export::<CompressedPlyExporter>(&scene)).1,
+    "Compressed PLY"
+  );
+  assert_eq!(
+    detect(export::<PointCloudPlyExporter>(&scene)).1,
+    "Point Cloud PLY"
+  );
+  assert_eq!(
+    detect(export::<SpzV2Exporter>(&scene)),
+    ("spz", "SPZ", Some("2".to_string()))
+  );
+  assert_eq!(
+    detect(export::<GlbExporter>(&scene)),
+    ("glb", "glTF binary", Some("2".to_string()))
+  );
+  assert_eq!(
+    detect(export::<GltfExporter>(&scene)),
+    ("gltf", "glTF", Some("2.0".to_string()))
+  );
+  assert_eq!(
+    detect(export::<SogExporter>(&scene)),
+    ("sog", "SOG", Some("2".to_string()))
+  );
+  assert_eq!(detect(export::<UsdzExporter>(&scene)).0, "usdz");
+  assert_eq!(
+    detect(export::<UsdaExporter>(&scene)),
+    ("usda", "OpenUSD text", Some("1.0".to_string()))
+  );
+  assert_eq!(
+    detect(export::<LasExporter>(&scene)),
+    ("las", "LAS", Some("1.4".to_string()))
+  );
+  assert_eq!(detect(export::<CsvExporter>(&scene)).0, "csv");
+  assert_eq!(
+    detect(export::<KsplatExporter>(&scene)),
+    ("ksplat", "KSPLAT", Some("0.1".to_string()))
+  );
+
+  // .splat has no header
+  assert!(detect_format(&export::<SplatExporter>(&scene)).is_none());
+}
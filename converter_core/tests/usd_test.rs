@@ -0,0 +1,104 @@
+use converter_core::{
+  Exporter, GaussianSplat, Importer, Scene,
+  formats::usd::{UsdImporter, UsdaExporter, UsdzExporter},
+};
+
+fn sample_scene() -> Scene {
+  let splats = (0..50)
+    .map(|i| {
+      let t = i as f32;
+      let (s, c) = (t * 0.3).sin_cos();
+      GaussianSplat {
+        position: [s, c, t * 0.1],
+        normal: [0.0, 0.0, 0.0],
+        spherical_harmonics_dc: [s, c, 0.25],
+        spherical_harmonics_rest: (0..9).map(|k| k as f32 * 0.1 - s).collect(),
+        opacity: c * 2.0,
+        scale: [-3.0, -2.5 + s, -4.0],
+        rotation: [0.0, 0.0, 1.0, 0.0],
+      }
+    })
+    .collect();
+
+  Scene {
+    splats,
+    cameras: Vec::new(),
+  }
+}
+
+fn assert_same_scene(original: &Scene, imported: &Scene) {
+  assert_eq!(imported.splats.len(), original.splats.len());
+  assert_eq!(imported.sh_degree(), 1);
+
+  for (a, b) in original.splats.iter().zip(&imported.splats) {
+    assert_eq!(a.position, b.position);
+    assert_eq!(a.rotation, b.rotation);
+    assert_eq!(a.spherical_harmonics_dc, b.spherical_harmonics_dc);
+    assert_eq!(a.spherical_harmonics_rest, b.spherical_harmonics_rest);
+    assert!((a.opacity - b.opacity).abs() < 1e-4);
+    for axis in 0..3 {
+      assert!((a.scale[axis] - b.scale[axis]).abs() < 1e-5);
+    }
+  }
+}
+
+#[test]
+fn usda_round_trip() {
+  let scene = sample_scene();
+  let mut output = Vec::new();
+  UsdaExporter::export(&scene, &mut output).unwrap();
+
+  let layer = String::from_utf8(output.clone()).unwrap();
+  assert!(layer.starts_with("#usda 1.0"));
+  assert!(layer.contains("def ParticleField3DGaussianSplat"));
+  assert!(layer.contains("uniform int radiance:sphericalHarmonicsDegree = 1"));
+
+  let imported = UsdImporter::import(&mut output.as_slice()).unwrap();
+  assert_same_scene(&scene, &imported);
+}
+
+#[test]
+fn usdz_round_trip() {
+  let scene = sample_scene();
+  let mut output = Vec::new();
+  UsdzExporter::export(&scene, &mut output).unwrap();
+
+  assert_eq!(output[0..4], *b"PK\x03\x04");
+  // The root layer's data starts on a 64-byte boundary
+  let name_length = u16::from_le_bytes([output[26], output[27]]) as usize;
+  let extra_length = u16::from_le_bytes([output[28], output[29]]) as usize;
+  assert_eq!((30 + name_length + extra_length) % 64, 0);
+
+  let imported = UsdImporter::import(&mut output.as_slice()).unwrap();
+  assert_same_scene(&scene, &imported);
+}
+
+#[test]
+fn usda_importer_reads_hand_written_layer() {
+  let layer = r#"#usda 1.0
+
+def Xform "World"
+{
+    def ParticleField3DGaussianSplat "Splats"
+    {
+        point3f[] positions = [(1, 2, 3), (4, 5, 6)]
+        quatf[] orientations = [(1, 0, 0, 0), (0, 1, 0, 0)]
+        float3[] scales = [(1, 1, 1), (0.5, 0.5, 0.5)]
+        float[] opacities = [0.5, 1]
+    }
+}
+"#;
+  let scene = UsdImporter::import(&mut layer.as_bytes()).unwrap();
+  assert_eq!(scene.splats.len(), 2);
+  assert_eq!(scene.splats[1].position, [4.0, 5.0, 6.0]);
+  assert_eq!(scene.splats[1].rotation, [0.0, 1.0, 0.0, 0.0]);
+  assert_eq!(scene.splats[0].scale, [0.0; 3]);
+  assert!(scene.splats[0].opacity.abs() < 1e-6);
+  assert_eq!(scene.sh_degree(), 0);
+}
+
+#[test]
+fn usd_importer_failure_on_bad_data() {
+  assert!(UsdImporter::import(&mut "PXR-USDC binary".as_bytes()).is_err());
+  assert!(UsdImporter::import(&mut "#usda 1.0\ndef Xform \"World\" {}".as_bytes()).is_err());
+}
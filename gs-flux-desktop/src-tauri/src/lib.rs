@@ -1,26 +1,79 @@
 use converter_core::{
+  camera::FramingOptions,
   formats::{
+    colmap::ColmapPointsImporter,
+    compressed_ply::CompressedPlyExporter,
     csv::{CsvExporter, CsvImporter},
+    ellipsoid::{EllipsoidObjExporter, EllipsoidPlyExporter},
+    gltf::{GlbExporter, GltfExporter, GltfImporter},
+    ksplat::{KsplatExporter, KsplatImporter},
+    las::{LasExporter, LasImporter},
     ply::{PlyASCIIExporter, PlyBinaryExporter, PlyImporter},
+    point_cloud::PointCloudPlyExporter,
+    sog::{SogExporter, SogImporter},
     splat::{SplatExporter, SplatImporter},
     spz::{SpzImporter, SpzV2Exporter},
+    usd::{UsdImporter, UsdaExporter, UsdzExporter},
   },
-  Exporter, Importer,
+  render::{render, write_png, RenderOptions},
+  Camera, Exporter, Importer, Scene,
 };
 use dirs_next::cache_dir;
 use serde::Serialize;
+use sha2::{Digest, Sha256};
 use std::env;
 use std::fs::{self, File};
-use std::io::{BufReader, BufWriter};
+use std::io::{self, BufReader, BufWriter, Write};
 use std::path::Path;
+use tauri::ipc::Response;
 use uuid::Uuid;
 
+const THUMBNAIL_WIDTH: u32 = 320;
+const THUMBNAIL_HEIGHT: u32 = 240;
+
+/// COLMAP sparse models are recognised by file name (`points3D.bin` / `points3D.txt`), as other
+/// .bin and .txt files hold no scene.
+const COLMAP_POINTS_FILE: &str = "points3d";
+
 #[derive(Serialize)]
 struct ConversionResult {
   path: String,
   size: u64, // File size in bytes
 }
 
+fn is_colmap_points_file(path: &str) -> bool {
+  Path::new(path)
+    .file_stem()
+    .is_some_and(|stem| stem.to_string_lossy().to_lowercase() == COLMAP_POINTS_FILE)
+}
+
+fn import_scene(input_path: &str, source_format: &str) -> Result<Scene, String> {
+  let input_file = File::open(input_path).map_err(|e| e.to_string())?;
+  let mut reader = BufReader::new(input_file);
+
+  match source_format {
+    "ply" => PlyImporter::import(&mut reader),
+    "spz" => SpzImporter::import(&mut reader),
+    "csv" => CsvImporter::import(&mut reader),
+    "splat" => SplatImporter::import(&mut reader),
+    "ksplat" => KsplatImporter::import(&mut reader),
+    "sog" => SogImporter::import(&mut reader),
+    "gltf" => {
+      let base_dir = Path::new(input_path)
+        .parent()
+        .unwrap_or_else(|| Path::new(""));
+      GltfImporter::import_with_resolver(&mut reader, |uri| Ok(fs::read(base_dir.join(uri))?))
+    }
+    "glb" => GltfImporter::import(&mut reader),
+    "usda" | "usdz" => UsdImporter::import(&mut reader),
+    "las" => LasImporter::import(&mut reader),
+    // COLMAP sparse models: points3D.bin / points3D.txt
+    "bin" | "txt" if is_colmap_points_file(input_path) => ColmapPointsImporter::import(&mut reader),
+    _ => Err(converter_core::ConversionError::UnsupportedFormat),
+  }
+  .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 async fn convert_to_temp_file(
   input_path: String,
@@ -37,30 +90,37 @@ async fn convert_to_temp_file(
   );
   let temp_file_path = temp_dir.join(temp_file_name);
 
-  let input_file = File::open(&input_path).map_err(|e| e.to_string())?;
-  let mut reader = BufReader::new(input_file);
-
-  let scene = match source_format {
-    "ply" => PlyImporter::import(&mut reader),
-    "spz" => SpzImporter::import(&mut reader),
-    "csv" => CsvImporter::import(&mut reader),
-    "splat" => SplatImporter::import(&mut reader),
-    _ => Err(converter_core::ConversionError::UnsupportedFormat),
-  }
-  .map_err(|e| e.to_string())?;
+  let scene = import_scene(&input_path, source_format)?;
 
   let output_file = File::create(&temp_file_path).map_err(|e| e.to_string())?;
   let mut writer = BufWriter::new(output_file);
 
-  match target_format {
+  let exported = match target_format {
     "ascii_ply" => PlyASCIIExporter::export(&scene, &mut writer),
     "binary_ply" => PlyBinaryExporter::export(&scene, &mut writer),
+    "compressed_ply" => CompressedPlyExporter::export(&scene, &mut writer),
+    "point_cloud_ply" => PointCloudPlyExporter::export(&scene, &mut writer),
     "spz_v2" => SpzV2Exporter::export(&scene, &mut writer),
     "csv" => CsvExporter::export(&scene, &mut writer),
     "splat" => SplatExporter::export(&scene, &mut writer),
+    "ksplat" => KsplatExporter::export(&scene, &mut writer),
+    "sog" => SogExporter::export(&scene, &mut writer),
+    "gltf" => GltfExporter::export(&scene, &mut writer),
+    "glb" => GlbExporter::export(&scene, &mut writer),
+    "usda" => UsdaExporter::export(&scene, &mut writer),
+    "usdz" => UsdzExporter::export(&scene, &mut writer),
+    "las" => LasExporter::export(&scene, &mut writer),
+    "ellipsoid_ply" => EllipsoidPlyExporter::export(&scene, &mut writer),
+    "ellipsoid_obj" => EllipsoidObjExporter::export(&scene, &mut writer),
     _ => Err(converter_core::ConversionError::UnsupportedFormat),
   }
-  .map_err(|e| e.to_string())?;
+  .and_then(|_| Ok(writer.flush()?));
+  drop(writer);
+  if let Err(e) = exported {
+    // Don't leave partial files in the cache
+    let _ = fs::remove_file(&temp_file_path);
+    return Err(e.to_string());
+  }
 
   let metadata = fs::metadata(&temp_file_path).map_err(|e| e.to_string())?;
 
@@ -70,12 +130,90 @@ async fn convert_to_temp_file(
   })
 }
 
+/// Moves a converted file from the cache to where the user saved it.
 #[tauri::command]
 async fn save_converted_file(temp_path: String, final_path: String) -> Result<(), String> {
-  fs::rename(temp_path, final_path).map_err(|e| e.to_string())?;
+  if fs::rename(&temp_path, &final_path).is_ok() {
+    return Ok(());
+  }
+
+  // The cache is often on another drive than the target, where rename fails. The copy goes next
+  // to the target first, so that a failed copy never leaves a partial file or truncates an
+  // existing one.
+  let final_path = Path::new(&final_path);
+  let file_name = final_path
+    .file_name()
+    .ok_or("The target needs a file name")?
+    .to_string_lossy();
+  let staging_path = final_path.with_file_name(format!(".{}.{}.tmp", file_name, Uuid::new_v4()));
+  if let Err(e) =
+    fs::copy(&temp_path, &staging_path).and_then(|_| fs::rename(&staging_path, final_path))
+  {
+    let _ = fs::remove_file(&staging_path);
+    return Err(e.to_string());
+  }
+  // The file is saved, a leftover in the cache is harmless
+  let _ = fs::remove_file(&temp_path);
   Ok(())
 }
 
+/// Renders a small preview of the file's scene as PNG, so users can check they picked the right
+/// scene before converting. Thumbnails are cached by the hash of the file contents.
+#[tauri::command]
+async fn generate_thumbnail(input_path: String, source_format: String) -> Result<Response, String> {
+  // Hashing and rendering take seconds on large scenes, which would stall the async runtime
+  let png = tauri::async_runtime::spawn_blocking(move || thumbnail(&input_path, &source_format))
+    .await
+    .map_err(|e| e.to_string())??;
+  Ok(Response::new(png))
+}
+
+fn thumbnail(input_path: &str, source_format: &str) -> Result<Vec<u8>, String> {
+  let mut hasher = Sha256::new();
+  let mut input_file = File::open(input_path).map_err(|e| e.to_string())?;
+  io::copy(&mut input_file, &mut hasher).map_err(|e| e.to_string())?;
+  let hash: String = hasher
+    .finalize()
+    .iter()
+    .map(|byte| format!("{:02x}", byte))
+    .collect();
+
+  let thumbnail_dir = cache_dir()
+    .unwrap_or_else(env::temp_dir)
+    .join("gs-flux-thumbnails");
+  let thumbnail_path = thumbnail_dir.join(format!(
+    "{}-{}x{}.png",
+    hash, THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT
+  ));
+  if let Ok(png) = fs::read(&thumbnail_path) {
+    return Ok(png);
+  }
+
+  let scene = import_scene(input_path, source_format)?;
+  // The first capture camera shows the scene as it was photographed, unless it has no image size
+  let camera = match scene.cameras.first() {
+    Some(camera) if camera.width > 0 && camera.height > 0 => {
+      camera.scaled(THUMBNAIL_WIDTH as f32 / camera.width as f32)
+    }
+    _ => Camera::framing(
+      &scene,
+      THUMBNAIL_WIDTH,
+      THUMBNAIL_HEIGHT,
+      &FramingOptions::default(),
+    ),
+  };
+  let image = render(&scene, &camera, &RenderOptions::default());
+
+  let mut png = Vec::new();
+  write_png(&image, &mut png).map_err(|e| e.to_string())?;
+
+  // A failure to cache only costs a re-render next time
+  if fs::create_dir_all(&thumbnail_dir).is_ok() {
+    let _ = fs::write(&thumbnail_path, &png);
+  }
+  Ok(png)
+}
+
 #[derive(Serialize)]
 struct FileMetadata {
   name: String,
@@ -105,7 +243,8 @@ pub fn run() {
     .invoke_handler(tauri::generate_handler![
       convert_to_temp_file,
       save_converted_file,
-      get_file_metadata
+      get_file_metadata,
+      generate_thumbnail
     ])
     .setup(|app| {
       if cfg!(debug_assertions) {
@@ -0,0 +1,205 @@
+//! Expanding the inputs of a batch conversion (files, folders and glob patterns) into source
+//! files, and mapping every source to its output path.
+
+use std::{
+  collections::{HashMap, HashSet},
+  fs,
+  path::{Component, Path, PathBuf},
+};
+
+use crate::is_importable;
+
+/// A source file and the folder its output path is made relative to.
+#[derive(Debug)]
+pub struct Source {
+  pub path: PathBuf,
+  pub base: PathBuf,
+}
+
+/// A file to convert.
+#[derive(Debug)]
+pub struct Job {
+  pub source: PathBuf,
+  pub output: PathBuf,
+}
+
+fn is_glob(input: &str) -> bool {
+  input.contains(['*', '?', '['])
+}
+
+/// The leading components of a glob pattern that contain no wildcards, e.g. `scans` for
+/// `scans/**/*.ply`.
+fn glob_base(pattern: &str) -> PathBuf {
+  let mut base = PathBuf::new();
+  for component in Path::new(pattern).components() {
+    if let Component::Normal(part) = component
+      && is_glob(&part.to_string_lossy())
+    {
+      break;
+    }
+    base.push(component);
+  }
+  base
+}
+
+/// All importable files below `folder`, skipping `exclude` (the output folder when it is inside
+/// the input).
+fn walk(folder: &Path, exclude: Option<&Path>, files: &mut Vec<PathBuf>) -> Result<(), String> {
+  let mut entries = fs::read_dir(folder)
+    .map_err(|e| format!("Could not read '{}': {}", folder.display(), e))?
+    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+    .collect::<Vec<_>>();
+  entries.sort();
+
+  for path in entries {
+    if exclude.is_some_and(|exclude| same_path(&path, exclude)) {
+      continue;
+    }
+    if path.is_dir() {
+      walk(&path, exclude, files)?;
+    } else if is_importable(&path.to_string_lossy()) {
+      files.push(path);
+    }
+  }
+  Ok(())
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+  match (a.canonicalize(), b.canonicalize()) {
+    (Ok(a), Ok(b)) => a == b,
+    _ => a == b,
+  }
+}
+
+/// The source files named by `inputs`: files as they are, every importable file below folders,
+/// and the importable files matching glob patterns (`**` matches any number of folders).
+pub fn expand_inputs(inputs: &[String], out_dir: Option<&Path>) -> Result<Vec<Source>, String> {
+  let mut sources = Vec::new();
+
+  for input in inputs {
+    let path = Path::new(input);
+    if is_glob(input) {
+      let base = glob_base(input);
+      let matches = glob::glob(input).map_err(|e| format!("Invalid pattern '{}': {}", input, e))?;
+      let count = sources.len();
+      for entry in matches {
+        let path = entry.map_err(|e| e.to_string())?;
+        if path.is_file()
+          && is_importable(&path.to_string_lossy())
+          && !out_dir.is_some_and(|out_dir| path.starts_with(out_dir))
+        {
+          sources.push(Source {
+            path,
+            base: base.clone(),
+          });
+        }
+      }
+      if sources.len() == count {
+        return Err(format!("No files to convert match '{}'", input));
+      }
+    } else if path.is_dir() {
+      let mut files = Vec::new();
+      walk(path, out_dir, &mut files)?;
+      sources.extend(files.into_iter().map(|file| Source {
+        path: file,
+        base: path.to_path_buf(),
+      }));
+    } else if path.is_file() {
+      if !is_importable(input) {
+        return Err(format!("'{}' is not a supported input format", input));
+      }
+      sources.push(Source {
+        path: path.to_path_buf(),
+        base: path.parent().unwrap_or_else(|| Path::new("")).to_path_buf(),
+      });
+    } else {
+      return Err(format!("'{}' does not exist", input));
+    }
+  }
+
+  Ok(sources)
+}
+
+/// Pairs every source with its output: the same path relative to `out_dir` (or next to the source
+/// without one) with the `to` extension.
+pub fn plan_jobs(
+  sources: Vec<Source>,
+  out_dir: Option<&Path>,
+  to: &str,
+) -> Result<Vec<Job>, String> {
+  let (jobs, refused) = plan_each(sources, out_dir, to);
+  match refused.into_iter().next() {
+    Some(message) => Err(message),
+    None => Ok(jobs),
+  }
+}
+
+/// Pairs the sources with their outputs like `plan_jobs`, but leaves out the sources that can't
+/// be converted instead of failing: those that would overwrite themselves, and all those that
+/// would share an output. Returns the jobs, and why sources were left out.
+pub fn plan_each(
+  sources: Vec<Source>,
+  out_dir: Option<&Path>,
+  to: &str,
+) -> (Vec<Job>, Vec<String>) {
+  let mut jobs: Vec<Job> = Vec::with_capacity(sources.len());
+  let mut refused = Vec::new();
+  let mut outputs: HashMap<PathBuf, usize> = HashMap::new();
+  let mut shared = HashSet::new();
+
+  for source in sources {
+    let relative = source
+      .path
+      .strip_prefix(&source.base)
+      .unwrap_or(&source.path)
+      .with_extension(to);
+    let output = match out_dir {
+      Some(out_dir) => out_dir.join(relative),
+      None => source.base.join(relative),
+    };
+
+    if same_path(&output, &source.path) {
+      refused.push(format!(
+        "'{}' would be overwritten by its own conversion, use --out-dir",
+        source.path.display()
+      ));
+      continue;
+    }
+    // The same file can be named twice, e.g. by a folder and a pattern
+    if let Some(&index) = outputs.get(&output) {
+      if jobs[index].source != source.path {
+        refused.push(format!(
+          "'{}' and '{}' would both be converted to '{}'",
+          jobs[index].source.display(),
+          source.path.display(),
+          output.display()
+        ));
+        shared.insert(index);
+      }
+      continue;
+    }
+    outputs.insert(output.clone(), jobs.len());
+    jobs.push(Job {
+      source: source.path,
+      output,
+    });
+  }
+
+  // None of the sources sharing an output wins
+  let jobs = jobs
+    .into_iter()
+    .enumerate()
+    .filter(|(index, _)| !shared.contains(index))
+    .map(|(_, job)| job)
+    .collect();
+  (jobs, refused)
+}
+
+/// Whether `job`'s output exists and is newer than its source.
+pub fn is_up_to_date(job: &Job) -> bool {
+  let modified = |path: &Path| fs::metadata(path).and_then(|metadata| metadata.modified());
+  match (modified(&job.source), modified(&job.output)) {
+    (Ok(source), Ok(output)) => output >= source,
+    _ => false,
+  }
+}
@@ -0,0 +1,153 @@
+//! `gs-flux compare`: how much a conversion changed a scene, from renders of both (PSNR/SSIM)
+//! and from the attributes of matching splats.
+
+use std::time::Instant;
+
+use converter_core::{
+  Camera,
+  camera::FramingOptions,
+  formats::point_cloud::PointCloudImportOptions,
+  metrics::{AttributeErrors, ErrorStats, attribute_errors, psnr, ssim},
+  render::{RenderOptions, render},
+};
+use serde::Serialize;
+
+use crate::convert::import_scene;
+use crate::{CompareParams, input_format, load_cameras, report, run_with_spinner};
+
+/// PSNR and SSIM of every view, and the attribute errors.
+struct Comparison {
+  views: Vec<(f64, f64)>,
+  attributes: AttributeErrors,
+}
+
+pub fn run_compare(params: CompareParams) {
+  let start = Instant::now();
+  let comparison = run_with_spinner("Comparing...", "✖ Comparison failed", move || {
+    let no_points = PointCloudImportOptions::default();
+    let reference = import_scene(
+      &params.reference_file,
+      &input_format(&params.reference_file),
+      &no_points,
+    )?;
+    let test = import_scene(
+      &params.test_file,
+      &input_format(&params.test_file),
+      &no_points,
+    )?;
+
+    let views = params.views as usize;
+    let capture_cameras = match &params.cameras {
+      Some(path) => load_cameras(path)?,
+      None => reference.cameras.clone(),
+    };
+    let cameras: Vec<Camera> = if !capture_cameras.is_empty() {
+      let step = capture_cameras.len().div_ceil(views).max(1);
+      capture_cameras
+        .iter()
+        .step_by(step)
+        .map(|camera| camera.scaled((params.width as f32 / camera.width as f32).min(1.0)))
+        .collect()
+    } else {
+      // Both scenes are seen from cameras framing the reference
+      (0..views)
+        .map(|view| {
+          let framing = FramingOptions {
+            azimuth_degrees: view as f32 * 360.0 / views as f32,
+            elevation_degrees: if view % 2 == 0 { 20.0 } else { -10.0 },
+            up: params.up,
+            ..FramingOptions::default()
+          };
+          Camera::framing(&reference, params.width, params.width * 3 / 4, &framing)
+        })
+        .collect()
+    };
+
+    let options = RenderOptions {
+      background: [0.0, 0.0, 0.0, 1.0],
+    };
+    let views = cameras
+      .iter()
+      .map(|camera| {
+        let (a, b) = (
+          render(&reference, camera, &options),
+          render(&test, camera, &options),
+        );
+        (psnr(&a, &b), ssim(&a, &b))
+      })
+      .collect();
+
+    Ok(Comparison {
+      views,
+      attributes: attribute_errors(&reference, &test),
+    })
+  });
+
+  let count = comparison.views.len() as f64;
+  let average = ViewScore {
+    psnr: comparison.views.iter().map(|(psnr, _)| psnr).sum::<f64>() / count,
+    ssim: comparison.views.iter().map(|(_, ssim)| ssim).sum::<f64>() / count,
+  };
+  if report::json() {
+    report::print_success(&CompareReport {
+      views: comparison
+        .views
+        .iter()
+        .map(|&(psnr, ssim)| ViewScore { psnr, ssim })
+        .collect(),
+      average,
+      attribute_errors: &comparison.attributes,
+      seconds: start.elapsed().as_secs_f32(),
+    });
+    return;
+  }
+
+  println!("\n{:<10}{:>12}{:>10}", "View", "PSNR (dB)", "SSIM");
+  for (index, (psnr, ssim)) in comparison.views.iter().enumerate() {
+    println!("{:<10}{:>12.2}{:>10.4}", index, psnr, ssim);
+  }
+  println!(
+    "{:<10}{:>12.2}{:>10.4}",
+    "Average", average.psnr, average.ssim
+  );
+
+  let errors = &comparison.attributes;
+  println!(
+    "\nSplats: {} in the reference, {} in the converted scene (matched by nearest position)",
+    errors.reference_count, errors.test_count
+  );
+  println!(
+    "{:<22}{:>12}{:>12}{:>12}",
+    "Attribute error", "Mean", "RMSE", "Max"
+  );
+  let rows: [(&str, &ErrorStats); 6] = [
+    ("Position", &errors.position),
+    ("Colour (0..1)", &errors.color),
+    ("Opacity (0..1)", &errors.opacity),
+    ("Log scale", &errors.log_scale),
+    ("Rotation (degrees)", &errors.rotation_degrees),
+    ("SH coefficients", &errors.spherical_harmonics),
+  ];
+  for (name, stats) in rows {
+    println!(
+      "{:<22}{:>12.6}{:>12.6}{:>12.6}",
+      name, stats.mean, stats.rmse, stats.max
+    );
+  }
+}
+
+#[derive(Serialize)]
+struct ViewScore {
+  /// Infinite (null in JSON) for identical renders.
+  psnr: f64,
+  ssim: f64,
+}
+
+/// What `compare --json` reports.
+#[derive(Serialize)]
+struct CompareReport<'a> {
+  views: Vec<ViewScore>,
+  average: ViewScore,
+  attribute_errors: &'a AttributeErrors,
+  seconds: f32,
+}
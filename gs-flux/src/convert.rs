@@ -0,0 +1,806 @@
+//! Converting files: the options of a conversion, single conversions (from and to pipes too) and
+//! batch conversions, and the import and export of every format.
+
+use std::{
+  fs::{self, File},
+  io::{self, BufReader, BufWriter, Cursor, Read, Write},
+  path::Path,
+  process,
+  time::Instant,
+};
+
+use converter_core::{
+  ConversionError, Exporter, Importer, Scene,
+  formats::{
+    colmap::{ColmapCamerasImporter, ColmapPointsImporter},
+    compressed_ply::CompressedPlyExporter,
+    csv::{CsvExporter, CsvImporter},
+    detect::{DETECTION_BYTES, detect_format},
+    ellipsoid::{EllipsoidExportOptions, EllipsoidObjExporter, EllipsoidPlyExporter},
+    gltf::{GlbExporter, GltfExportOptions, GltfExporter, GltfImporter},
+    ksplat::{KsplatExportOptions, KsplatExporter, KsplatImporter},
+    las::{LasExporter, LasImporter},
+    ply::{PlyASCIIExporter, PlyBinaryExporter, PlyExportOptions, PlyImporter},
+    point_cloud::{PointCloudExportOptions, PointCloudImportOptions, PointCloudPlyExporter},
+    sog::{SogExportOptions, SogExporter, SogImporter},
+    splat::{SplatExportOptions, SplatExporter, SplatImporter, SplatOrder},
+    spz::{SpzImporter, SpzV2Exporter},
+    usd::{UsdImporter, UsdaExporter, UsdzExporter},
+  },
+  operations::pipeline::{Operation, Pipeline},
+};
+use indicatif::{ProgressBar, ProgressStyle};
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::batch::{self, Job};
+use crate::output::AtomicFile;
+use crate::{
+  ConvertOptions, ConvertParams, STDIO, colmap_model_files, extension_of, input_format,
+  load_cameras, report, run_with_spinner, validate_source_path, validate_target_path,
+};
+
+/// Format-specific settings for the exporters.
+pub struct ExportOptions {
+  encoding: Option<String>,
+  ply: PlyExportOptions,
+  point_cloud: bool,
+  points: PointCloudExportOptions,
+  ellipsoids: bool,
+  ellipsoid: EllipsoidExportOptions,
+  splat: SplatExportOptions,
+  ksplat: KsplatExportOptions,
+  sog: SogExportOptions,
+  gltf: GltfExportOptions,
+}
+
+/// Processing applied to the imported scene before it is exported.
+pub struct SceneOperations {
+  cameras: Option<String>,
+  steps: Vec<Operation>,
+}
+
+/// How long a processing step took and how many splats it left.
+#[derive(Serialize)]
+pub struct StepReport {
+  operation: String,
+  seconds: f32,
+  splats_before: usize,
+  splats_after: usize,
+}
+
+impl SceneOperations {
+  pub fn apply(&self, scene: &mut Scene) -> Result<Vec<StepReport>, ConversionError> {
+    if let Some(path) = &self.cameras {
+      scene.cameras = load_cameras(path)?;
+    }
+    let reports = self
+      .steps
+      .iter()
+      .map(|step| {
+        let (start, splats_before) = (Instant::now(), scene.splats.len());
+        step.apply(scene);
+        StepReport {
+          operation: step.to_string(),
+          seconds: start.elapsed().as_secs_f32(),
+          splats_before,
+          splats_after: scene.splats.len(),
+        }
+      })
+      .collect();
+    Ok(reports)
+  }
+}
+
+/// The steps of a pipeline file, read as TOML or JSON by its extension.
+fn load_pipeline(path: &str) -> Result<Pipeline, String> {
+  let text = fs::read_to_string(path).map_err(|e| format!("Could not read '{}': {}", path, e))?;
+  match extension_of(path).as_str() {
+    "toml" => toml::from_str(&text).map_err(|e| e.to_string()),
+    _ => serde_json::from_str(&text).map_err(|e| e.to_string()),
+  }
+  .map_err(|e| format!("Invalid pipeline '{}': {}", path, e))
+}
+
+/// Everything that controls a conversion besides the source and target files.
+pub struct Conversion {
+  pub import: PointCloudImportOptions,
+  pub export: ExportOptions,
+  pub operations: SceneOperations,
+  /// Fail instead of replacing an existing output.
+  pub no_clobber: bool,
+}
+
+impl Conversion {
+  pub fn new(options: ConvertOptions) -> Self {
+    Self::try_new(options).unwrap_or_else(|message| report::fail_usage(message))
+  }
+
+  /// The conversion `options` describe, or why they don't fit together.
+  pub fn try_new(options: ConvertOptions) -> Result<Self, String> {
+    if options.bake_sh && options.sh_degree != Some(0) {
+      return Err("--bake-sh can only be used with --sh-degree 0".to_string());
+    }
+
+    // --where runs first, then the pipeline file, then --op, then --sh-degree
+    let mut steps: Vec<Operation> = options.filter.map(Operation::Where).into_iter().collect();
+    if let Some(path) = &options.pipeline {
+      steps.extend(load_pipeline(path)?.operations);
+    }
+    steps.extend(options.operations);
+    match options.sh_degree {
+      Some(_) if options.bake_sh => steps.push(Operation::BakeSh),
+      Some(degree) => steps.push(Operation::ShDegree(degree)),
+      None => {}
+    }
+    // A .ply keeps the 45 `f_rest_*` properties of 3DGS output unless the SH bands are reduced
+    let sh_degree = steps
+      .iter()
+      .filter_map(|step| match step {
+        Operation::ShDegree(degree) => Some(*degree),
+        Operation::BakeSh => Some(0),
+        _ => None,
+      })
+      .min();
+
+    Ok(Conversion {
+      import: PointCloudImportOptions {
+        initial_opacity: options
+          .initial_opacity
+          .unwrap_or(PointCloudImportOptions::default().initial_opacity),
+      },
+      export: ExportOptions {
+        encoding: options.encoding,
+        ply: PlyExportOptions { sh_degree },
+        point_cloud: options.point_cloud,
+        points: PointCloudExportOptions {
+          min_opacity: options.min_opacity,
+        },
+        ellipsoids: options.ellipsoids,
+        ellipsoid: EllipsoidExportOptions {
+          sigma: options
+            .sigma
+            .unwrap_or(EllipsoidExportOptions::default().sigma),
+          min_opacity: options.min_opacity,
+          max_triangles: options.max_triangles,
+        },
+        splat: SplatExportOptions {
+          order: if options.importance_order {
+            SplatOrder::Importance
+          } else {
+            SplatOrder::Input
+          },
+          min_opacity: options.min_opacity,
+        },
+        ksplat: KsplatExportOptions {
+          compression_level: options
+            .compression_level
+            .unwrap_or(KsplatExportOptions::default().compression_level),
+          sh_degree: None,
+        },
+        sog: SogExportOptions {
+          sh_palette_size: options.sh_palette_size.map(|size| size as usize),
+          ..SogExportOptions::default()
+        },
+        gltf: GltfExportOptions {
+          meshopt_compression: options.meshopt,
+        },
+      },
+      operations: SceneOperations {
+        cameras: options.cameras,
+        steps,
+      },
+      no_clobber: options.no_clobber,
+    })
+  }
+}
+
+/// Converts one file, where `-` reads from stdin or writes to stdout. `--from` and `to` take
+/// precedence over the extensions.
+pub fn convert_file(
+  source_file: String,
+  output_file: String,
+  to: Option<String>,
+  options: ConvertOptions,
+) {
+  let input_type = match &options.from {
+    Some(from) => Some(from.clone()),
+    None if source_file == STDIO => None,
+    None => Some(input_format(&source_file)),
+  };
+  let output_type = match to {
+    Some(to) => to,
+    None if output_file == STDIO => report::fail_usage("--to is required when writing to stdout"),
+    None => extension_of(&output_file),
+  };
+  if output_file == STDIO {
+    report::take_stdout();
+  }
+  let conversion = Conversion::new(options);
+
+  let result = run_with_spinner("Converting...", "✖ Conversion failed", move || {
+    convert(
+      &source_file,
+      input_type.as_deref(),
+      &output_file,
+      &output_type,
+      &conversion,
+    )
+  });
+
+  if report::json() {
+    report::print_success(&result);
+    return;
+  }
+  // On stderr, as stdout may be the converted scene
+  if !result.operations.is_empty() {
+    eprintln!(
+      "\n{:<48}{:>10}{:>14}{:>14}",
+      "Operation", "Time (s)", "Splats before", "Splats after"
+    );
+    for step in &result.operations {
+      eprintln!(
+        "{:<48}{:>10.3}{:>14}{:>14}",
+        step.operation, step.seconds, step.splats_before, step.splats_after
+      );
+    }
+  }
+  for warning in &result.warnings {
+    eprintln!("⚠ {}", warning);
+  }
+}
+
+pub fn run_convert(params: ConvertParams) {
+  let streams = params.inputs.iter().any(|input| input == STDIO);
+  if let Some(to) = params.to.clone()
+    && !streams
+  {
+    return run_batch(params, &to);
+  }
+
+  let Ok([source_file, output_file]) = <[String; 2]>::try_from(params.inputs) else {
+    report::fail_usage(
+      "Expected a source file and a target filename, or --to to convert many files",
+    );
+  };
+  if let Err(message) =
+    validate_source_path(&source_file).and_then(|_| validate_target_path(&output_file))
+  {
+    report::fail_usage(message);
+  }
+
+  convert_file(source_file, output_file, params.to, params.options);
+}
+
+/// Converts every file named by the inputs to `to` in parallel, skipping outputs that are newer
+/// than their source.
+fn run_batch(params: ConvertParams, to: &str) {
+  let out_dir = params.out_dir.as_deref().map(Path::new);
+  let jobs = match batch::expand_inputs(&params.inputs, out_dir)
+    .and_then(|sources| batch::plan_jobs(sources, out_dir, to))
+  {
+    Ok(jobs) => jobs,
+    Err(message) => report::fail_usage(message),
+  };
+  let total = jobs.len();
+  // Existing outputs are kept with --no-clobber, and otherwise while newer than their source
+  let no_clobber = params.options.no_clobber;
+  let (jobs, skipped): (Vec<Job>, Vec<Job>) = jobs.into_iter().partition(|job| {
+    if no_clobber {
+      !job.output.exists()
+    } else {
+      params.force || !batch::is_up_to_date(job)
+    }
+  });
+  let conversion = Conversion::new(params.options);
+
+  let start = Instant::now();
+  let progress = if report::json() {
+    ProgressBar::hidden()
+  } else {
+    ProgressBar::new(jobs.len() as u64)
+  };
+  progress.set_style(
+    ProgressStyle::default_bar()
+      .template("{bar:40.blue} {pos}/{len} {wide_msg}")
+      .unwrap(),
+  );
+  // 0 threads lets rayon use one per CPU
+  let pool = rayon::ThreadPoolBuilder::new()
+    .num_threads(params.jobs.unwrap_or(0) as usize)
+    .build()
+    .unwrap();
+  let results: Vec<Result<ConversionReport, ConversionError>> = pool.install(|| {
+    jobs
+      .par_iter()
+      .map(|job| {
+        progress.set_message(job.source.display().to_string());
+        let result = convert_job(job, &conversion);
+        progress.inc(1);
+        result
+      })
+      .collect()
+  });
+  progress.finish_and_clear();
+  let seconds = start.elapsed().as_secs_f32();
+  let failed = results.iter().filter(|result| result.is_err()).count();
+  // The first failure decides the exit code
+  let exit_code = results
+    .iter()
+    .find_map(|result| result.as_ref().err())
+    .map(report::exit_code);
+
+  if report::json() {
+    let mut files: Vec<BatchFile> = jobs
+      .iter()
+      .zip(results)
+      .map(|(job, result)| match result {
+        Ok(report) => BatchFile::Converted(report),
+        Err(e) => BatchFile::Failed {
+          source: job.source.display().to_string(),
+          target: job.output.display().to_string(),
+          error: (&e).into(),
+        },
+      })
+      .collect();
+    files.extend(skipped.iter().map(|job| BatchFile::Skipped {
+      source: job.source.display().to_string(),
+      target: job.output.display().to_string(),
+    }));
+    report::print_json(&BatchReport {
+      status: if exit_code.is_none() { "ok" } else { "error" },
+      exit_code: exit_code.unwrap_or(0),
+      converted: jobs.len() - failed,
+      skipped: skipped.len(),
+      failed,
+      seconds,
+      files,
+    });
+  } else {
+    for (job, result) in jobs.iter().zip(&results) {
+      match result {
+        Ok(report) => {
+          for warning in &report.warnings {
+            eprintln!("⚠ {}: {}", job.source.display(), warning);
+          }
+        }
+        Err(error) => eprintln!("✖ {}: {}", job.source.display(), error),
+      }
+    }
+    println!(
+      "{} Converted {} of {} files in {:.2}s ({} {}, {} failed)",
+      if failed == 0 { "✔" } else { "✖" },
+      jobs.len() - failed,
+      total,
+      seconds,
+      skipped.len(),
+      if no_clobber {
+        "already exist"
+      } else {
+        "up to date"
+      },
+      failed
+    );
+  }
+  if let Some(exit_code) = exit_code {
+    process::exit(exit_code);
+  }
+}
+
+/// What `convert --to --json` reports.
+#[derive(Serialize)]
+struct BatchReport {
+  status: &'static str,
+  exit_code: i32,
+  converted: usize,
+  /// Outputs that were up to date, or existed with --no-clobber.
+  skipped: usize,
+  failed: usize,
+  seconds: f32,
+  files: Vec<BatchFile>,
+}
+
+/// The outcome of one file of a batch conversion.
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum BatchFile {
+  Converted(ConversionReport),
+  Skipped {
+    source: String,
+    target: String,
+  },
+  Failed {
+    source: String,
+    target: String,
+    error: report::ErrorReport,
+  },
+}
+
+pub fn convert_job(
+  job: &Job,
+  conversion: &Conversion,
+) -> Result<ConversionReport, ConversionError> {
+  if let Some(parent) = job.output.parent() {
+    fs::create_dir_all(parent)?;
+  }
+  let source = job.source.to_string_lossy();
+  convert(
+    &source,
+    Some(&input_format(&source)),
+    &job.output.to_string_lossy(),
+    &extension_of(&job.output.to_string_lossy()),
+    conversion,
+  )
+}
+
+pub fn import_scene(
+  input_file_path: &str,
+  input_file_type: &str,
+  import_options: &PointCloudImportOptions,
+) -> Result<Scene, ConversionError> {
+  let file = File::open(input_file_path)?;
+  let folder = Path::new(input_file_path)
+    .parent()
+    .unwrap_or_else(|| Path::new(""));
+  read_scene(
+    &mut BufReader::new(file),
+    input_file_type,
+    Some(folder),
+    import_options,
+  )
+}
+
+/// Imports a scene from stdin, recognising its format from the first bytes when it isn't given.
+/// Returns the scene with its format and the number of bytes read.
+fn import_stdin(
+  input_file_type: Option<&str>,
+  import_options: &PointCloudImportOptions,
+) -> Result<(Scene, String, u64), ConversionError> {
+  let mut data = Vec::new();
+  io::stdin().lock().read_to_end(&mut data)?;
+
+  let input_file_type = match input_file_type {
+    Some(input_file_type) => input_file_type,
+    None => {
+      detect_format(&data[..data.len().min(DETECTION_BYTES)])
+        .ok_or_else(|| ConversionError::ParseError {
+          format: "stdin".to_string(),
+          message: "Unrecognised format, use --from to name it".to_string(),
+        })?
+        .format
+    }
+  };
+  let size = data.len() as u64;
+  let scene = read_scene(
+    &mut Cursor::new(data),
+    input_file_type,
+    None,
+    import_options,
+  )?;
+  Ok((scene, input_file_type.to_string(), size))
+}
+
+/// Imports a scene in `input_file_type` from `reader`. `folder` holds the files it refers to,
+/// which can't be resolved for streams.
+pub fn read_scene(
+  reader: &mut impl Read,
+  input_file_type: &str,
+  folder: Option<&Path>,
+  import_options: &PointCloudImportOptions,
+) -> Result<Scene, ConversionError> {
+  let scene = match input_file_type {
+    "ply" => PlyImporter::import_with_options(reader, import_options)?,
+    "spz" => SpzImporter::import(reader)?,
+    "csv" => CsvImporter::import(reader)?,
+    "splat" => SplatImporter::import(reader)?,
+    "ksplat" => KsplatImporter::import(reader)?,
+    "sog" => SogImporter::import(reader)?,
+    "gltf" => match folder {
+      // External buffers are resolved relative to the .gltf file
+      Some(base_dir) => {
+        GltfImporter::import_with_resolver(reader, |uri| Ok(fs::read(base_dir.join(uri))?))?
+      }
+      None => GltfImporter::import(reader)?,
+    },
+    "glb" => GltfImporter::import(reader)?,
+    "usda" | "usdz" => UsdImporter::import(reader)?,
+    "las" => LasImporter::import_with_options(reader, import_options)?,
+    "colmap" => {
+      let mut scene = ColmapPointsImporter::import_with_options(reader, import_options)?;
+      // The cameras come along when the rest of the model is next to points3D
+      if let Some(Ok((cameras_path, images_path))) =
+        folder.map(|folder| colmap_model_files(&folder.to_string_lossy()))
+      {
+        scene.cameras = ColmapCamerasImporter::import(
+          &mut BufReader::new(File::open(cameras_path)?),
+          &mut BufReader::new(File::open(images_path)?),
+        )?;
+      }
+      scene
+    }
+    _ => return Err(ConversionError::UnsupportedFormat),
+  };
+
+  Ok(scene)
+}
+
+/// Converts `input_file_path` to `output_file_path`, where `-` stands for stdin and stdout. The
+/// format of stdin is recognised from its content when `input_file_type` is `None`.
+fn convert(
+  input_file_path: &str,
+  input_file_type: Option<&str>,
+  output_file_path: &str,
+  output_file_type: &str,
+  conversion: &Conversion,
+  // version: Option<u32>,
+) -> Result<ConversionReport, ConversionError> {
+  let export_options = &conversion.export;
+  let start = Instant::now();
+  // Fails early on outputs that exist already, the commits below catch those created meanwhile
+  let bin_path = Path::new(output_file_path).with_extension("bin");
+  let mut outputs = vec![Path::new(output_file_path)];
+  if output_file_type == "gltf" {
+    outputs.push(&bin_path);
+  }
+  if conversion.no_clobber
+    && output_file_path != STDIO
+    && let Some(existing) = outputs.iter().find(|path| path.exists())
+  {
+    return Err(ConversionError::Io(io::Error::new(
+      io::ErrorKind::AlreadyExists,
+      format!("'{}' already exists (--no-clobber)", existing.display()),
+    )));
+  }
+
+  let (mut scene, source_format, source_size) = if input_file_path == STDIO {
+    import_stdin(input_file_type, &conversion.import)?
+  } else {
+    let input_file_type = input_file_type
+      .map(str::to_string)
+      .unwrap_or_else(|| input_format(input_file_path));
+    let scene = import_scene(input_file_path, &input_file_type, &conversion.import)?;
+    (scene, input_file_type, fs::metadata(input_file_path)?.len())
+  };
+  let import_seconds = start.elapsed().as_secs_f32();
+  let splats_read = scene.splats.len();
+
+  let steps = conversion.operations.apply(&mut scene)?;
+  let operations_seconds = start.elapsed().as_secs_f32() - import_seconds;
+  let warnings = conversion_warnings(&scene, output_file_type, export_options);
+
+  if output_file_path == STDIO && output_file_type == "gltf" {
+    return Err(ConversionError::WriteError {
+      format: "glTF".to_string(),
+      message: "A .gltf with its separate .bin can't be written to stdout, use glb".to_string(),
+    });
+  }
+  let output = if output_file_path == STDIO {
+    None
+  } else {
+    Some(AtomicFile::create(output_file_path)?)
+  };
+  let file: Box<dyn Write + '_> = match &output {
+    Some(output) => Box::new(output.file()),
+    None => Box::new(io::stdout().lock()),
+  };
+  let mut writer = BufWriter::new(CountingWriter::new(file));
+  let mut bin_output = None;
+  let mut extra_bytes = 0;
+
+  match output_file_type {
+    "gltf" => {
+      // The buffer goes to a .bin file next to the .gltf
+      let bin_uri = bin_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+      let bin = AtomicFile::create(&bin_path)?;
+      let mut bin_writer = BufWriter::new(CountingWriter::new(bin.file()));
+      GltfExporter::export_with_external_buffer(
+        &scene,
+        &mut writer,
+        &mut bin_writer,
+        &bin_uri,
+        &export_options.gltf,
+      )?;
+      bin_writer.flush()?;
+      extra_bytes = bin_writer.get_ref().count;
+      drop(bin_writer);
+      bin_output = Some(bin);
+    }
+    _ => write_scene(&scene, output_file_type, &mut writer, export_options)?,
+  };
+  writer.flush()?;
+  let target_size = writer.get_ref().count + extra_bytes;
+  drop(writer);
+  let commit = |file: AtomicFile| {
+    if conversion.no_clobber {
+      file.commit_new()
+    } else {
+      file.commit()
+    }
+  };
+  // The .bin goes in place first, so that the .gltf never refers to a missing buffer
+  let bin_committed = bin_output.is_some();
+  if let Some(bin) = bin_output {
+    commit(bin)?;
+  }
+  if let Some(output) = output
+    && let Err(e) = commit(output)
+  {
+    // The .bin is new with --no-clobber, and useless without its .gltf
+    if bin_committed && conversion.no_clobber {
+      let _ = fs::remove_file(&bin_path);
+    }
+    return Err(e.into());
+  }
+
+  let total_seconds = start.elapsed().as_secs_f32();
+  Ok(ConversionReport {
+    source: input_file_path.to_string(),
+    target: output_file_path.to_string(),
+    source_format,
+    target_format: output_file_type.to_string(),
+    source_size,
+    target_size,
+    splats_read,
+    splats_written: scene.splats.len(),
+    cameras: scene.cameras.len(),
+    seconds: Timings {
+      import: import_seconds,
+      operations: operations_seconds,
+      export: total_seconds - import_seconds - operations_seconds,
+      total: total_seconds,
+    },
+    operations: steps,
+    warnings,
+  })
+}
+
+/// Exports `scene` to `writer` in `output_file_type`, for every format written as a single file.
+pub fn write_scene(
+  scene: &Scene,
+  output_file_type: &str,
+  writer: &mut impl Write,
+  export_options: &ExportOptions,
+) -> Result<(), ConversionError> {
+  match output_file_type {
+    "ply" if export_options.ellipsoids => {
+      EllipsoidPlyExporter::export_with_options(scene, writer, &export_options.ellipsoid)?
+    }
+    "ply" if export_options.point_cloud => {
+      PointCloudPlyExporter::export_with_options(scene, writer, &export_options.points)?
+    }
+    "ply" => match export_options.encoding.as_deref().unwrap_or("binary") {
+      "ascii" => PlyASCIIExporter::export_with_options(scene, writer, &export_options.ply)?,
+      "binary" => PlyBinaryExporter::export_with_options(scene, writer, &export_options.ply)?,
+      "compressed" => CompressedPlyExporter::export(scene, writer)?,
+      other => {
+        return Err(ConversionError::ParseError {
+          format: "PLY".to_string(),
+          message: format!("Unsupported PLY encoding: {}", other),
+        });
+      }
+    },
+    "spz" => SpzV2Exporter::export(scene, writer)?,
+    "las" => LasExporter::export_with_options(scene, writer, &export_options.points)?,
+    "obj" => EllipsoidObjExporter::export_with_options(scene, writer, &export_options.ellipsoid)?,
+    "csv" => CsvExporter::export(scene, writer)?,
+    "splat" => SplatExporter::export_with_options(scene, writer, &export_options.splat)?,
+    "ksplat" => KsplatExporter::export_with_options(scene, writer, &export_options.ksplat)?,
+    "sog" => SogExporter::export_with_options(scene, writer, &export_options.sog)?,
+    "glb" => GlbExporter::export_with_options(scene, writer, &export_options.gltf)?,
+    "usda" => UsdaExporter::export(scene, writer)?,
+    "usdz" => UsdzExporter::export(scene, writer)?,
+    "gltf" => {
+      return Err(ConversionError::WriteError {
+        format: "glTF".to_string(),
+        message: "A .gltf needs a separate .bin file, use glb".to_string(),
+      });
+    }
+    _ => return Err(ConversionError::UnsupportedFormat),
+  };
+  Ok(())
+}
+
+/// What a conversion read, wrote and did, for `--json`.
+#[derive(Serialize)]
+pub struct ConversionReport {
+  pub source: String,
+  pub target: String,
+  pub source_format: String,
+  pub target_format: String,
+  /// Bytes read and written, including the .bin of a .gltf.
+  pub source_size: u64,
+  pub target_size: u64,
+  pub splats_read: usize,
+  /// Splats after the operations. Point cloud, ellipsoid and .splat outputs may still leave out
+  /// those below --min-opacity.
+  pub splats_written: usize,
+  pub cameras: usize,
+  pub seconds: Timings,
+  pub operations: Vec<StepReport>,
+  pub warnings: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct Timings {
+  pub import: f32,
+  pub operations: f32,
+  pub export: f32,
+  pub total: f32,
+}
+
+/// A writer that counts the bytes written through it.
+struct CountingWriter<W: Write> {
+  inner: W,
+  count: u64,
+}
+
+impl<W: Write> CountingWriter<W> {
+  fn new(inner: W) -> Self {
+    Self { inner, count: 0 }
+  }
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    let written = self.inner.write(buf)?;
+    self.count += written as u64;
+    Ok(written)
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    self.inner.flush()
+  }
+}
+
+/// What the scene loses in the target format, or what looks wrong with it.
+pub fn conversion_warnings(
+  scene: &Scene,
+  output_file_type: &str,
+  export_options: &ExportOptions,
+) -> Vec<String> {
+  let mut warnings = Vec::new();
+  if scene.splats.is_empty() {
+    warnings.push("The scene has no splats".to_string());
+  }
+  let non_finite = scene
+    .splats
+    .par_iter()
+    .filter(|splat| {
+      !splat
+        .position
+        .iter()
+        .chain(&splat.scale)
+        .chain(&splat.rotation)
+        .chain(&splat.spherical_harmonics_dc)
+        .chain(&splat.spherical_harmonics_rest)
+        .chain([&splat.opacity])
+        .all(|value| value.is_finite())
+    })
+    .count();
+  if non_finite > 0 {
+    warnings.push(format!("{} splats have NaN or infinite values", non_finite));
+  }
+  let keeps_sh = match output_file_type {
+    "splat" | "las" | "obj" => false,
+    "ply" => !export_options.point_cloud && !export_options.ellipsoids,
+    _ => true,
+  };
+  let sh_degree = scene.sh_degree();
+  if !keeps_sh && sh_degree > 0 {
+    warnings.push(format!(
+      "The view-dependent colour (SH degree {}) is dropped, --sh-degree 0 --bake-sh keeps its average",
+      sh_degree
+    ));
+  }
+  if !scene.cameras.is_empty() && !matches!(output_file_type, "gltf" | "glb" | "usda" | "usdz") {
+    warnings.push(format!(
+      "{} cameras are dropped, .{} can't store them",
+      scene.cameras.len(),
+      output_file_type
+    ));
+  }
+  warnings
+}
@@ -0,0 +1,141 @@
+//! `gs-flux info`: the format, splat count, bounds and attribute statistics of a scene, and its
+//! size in every output format.
+
+use std::{
+  fs::{self, File},
+  io::Read,
+};
+
+use converter_core::{
+  formats::{
+    detect::{DETECTION_BYTES, detect_format},
+    point_cloud::PointCloudImportOptions,
+  },
+  stats::{PERCENTILES, SceneStats, SizeEstimate, estimate_sizes, scene_stats},
+};
+use serde::Serialize;
+
+use crate::convert::import_scene;
+use crate::{InfoParams, input_format, report, run_with_spinner};
+
+/// Everything `gs-flux info` reports about a file.
+#[derive(Serialize)]
+struct InfoReport {
+  file: String,
+  file_size: u64,
+  format: String,
+  version: Option<String>,
+  #[serde(flatten)]
+  stats: SceneStats,
+  estimated_sizes: Vec<SizeEstimate>,
+}
+
+pub fn run_info(params: InfoParams) {
+  let report = run_with_spinner("Inspecting...", "✖ Inspection failed", move || {
+    let input_ext = input_format(&params.source_file);
+    let mut header = Vec::with_capacity(DETECTION_BYTES);
+    File::open(&params.source_file)?
+      .take(DETECTION_BYTES as u64)
+      .read_to_end(&mut header)?;
+    // Files without a header to recognise are described by their extension
+    let (format, version) = match detect_format(&header) {
+      Some(detected) => (detected.name.to_string(), detected.version),
+      None => (input_ext.to_uppercase(), None),
+    };
+
+    let scene = import_scene(
+      &params.source_file,
+      &input_ext,
+      &PointCloudImportOptions::default(),
+    )?;
+    Ok(InfoReport {
+      file_size: fs::metadata(&params.source_file)?.len(),
+      file: params.source_file,
+      format,
+      version,
+      stats: scene_stats(&scene),
+      estimated_sizes: estimate_sizes(&scene),
+    })
+  });
+
+  if report::json() {
+    report::print_success(&report);
+    return;
+  }
+
+  let stats = &report.stats;
+  let bounds = |bounds: Option<([f32; 3], [f32; 3])>| match bounds {
+    Some((min, max)) => format!(
+      "{:.3}, {:.3}, {:.3} to {:.3}, {:.3}, {:.3}",
+      min[0], min[1], min[2], max[0], max[1], max[2]
+    ),
+    None => "-".to_string(),
+  };
+  println!();
+  println!(
+    "{:<24}{} ({})",
+    "File",
+    report.file,
+    format_bytes(report.file_size)
+  );
+  match &report.version {
+    Some(version) => println!("{:<24}{} ({})", "Format", report.format, version),
+    None => println!("{:<24}{}", "Format", report.format),
+  }
+  println!("{:<24}{}", "Splats", stats.splat_count);
+  println!("{:<24}{}", "SH degree", stats.sh_degree);
+  println!("{:<24}{}", "Cameras", stats.camera_count);
+  println!("{:<24}{}", "Bounds", bounds(stats.bounds));
+  println!(
+    "{:<24}{}",
+    "Bounds (1st-99th pct)",
+    bounds(stats.robust_bounds)
+  );
+  println!("{:<24}{}", "Splats with NaN/inf", stats.non_finite_splats);
+
+  print!("\n{:<18}{:>11}", "Attribute", "Min");
+  for percentile in PERCENTILES {
+    print!("{:>11}", format!("P{}", percentile));
+  }
+  println!("{:>11}{:>11}{:>9}", "Max", "Mean", "NaN/inf");
+  for attribute in &stats.attributes {
+    print!("{:<18}{:>11.4}", attribute.name, attribute.min);
+    for value in attribute.percentiles {
+      print!("{:>11.4}", value);
+    }
+    println!(
+      "{:>11.4}{:>11.4}{:>9}",
+      attribute.max, attribute.mean, attribute.non_finite
+    );
+  }
+
+  println!(
+    "\n{:<18}{:>11}{:>13}",
+    "Estimated size", "Extension", "Size"
+  );
+  for estimate in &report.estimated_sizes {
+    let size = estimate.bytes.map_or("-".to_string(), format_bytes);
+    println!(
+      "{:<18}{:>11}{:>13}",
+      estimate.format,
+      format!(".{}", estimate.extension),
+      size
+    );
+  }
+}
+
+/// A byte count in B, KB, MB or GB (powers of 1024).
+fn format_bytes(bytes: u64) -> String {
+  let units = ["B", "KB", "MB", "GB"];
+  let mut value = bytes as f64;
+  let mut unit = 0;
+  while value >= 1024.0 && unit < units.len() - 1 {
+    value /= 1024.0;
+    unit += 1;
+  }
+  if unit == 0 {
+    format!("{} B", bytes)
+  } else {
+    format!("{:.1} {}", value, units[unit])
+  }
+}
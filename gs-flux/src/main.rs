@@ -1,107 +1,549 @@
-use clap::Parser;
+mod batch;
+mod compare;
+mod convert;
+mod info;
+mod mesh;
+mod output;
+mod render;
+mod report;
+mod serve;
+mod validate;
+mod watch;
+
+use clap::{Args, Parser, Subcommand};
+use convert::Conversion;
 use converter_core::{
-  ConversionError, Exporter, Importer, Scene,
-  formats::{
-    csv::{CsvExporter, CsvImporter},
-    ply::{PlyASCIIExporter, PlyBinaryExporter, PlyImporter},
-    splat::{SplatExporter, SplatImporter},
-    spz::{SpzImporter, SpzV2Exporter},
-  },
+  Camera, ConversionError,
+  formats::{cameras_json::CamerasJsonImporter, colmap::ColmapCamerasImporter},
+  operations::expression::Expression,
+  operations::pipeline::Operation,
 };
 use indicatif::{ProgressBar, ProgressStyle};
 use std::{
-  fs::File,
-  io::{BufReader, BufWriter},
-  path::Path,
-  process, thread,
+  env,
+  fs::{self, File},
+  io::BufReader,
+  path::{Path, PathBuf},
+  thread,
   time::{Duration, Instant},
 };
 
-const SUPPORTED_FORMATS: [&str; 4] = ["ply", "spz", "splat", "csv"];
+/// Path that stands for stdin as a source and stdout as a target.
+const STDIO: &str = "-";
+
+/// COLMAP sparse models are recognised by file name (`points3D.bin` / `points3D.txt`) rather than
+/// by extension, and can only be read.
+const COLMAP_POINTS_FILE: &str = "points3d";
+
+const SUPPORTED_FORMATS: [&str; 12] = [
+  "ply", "spz", "splat", "csv", "ksplat", "sog", "gltf", "glb", "usda", "usdz", "las", "obj",
+];
+
+/// The formats that can be read, which batch conversions pick up from folders and patterns.
+const IMPORT_FORMATS: [&str; 11] = [
+  "ply", "spz", "splat", "csv", "ksplat", "sog", "gltf", "glb", "usda", "usdz", "las",
+];
 
 #[derive(Parser, Debug)]
-#[command(name = "GS-Flux", version, about = "Convert gaussian splatting files")]
+#[command(
+  name = "GS-Flux",
+  version,
+  about = "Convert gaussian splatting files",
+  subcommand_negates_reqs = true,
+  args_conflicts_with_subcommands = true
+)]
 struct Params {
-  /// Source file
-  #[arg(value_parser = validate_input_path)]
-  source_file: String,
+  #[command(subcommand)]
+  command: Option<Command>,
 
-  /// The target filename
-  #[arg(value_parser = validate_output_path)]
-  output_file: String,
+  /// Source file, or - for stdin
+  #[arg(value_parser = validate_source_path, required = true)]
+  source_file: Option<String>,
+
+  /// The target filename, or - for stdout
+  #[arg(value_parser = validate_target_path, required = true)]
+  output_file: Option<String>,
+
+  /// Format of the target instead of its extension (Required when writing to stdout)
+  #[arg(long, value_parser = SUPPORTED_FORMATS)]
+  to: Option<String>,
+
+  #[command(flatten)]
+  options: ConvertOptions,
+
+  /// Print results and errors as JSON (on stderr when the scene is written to stdout)
+  #[arg(long, global = true)]
+  json: bool,
+}
 
-  /// Output encoding (Valid only for .ply)
-  #[arg(long, value_parser = ["ascii", "binary"])]
+/// Settings shared by `gs-flux <source> <output>` and `gs-flux convert`.
+#[derive(Args, Debug)]
+struct ConvertOptions {
+  /// Format of the source instead of its extension. Input from stdin is otherwise recognised by
+  /// its first bytes, except .splat which always needs it
+  #[arg(long, value_parser = IMPORT_FORMATS)]
+  from: Option<String>,
+
+  /// Output encoding, "compressed" writes the PlayCanvas / SuperSplat layout (Valid only for .ply)
+  #[arg(long, value_parser = ["ascii", "binary", "compressed"])]
   encoding: Option<String>,
+
+  /// Opacity (0..1) of the Gaussians created from a point cloud (Valid only for point-cloud
+  /// .ply, .las and COLMAP points3D inputs)
+  #[arg(long, value_parser = parse_unit_interval)]
+  initial_opacity: Option<f32>,
+
+  /// Write a plain coloured point cloud of the splat centres (Valid only for .ply)
+  #[arg(long, conflicts_with = "encoding")]
+  point_cloud: bool,
+
+  /// Write every splat as a low-poly ellipsoid mesh with vertex colours, as .obj always does
+  /// (Valid only for .ply)
+  #[arg(long, conflicts_with_all = ["encoding", "point_cloud"])]
+  ellipsoids: bool,
+
+  /// Ellipsoid radius in standard deviations of each Gaussian (Valid only for ellipsoid meshes)
+  #[arg(long, value_parser = parse_positive)]
+  sigma: Option<f32>,
+
+  /// Maximum number of triangles in the ellipsoid mesh (Valid only for ellipsoid meshes)
+  #[arg(long)]
+  max_triangles: Option<usize>,
+
+  /// Write the most important splats first, like antimatter15's convert.py (Valid only for .splat)
+  #[arg(long)]
+  importance_order: bool,
+
+  /// Drop splats whose opacity (0..1) is below this value (Valid only for .splat, point clouds
+  /// and ellipsoid meshes)
+  #[arg(long)]
+  min_opacity: Option<f32>,
+
+  /// Compression level, 0 (none) to 2 (smallest) (Valid only for .ksplat)
+  #[arg(long, value_parser = clap::value_parser!(u8).range(0..=2))]
+  compression_level: Option<u8>,
+
+  /// Number of entries in the spherical harmonics palette (Valid only for .sog)
+  #[arg(long, value_parser = clap::value_parser!(u32).range(1..=65536))]
+  sh_palette_size: Option<u32>,
+
+  /// Compress the buffers with KHR_meshopt_compression (Valid only for .gltf and .glb)
+  #[arg(long)]
+  meshopt: bool,
+
+  /// Reduce spherical harmonics to this degree before exporting
+  #[arg(long, value_parser = clap::value_parser!(u8).range(0..=3))]
+  sh_degree: Option<u8>,
+
+  /// Bake the average view-dependent colour into the base colour, as seen from the scene's
+  /// cameras when it has any (Valid only with --sh-degree 0)
+  #[arg(long, requires = "sh_degree")]
+  bake_sh: bool,
+
+  /// Attach the cameras of a cameras.json file or COLMAP model folder to the scene, for formats
+  /// that store cameras (.gltf, .glb, .usda and .usdz)
+  #[arg(long, value_parser = validate_cameras_path)]
+  cameras: Option<String>,
+
+  /// Keep only the splats an expression over their attributes holds for, before any other step
+  /// (e.g. "opacity > 0.1 and max_scale < 0.5 and z > 0")
+  #[arg(long = "where", value_name = "EXPRESSION", value_parser = parse_expression)]
+  filter: Option<Expression>,
+
+  /// Processing step applied to the scene before exporting, in the order given: crop,
+  /// min-opacity, max-scale, where, sh-degree, bake-sh, transform or sort, with parameters after
+  /// colons (e.g. crop:box=-1,-1,-1,1,1,1 or min-opacity:0.05)
+  #[arg(long = "op", value_name = "OPERATION", value_parser = parse_operation)]
+  operations: Vec<Operation>,
+
+  /// Run the steps of a pipeline file (.json or .toml) before the --op steps
+  #[arg(long, value_parser = validate_pipeline_path)]
+  pipeline: Option<String>,
+
+  /// Never replace existing outputs: a single conversion fails, batch conversions skip them
+  #[arg(long)]
+  no_clobber: bool,
   //
   // Output version (no need to implement yet, just something for the future.)
   // #[arg(long)]
   // version: Option<u32>,
 }
 
+#[derive(Subcommand, Debug)]
+enum Command {
+  /// Convert a file, or every file of folders and glob patterns with --to
+  Convert(Box<ConvertParams>),
+  /// Convert the files of a folder whenever they appear or change, e.g. the snapshots written
+  /// while training
+  Watch(Box<WatchParams>),
+  /// Serve conversions over HTTP to local tools
+  Serve(ServeParams),
+  /// Check that files can be read and hold no NaN or infinite values
+  Validate(ValidateParams),
+  /// Extract a surface mesh (.obj or .ply) from the scene's opacity field
+  Mesh(MeshParams),
+  /// Render a preview image (.png) of the scene on the CPU
+  Render(RenderParams),
+  /// Measure how much a conversion changed a scene, from renders (PSNR/SSIM) and splat attributes
+  Compare(CompareParams),
+  /// Show the splat count, SH degree, bounds and attribute statistics of a scene, and its size in
+  /// every output format
+  Info(InfoParams),
+}
+
+#[derive(Args, Debug)]
+struct ConvertParams {
+  /// The source file and the target filename (- for stdin and stdout), or with --to any number
+  /// of files, folders and glob patterns (quote them, e.g. 'scans/**/*.ply')
+  #[arg(required = true)]
+  inputs: Vec<String>,
+
+  /// Convert every input to this format. Outputs are written next to their sources, or to
+  /// --out-dir. With - as the target it is the format written to stdout
+  #[arg(long, value_parser = SUPPORTED_FORMATS)]
+  to: Option<String>,
+
+  /// Folder for the outputs of --to, mirroring the folders below each input folder or pattern
+  #[arg(long, requires = "to")]
+  out_dir: Option<String>,
+
+  /// Convert files whose output is already newer than the source (Valid only with --to)
+  #[arg(long, requires = "to", conflicts_with = "no_clobber")]
+  force: bool,
+
+  /// Number of files converted at the same time (Valid only with --to, defaults to the number
+  /// of CPUs)
+  #[arg(long, requires = "to", value_parser = clap::value_parser!(u32).range(1..))]
+  jobs: Option<u32>,
+
+  #[command(flatten)]
+  options: ConvertOptions,
+}
+
+#[derive(Args, Debug)]
+struct WatchParams {
+  /// Folder to watch, including its subfolders
+  #[arg(value_parser = validate_watch_folder)]
+  folder: String,
+
+  /// Format to convert every file to
+  #[arg(long, required = true, value_parser = SUPPORTED_FORMATS)]
+  to: String,
+
+  /// Folder for the outputs, mirroring the watched folder. Without it outputs are written next to
+  /// their sources
+  #[arg(long)]
+  out_dir: Option<String>,
+
+  /// Seconds between scans of the folder. A file is converted once it stayed the same for a whole
+  /// interval
+  #[arg(long, default_value_t = 2.0, value_parser = parse_positive)]
+  interval: f32,
+
+  /// Only convert the latest iteration of training snapshots (the highest N of iteration_N
+  /// folders), and delete the outputs of earlier iterations
+  #[arg(long)]
+  latest: bool,
+
+  /// Convert files whose output is already newer than the source when the watch starts
+  #[arg(long, conflicts_with = "no_clobber")]
+  force: bool,
+
+  #[command(flatten)]
+  options: ConvertOptions,
+}
+
+#[derive(Args, Debug)]
+struct ServeParams {
+  /// Port to listen on, 0 picks a free one
+  #[arg(long, default_value_t = 8080)]
+  port: u16,
+
+  /// Address to listen on. The default only accepts connections from this machine
+  #[arg(long, default_value = "127.0.0.1")]
+  host: String,
+
+  /// Largest accepted upload in megabytes, at most 1048576 (1 TB)
+  #[arg(long, default_value_t = 1024, value_parser = clap::value_parser!(u64).range(1..=1_048_576))]
+  max_size: u64,
+
+  /// Number of conversions run at the same time, further requests are turned away with 503
+  /// (defaults to the number of CPUs)
+  #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+  jobs: Option<u32>,
+}
+
+#[derive(Args, Debug)]
+struct ValidateParams {
+  /// Files, folders or glob patterns to check
+  #[arg(required = true)]
+  inputs: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+struct MeshParams {
+  /// Source file
+  #[arg(value_parser = validate_input_path)]
+  source_file: String,
+
+  /// The target mesh (.obj or .ply)
+  #[arg(value_parser = validate_mesh_output_path)]
+  output_file: String,
+
+  /// Number of grid cells along the longest side of the scene
+  #[arg(long, default_value_t = 256, value_parser = clap::value_parser!(u32).range(8..=4096))]
+  resolution: u32,
+
+  /// Opacity (0..1) at which the surface is placed
+  #[arg(long, default_value_t = 0.5, value_parser = parse_unit_interval)]
+  iso_level: f32,
+}
+
+#[derive(Args, Debug)]
+struct RenderParams {
+  /// Source file
+  #[arg(value_parser = validate_input_path)]
+  source_file: String,
+
+  /// The target image (.png)
+  #[arg(value_parser = validate_image_output_path)]
+  output_file: String,
+
+  /// Image width in pixels
+  #[arg(long, default_value_t = 1024, value_parser = clap::value_parser!(u32).range(1..=16384))]
+  width: u32,
+
+  /// Image height in pixels
+  #[arg(long, default_value_t = 768, value_parser = clap::value_parser!(u32).range(1..=16384))]
+  height: u32,
+
+  /// Vertical field of view in degrees
+  #[arg(long, default_value_t = 50.0, value_parser = parse_field_of_view)]
+  fov: f32,
+
+  /// Camera rotation around the up axis in degrees
+  #[arg(long, default_value_t = 0.0, allow_hyphen_values = true)]
+  azimuth: f32,
+
+  /// Camera angle above the horizon in degrees
+  #[arg(long, default_value_t = 20.0, allow_hyphen_values = true, value_parser = parse_elevation)]
+  elevation: f32,
+
+  /// World up direction as x,y,z (3DGS scenes are usually -Y up)
+  #[arg(long, default_value = "0,-1,0", allow_hyphen_values = true, value_parser = parse_direction)]
+  up: [f32; 3],
+
+  /// Background colour as RRGGBB or RRGGBBAA hex (transparent by default)
+  #[arg(long, value_parser = parse_hex_color)]
+  background: Option<[f32; 4]>,
+
+  /// Render from this camera of the scene (or of --cameras) instead of framing it, scaled to
+  /// --width. --height, --fov and the orbit options are then ignored
+  #[arg(long)]
+  camera: Option<usize>,
+
+  /// Use the cameras of a cameras.json file or COLMAP model folder
+  #[arg(long, value_parser = validate_cameras_path)]
+  cameras: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct CompareParams {
+  /// The original scene
+  #[arg(value_parser = validate_input_path)]
+  reference_file: String,
+
+  /// The converted scene
+  #[arg(value_parser = validate_input_path)]
+  test_file: String,
+
+  /// Number of views to render. Cameras are subsampled evenly to this many
+  #[arg(long, default_value_t = 8, value_parser = clap::value_parser!(u32).range(1..=1000))]
+  views: u32,
+
+  /// Render from the cameras of a cameras.json file or COLMAP model folder (cameras and images
+  /// .bin or .txt). Without it the reference scene's own cameras are used, or views orbiting it
+  #[arg(long, value_parser = validate_cameras_path)]
+  cameras: Option<String>,
+
+  /// Width of the renders in pixels. Cameras are scaled down to it, keeping their aspect
+  #[arg(long, default_value_t = 512, value_parser = clap::value_parser!(u32).range(16..=8192))]
+  width: u32,
+
+  /// World up direction as x,y,z for the orbit views
+  #[arg(long, default_value = "0,-1,0", allow_hyphen_values = true, value_parser = parse_direction)]
+  up: [f32; 3],
+}
+
+#[derive(Args, Debug)]
+struct InfoParams {
+  /// Source file
+  #[arg(value_parser = validate_input_path)]
+  source_file: String,
+}
+
 fn main() {
-  let params = Params::parse();
+  let params = match Params::try_parse() {
+    Ok(params) => params,
+    // Help and version go to stdout and aren't failures
+    Err(e) if e.use_stderr() && env::args().any(|arg| arg == "--json") => {
+      report::use_json();
+      report::fail_usage(usage_message(&e))
+    }
+    Err(e) => e.exit(),
+  };
+  if params.json {
+    report::use_json();
+  }
 
-  let input_ext = Path::new(&params.source_file)
-    .extension()
-    .and_then(|ext| ext.to_str())
-    .unwrap_or("")
-    .to_lowercase();
+  match params.command {
+    Some(Command::Convert(convert_params)) => return convert::run_convert(*convert_params),
+    Some(Command::Watch(watch_params)) => run_watch(*watch_params),
+    Some(Command::Serve(serve_params)) => serve::serve(&serve_params),
+    Some(Command::Validate(validate_params)) => return validate::run_validate(validate_params),
+    Some(Command::Mesh(mesh_params)) => return mesh::run_mesh(mesh_params),
+    Some(Command::Render(render_params)) => return render::run_render(render_params),
+    Some(Command::Compare(compare_params)) => return compare::run_compare(compare_params),
+    Some(Command::Info(info_params)) => return info::run_info(info_params),
+    None => {}
+  }
 
-  let output_ext = Path::new(&params.output_file)
-    .extension()
-    .and_then(|ext| ext.to_str())
-    .unwrap_or("")
-    .to_lowercase();
+  // Both are required when no subcommand is given
+  convert::convert_file(
+    params.source_file.unwrap(),
+    params.output_file.unwrap(),
+    params.to,
+    params.options,
+  );
+}
+
+/// The message of a clap error without the usage and help that follow it, e.g. "the following
+/// required arguments were not provided: --to <TO>".
+fn usage_message(error: &clap::Error) -> String {
+  let text = error.to_string();
+  let message = text
+    .lines()
+    .take_while(|line| !line.trim().is_empty())
+    .map(str::trim)
+    .collect::<Vec<_>>()
+    .join(" ");
+  message
+    .strip_prefix("error: ")
+    .unwrap_or(&message)
+    .to_string()
+}
 
+fn run_watch(params: WatchParams) -> ! {
+  let conversion = Conversion::new(params.options);
+  watch::watch(
+    &watch::WatchOptions {
+      folder: params.folder,
+      out_dir: params.out_dir.map(PathBuf::from),
+      to: params.to,
+      interval: Duration::from_secs_f32(params.interval),
+      latest: params.latest,
+      force: params.force,
+    },
+    &conversion,
+  )
+}
+
+/// Size of the file at `path`, if it can be read.
+fn file_size(path: &str) -> Option<u64> {
+  fs::metadata(path).map(|metadata| metadata.len()).ok()
+}
+
+/// The cameras and images files of a COLMAP model folder, preferring the binary ones.
+fn colmap_model_files(folder: &str) -> Result<(PathBuf, PathBuf), ConversionError> {
+  let find = |name: &str| {
+    ["bin", "txt"]
+      .iter()
+      .map(|ext| Path::new(folder).join(format!("{}.{}", name, ext)))
+      .find(|path| path.is_file())
+      .ok_or_else(|| ConversionError::ParseError {
+        format: "COLMAP".to_string(),
+        message: format!("No {}.bin or {}.txt in {}", name, name, folder),
+      })
+  };
+  Ok((find("cameras")?, find("images")?))
+}
+
+/// Reads the cameras of a cameras.json file or a COLMAP model folder.
+fn load_cameras(path: &str) -> Result<Vec<Camera>, ConversionError> {
+  if Path::new(path).is_dir() {
+    let (cameras_path, images_path) = colmap_model_files(path)?;
+    ColmapCamerasImporter::import(
+      &mut BufReader::new(File::open(cameras_path)?),
+      &mut BufReader::new(File::open(images_path)?),
+    )
+  } else {
+    CamerasJsonImporter::import(&mut BufReader::new(File::open(path)?))
+  }
+}
+
+/// Runs `task` on a worker thread behind a spinner, then reports the time taken or the error.
+fn run_with_spinner<T: Send + 'static>(
+  message: &'static str,
+  failure_message: &'static str,
+  task: impl FnOnce() -> Result<T, ConversionError> + Send + 'static,
+) -> T {
   // Start timer
   let start = Instant::now();
 
-  let spinner = ProgressBar::new_spinner();
+  let spinner = if report::json() {
+    ProgressBar::hidden()
+  } else {
+    ProgressBar::new_spinner()
+  };
   spinner.set_style(
     ProgressStyle::default_spinner()
       .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"])
       .template("{spinner:.blue} {msg}")
       .unwrap(),
   );
-  spinner.set_message("Converting...");
+  spinner.set_message(message);
   spinner.enable_steady_tick(Duration::from_millis(100));
 
-  let conversion_thread = thread::spawn({
-    let source_file = params.source_file.clone();
-    let output_file = params.output_file.clone();
-    let encoding = params.encoding.clone();
-    move || {
-      convert(
-        &source_file,
-        &input_ext,
-        &output_file,
-        &output_ext,
-        encoding.as_deref(),
-      )
-    }
-  });
-
-  let result = conversion_thread.join().unwrap();
+  let result = thread::spawn(task).join().unwrap();
 
   // Calculate elapsed time
   let elapsed = start.elapsed();
   let elapsed_secs = elapsed.as_secs_f32();
 
   match result {
-    Ok(_) => {
+    Ok(value) => {
       let finish_message = format!("✔ Done in {:.2}s", elapsed_secs);
       spinner.set_style(ProgressStyle::default_spinner().template("{msg}").unwrap());
       spinner.finish_with_message(finish_message);
+      value
     }
     Err(e) => {
-      spinner.abandon_with_message("✖ Conversion failed");
-      eprintln!("\nError: {:?}", e);
-      process::exit(1);
+      spinner.abandon_with_message(failure_message);
+      if !report::json() {
+        eprintln!();
+      }
+      report::fail_conversion(&e)
     }
   }
 }
 
+fn extension_of(path: &str) -> String {
+  Path::new(path)
+    .extension()
+    .and_then(|ext| ext.to_str())
+    .unwrap_or("")
+    .to_lowercase()
+}
+
+/// The importer to use for `path`: its extension, or "colmap" for COLMAP sparse models.
+fn input_format(path: &str) -> String {
+  if is_colmap_points_file(path) {
+    "colmap".to_string()
+  } else {
+    extension_of(path)
+  }
+}
+
 fn validate_extension(s: &str) -> Result<(), String> {
   let extension = s
     .rsplit('.')
@@ -123,8 +565,123 @@ fn validate_extension(s: &str) -> Result<(), String> {
   Ok(())
 }
 
+fn is_importable(s: &str) -> bool {
+  is_colmap_points_file(s) || IMPORT_FORMATS.contains(&extension_of(s).as_str())
+}
+
+fn is_colmap_points_file(s: &str) -> bool {
+  Path::new(s)
+    .file_stem()
+    .is_some_and(|stem| stem.to_string_lossy().to_lowercase() == COLMAP_POINTS_FILE)
+    && matches!(
+      Path::new(s)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .as_deref(),
+      Some("bin" | "txt")
+    )
+}
+
+fn parse_unit_interval(s: &str) -> Result<f32, String> {
+  let value: f32 = s.parse().map_err(|e| format!("{}", e))?;
+  if !(value > 0.0 && value < 1.0) {
+    return Err("Value must be between 0 and 1 (exclusive)".to_string());
+  }
+  Ok(value)
+}
+
+fn parse_positive(s: &str) -> Result<f32, String> {
+  let value: f32 = s.parse().map_err(|e| format!("{}", e))?;
+  if !(value > 0.0 && value.is_finite()) {
+    return Err("Value must be greater than 0".to_string());
+  }
+  Ok(value)
+}
+
+fn parse_field_of_view(s: &str) -> Result<f32, String> {
+  let value: f32 = s.parse().map_err(|e| format!("{}", e))?;
+  if !(value > 0.0 && value < 180.0) {
+    return Err("Field of view must be between 0 and 180 degrees (exclusive)".to_string());
+  }
+  Ok(value)
+}
+
+fn parse_elevation(s: &str) -> Result<f32, String> {
+  let value: f32 = s.parse().map_err(|e| format!("{}", e))?;
+  if !(value > -90.0 && value < 90.0) {
+    return Err("Elevation must be between -90 and 90 degrees (exclusive)".to_string());
+  }
+  Ok(value)
+}
+
+fn parse_direction(s: &str) -> Result<[f32; 3], String> {
+  let values = s
+    .split(',')
+    .map(|v| v.trim().parse::<f32>().map_err(|e| format!("{}", e)))
+    .collect::<Result<Vec<_>, _>>()?;
+  match values[..] {
+    [x, y, z] if x * x + y * y + z * z > 0.0 => Ok([x, y, z]),
+    _ => Err("Direction must be three comma-separated numbers, not all zero".to_string()),
+  }
+}
+
+fn parse_hex_color(s: &str) -> Result<[f32; 4], String> {
+  let hex = s.trim_start_matches('#');
+  if !matches!(hex.len(), 6 | 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+    return Err("Colour must be RRGGBB or RRGGBBAA hex".to_string());
+  }
+  let mut color = [1.0f32; 4];
+  for (i, channel) in color.iter_mut().take(hex.len() / 2).enumerate() {
+    *channel = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).unwrap() as f32 / 255.0;
+  }
+  Ok(color)
+}
+
+fn parse_operation(s: &str) -> Result<Operation, String> {
+  s.parse().map_err(|e: ConversionError| e.to_string())
+}
+
+fn parse_expression(s: &str) -> Result<Expression, String> {
+  s.parse().map_err(|e: ConversionError| e.to_string())
+}
+
+fn validate_pipeline_path(s: &str) -> Result<String, String> {
+  if !matches!(extension_of(s).as_str(), "json" | "toml") || !Path::new(s).is_file() {
+    return Err("Pipeline must be a .json or .toml file".to_string());
+  }
+  Ok(s.to_string())
+}
+
+fn validate_cameras_path(s: &str) -> Result<String, String> {
+  let path = Path::new(s);
+  if path.is_dir() {
+    colmap_model_files(s).map_err(|e| e.to_string())?;
+  } else if extension_of(s) != "json" || !path.is_file() {
+    return Err("Cameras must be a cameras.json file or a COLMAP model folder".to_string());
+  }
+  Ok(s.to_string())
+}
+
+/// A conversion source, which can also be stdin.
+fn validate_source_path(s: &str) -> Result<String, String> {
+  if s == STDIO {
+    return Ok(s.to_string());
+  }
+  validate_input_path(s)
+}
+
+/// A conversion target, which can also be stdout.
+fn validate_target_path(s: &str) -> Result<String, String> {
+  if s == STDIO {
+    return Ok(s.to_string());
+  }
+  validate_output_path(s)
+}
+
 fn validate_input_path(s: &str) -> Result<String, String> {
-  validate_extension(s)?;
+  if !is_colmap_points_file(s) {
+    validate_extension(s)?;
+  }
 
   let path = Path::new(s);
   if !path.is_file() {
@@ -134,9 +691,33 @@ fn validate_input_path(s: &str) -> Result<String, String> {
   Ok(s.to_string())
 }
 
+fn validate_mesh_output_path(s: &str) -> Result<String, String> {
+  if !matches!(extension_of(s).as_str(), "obj" | "ply") {
+    return Err("Mesh must have one of the following extensions: .obj, .ply".to_string());
+  }
+  validate_output_path(s)
+}
+
+fn validate_image_output_path(s: &str) -> Result<String, String> {
+  if extension_of(s) != "png" {
+    return Err("Image must have the extension .png".to_string());
+  }
+  validate_output_folder(s)
+}
+
 fn validate_output_path(s: &str) -> Result<String, String> {
   validate_extension(s)?;
+  validate_output_folder(s)
+}
+
+fn validate_watch_folder(s: &str) -> Result<String, String> {
+  if !Path::new(s).is_dir() {
+    return Err(format!("'{}' is not a folder", s));
+  }
+  Ok(s.to_string())
+}
 
+fn validate_output_folder(s: &str) -> Result<String, String> {
   let path = Path::new(s);
   let parent = path.parent().unwrap_or_else(|| Path::new(""));
 
@@ -149,45 +730,3 @@ fn validate_output_path(s: &str) -> Result<String, String> {
 
   Ok(s.to_string())
 }
-
-fn convert(
-  input_file_path: &str,
-  input_file_type: &str,
-  output_file_path: &str,
-  output_file_type: &str,
-  encoding: Option<&str>,
-  // version: Option<u32>,
-) -> Result<(), ConversionError> {
-  let file = File::open(input_file_path)?;
-  let mut reader = BufReader::new(file);
-
-  let scene: Scene = match input_file_type {
-    "ply" => PlyImporter::import(&mut reader)?,
-    "spz" => SpzImporter::import(&mut reader)?,
-    "csv" => CsvImporter::import(&mut reader)?,
-    "splat" => SplatImporter::import(&mut reader)?,
-    _ => return Err(ConversionError::UnsupportedFormat),
-  };
-
-  let file: File = File::create(output_file_path)?;
-  let mut writer = BufWriter::new(file);
-
-  match output_file_type {
-    "ply" => match encoding.unwrap_or("binary".into()) {
-      "ascii" => PlyASCIIExporter::export(&scene, &mut writer)?,
-      "binary" => PlyBinaryExporter::export(&scene, &mut writer)?,
-      other => {
-        return Err(ConversionError::ParseError {
-          format: "PLY".to_string(),
-          message: format!("Unsupported PLY encoding: {}", other),
-        });
-      }
-    },
-    "spz" => SpzV2Exporter::export(&scene, &mut writer)?,
-    "csv" => CsvExporter::export(&scene, &mut writer)?,
-    "splat" => SplatExporter::export(&scene, &mut writer)?,
-    _ => return Err(ConversionError::UnsupportedFormat),
-  };
-
-  Ok(())
-}
@@ -0,0 +1,73 @@
+//! `gs-flux mesh`: extracting a surface mesh from the scene's opacity field.
+
+use std::{
+  io::{BufWriter, Write},
+  time::Instant,
+};
+
+use converter_core::{
+  formats::{
+    mesh::{MeshObjExporter, MeshPlyExporter},
+    point_cloud::PointCloudImportOptions,
+  },
+  operations::mesh_extraction::{MeshExtractionOptions, extract_mesh},
+};
+use serde::Serialize;
+
+use crate::convert::import_scene;
+use crate::output::AtomicFile;
+use crate::{MeshParams, extension_of, file_size, input_format, report, run_with_spinner};
+
+pub fn run_mesh(params: MeshParams) {
+  let options = MeshExtractionOptions {
+    resolution: params.resolution as usize,
+    iso_level: params.iso_level,
+  };
+
+  let start = Instant::now();
+  let output_file = params.output_file.clone();
+  let (vertices, triangles) = run_with_spinner(
+    "Extracting mesh...",
+    "✖ Mesh extraction failed",
+    move || {
+      let input_ext = input_format(&params.source_file);
+      let scene = import_scene(
+        &params.source_file,
+        &input_ext,
+        &PointCloudImportOptions::default(),
+      )?;
+      let mesh = extract_mesh(&scene, &options);
+
+      let output = AtomicFile::create(&params.output_file)?;
+      let mut writer = BufWriter::new(output.file());
+      match extension_of(&params.output_file).as_str() {
+        "obj" => MeshObjExporter::export(&mesh, &mut writer)?,
+        _ => MeshPlyExporter::export(&mesh, &mut writer)?,
+      }
+      writer.flush()?;
+      drop(writer);
+      output.commit()?;
+      Ok((mesh.vertices.len(), mesh.triangles.len()))
+    },
+  );
+
+  if report::json() {
+    report::print_success(&MeshReport {
+      output_size: file_size(&output_file),
+      output: output_file,
+      vertices,
+      triangles,
+      seconds: start.elapsed().as_secs_f32(),
+    });
+  }
+}
+
+/// What `mesh --json` reports.
+#[derive(Serialize)]
+struct MeshReport {
+  output: String,
+  output_size: Option<u64>,
+  vertices: usize,
+  triangles: usize,
+  seconds: f32,
+}
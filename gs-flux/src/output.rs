@@ -0,0 +1,103 @@
+//! Writing outputs safely: every file is written under a temporary name next to its target and
+//! only renamed over it once complete, so that a failed or interrupted conversion never leaves a
+//! partial file or truncates an existing one.
+
+use std::{
+  fs::{self, File},
+  io,
+  path::{Path, PathBuf},
+  process,
+  sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Tells apart the temporary files of one process, e.g. the outputs of parallel batch jobs.
+static TEMP_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// A file being written in place of `target`.
+pub struct AtomicFile {
+  file: File,
+  temp: PathBuf,
+  target: PathBuf,
+  committed: bool,
+}
+
+impl AtomicFile {
+  /// Creates the temporary file in the folder of `target`, so that the final rename stays on one
+  /// filesystem.
+  pub fn create(target: impl AsRef<Path>) -> io::Result<Self> {
+    let target = target.as_ref().to_path_buf();
+    let name = target
+      .file_name()
+      .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "The output needs a file name"))?;
+    // Hidden, and unique to the process and file so that concurrent writers never share it
+    let temp = target.with_file_name(format!(
+      ".{}.{}.{}.tmp",
+      name.to_string_lossy(),
+      process::id(),
+      TEMP_FILES.fetch_add(1, Ordering::Relaxed)
+    ));
+    Ok(Self {
+      file: File::options().write(true).create_new(true).open(&temp)?,
+      temp,
+      target,
+      committed: false,
+    })
+  }
+
+  pub fn file(&self) -> &File {
+    &self.file
+  }
+
+  /// Flushes the file to disk and moves it over the target.
+  pub fn commit(mut self) -> io::Result<()> {
+    self.file.sync_all()?;
+    fs::rename(&self.temp, &self.target)?;
+    self.committed = true;
+    Ok(())
+  }
+
+  /// Like `commit`, but fails with `AlreadyExists` instead of replacing a target that exists by
+  /// then, however recently it appeared.
+  pub fn commit_new(mut self) -> io::Result<()> {
+    self.file.sync_all()?;
+    let already_exists = || {
+      io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        format!("'{}' already exists (--no-clobber)", self.target.display()),
+      )
+    };
+    // Linking never replaces the target, unlike renaming
+    match fs::hard_link(&self.temp, &self.target) {
+      Ok(()) => {}
+      Err(e) if e.kind() == io::ErrorKind::AlreadyExists => return Err(already_exists()),
+      // Filesystems without hard links get an exclusively created copy
+      Err(_) => {
+        let mut target = match File::options()
+          .write(true)
+          .create_new(true)
+          .open(&self.target)
+        {
+          Ok(target) => target,
+          Err(e) if e.kind() == io::ErrorKind::AlreadyExists => return Err(already_exists()),
+          Err(e) => return Err(e),
+        };
+        let copied = io::copy(&mut File::open(&self.temp)?, &mut target);
+        if let Err(e) = copied.and_then(|_| target.sync_all()) {
+          let _ = fs::remove_file(&self.target);
+          return Err(e);
+        }
+      }
+    }
+    self.committed = true;
+    let _ = fs::remove_file(&self.temp);
+    Ok(())
+  }
+}
+
+impl Drop for AtomicFile {
+  fn drop(&mut self) {
+    if !self.committed {
+      let _ = fs::remove_file(&self.temp);
+    }
+  }
+}
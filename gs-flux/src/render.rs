@@ -0,0 +1,90 @@
+//! `gs-flux render`: a preview image of the scene, rendered on the CPU.
+
+use std::{
+  io::{BufWriter, Write},
+  time::Instant,
+};
+
+use converter_core::{
+  Camera, ConversionError,
+  camera::FramingOptions,
+  formats::point_cloud::PointCloudImportOptions,
+  render::{RenderOptions, render, write_png},
+};
+use serde::Serialize;
+
+use crate::convert::import_scene;
+use crate::output::AtomicFile;
+use crate::{RenderParams, file_size, input_format, load_cameras, report, run_with_spinner};
+
+pub fn run_render(params: RenderParams) {
+  let framing = FramingOptions {
+    azimuth_degrees: params.azimuth,
+    elevation_degrees: params.elevation,
+    fov_y_degrees: params.fov,
+    up: params.up,
+  };
+  let options = RenderOptions {
+    background: params.background.unwrap_or_default(),
+  };
+
+  let start = Instant::now();
+  let output_file = params.output_file.clone();
+  let (width, height) = run_with_spinner("Rendering...", "✖ Rendering failed", move || {
+    let input_ext = input_format(&params.source_file);
+    let scene = import_scene(
+      &params.source_file,
+      &input_ext,
+      &PointCloudImportOptions::default(),
+    )?;
+    let cameras = match &params.cameras {
+      Some(path) => load_cameras(path)?,
+      None => scene.cameras.clone(),
+    };
+    let camera = match params.camera {
+      Some(index) => {
+        let camera = cameras
+          .get(index)
+          .ok_or_else(|| ConversionError::ParseError {
+            format: "Cameras".to_string(),
+            message: format!(
+              "Camera {} does not exist, the scene has {} cameras",
+              index,
+              cameras.len()
+            ),
+          })?;
+        camera.scaled(params.width as f32 / camera.width as f32)
+      }
+      None => Camera::framing(&scene, params.width, params.height, &framing),
+    };
+    let image = render(&scene, &camera, &options);
+
+    let output = AtomicFile::create(&params.output_file)?;
+    let mut writer = BufWriter::new(output.file());
+    write_png(&image, &mut writer)?;
+    writer.flush()?;
+    drop(writer);
+    output.commit()?;
+    Ok((camera.width, camera.height))
+  });
+
+  if report::json() {
+    report::print_success(&RenderReport {
+      output_size: file_size(&output_file),
+      output: output_file,
+      width,
+      height,
+      seconds: start.elapsed().as_secs_f32(),
+    });
+  }
+}
+
+/// What `render --json` reports.
+#[derive(Serialize)]
+struct RenderReport {
+  output: String,
+  output_size: Option<u64>,
+  width: u32,
+  height: u32,
+  seconds: f32,
+}
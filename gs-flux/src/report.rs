@@ -0,0 +1,193 @@
+//! How results and failures are reported: as text, or as JSON with `--json`, and the exit code
+//! of every kind of failure.
+
+use std::{
+  fmt::Display,
+  io::{self, Write},
+  process,
+  sync::atomic::{AtomicBool, Ordering},
+};
+
+use converter_core::ConversionError;
+use serde::Serialize;
+
+/// Exit code of failures that fit no other category.
+pub const EXIT_FAILURE: i32 = 1;
+/// Exit code of invalid arguments, options or pipeline files.
+pub const EXIT_USAGE: i32 = 2;
+/// Exit code of files that can't be read or written.
+pub const EXIT_IO: i32 = 3;
+/// Exit code of inputs that can't be parsed or hold invalid values.
+pub const EXIT_PARSE: i32 = 4;
+/// Exit code of scenes the target format can't hold.
+pub const EXIT_WRITE: i32 = 5;
+/// Exit code of formats that can't be read or written.
+pub const EXIT_UNSUPPORTED: i32 = 6;
+
+static JSON: AtomicBool = AtomicBool::new(false);
+static STDOUT_TAKEN: AtomicBool = AtomicBool::new(false);
+
+/// Reports in JSON from now on.
+pub fn use_json() {
+  JSON.store(true, Ordering::Relaxed);
+}
+
+pub fn json() -> bool {
+  JSON.load(Ordering::Relaxed)
+}
+
+/// Marks stdout as carrying the converted scene, so that reports go to stderr.
+pub fn take_stdout() {
+  STDOUT_TAKEN.store(true, Ordering::Relaxed);
+}
+
+pub fn exit_code(error: &ConversionError) -> i32 {
+  match error {
+    ConversionError::Io(_) => EXIT_IO,
+    ConversionError::ParseError { .. } => EXIT_PARSE,
+    ConversionError::WriteError { .. } => EXIT_WRITE,
+    ConversionError::UnsupportedFormat => EXIT_UNSUPPORTED,
+    ConversionError::Unknown => EXIT_FAILURE,
+  }
+}
+
+/// A failure as it appears in JSON reports.
+#[derive(Serialize)]
+pub struct ErrorReport {
+  pub kind: String,
+  pub format: Option<String>,
+  pub message: String,
+}
+
+impl From<&ConversionError> for ErrorReport {
+  fn from(error: &ConversionError) -> Self {
+    Self {
+      kind: error.kind().to_string(),
+      format: error.format().map(str::to_string),
+      message: error.to_string(),
+    }
+  }
+}
+
+#[derive(Serialize)]
+struct Success<'a, T: Serialize> {
+  status: &'static str,
+  #[serde(flatten)]
+  report: &'a T,
+}
+
+/// `report` marked with `"status": "ok"`.
+pub fn success<T: Serialize>(report: &T) -> impl Serialize + '_ {
+  Success {
+    status: "ok",
+    report,
+  }
+}
+
+#[derive(Serialize)]
+struct Failure<'a> {
+  status: &'static str,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  exit_code: Option<i32>,
+  error: &'a ErrorReport,
+}
+
+/// `error` marked with `"status": "error"`, for failures that don't end the process.
+pub fn failure(error: &ErrorReport) -> impl Serialize + '_ {
+  Failure {
+    status: "error",
+    exit_code: None,
+    error,
+  }
+}
+
+/// Prints `report` as JSON on stdout, or on stderr when stdout carries the converted scene.
+pub fn print_json(report: &impl Serialize) {
+  let text = serde_json::to_string_pretty(report).unwrap();
+  if STDOUT_TAKEN.load(Ordering::Relaxed) {
+    eprintln!("{}", text);
+  } else {
+    let mut stdout = io::stdout().lock();
+    // A closed stdout leaves nobody to report to
+    let _ = writeln!(stdout, "{}", text);
+  }
+}
+
+/// Prints `event` as a single line of JSON on stdout, for commands that report as they go.
+pub fn print_json_line(event: &impl Serialize) {
+  let mut stdout = io::stdout().lock();
+  let _ = writeln!(stdout, "{}", serde_json::to_string(event).unwrap());
+}
+
+/// Prints the report of a command that succeeded as JSON, marked with `"status": "ok"`.
+pub fn print_success<T: Serialize>(report: &T) {
+  print_json(&success(report));
+}
+
+/// Reports `error` and exits with `exit_code`.
+pub fn fail(error: &ErrorReport, exit_code: i32) -> ! {
+  if json() {
+    print_json(&Failure {
+      status: "error",
+      exit_code: Some(exit_code),
+      error,
+    });
+  } else {
+    eprintln!("Error: {}", error.message);
+  }
+  process::exit(exit_code);
+}
+
+/// Reports a failed conversion and exits with the code of its category.
+pub fn fail_conversion(error: &ConversionError) -> ! {
+  fail(&error.into(), exit_code(error))
+}
+
+/// Reports invalid arguments and exits with `EXIT_USAGE`.
+pub fn fail_usage(message: impl Display) -> ! {
+  fail(
+    &ErrorReport {
+      kind: "usage".to_string(),
+      format: None,
+      message: message.to_string(),
+    },
+    EXIT_USAGE,
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // The command line can't produce every kind of failure, e.g. unsupported formats are refused
+  // while parsing the arguments
+  #[test]
+  fn every_failure_has_its_exit_code() {
+    let parse = ConversionError::ParseError {
+      format: "PLY".to_string(),
+      message: String::new(),
+    };
+    let write = ConversionError::WriteError {
+      format: "PLY".to_string(),
+      message: String::new(),
+    };
+    for (error, code) in [
+      (ConversionError::Io(io::ErrorKind::NotFound.into()), EXIT_IO),
+      (parse, EXIT_PARSE),
+      (write, EXIT_WRITE),
+      (ConversionError::UnsupportedFormat, EXIT_UNSUPPORTED),
+      (ConversionError::Unknown, EXIT_FAILURE),
+    ] {
+      assert_eq!(exit_code(&error), code, "{}", error);
+    }
+    let codes = [
+      EXIT_FAILURE,
+      EXIT_USAGE,
+      EXIT_IO,
+      EXIT_PARSE,
+      EXIT_WRITE,
+      EXIT_UNSUPPORTED,
+    ];
+    assert!((1..codes.len()).all(|i| !codes[..i].contains(&codes[i])));
+  }
+}
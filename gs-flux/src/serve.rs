@@ -0,0 +1,413 @@
+//! A small HTTP API for tools that need conversions without embedding Rust:
+//!
+//! - `GET /formats` lists the formats that can be read and written.
+//! - `POST /convert?to=<format>` converts the request body and answers with the converted file,
+//!   or with the JSON report of the conversion when `report` is set. Every other conversion option
+//!   of the command line is a query parameter of the same name, e.g. `sh-degree=0` or `op=...`.
+//!
+//! Uploads larger than `--max-size` are refused with 413, and requests beyond `--jobs` conversions
+//! at a time with 503. A conversion that panics answers with 500 and frees its slot like any other.
+
+use std::{
+  any::Any,
+  io::{Cursor, Read},
+  panic::{self, AssertUnwindSafe},
+  sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+  },
+  thread,
+  time::Instant,
+};
+
+use clap::{CommandFactory, Parser};
+use converter_core::{
+  ConversionError, formats::detect::DETECTION_BYTES, formats::detect::detect_format,
+};
+use serde::Serialize;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::convert::{
+  Conversion, ConversionReport, Timings, conversion_warnings, read_scene, write_scene,
+};
+use crate::report::{self, ErrorReport};
+use crate::{ConvertOptions, IMPORT_FORMATS, SUPPORTED_FORMATS, ServeParams, usage_message};
+
+/// Options that name files on the server, which clients have no business reading or writing.
+const LOCAL_OPTIONS: [&str; 3] = ["cameras", "pipeline", "no-clobber"];
+
+/// The query of `POST /convert`, parsed like the command line.
+#[derive(Parser, Debug)]
+#[command(
+  no_binary_name = true,
+  disable_help_flag = true,
+  disable_version_flag = true
+)]
+struct ConvertQuery {
+  #[arg(long, required = true, value_parser = SUPPORTED_FORMATS)]
+  to: String,
+
+  /// Answer with the report of the conversion instead of the converted file
+  #[arg(long)]
+  report: bool,
+
+  #[command(flatten)]
+  options: ConvertOptions,
+}
+
+/// A request that could not be served, with its HTTP status.
+struct Failure {
+  status: u16,
+  error: ErrorReport,
+}
+
+impl Failure {
+  fn new(status: u16, kind: &str, message: impl Into<String>) -> Self {
+    Self {
+      status,
+      error: ErrorReport {
+        kind: kind.to_string(),
+        format: None,
+        message: message.into(),
+      },
+    }
+  }
+}
+
+impl From<ConversionError> for Failure {
+  fn from(error: ConversionError) -> Self {
+    let status = match error {
+      ConversionError::UnsupportedFormat => 415,
+      ConversionError::Unknown => 500,
+      // The upload is in memory, so I/O errors come from truncated or malformed content
+      _ => 422,
+    };
+    Self {
+      status,
+      error: (&error).into(),
+    }
+  }
+}
+
+/// One of the `--jobs` conversion slots, given back when dropped, however the conversion ended.
+struct Slot(Arc<AtomicUsize>);
+
+impl Slot {
+  /// Claims a slot, or `None` when all `jobs` are taken.
+  fn claim(active: &Arc<AtomicUsize>, jobs: usize) -> Option<Self> {
+    let slot = Self(Arc::clone(active));
+    // Dropping the slot gives the claim back when it came one too many
+    (active.fetch_add(1, Ordering::SeqCst) < jobs).then_some(slot)
+  }
+}
+
+impl Drop for Slot {
+  fn drop(&mut self) {
+    self.0.fetch_sub(1, Ordering::SeqCst);
+  }
+}
+
+#[derive(Serialize)]
+struct Formats {
+  import: &'static [&'static str],
+  export: &'static [&'static str],
+}
+
+/// A served request, as logged with `--json`.
+#[derive(Serialize)]
+struct RequestLog<'a> {
+  method: String,
+  url: &'a str,
+  status: u16,
+  seconds: f32,
+}
+
+/// Serves conversions until the process is stopped.
+pub fn serve(params: &ServeParams) -> ! {
+  let server = match Server::http((params.host.as_str(), params.port)) {
+    Ok(server) => server,
+    Err(e) => report::fail(
+      &ErrorReport {
+        kind: "io".to_string(),
+        format: None,
+        message: format!("Could not listen on {}:{}: {}", params.host, params.port, e),
+      },
+      report::EXIT_IO,
+    ),
+  };
+  let address = server
+    .server_addr()
+    .to_ip()
+    .map(|address| address.to_string())
+    .unwrap_or_default();
+  if report::json() {
+    report::print_json_line(&serde_json::json!({ "listening": address }));
+  } else {
+    println!("Listening on http://{} (Ctrl+C to stop)", address);
+  }
+
+  let max_size = params.max_size * 1024 * 1024;
+  let jobs = params.jobs.map(|jobs| jobs as usize).unwrap_or_else(|| {
+    thread::available_parallelism()
+      .map(|count| count.get())
+      .unwrap_or(1)
+  });
+  let active = Arc::new(AtomicUsize::new(0));
+
+  for request in server.incoming_requests() {
+    let start = Instant::now();
+    let path = request.url().split('?').next().unwrap_or_default();
+    match (request.method(), path) {
+      (Method::Get, "/formats") => {
+        let formats = Formats {
+          import: &IMPORT_FORMATS,
+          export: &SUPPORTED_FORMATS,
+        };
+        respond(
+          request,
+          json_response(200, &report::success(&formats)),
+          start,
+        );
+      }
+      (Method::Post, "/convert") => {
+        let Some(slot) = Slot::claim(&active, jobs) else {
+          let failure = Failure::new(
+            503,
+            "busy",
+            format!("All {} conversion slots are taken, try again shortly", jobs),
+          );
+          let response = failure_response(failure).with_header(header("Retry-After", "1"));
+          respond(request, response, start);
+          continue;
+        };
+        thread::spawn(move || {
+          let mut request = request;
+          let converted =
+            panic::catch_unwind(AssertUnwindSafe(|| handle_convert(&mut request, max_size)));
+          let response = match converted {
+            Ok(Ok(response)) => response,
+            Ok(Err(failure)) => failure_response(failure),
+            Err(panic) => failure_response(Failure::new(
+              500,
+              "internal",
+              format!(
+                "The conversion failed unexpectedly: {}",
+                panic_message(&*panic)
+              ),
+            )),
+          };
+          // Freed before answering, so that the client can send its next request right away
+          drop(slot);
+          respond(request, response, start);
+        });
+      }
+      (_, "/formats" | "/convert") => {
+        let allowed = if path == "/formats" { "GET" } else { "POST" };
+        let failure = Failure::new(
+          405,
+          "method_not_allowed",
+          format!("{} only accepts {}", path, allowed),
+        );
+        respond(
+          request,
+          failure_response(failure).with_header(header("Allow", allowed)),
+          start,
+        );
+      }
+      _ => {
+        let failure = Failure::new(404, "not_found", format!("No such endpoint: {}", path));
+        respond(request, failure_response(failure), start);
+      }
+    }
+  }
+  report::fail(
+    &ErrorReport {
+      kind: "io".to_string(),
+      format: None,
+      message: "The server stopped accepting connections".to_string(),
+    },
+    report::EXIT_IO,
+  )
+}
+
+/// Converts the body of a `POST /convert` request as its query says.
+fn handle_convert(
+  request: &mut Request,
+  max_size: u64,
+) -> Result<Response<Cursor<Vec<u8>>>, Failure> {
+  let query = request
+    .url()
+    .split_once('?')
+    .map(|(_, query)| query)
+    .unwrap_or_default();
+  let query = parse_query(query).map_err(|message| Failure::new(400, "usage", message))?;
+  let from = query.options.from.clone();
+  let conversion =
+    Conversion::try_new(query.options).map_err(|message| Failure::new(400, "usage", message))?;
+
+  let too_large = || {
+    Failure::new(
+      413,
+      "too_large",
+      format!("Uploads are limited to {} MB", max_size / 1024 / 1024),
+    )
+  };
+  if request
+    .body_length()
+    .is_some_and(|length| length as u64 > max_size)
+  {
+    return Err(too_large());
+  }
+  // The length isn't known up front for chunked uploads
+  let mut data = Vec::new();
+  request
+    .as_reader()
+    .take(max_size + 1)
+    .read_to_end(&mut data)
+    .map_err(|e| Failure::new(400, "io", format!("Could not read the upload: {}", e)))?;
+  if data.len() as u64 > max_size {
+    return Err(too_large());
+  }
+
+  let (output, conversion_report) = convert_upload(data, from.as_deref(), &query.to, &conversion)?;
+  if query.report {
+    return Ok(json_response(200, &report::success(&conversion_report)));
+  }
+  Ok(
+    Response::from_data(output)
+      .with_header(header("Content-Type", "application/octet-stream"))
+      .with_header(header(
+        "Content-Disposition",
+        &format!("attachment; filename=\"scene.{}\"", query.to),
+      )),
+  )
+}
+
+/// The query parameters as command line arguments: `name=value` as `--name=value`, and flags as
+/// `--name` when set to `true` (or given without a value).
+fn parse_query(query: &str) -> Result<ConvertQuery, String> {
+  let command = ConvertQuery::command();
+  let mut args = Vec::new();
+  for (key, value) in form_urlencoded::parse(query.as_bytes()) {
+    let key = key.replace('_', "-");
+    if LOCAL_OPTIONS.contains(&key.as_str()) {
+      return Err(format!("'{}' is not available over HTTP", key));
+    }
+    let takes_value = command
+      .get_arguments()
+      .find(|arg| arg.get_long() == Some(key.as_str()))
+      .map(|arg| arg.get_action().takes_values())
+      .ok_or_else(|| format!("Unknown parameter '{}'", key))?;
+    match (takes_value, value.as_ref()) {
+      (true, _) => args.push(format!("--{}={}", key, value)),
+      (false, "" | "true") => args.push(format!("--{}", key)),
+      (false, "false") => {}
+      (false, _) => return Err(format!("'{}' is either true or false", key)),
+    }
+  }
+  ConvertQuery::try_parse_from(args).map_err(|e| usage_message(&e))
+}
+
+/// Converts an upload in memory, recognising its format from its first bytes when `from` is
+/// `None`.
+fn convert_upload(
+  data: Vec<u8>,
+  from: Option<&str>,
+  to: &str,
+  conversion: &Conversion,
+) -> Result<(Vec<u8>, ConversionReport), ConversionError> {
+  let start = Instant::now();
+  let source_size = data.len() as u64;
+  let source_format = match from {
+    Some(from) => from,
+    None => {
+      detect_format(&data[..data.len().min(DETECTION_BYTES)])
+        .ok_or_else(|| ConversionError::ParseError {
+          format: "upload".to_string(),
+          message: "Unrecognised format, name it with from=<format>".to_string(),
+        })?
+        .format
+    }
+  };
+  let mut scene = read_scene(
+    &mut Cursor::new(data),
+    source_format,
+    None,
+    &conversion.import,
+  )?;
+  let import_seconds = start.elapsed().as_secs_f32();
+  let splats_read = scene.splats.len();
+
+  let steps = conversion.operations.apply(&mut scene)?;
+  let operations_seconds = start.elapsed().as_secs_f32() - import_seconds;
+  let warnings = conversion_warnings(&scene, to, &conversion.export);
+
+  let mut output = Vec::new();
+  write_scene(&scene, to, &mut output, &conversion.export)?;
+
+  let total_seconds = start.elapsed().as_secs_f32();
+  let conversion_report = ConversionReport {
+    source: "upload".to_string(),
+    target: "response".to_string(),
+    source_format: source_format.to_string(),
+    target_format: to.to_string(),
+    source_size,
+    target_size: output.len() as u64,
+    splats_read,
+    splats_written: scene.splats.len(),
+    cameras: scene.cameras.len(),
+    seconds: Timings {
+      import: import_seconds,
+      operations: operations_seconds,
+      export: total_seconds - import_seconds - operations_seconds,
+      total: total_seconds,
+    },
+    operations: steps,
+    warnings,
+  };
+  Ok((output, conversion_report))
+}
+
+/// The message a panic was raised with.
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+  panic
+    .downcast_ref::<&str>()
+    .copied()
+    .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+    .unwrap_or("unknown error")
+}
+
+fn header(name: &str, value: &str) -> Header {
+  Header::from_bytes(name.as_bytes(), value.as_bytes()).unwrap()
+}
+
+fn json_response(status: u16, body: &impl Serialize) -> Response<Cursor<Vec<u8>>> {
+  Response::from_data(serde_json::to_vec(body).unwrap())
+    .with_status_code(status)
+    .with_header(header("Content-Type", "application/json"))
+}
+
+fn failure_response(failure: Failure) -> Response<Cursor<Vec<u8>>> {
+  json_response(failure.status, &report::failure(&failure.error))
+}
+
+/// Sends `response` and logs the request.
+fn respond(request: Request, response: Response<Cursor<Vec<u8>>>, start: Instant) {
+  let status = response.status_code().0;
+  let method = request.method().to_string();
+  let url = request.url().to_string();
+  // A client that went away has nobody left to answer
+  let _ = request.respond(response);
+
+  let seconds = start.elapsed().as_secs_f32();
+  if report::json() {
+    report::print_json_line(&RequestLog {
+      method,
+      url: &url,
+      status,
+      seconds,
+    });
+  } else {
+    println!("{} {} {} ({:.2}s)", method, url, status, seconds);
+  }
+}
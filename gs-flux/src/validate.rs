@@ -0,0 +1,137 @@
+//! `gs-flux validate`: checking that files can be read and hold no NaN or infinite values.
+
+use std::process;
+
+use converter_core::{
+  ConversionError,
+  formats::point_cloud::PointCloudImportOptions,
+  stats::{SceneStats, scene_stats},
+};
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::convert::import_scene;
+use crate::{ValidateParams, batch, input_format, report};
+
+pub fn run_validate(params: ValidateParams) {
+  let sources = match batch::expand_inputs(&params.inputs, None) {
+    Ok(sources) => sources,
+    Err(message) => report::fail_usage(message),
+  };
+
+  let results: Vec<Result<SceneStats, ConversionError>> = sources
+    .par_iter()
+    .map(|source| {
+      let path = source.path.to_string_lossy();
+      let scene = import_scene(
+        &path,
+        &input_format(&path),
+        &PointCloudImportOptions::default(),
+      )?;
+      Ok(scene_stats(&scene))
+    })
+    .collect();
+
+  let files: Vec<FileValidation> = sources
+    .iter()
+    .zip(results)
+    .map(|(source, result)| {
+      let file = source.path.display().to_string();
+      match result {
+        Ok(stats) if stats.non_finite_splats == 0 => FileValidation::Valid {
+          file,
+          splats: stats.splat_count,
+          sh_degree: stats.sh_degree,
+        },
+        Ok(stats) => FileValidation::Invalid {
+          file,
+          splats: stats.splat_count,
+          non_finite_splats: stats.non_finite_splats,
+        },
+        Err(e) => FileValidation::Failed {
+          file,
+          exit_code: report::exit_code(&e),
+          error: (&e).into(),
+        },
+      }
+    })
+    .collect();
+  let invalid = files
+    .iter()
+    .filter(|file| !matches!(file, FileValidation::Valid { .. }))
+    .count();
+  // The first invalid file decides the exit code, NaN values counting as unparseable input
+  let exit_code = files.iter().find_map(|file| match file {
+    FileValidation::Valid { .. } => None,
+    FileValidation::Invalid { .. } => Some(report::EXIT_PARSE),
+    FileValidation::Failed { exit_code, .. } => Some(*exit_code),
+  });
+
+  if report::json() {
+    report::print_json(&ValidationReport {
+      status: if exit_code.is_none() { "ok" } else { "error" },
+      exit_code: exit_code.unwrap_or(0),
+      valid: files.len() - invalid,
+      invalid,
+      files,
+    });
+  } else {
+    for file in &files {
+      match file {
+        FileValidation::Valid {
+          file,
+          splats,
+          sh_degree,
+        } => println!("✔ {}: {} splats, SH degree {}", file, splats, sh_degree),
+        FileValidation::Invalid {
+          file,
+          splats,
+          non_finite_splats,
+        } => println!(
+          "✖ {}: {} of {} splats have NaN or infinite values",
+          file, non_finite_splats, splats
+        ),
+        FileValidation::Failed { file, error, .. } => println!("✖ {}: {}", file, error.message),
+      }
+    }
+    if invalid > 0 {
+      eprintln!("\n{} of {} files are invalid", invalid, files.len());
+    }
+  }
+  if let Some(exit_code) = exit_code {
+    process::exit(exit_code);
+  }
+}
+
+/// What `validate --json` reports.
+#[derive(Serialize)]
+struct ValidationReport {
+  status: &'static str,
+  exit_code: i32,
+  valid: usize,
+  invalid: usize,
+  files: Vec<FileValidation>,
+}
+
+/// The outcome of checking one file.
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum FileValidation {
+  Valid {
+    file: String,
+    splats: usize,
+    sh_degree: u8,
+  },
+  /// Readable, but with NaN or infinite values.
+  Invalid {
+    file: String,
+    splats: usize,
+    non_finite_splats: usize,
+  },
+  Failed {
+    file: String,
+    #[serde(skip)]
+    exit_code: i32,
+    error: report::ErrorReport,
+  },
+}
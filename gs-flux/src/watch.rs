@@ -0,0 +1,266 @@
+//! Converting the files of a folder as they appear or change, e.g. the
+//! `point_cloud/iteration_N/point_cloud.ply` snapshots written during training. The folder is
+//! scanned at a fixed interval, and a file is converted once it stayed the same for a whole
+//! interval, so that files still being written are left alone.
+
+use std::{
+  collections::{HashMap, HashSet},
+  fs,
+  path::{Component, Path, PathBuf},
+  thread,
+  time::{Duration, SystemTime},
+};
+
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::batch::{self, Job, Source};
+use crate::convert::{Conversion, ConversionReport, convert_job};
+use crate::{extension_of, report};
+
+/// What to watch and where the outputs go.
+pub struct WatchOptions {
+  pub folder: String,
+  pub out_dir: Option<PathBuf>,
+  pub to: String,
+  pub interval: Duration,
+  /// Only convert the latest iteration of every snapshot and delete the outputs of older ones.
+  pub latest: bool,
+  /// Also convert files whose output is already newer than the source.
+  pub force: bool,
+}
+
+/// Size and modification time of a file, which change while it is written.
+#[derive(Clone, Copy, PartialEq)]
+struct Signature {
+  size: u64,
+  modified: Option<SystemTime>,
+}
+
+fn signature(path: &Path) -> Option<Signature> {
+  let metadata = fs::metadata(path).ok()?;
+  Some(Signature {
+    size: metadata.len(),
+    modified: metadata.modified().ok(),
+  })
+}
+
+/// The iteration of a training snapshot, from an `iteration_N` folder in its path, with the path
+/// the snapshots of all iterations share.
+fn iteration(path: &Path) -> Option<(PathBuf, u64)> {
+  let mut found = None;
+  let mut shared = PathBuf::new();
+  for component in path.components() {
+    if let Component::Normal(part) = component
+      && let Some(number) = part
+        .to_str()
+        .and_then(|part| part.strip_prefix("iteration_"))
+        .and_then(|number| number.parse::<u64>().ok())
+    {
+      found = Some(number);
+      shared.push("iteration_*");
+    } else {
+      shared.push(component);
+    }
+  }
+  found.map(|number| (shared, number))
+}
+
+/// An event printed as one line of JSON with `--json`.
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum WatchEvent<'a> {
+  Converted(&'a ConversionReport),
+  Failed {
+    source: String,
+    target: String,
+    error: report::ErrorReport,
+  },
+  /// A file left out because its output would collide with another, or with itself.
+  Refused {
+    message: &'a str,
+  },
+  /// The output of an older iteration, deleted with `--latest`.
+  Removed {
+    path: String,
+  },
+}
+
+/// Watches `options.folder` until the process is stopped.
+pub fn watch(options: &WatchOptions, conversion: &Conversion) -> ! {
+  let out_dir = options.out_dir.as_deref();
+  let folder = [options.folder.clone()];
+  // The signature of every source at the last scan, and when it was last converted
+  let mut seen: HashMap<PathBuf, Signature> = HashMap::new();
+  let mut converted: HashMap<PathBuf, Signature> = HashMap::new();
+  let mut last_error: Option<String> = None;
+  // Why files were left out at the last scan, reported once until they change
+  let mut last_refused: HashSet<String> = HashSet::new();
+
+  if !report::json() {
+    println!(
+      "Watching {} for files to convert to .{} (Ctrl+C to stop)",
+      options.folder, options.to
+    );
+  }
+
+  loop {
+    let sources = match batch::expand_inputs(&folder, out_dir) {
+      Ok(sources) => {
+        last_error = None;
+        sources
+      }
+      Err(message) => {
+        if last_error.as_ref() != Some(&message) {
+          eprintln!("✖ {}", message);
+          last_error = Some(message);
+        }
+        thread::sleep(options.interval);
+        continue;
+      }
+    };
+    // Files whose output would collide are left out, the others are converted all the same
+    let (jobs, refused) = batch::plan_each(without_outputs(sources, options), out_dir, &options.to);
+    for message in &refused {
+      if !last_refused.contains(message) {
+        if report::json() {
+          report::print_json_line(&WatchEvent::Refused { message });
+        } else {
+          eprintln!("✖ {}", message);
+        }
+      }
+    }
+    last_refused = refused.into_iter().collect();
+
+    let latest = latest_iterations(&jobs);
+    let mut ready = Vec::new();
+    for job in &jobs {
+      if options.latest && !is_latest(job, &latest) {
+        continue;
+      }
+      let Some(current) = signature(&job.source) else {
+        continue;
+      };
+      // Files still being written differ from the previous scan
+      let stable = seen.insert(job.source.clone(), current) == Some(current);
+      if !stable || converted.get(&job.source) == Some(&current) {
+        continue;
+      }
+      let first_sight = !converted.contains_key(&job.source);
+      let skip = (conversion.no_clobber && job.output.exists())
+        || (first_sight && !options.force && batch::is_up_to_date(job));
+      if skip {
+        converted.insert(job.source.clone(), current);
+        if options.latest {
+          remove_older_iterations(job, &jobs);
+        }
+        continue;
+      }
+      ready.push((job, current));
+    }
+
+    let results: Vec<_> = ready
+      .par_iter()
+      .map(|(job, _)| convert_job(job, conversion))
+      .collect();
+    for ((job, current), result) in ready.into_iter().zip(results) {
+      // Failed files are retried once they change again
+      converted.insert(job.source.clone(), current);
+      match result {
+        Ok(conversion_report) => {
+          if report::json() {
+            report::print_json_line(&WatchEvent::Converted(&conversion_report));
+          } else {
+            println!(
+              "✔ {} → {} ({:.2}s)",
+              job.source.display(),
+              job.output.display(),
+              conversion_report.seconds.total
+            );
+            for warning in &conversion_report.warnings {
+              eprintln!("⚠ {}: {}", job.source.display(), warning);
+            }
+          }
+          if options.latest {
+            remove_older_iterations(job, &jobs);
+          }
+        }
+        Err(error) => {
+          if report::json() {
+            report::print_json_line(&WatchEvent::Failed {
+              source: job.source.display().to_string(),
+              target: job.output.display().to_string(),
+              error: (&error).into(),
+            });
+          } else {
+            eprintln!("✖ {}: {}", job.source.display(), error);
+          }
+        }
+      }
+    }
+
+    thread::sleep(options.interval);
+  }
+}
+
+/// Leaves out the files in the target format when the outputs are written next to their sources,
+/// as they are the outputs of earlier scans.
+fn without_outputs(sources: Vec<Source>, options: &WatchOptions) -> Vec<Source> {
+  if options.out_dir.is_some() {
+    return sources;
+  }
+  sources
+    .into_iter()
+    .filter(|source| extension_of(&source.path.to_string_lossy()) != options.to)
+    .collect()
+}
+
+/// The latest iteration of every snapshot.
+fn latest_iterations(jobs: &[Job]) -> HashMap<PathBuf, u64> {
+  let mut latest: HashMap<PathBuf, u64> = HashMap::new();
+  for (shared, number) in jobs.iter().filter_map(|job| iteration(&job.source)) {
+    let entry = latest.entry(shared).or_insert(number);
+    *entry = (*entry).max(number);
+  }
+  latest
+}
+
+fn is_latest(job: &Job, latest: &HashMap<PathBuf, u64>) -> bool {
+  match iteration(&job.source) {
+    Some((shared, number)) => latest.get(&shared) == Some(&number),
+    None => true,
+  }
+}
+
+/// Deletes the outputs of the earlier iterations of `job`'s snapshot, and their folders once
+/// empty when they are in the output folder.
+fn remove_older_iterations(job: &Job, jobs: &[Job]) {
+  let Some((shared, number)) = iteration(&job.source) else {
+    return;
+  };
+  for older in jobs {
+    let is_older = iteration(&older.source)
+      .is_some_and(|(other_shared, other)| other_shared == shared && other < number);
+    if !is_older || !older.output.is_file() {
+      continue;
+    }
+    match fs::remove_file(&older.output) {
+      Ok(()) => {
+        if report::json() {
+          report::print_json_line(&WatchEvent::Removed {
+            path: older.output.display().to_string(),
+          });
+        } else {
+          println!("- Removed {}", older.output.display());
+        }
+        // Folders next to the sources are left alone, they hold the sources themselves
+        if let Some(parent) = older.output.parent()
+          && parent != older.source.parent().unwrap_or(Path::new(""))
+        {
+          let _ = fs::remove_dir(parent);
+        }
+      }
+      Err(e) => eprintln!("✖ Could not remove {}: {}", older.output.display(), e),
+    }
+  }
+}
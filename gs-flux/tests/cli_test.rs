@@ -0,0 +1,370 @@
+//! The command line as scripts use it: exit codes, pipes, batch conversions and `--no-clobber`.
+
+mod common;
+
+use std::{
+  fs::{self, File},
+  io::Write,
+  path::Path,
+  process::{Command, Output, Stdio},
+  time::{Duration, SystemTime},
+};
+
+use common::{gs_flux, json, splat_file};
+use tempfile::TempDir;
+
+/// Runs `command` with `input` on stdin.
+fn run(command: &mut Command, input: &[u8]) -> Output {
+  let mut child = command
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .unwrap();
+  // A command that fails early may close stdin before reading it
+  let _ = child.stdin.take().unwrap().write_all(input);
+  child.wait_with_output().unwrap()
+}
+
+fn write_splats(path: &Path, count: usize) {
+  fs::create_dir_all(path.parent().unwrap()).unwrap();
+  fs::write(path, splat_file(count)).unwrap();
+}
+
+fn set_modified(path: &Path, time: SystemTime) {
+  File::options()
+    .write(true)
+    .open(path)
+    .unwrap()
+    .set_modified(time)
+    .unwrap();
+}
+
+/// Checks that the failure of `output` is reported in JSON with `exit_code` and `kind`.
+fn assert_failure(output: &Output, exit_code: i32, kind: &str) {
+  assert_eq!(
+    output.status.code(),
+    Some(exit_code),
+    "{}",
+    String::from_utf8_lossy(&output.stderr)
+  );
+  // Reports go to stderr while stdout carries the scene
+  let report = if output.stdout.is_empty() {
+    json(&output.stderr)
+  } else {
+    json(&output.stdout)
+  };
+  assert_eq!(report["status"], "error");
+  assert_eq!(report["exit_code"], exit_code);
+  assert_eq!(report["error"]["kind"], kind);
+}
+
+#[test]
+fn pipes_give_the_same_files_as_paths() {
+  let dir = TempDir::new().unwrap();
+  let source = dir.path().join("scene.splat");
+  write_splats(&source, 5);
+  let ply = dir.path().join("scene.ply");
+  let glb = dir.path().join("scene.glb");
+  let splat = dir.path().join("back.splat");
+  for (from, to) in [(&source, &ply), (&source, &glb), (&glb, &splat)] {
+    assert!(gs_flux().arg(from).arg(to).status().unwrap().success());
+  }
+
+  // .splat has no magic bytes, so stdin needs --from
+  let piped_ply = run(
+    gs_flux().args(["-", "-", "--from", "splat", "--to", "ply"]),
+    &splat_file(5),
+  );
+  assert!(piped_ply.status.success());
+  assert_eq!(piped_ply.stdout, fs::read(&ply).unwrap());
+
+  let piped_glb = run(
+    gs_flux().args(["convert", "-", "-", "--from", "splat", "--to", "glb"]),
+    &splat_file(5),
+  );
+  assert!(piped_glb.status.success());
+  assert_eq!(piped_glb.stdout, fs::read(&glb).unwrap());
+
+  // .glb is recognised from its header
+  let piped_splat = run(
+    gs_flux().args(["convert", "-", "-", "--to", "splat"]),
+    &piped_glb.stdout,
+  );
+  assert!(piped_splat.status.success());
+  assert_eq!(piped_splat.stdout, fs::read(&splat).unwrap());
+  assert_eq!(piped_splat.stdout.len(), 5 * 32);
+
+  // Files and pipes mix
+  let out = dir.path().join("from_stdin.ply");
+  let output = run(
+    gs_flux().args(["-", "--from", "splat"]).arg(&out),
+    &splat_file(5),
+  );
+  assert!(output.status.success());
+  assert_eq!(fs::read(&out).unwrap(), fs::read(&ply).unwrap());
+}
+
+#[test]
+fn reports_go_to_stderr_while_stdout_carries_the_scene() {
+  let output = run(
+    gs_flux().args(["-", "-", "--from", "splat", "--to", "splat", "--json"]),
+    &splat_file(3),
+  );
+  assert!(output.status.success());
+  assert_eq!(output.stdout.len(), 3 * 32);
+  let report = json(&output.stderr);
+  assert_eq!(report["status"], "ok");
+  assert_eq!(report["splats_read"], 3);
+}
+
+#[test]
+fn failures_exit_with_the_code_of_their_kind() {
+  let dir = TempDir::new().unwrap();
+  let source = dir.path().join("scene.splat");
+  write_splats(&source, 2);
+
+  // Usage: stdout needs a format
+  let output = run(gs_flux().arg(&source).args(["-", "--json"]), b"");
+  assert_failure(&output, 2, "usage");
+  let output = run(gs_flux().args(["convert", "--json"]).arg(&source), b"");
+  assert_failure(&output, 2, "usage");
+
+  // I/O: the output exists and may not be replaced
+  let existing = dir.path().join("existing.ply");
+  fs::write(&existing, b"keep").unwrap();
+  let output = run(
+    gs_flux()
+      .arg(&source)
+      .arg(&existing)
+      .args(["--no-clobber", "--json"]),
+    b"",
+  );
+  assert_failure(&output, 3, "io");
+
+  // Parse: a .las whose header claims more than the file holds
+  let las = dir.path().join("broken.las");
+  let mut data = b"LASF".to_vec();
+  data.resize(400, 0xff);
+  fs::write(&las, data).unwrap();
+  let output = run(
+    gs_flux()
+      .arg(&las)
+      .arg(dir.path().join("broken.ply"))
+      .arg("--json"),
+    b"",
+  );
+  assert_failure(&output, 4, "parse");
+  // Stdin that is no known format
+  let output = run(
+    gs_flux().args(["-", "-", "--to", "ply", "--json"]),
+    b"not a scene",
+  );
+  assert_failure(&output, 4, "parse");
+
+  // Write: a .gltf can't go to stdout without its .bin
+  let output = run(
+    gs_flux().arg(&source).args(["-", "--to", "gltf", "--json"]),
+    b"",
+  );
+  assert_failure(&output, 5, "write");
+
+  // Nothing is left behind by the failures
+  let mut names: Vec<_> = fs::read_dir(dir.path())
+    .unwrap()
+    .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+    .collect();
+  names.sort();
+  assert_eq!(names, ["broken.las", "existing.ply", "scene.splat"]);
+  assert_eq!(fs::read(&existing).unwrap(), b"keep");
+}
+
+#[test]
+fn batches_mirror_folders_and_skip_outputs_that_are_up_to_date() {
+  let dir = TempDir::new().unwrap();
+  let scans = dir.path().join("scans");
+  write_splats(&scans.join("a.splat"), 2);
+  write_splats(&scans.join("room/b.splat"), 3);
+  write_splats(&scans.join("room/deep/c.splat"), 4);
+  fs::write(scans.join("notes.txt"), b"not a scene").unwrap();
+  let dist = dir.path().join("dist");
+
+  let batch = |extra: &[&str]| {
+    let output = gs_flux()
+      .arg("convert")
+      .arg(&scans)
+      .args(["--to", "ply", "--out-dir"])
+      .arg(&dist)
+      .arg("--json")
+      .args(extra)
+      .output()
+      .unwrap();
+    assert!(output.status.success());
+    json(&output.stdout)
+  };
+
+  let report = batch(&[]);
+  assert_eq!(report["converted"], 3);
+  assert_eq!(report["skipped"], 0);
+  for output in ["a.ply", "room/b.ply", "room/deep/c.ply"] {
+    assert!(dist.join(output).is_file(), "{}", output);
+  }
+
+  // Up to date until a source changes
+  let report = batch(&[]);
+  assert_eq!(report["converted"], 0);
+  assert_eq!(report["skipped"], 3);
+
+  let later = SystemTime::now() + Duration::from_secs(60);
+  set_modified(&scans.join("room/b.splat"), later);
+  let report = batch(&[]);
+  assert_eq!(report["converted"], 1);
+  assert_eq!(
+    report["files"][0]["source"],
+    scans.join("room/b.splat").to_str().unwrap()
+  );
+
+  let report = batch(&["--force"]);
+  assert_eq!(report["converted"], 3);
+
+  // Patterns mirror the folders below their fixed part
+  let glob_dist = dir.path().join("glob_dist");
+  let pattern = format!("{}/**/*.splat", scans.display());
+  let output = gs_flux()
+    .args(["convert", &pattern, "--to", "splat", "--out-dir"])
+    .arg(&glob_dist)
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+  assert_eq!(
+    fs::metadata(glob_dist.join("room/deep/c.splat"))
+      .unwrap()
+      .len(),
+    4 * 32
+  );
+}
+
+#[test]
+fn batches_refuse_outputs_that_collide() {
+  let dir = TempDir::new().unwrap();
+  write_splats(&dir.path().join("scene.splat"), 2);
+
+  // Converting next to the sources would overwrite them
+  let output = gs_flux()
+    .arg("convert")
+    .arg(dir.path())
+    .args(["--to", "splat", "--json"])
+    .output()
+    .unwrap();
+  assert_failure(&output, 2, "usage");
+  let report = json(&output.stdout);
+  assert!(
+    report["error"]["message"]
+      .as_str()
+      .unwrap()
+      .contains("would be overwritten by its own conversion")
+  );
+
+  // Two sources with the same stem
+  fs::write(dir.path().join("scene.ksplat"), b"").unwrap();
+  let output = gs_flux()
+    .arg("convert")
+    .arg(dir.path())
+    .args(["--to", "ply", "--json"])
+    .output()
+    .unwrap();
+  assert_failure(&output, 2, "usage");
+  assert!(
+    json(&output.stdout)["error"]["message"]
+      .as_str()
+      .unwrap()
+      .contains("would both be converted to")
+  );
+  assert!(!dir.path().join("scene.ply").exists());
+
+  // The same file named twice is converted once
+  let file = dir.path().join("scene.splat");
+  let output = gs_flux()
+    .arg("convert")
+    .arg(&file)
+    .arg(&file)
+    .args(["--to", "ply", "--json"])
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+  assert_eq!(json(&output.stdout)["converted"], 1);
+}
+
+#[test]
+fn batch_failures_are_reported_per_file() {
+  let dir = TempDir::new().unwrap();
+  write_splats(&dir.path().join("good.splat"), 2);
+  let mut las = b"LASF".to_vec();
+  las.resize(400, 0xff);
+  fs::write(dir.path().join("bad.las"), las).unwrap();
+
+  let output = gs_flux()
+    .arg("convert")
+    .arg(dir.path())
+    .args(["--to", "ply", "--json"])
+    .output()
+    .unwrap();
+  assert_eq!(output.status.code(), Some(4));
+  let report = json(&output.stdout);
+  assert_eq!(report["status"], "error");
+  assert_eq!(report["exit_code"], 4);
+  assert_eq!(report["converted"], 1);
+  assert_eq!(report["failed"], 1);
+  assert!(dir.path().join("good.ply").is_file());
+  assert!(!dir.path().join("bad.ply").exists());
+}
+
+#[test]
+fn no_clobber_keeps_existing_files() {
+  let dir = TempDir::new().unwrap();
+  let source = dir.path().join("scene.splat");
+  write_splats(&source, 2);
+
+  // A .gltf is refused when its .bin exists, and neither file is written
+  let gltf = dir.path().join("scene.gltf");
+  let bin = dir.path().join("scene.bin");
+  fs::write(&bin, b"keep").unwrap();
+  let output = gs_flux()
+    .arg(&source)
+    .arg(&gltf)
+    .args(["--no-clobber", "--json"])
+    .output()
+    .unwrap();
+  assert_failure(&output, 3, "io");
+  assert!(!gltf.exists());
+  assert_eq!(fs::read(&bin).unwrap(), b"keep");
+
+  fs::remove_file(&bin).unwrap();
+  assert!(
+    gs_flux()
+      .arg(&source)
+      .arg(&gltf)
+      .arg("--no-clobber")
+      .status()
+      .unwrap()
+      .success()
+  );
+  assert!(gltf.is_file() && bin.is_file());
+
+  // Batches skip existing outputs, whatever their age
+  let ply = dir.path().join("scene.ply");
+  fs::write(&ply, b"keep").unwrap();
+  set_modified(&ply, SystemTime::UNIX_EPOCH + Duration::from_secs(60));
+  let output = gs_flux()
+    .args(["convert", "--to", "ply", "--no-clobber", "--json"])
+    .arg(&source)
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+  assert_eq!(json(&output.stdout)["skipped"], 1);
+  assert_eq!(fs::read(&ply).unwrap(), b"keep");
+
+  // Without it the output is replaced
+  assert!(gs_flux().arg(&source).arg(&ply).status().unwrap().success());
+  assert!(fs::read(&ply).unwrap().starts_with(b"ply\n"));
+}
@@ -0,0 +1,23 @@
+//! Helpers shared by the tests that run the `gs-flux` binary.
+
+use std::process::Command;
+
+pub fn gs_flux() -> Command {
+  Command::new(env!("CARGO_BIN_EXE_gs-flux"))
+}
+
+/// `count` splats in the .splat layout.
+pub fn splat_file(count: usize) -> Vec<u8> {
+  let mut data = Vec::new();
+  for i in 0..count {
+    for value in [i as f32, 0.0, 1.0, 0.1, 0.1, 0.1] {
+      data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(&[200, 100, 50, 255, 255, 128, 128, 128]);
+  }
+  data
+}
+
+pub fn json(body: &[u8]) -> serde_json::Value {
+  serde_json::from_slice(body).unwrap()
+}
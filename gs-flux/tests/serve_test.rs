@@ -0,0 +1,183 @@
+//! `gs-flux serve` on a free port, driven by a minimal HTTP client.
+
+mod common;
+
+use std::{
+  io::{BufRead, BufReader, Read, Write},
+  net::TcpStream,
+  process::{Child, Stdio},
+  thread,
+  time::{Duration, Instant},
+};
+
+use common::{gs_flux, json, splat_file};
+
+/// A running server, stopped when dropped.
+struct Server {
+  child: Child,
+  address: String,
+}
+
+impl Server {
+  fn start(args: &[&str]) -> Self {
+    let mut child = gs_flux()
+      .args(["serve", "--port", "0", "--json"])
+      .args(args)
+      .stdout(Stdio::piped())
+      .stderr(Stdio::null())
+      .spawn()
+      .unwrap();
+    let mut line = String::new();
+    BufReader::new(child.stdout.take().unwrap())
+      .read_line(&mut line)
+      .unwrap();
+    let listening: serde_json::Value = serde_json::from_str(&line).unwrap();
+    Self {
+      child,
+      address: listening["listening"].as_str().unwrap().to_string(),
+    }
+  }
+
+  fn connect(&self) -> TcpStream {
+    TcpStream::connect(&self.address).unwrap()
+  }
+
+  /// Sends a request with `body` and returns the status and body of the response.
+  fn request(&self, method: &str, path: &str, body: &[u8]) -> (u16, Vec<u8>) {
+    let mut stream = self.connect();
+    write!(
+      stream,
+      "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+      method,
+      path,
+      body.len()
+    )
+    .unwrap();
+    stream.write_all(body).unwrap();
+    read_response(stream)
+  }
+}
+
+impl Drop for Server {
+  fn drop(&mut self) {
+    let _ = self.child.kill();
+    let _ = self.child.wait();
+  }
+}
+
+fn read_response(mut stream: TcpStream) -> (u16, Vec<u8>) {
+  let mut response = Vec::new();
+  stream.read_to_end(&mut response).unwrap();
+  let end = response
+    .windows(4)
+    .position(|window| window == b"\r\n\r\n")
+    .unwrap();
+  let head = String::from_utf8_lossy(&response[..end]);
+  let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+  (status, response[end + 4..].to_vec())
+}
+
+#[test]
+fn lists_formats() {
+  let server = Server::start(&[]);
+  let (status, body) = server.request("GET", "/formats", b"");
+  assert_eq!(status, 200);
+  let formats = json(&body);
+  assert_eq!(formats["status"], "ok");
+  let export = formats["export"].as_array().unwrap();
+  assert!(export.iter().any(|format| format == "spz"));
+}
+
+#[test]
+fn converts_uploads() {
+  let server = Server::start(&[]);
+  let (status, body) = server.request("POST", "/convert?to=ply&from=splat", &splat_file(4));
+  assert_eq!(status, 200);
+  assert!(body.starts_with(b"ply\n"));
+
+  let (status, body) = server.request(
+    "POST",
+    "/convert?to=splat&from=splat&report",
+    &splat_file(4),
+  );
+  assert_eq!(status, 200);
+  let report = json(&body);
+  assert_eq!(report["splats_read"], 4);
+  assert_eq!(report["target_size"], 4 * 32);
+}
+
+#[test]
+fn refuses_uploads_over_the_size_limit() {
+  let server = Server::start(&["--max-size", "1"]);
+  // Refused from the announced length, before the body is sent
+  let mut stream = server.connect();
+  write!(
+    stream,
+    "POST /convert?to=ply HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+    2 * 1024 * 1024
+  )
+  .unwrap();
+  let (status, body) = read_response(stream);
+  assert_eq!(status, 413);
+  assert_eq!(json(&body)["error"]["kind"], "too_large");
+
+  // Limits whose byte count doesn't fit are a usage error
+  let output = gs_flux()
+    .args(["serve", "--port", "0", "--max-size", "17592186044416"])
+    .output()
+    .unwrap();
+  assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn turns_requests_away_when_every_slot_is_taken() {
+  let server = Server::start(&["--jobs", "1"]);
+  // Holds the only slot with an upload that never finishes. Small bodies are read before the
+  // request is handed over, so this one announces more.
+  let mut stalled = server.connect();
+  write!(
+    stalled,
+    "POST /convert?to=ply&from=splat HTTP/1.1\r\nHost: localhost\r\nContent-Length: 65536\r\n\r\n"
+  )
+  .unwrap();
+  stalled.write_all(&splat_file(1)).unwrap();
+
+  let deadline = Instant::now() + Duration::from_secs(10);
+  loop {
+    let (status, body) = server.request("POST", "/convert?to=ply&from=splat", &splat_file(1));
+    if status == 503 {
+      assert_eq!(json(&body)["error"]["kind"], "busy");
+      break;
+    }
+    assert!(
+      Instant::now() < deadline,
+      "the stalled upload never took the slot"
+    );
+    thread::sleep(Duration::from_millis(20));
+  }
+
+  // The slot is free again once the stalled upload goes away
+  drop(stalled);
+  let deadline = Instant::now() + Duration::from_secs(10);
+  while server
+    .request("POST", "/convert?to=ply&from=splat", &splat_file(1))
+    .0
+    != 200
+  {
+    assert!(Instant::now() < deadline, "the slot was never given back");
+    thread::sleep(Duration::from_millis(20));
+  }
+}
+
+#[test]
+fn keeps_serving_after_a_malformed_upload() {
+  let server = Server::start(&["--jobs", "1"]);
+  let mut las = b"LASF".to_vec();
+  las.resize(400, 0xff);
+  let (status, body) = server.request("POST", "/convert?to=ply&from=las", &las);
+  assert_eq!(status, 422);
+  assert_eq!(json(&body)["error"]["kind"], "parse");
+
+  let (status, _) = server.request("POST", "/convert?to=ply&from=splat", &splat_file(2));
+  assert_eq!(status, 200);
+}
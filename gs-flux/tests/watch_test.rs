@@ -0,0 +1,94 @@
+//! `gs-flux watch` on a temporary folder, followed through its JSON events.
+
+mod common;
+
+use std::{
+  fs,
+  io::{BufRead, BufReader},
+  process::{Child, Stdio},
+  sync::mpsc::{self, Receiver},
+  thread,
+  time::Duration,
+};
+
+use common::{gs_flux, json, splat_file};
+use tempfile::TempDir;
+
+/// A running watch, stopped when dropped.
+struct Watch {
+  child: Child,
+  events: Receiver<serde_json::Value>,
+}
+
+impl Watch {
+  fn start(folder: &TempDir, args: &[&str]) -> Self {
+    let mut child = gs_flux()
+      .arg("watch")
+      .arg(folder.path())
+      .args(["--interval", "0.05", "--json"])
+      .args(args)
+      .stdout(Stdio::piped())
+      .stderr(Stdio::null())
+      .spawn()
+      .unwrap();
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    let (sender, events) = mpsc::channel();
+    thread::spawn(move || {
+      for line in stdout.lines().map_while(Result::ok) {
+        if sender.send(json(line.as_bytes())).is_err() {
+          break;
+        }
+      }
+    });
+    Self { child, events }
+  }
+
+  fn next_event(&self) -> serde_json::Value {
+    self
+      .events
+      .recv_timeout(Duration::from_secs(10))
+      .expect("no event within 10s")
+  }
+}
+
+impl Drop for Watch {
+  fn drop(&mut self) {
+    let _ = self.child.kill();
+    let _ = self.child.wait();
+  }
+}
+
+#[test]
+fn files_whose_outputs_collide_dont_hold_up_the_others() {
+  let folder = TempDir::new().unwrap();
+  fs::write(folder.path().join("scene.splat"), splat_file(2)).unwrap();
+  fs::write(folder.path().join("scene.ksplat"), b"").unwrap();
+  fs::write(folder.path().join("other.splat"), splat_file(3)).unwrap();
+  let out_dir = TempDir::new().unwrap();
+  let watch = Watch::start(
+    &folder,
+    &["--to", "ply", "--out-dir", out_dir.path().to_str().unwrap()],
+  );
+
+  // Reported once, while the other file is converted
+  let refused = watch.next_event();
+  assert_eq!(refused["event"], "refused");
+  assert!(
+    refused["message"]
+      .as_str()
+      .unwrap()
+      .contains("would both be converted to")
+  );
+  let converted = watch.next_event();
+  assert_eq!(converted["event"], "converted");
+  assert_eq!(converted["splats_read"], 3);
+  assert!(out_dir.path().join("other.ply").is_file());
+  assert!(!out_dir.path().join("scene.ply").exists());
+
+  // Converted once the collision is gone
+  fs::remove_file(folder.path().join("scene.ksplat")).unwrap();
+  let converted = watch.next_event();
+  assert_eq!(converted["event"], "converted");
+  assert_eq!(converted["splats_read"], 2);
+  assert!(out_dir.path().join("scene.ply").is_file());
+}
@@ -0,0 +1,206 @@
+//! Reading and writing scenes by format name, with the options of `gs-flux`.
+
+use std::{fs, io::Read, io::Write, path::Path};
+
+use converter_core::{
+  ConversionError, Exporter, Importer, Scene,
+  formats::{
+    colmap::ColmapPointsImporter,
+    compressed_ply::CompressedPlyExporter,
+    csv::{CsvExporter, CsvImporter},
+    ellipsoid::{EllipsoidExportOptions, EllipsoidObjExporter, EllipsoidPlyExporter},
+    gltf::{GlbExporter, GltfExportOptions, GltfExporter, GltfImporter},
+    ksplat::{KsplatExportOptions, KsplatExporter, KsplatImporter},
+    las::{LasExporter, LasImporter},
+    ply::{PlyASCIIExporter, PlyBinaryExporter, PlyExportOptions, PlyImporter},
+    point_cloud::{PointCloudExportOptions, PointCloudImportOptions, PointCloudPlyExporter},
+    sog::{SogExportOptions, SogExporter, SogImporter},
+    splat::{SplatExportOptions, SplatExporter, SplatImporter, SplatOrder},
+    spz::{SpzImporter, SpzV2Exporter},
+    usd::{UsdImporter, UsdaExporter, UsdzExporter},
+  },
+};
+use pyo3::{
+  exceptions::{PyTypeError, PyValueError},
+  prelude::*,
+  types::PyDict,
+};
+
+/// The formats that can be read.
+pub const IMPORT_FORMATS: [&str; 12] = [
+  "ply", "spz", "splat", "csv", "ksplat", "sog", "gltf", "glb", "usda", "usdz", "las", "colmap",
+];
+
+/// The formats that can be written.
+pub const EXPORT_FORMATS: [&str; 12] = [
+  "ply", "spz", "splat", "csv", "ksplat", "sog", "gltf", "glb", "usda", "usdz", "las", "obj",
+];
+
+/// COLMAP sparse models are recognised by file name rather than by extension.
+const COLMAP_POINTS_FILE: &str = "points3d";
+
+/// The format of `path` from its extension, or "colmap" for `points3D.bin` / `points3D.txt`.
+pub fn format_of(path: &Path) -> String {
+  let extension = path
+    .extension()
+    .map(|ext| ext.to_string_lossy().to_lowercase())
+    .unwrap_or_default();
+  let is_colmap = path
+    .file_stem()
+    .is_some_and(|stem| stem.to_string_lossy().to_lowercase() == COLMAP_POINTS_FILE);
+  if is_colmap && matches!(extension.as_str(), "bin" | "txt") {
+    "colmap".to_string()
+  } else {
+    extension
+  }
+}
+
+/// Imports a scene in `format` from `reader`. `folder` holds the files a .gltf refers to.
+pub fn read_scene(
+  reader: &mut impl Read,
+  format: &str,
+  folder: Option<&Path>,
+  options: &PointCloudImportOptions,
+) -> Result<Scene, ConversionError> {
+  let scene = match format {
+    "ply" => PlyImporter::import_with_options(reader, options)?,
+    "spz" => SpzImporter::import(reader)?,
+    "csv" => CsvImporter::import(reader)?,
+    "splat" => SplatImporter::import(reader)?,
+    "ksplat" => KsplatImporter::import(reader)?,
+    "sog" => SogImporter::import(reader)?,
+    "gltf" => match folder {
+      // External buffers are resolved relative to the .gltf file
+      Some(folder) => {
+        GltfImporter::import_with_resolver(reader, |uri| Ok(fs::read(folder.join(uri))?))?
+      }
+      None => GltfImporter::import(reader)?,
+    },
+    "glb" => GltfImporter::import(reader)?,
+    "usda" | "usdz" => UsdImporter::import(reader)?,
+    "las" => LasImporter::import_with_options(reader, options)?,
+    "colmap" => ColmapPointsImporter::import_with_options(reader, options)?,
+    _ => return Err(ConversionError::UnsupportedFormat),
+  };
+  Ok(scene)
+}
+
+/// How scenes are written, set by the keyword arguments of `Scene.write` and `Scene.dumps`.
+#[derive(Default)]
+pub struct ExportOptions {
+  /// "ascii", "binary" (the default) or "compressed", for .ply.
+  encoding: Option<String>,
+  ply: PlyExportOptions,
+  point_cloud: bool,
+  ellipsoids: bool,
+  points: PointCloudExportOptions,
+  ellipsoid: EllipsoidExportOptions,
+  splat: SplatExportOptions,
+  ksplat: KsplatExportOptions,
+  sog: SogExportOptions,
+  gltf: GltfExportOptions,
+}
+
+impl ExportOptions {
+  /// The options named by `kwargs`, failing on unknown names.
+  pub fn from_kwargs(kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+    let mut options = Self::default();
+    let Some(kwargs) = kwargs else {
+      return Ok(options);
+    };
+    for (key, value) in kwargs {
+      let key: String = key.extract()?;
+      match key.as_str() {
+        "encoding" => options.encoding = value.extract()?,
+        "point_cloud" => options.point_cloud = value.extract()?,
+        "ellipsoids" => options.ellipsoids = value.extract()?,
+        "sigma" => options.ellipsoid.sigma = value.extract()?,
+        "max_triangles" => options.ellipsoid.max_triangles = value.extract()?,
+        "min_opacity" => {
+          let min_opacity: Option<f32> = value.extract()?;
+          options.points.min_opacity = min_opacity;
+          options.ellipsoid.min_opacity = min_opacity;
+          options.splat.min_opacity = min_opacity;
+        }
+        "importance_order" => {
+          if value.extract()? {
+            options.splat.order = SplatOrder::Importance;
+          }
+        }
+        "compression_level" => {
+          let level: u8 = value.extract()?;
+          if level > 2 {
+            return Err(PyValueError::new_err("compression_level is 0, 1 or 2"));
+          }
+          options.ksplat.compression_level = level;
+        }
+        "sh_palette_size" => {
+          let size: Option<usize> = value.extract()?;
+          if size.is_some_and(|size| !(1..=65536).contains(&size)) {
+            return Err(PyValueError::new_err(
+              "sh_palette_size is between 1 and 65536",
+            ));
+          }
+          options.sog.sh_palette_size = size;
+        }
+        "sh_degree" => {
+          let degree: Option<u8> = value.extract()?;
+          if degree.is_some_and(|degree| degree > 3) {
+            return Err(PyValueError::new_err("sh_degree is 0, 1, 2 or 3"));
+          }
+          options.ply.sh_degree = degree;
+        }
+        "meshopt" => options.gltf.meshopt_compression = value.extract()?,
+        _ => {
+          return Err(PyTypeError::new_err(format!(
+            "Unknown export option '{}'",
+            key
+          )));
+        }
+      }
+    }
+    Ok(options)
+  }
+}
+
+/// Exports `scene` in `format` to `writer`. A .gltf embeds its buffer, so that it is a single
+/// file.
+pub fn write_scene(
+  scene: &Scene,
+  format: &str,
+  writer: &mut impl Write,
+  options: &ExportOptions,
+) -> Result<(), ConversionError> {
+  match format {
+    "ply" if options.ellipsoids => {
+      EllipsoidPlyExporter::export_with_options(scene, writer, &options.ellipsoid)?
+    }
+    "ply" if options.point_cloud => {
+      PointCloudPlyExporter::export_with_options(scene, writer, &options.points)?
+    }
+    "ply" => match options.encoding.as_deref().unwrap_or("binary") {
+      "ascii" => PlyASCIIExporter::export_with_options(scene, writer, &options.ply)?,
+      "binary" => PlyBinaryExporter::export_with_options(scene, writer, &options.ply)?,
+      "compressed" => CompressedPlyExporter::export(scene, writer)?,
+      other => {
+        return Err(ConversionError::WriteError {
+          format: "PLY".to_string(),
+          message: format!("Unsupported PLY encoding: {}", other),
+        });
+      }
+    },
+    "spz" => SpzV2Exporter::export(scene, writer)?,
+    "las" => LasExporter::export_with_options(scene, writer, &options.points)?,
+    "obj" => EllipsoidObjExporter::export_with_options(scene, writer, &options.ellipsoid)?,
+    "csv" => CsvExporter::export(scene, writer)?,
+    "splat" => SplatExporter::export_with_options(scene, writer, &options.splat)?,
+    "ksplat" => KsplatExporter::export_with_options(scene, writer, &options.ksplat)?,
+    "sog" => SogExporter::export_with_options(scene, writer, &options.sog)?,
+    "gltf" => GltfExporter::export_with_options(scene, writer, &options.gltf)?,
+    "glb" => GlbExporter::export_with_options(scene, writer, &options.gltf)?,
+    "usda" => UsdaExporter::export(scene, writer)?,
+    "usdz" => UsdzExporter::export(scene, writer)?,
+    _ => return Err(ConversionError::UnsupportedFormat),
+  };
+  Ok(())
+}
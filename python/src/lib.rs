@@ -0,0 +1,106 @@
+//! Python bindings of `converter_core`: reading and writing every format, the splats of a scene as
+//! NumPy arrays, and the processing operations of `gs-flux`.
+
+mod formats;
+mod scene;
+
+use std::{
+  fs::File,
+  io::{BufReader, Cursor},
+  path::{Path, PathBuf},
+};
+
+use converter_core::{
+  ConversionError as CoreError,
+  formats::{
+    detect::{DETECTION_BYTES, detect_format},
+    point_cloud::PointCloudImportOptions,
+  },
+  operations::pipeline::OPERATIONS,
+};
+use pyo3::{create_exception, exceptions::PyException, prelude::*};
+
+use formats::{EXPORT_FORMATS, IMPORT_FORMATS, format_of, read_scene};
+use scene::Scene;
+
+create_exception!(
+  gs_flux,
+  ConversionError,
+  PyException,
+  "A scene that could not be read, processed or written."
+);
+
+/// I/O failures become `OSError`s (e.g. `FileNotFoundError`), everything else a `ConversionError`.
+fn to_py_err(error: CoreError) -> PyErr {
+  match error {
+    CoreError::Io(error) => error.into(),
+    error => ConversionError::new_err(error.to_string()),
+  }
+}
+
+fn import_options(initial_opacity: Option<f32>) -> PointCloudImportOptions {
+  PointCloudImportOptions {
+    initial_opacity: initial_opacity.unwrap_or(PointCloudImportOptions::default().initial_opacity),
+  }
+}
+
+/// Reads the scene in `path`, in `format` or the one of its extension. `initial_opacity` (0..1)
+/// is given to the Gaussians created from point clouds.
+#[pyfunction]
+#[pyo3(signature = (path, format = None, *, initial_opacity = None))]
+fn read(
+  py: Python<'_>,
+  path: PathBuf,
+  format: Option<String>,
+  initial_opacity: Option<f32>,
+) -> PyResult<Scene> {
+  let format = format.unwrap_or_else(|| format_of(&path));
+  let options = import_options(initial_opacity);
+  let scene = py
+    .detach(|| {
+      let folder = path.parent().unwrap_or_else(|| Path::new(""));
+      let mut reader = BufReader::new(File::open(&path)?);
+      read_scene(&mut reader, &format, Some(folder), &options)
+    })
+    .map_err(to_py_err)?;
+  Ok(Scene::from_core(py, scene))
+}
+
+/// Reads a scene from bytes, in `format` or the one recognised from its first bytes (.splat
+/// always needs it).
+#[pyfunction]
+#[pyo3(signature = (data, format = None, *, initial_opacity = None))]
+fn loads(
+  py: Python<'_>,
+  data: Vec<u8>,
+  format: Option<String>,
+  initial_opacity: Option<f32>,
+) -> PyResult<Scene> {
+  let format = match format {
+    Some(format) => format,
+    None => detect_format(&data[..data.len().min(DETECTION_BYTES)])
+      .map(|detected| detected.format.to_string())
+      .ok_or_else(|| ConversionError::new_err("Unrecognised format, pass format="))?,
+  };
+  let options = import_options(initial_opacity);
+  let scene = py
+    .detach(|| read_scene(&mut Cursor::new(data), &format, None, &options))
+    .map_err(to_py_err)?;
+  Ok(Scene::from_core(py, scene))
+}
+
+#[pymodule]
+fn gs_flux(module: &Bound<'_, PyModule>) -> PyResult<()> {
+  let py = module.py();
+  // Without NumPy the module can't hand out a single scene, so it fails to import instead
+  py.import("numpy")?;
+  module.add_class::<Scene>()?;
+  module.add_function(wrap_pyfunction!(read, module)?)?;
+  module.add_function(wrap_pyfunction!(loads, module)?)?;
+  module.add("ConversionError", py.get_type::<ConversionError>())?;
+  module.add("IMPORT_FORMATS", IMPORT_FORMATS.to_vec())?;
+  module.add("EXPORT_FORMATS", EXPORT_FORMATS.to_vec())?;
+  // Names of the processing steps with their parameters, for `Scene.apply`
+  module.add("OPERATIONS", OPERATIONS.to_vec())?;
+  Ok(())
+}
@@ -0,0 +1,398 @@
+//! `gs_flux.Scene`: the splats of a scene as one NumPy array per attribute. Arrays are shared with
+//! Python rather than copied, so changes made to them in place are what gets written.
+
+use std::{fs::File, io::BufWriter, io::Write, path::PathBuf};
+
+use converter_core::{
+  Camera, GaussianSplat, Scene as CoreScene,
+  operations::pipeline::{Operation, apply_operations},
+  sh_coefficients_per_channel,
+};
+use numpy::{
+  IntoPyArray, PyArray, PyArray1, PyArray2, PyArray3, PyArrayMethods, PyUntypedArrayMethods,
+  ndarray::{Array1, Array2, Array3, Dimension},
+};
+use pyo3::{
+  exceptions::{PyTypeError, PyValueError},
+  prelude::*,
+  types::{PyBytes, PyDict, PyString},
+};
+
+use crate::formats::{ExportOptions, format_of, write_scene};
+use crate::{ConversionError, to_py_err};
+
+/// Gaussian splats as NumPy `float32` arrays, in the values 3DGS trains and stores: log scales,
+/// logit opacities, `(w, x, y, z)` rotations and raw SH coefficients. `sh_rest` is
+/// `(N, 3, coefficients)`, channel-major like the `f_rest_*` properties of a .ply.
+#[pyclass(module = "gs_flux")]
+pub struct Scene {
+  #[pyo3(get)]
+  positions: Py<PyArray2<f32>>,
+  #[pyo3(get)]
+  normals: Py<PyArray2<f32>>,
+  #[pyo3(get)]
+  scales: Py<PyArray2<f32>>,
+  #[pyo3(get)]
+  rotations: Py<PyArray2<f32>>,
+  #[pyo3(get)]
+  opacities: Py<PyArray1<f32>>,
+  #[pyo3(get)]
+  sh_dc: Py<PyArray2<f32>>,
+  #[pyo3(get)]
+  sh_rest: Py<PyArray3<f32>>,
+  /// Cameras of the source file, written again by the formats that store them.
+  cameras: Vec<Camera>,
+}
+
+/// `value` as a C-contiguous `float32` array of `ndim` dimensions, without copying arrays that
+/// already are one.
+fn float_array<'py, D: Dimension>(
+  value: &Bound<'py, PyAny>,
+  name: &str,
+  ndim: usize,
+) -> PyResult<Bound<'py, PyArray<f32, D>>> {
+  let py = value.py();
+  let kwargs = PyDict::new(py);
+  kwargs.set_item("dtype", "float32")?;
+  let array = py
+    .import("numpy")?
+    .call_method("ascontiguousarray", (value,), Some(&kwargs))?;
+  array
+    .cast_into::<PyArray<f32, D>>()
+    .map_err(|_| PyValueError::new_err(format!("{} must be a {}-dimensional array", name, ndim)))
+}
+
+/// `value` as an `(N, columns)` array.
+fn columns_array<'py>(
+  value: &Bound<'py, PyAny>,
+  name: &str,
+  columns: usize,
+) -> PyResult<Bound<'py, PyArray2<f32>>> {
+  let array = float_array(value, name, 2)?;
+  if array.shape()[1] != columns {
+    return Err(PyValueError::new_err(format!(
+      "{} must have {} columns, not {}",
+      name,
+      columns,
+      array.shape()[1]
+    )));
+  }
+  Ok(array)
+}
+
+/// `value` as an `(N, 3, coefficients)` array of higher SH bands.
+fn sh_rest_array<'py>(value: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyArray3<f32>>> {
+  let array = float_array(value, "sh_rest", 3)?;
+  let shape = array.shape();
+  if shape[1] != 3 || !(0..=3).any(|degree| sh_coefficients_per_channel(degree) == shape[2]) {
+    return Err(PyValueError::new_err(format!(
+      "sh_rest must be (N, 3, 0 | 3 | 8 | 15), not {:?}",
+      shape
+    )));
+  }
+  Ok(array)
+}
+
+impl Scene {
+  /// Moves the splats of `scene` into new arrays, one pass over the splats.
+  pub fn from_core(py: Python<'_>, scene: CoreScene) -> Self {
+    let count = scene.splats.len();
+    let coefficients = sh_coefficients_per_channel(scene.sh_degree());
+    let columns = py.detach(|| {
+      let mut positions = Vec::with_capacity(count * 3);
+      let mut normals = Vec::with_capacity(count * 3);
+      let mut scales = Vec::with_capacity(count * 3);
+      let mut rotations = Vec::with_capacity(count * 4);
+      let mut opacities = Vec::with_capacity(count);
+      let mut sh_dc = Vec::with_capacity(count * 3);
+      let mut sh_rest = Vec::with_capacity(count * 3 * coefficients);
+      for splat in &scene.splats {
+        positions.extend(splat.position);
+        normals.extend(splat.normal);
+        scales.extend(splat.scale);
+        rotations.extend(splat.rotation);
+        opacities.push(splat.opacity);
+        sh_dc.extend(splat.spherical_harmonics_dc);
+        // Splats below the scene's degree read as zeros in the bands they lack
+        for channel in 0..3 {
+          sh_rest.extend((0..coefficients).map(|index| splat.sh_rest(channel, index)));
+        }
+      }
+      (
+        positions, normals, scales, rotations, opacities, sh_dc, sh_rest,
+      )
+    });
+    let (positions, normals, scales, rotations, opacities, sh_dc, sh_rest) = columns;
+    let rows = |values: Vec<f32>, columns: usize| {
+      Array2::from_shape_vec((count, columns), values)
+        .unwrap()
+        .into_pyarray(py)
+        .unbind()
+    };
+
+    Self {
+      positions: rows(positions, 3),
+      normals: rows(normals, 3),
+      scales: rows(scales, 3),
+      rotations: rows(rotations, 4),
+      opacities: Array1::from_vec(opacities).into_pyarray(py).unbind(),
+      sh_dc: rows(sh_dc, 3),
+      sh_rest: Array3::from_shape_vec((count, 3, coefficients), sh_rest)
+        .unwrap()
+        .into_pyarray(py)
+        .unbind(),
+      cameras: scene.cameras,
+    }
+  }
+
+  /// The splats of the arrays, which must all have the same number of rows.
+  pub fn to_core(&self, py: Python<'_>) -> PyResult<CoreScene> {
+    let positions = self.positions.bind(py).readonly();
+    let normals = self.normals.bind(py).readonly();
+    let scales = self.scales.bind(py).readonly();
+    let rotations = self.rotations.bind(py).readonly();
+    let opacities = self.opacities.bind(py).readonly();
+    let sh_dc = self.sh_dc.bind(py).readonly();
+    let sh_rest = self.sh_rest.bind(py).readonly();
+    let (positions, normals, scales, rotations, opacities, sh_dc, sh_rest) = (
+      positions.as_array(),
+      normals.as_array(),
+      scales.as_array(),
+      rotations.as_array(),
+      opacities.as_array(),
+      sh_dc.as_array(),
+      sh_rest.as_array(),
+    );
+
+    let count = positions.nrows();
+    for (name, rows) in [
+      ("normals", normals.nrows()),
+      ("scales", scales.nrows()),
+      ("rotations", rotations.nrows()),
+      ("opacities", opacities.len()),
+      ("sh_dc", sh_dc.nrows()),
+      ("sh_rest", sh_rest.shape()[0]),
+    ] {
+      if rows != count {
+        return Err(PyValueError::new_err(format!(
+          "positions has {} rows but {} has {}",
+          count, name, rows
+        )));
+      }
+    }
+
+    let row3 = |array: &numpy::ndarray::ArrayView2<f32>, index: usize| {
+      [array[[index, 0]], array[[index, 1]], array[[index, 2]]]
+    };
+    let splats = (0..count)
+      .map(|index| GaussianSplat {
+        position: row3(&positions, index),
+        normal: row3(&normals, index),
+        spherical_harmonics_dc: row3(&sh_dc, index),
+        // Channel-major, as the array is (N, 3, coefficients)
+        spherical_harmonics_rest: sh_rest
+          .index_axis(numpy::ndarray::Axis(0), index)
+          .iter()
+          .copied()
+          .collect(),
+        opacity: opacities[index],
+        scale: row3(&scales, index),
+        rotation: [
+          rotations[[index, 0]],
+          rotations[[index, 1]],
+          rotations[[index, 2]],
+          rotations[[index, 3]],
+        ],
+      })
+      .collect();
+
+    Ok(CoreScene {
+      splats,
+      cameras: self.cameras.clone(),
+    })
+  }
+}
+
+#[pymethods]
+impl Scene {
+  /// Builds a scene from arrays of `N` rows, e.g. the parameters of a training run. `sh_rest`
+  /// defaults to no higher bands and `normals` to zeros.
+  #[new]
+  #[pyo3(signature = (positions, scales, rotations, opacities, sh_dc, sh_rest = None, normals = None))]
+  fn new(
+    positions: &Bound<'_, PyAny>,
+    scales: &Bound<'_, PyAny>,
+    rotations: &Bound<'_, PyAny>,
+    opacities: &Bound<'_, PyAny>,
+    sh_dc: &Bound<'_, PyAny>,
+    sh_rest: Option<&Bound<'_, PyAny>>,
+    normals: Option<&Bound<'_, PyAny>>,
+  ) -> PyResult<Self> {
+    let py = positions.py();
+    let positions = columns_array(positions, "positions", 3)?;
+    let count = positions.shape()[0];
+    let normals = match normals {
+      Some(normals) => columns_array(normals, "normals", 3)?,
+      None => Array2::<f32>::zeros((count, 3)).into_pyarray(py),
+    };
+    let sh_rest = match sh_rest {
+      Some(sh_rest) => sh_rest_array(sh_rest)?,
+      None => Array3::<f32>::zeros((count, 3, 0)).into_pyarray(py),
+    };
+    let scene = Self {
+      positions: positions.unbind(),
+      normals: normals.unbind(),
+      scales: columns_array(scales, "scales", 3)?.unbind(),
+      rotations: columns_array(rotations, "rotations", 4)?.unbind(),
+      opacities: float_array(opacities, "opacities", 1)?.unbind(),
+      sh_dc: columns_array(sh_dc, "sh_dc", 3)?.unbind(),
+      sh_rest: sh_rest.unbind(),
+      cameras: Vec::new(),
+    };
+    // Fails early on arrays of different lengths
+    scene.to_core(py)?;
+    Ok(scene)
+  }
+
+  #[setter]
+  fn set_positions(&mut self, value: &Bound<'_, PyAny>) -> PyResult<()> {
+    self.positions = columns_array(value, "positions", 3)?.unbind();
+    Ok(())
+  }
+
+  #[setter]
+  fn set_normals(&mut self, value: &Bound<'_, PyAny>) -> PyResult<()> {
+    self.normals = columns_array(value, "normals", 3)?.unbind();
+    Ok(())
+  }
+
+  #[setter]
+  fn set_scales(&mut self, value: &Bound<'_, PyAny>) -> PyResult<()> {
+    self.scales = columns_array(value, "scales", 3)?.unbind();
+    Ok(())
+  }
+
+  #[setter]
+  fn set_rotations(&mut self, value: &Bound<'_, PyAny>) -> PyResult<()> {
+    self.rotations = columns_array(value, "rotations", 4)?.unbind();
+    Ok(())
+  }
+
+  #[setter]
+  fn set_opacities(&mut self, value: &Bound<'_, PyAny>) -> PyResult<()> {
+    self.opacities = float_array(value, "opacities", 1)?.unbind();
+    Ok(())
+  }
+
+  #[setter]
+  fn set_sh_dc(&mut self, value: &Bound<'_, PyAny>) -> PyResult<()> {
+    self.sh_dc = columns_array(value, "sh_dc", 3)?.unbind();
+    Ok(())
+  }
+
+  #[setter]
+  fn set_sh_rest(&mut self, value: &Bound<'_, PyAny>) -> PyResult<()> {
+    self.sh_rest = sh_rest_array(value)?.unbind();
+    Ok(())
+  }
+
+  /// The SH degree (0-3) of `sh_rest`.
+  #[getter]
+  fn sh_degree(&self, py: Python<'_>) -> u8 {
+    let coefficients = self.sh_rest.bind(py).shape()[2];
+    (0..=3)
+      .find(|&degree| sh_coefficients_per_channel(degree) == coefficients)
+      .unwrap_or(0)
+  }
+
+  /// Number of cameras stored with the scene.
+  #[getter]
+  fn camera_count(&self) -> usize {
+    self.cameras.len()
+  }
+
+  fn __len__(&self, py: Python<'_>) -> usize {
+    self.positions.bind(py).shape()[0]
+  }
+
+  fn __repr__(&self, py: Python<'_>) -> String {
+    format!(
+      "Scene({} splats, SH degree {}, {} cameras)",
+      self.__len__(py),
+      self.sh_degree(py),
+      self.cameras.len()
+    )
+  }
+
+  /// Applies processing steps in order, each written like `gs-flux --op` (e.g.
+  /// `"min-opacity:0.05"`, `"where:opacity > 0.1 and z > 0"`) or as a dict like a pipeline file
+  /// (e.g. `{"op": "crop", "box": [-1, -1, -1, 1, 1, 1]}`). The arrays are replaced by new ones.
+  #[pyo3(signature = (*operations))]
+  fn apply(&mut self, py: Python<'_>, operations: Vec<Bound<'_, PyAny>>) -> PyResult<()> {
+    let operations = operations
+      .iter()
+      .map(|operation| {
+        if let Ok(text) = operation.cast::<PyString>() {
+          return text.to_str()?.parse::<Operation>().map_err(to_py_err);
+        }
+        if operation.is_instance_of::<PyDict>() {
+          let json: String = py
+            .import("json")?
+            .call_method1("dumps", (operation,))?
+            .extract()?;
+          return serde_json::from_str::<Operation>(&json)
+            .map_err(|e| ConversionError::new_err(e.to_string()));
+        }
+        Err(PyTypeError::new_err("Operations are strings or dicts"))
+      })
+      .collect::<PyResult<Vec<_>>>()?;
+
+    let mut scene = self.to_core(py)?;
+    py.detach(|| apply_operations(&mut scene, &operations));
+    *self = Self::from_core(py, scene);
+    Ok(())
+  }
+
+  /// Writes the scene to `path`, in `format` or the one of its extension. Keyword arguments are
+  /// the export options of `gs-flux`: encoding, point_cloud, ellipsoids, sigma, max_triangles,
+  /// min_opacity, importance_order, compression_level, sh_palette_size and meshopt, plus sh_degree
+  /// for .ply.
+  #[pyo3(signature = (path, format = None, **options))]
+  fn write(
+    &self,
+    py: Python<'_>,
+    path: PathBuf,
+    format: Option<String>,
+    options: Option<&Bound<'_, PyDict>>,
+  ) -> PyResult<()> {
+    let options = ExportOptions::from_kwargs(options)?;
+    let format = format.unwrap_or_else(|| format_of(&path));
+    let scene = self.to_core(py)?;
+    py.detach(|| {
+      let mut writer = BufWriter::new(File::create(&path)?);
+      write_scene(&scene, &format, &mut writer, &options)?;
+      writer.flush()?;
+      Ok::<_, converter_core::ConversionError>(())
+    })
+    .map_err(to_py_err)
+  }
+
+  /// The scene written in `format`, with the same options as `write`.
+  #[pyo3(signature = (format, **options))]
+  fn dumps<'py>(
+    &self,
+    py: Python<'py>,
+    format: &str,
+    options: Option<&Bound<'_, PyDict>>,
+  ) -> PyResult<Bound<'py, PyBytes>> {
+    let options = ExportOptions::from_kwargs(options)?;
+    let scene = self.to_core(py)?;
+    let data = py
+      .detach(|| {
+        let mut data = Vec::new();
+        write_scene(&scene, format, &mut data, &options).map(|_| data)
+      })
+      .map_err(to_py_err)?;
+    Ok(PyBytes::new(py, &data))
+  }
+}
@@ -0,0 +1,122 @@
+"""The `gs_flux` module as training code uses it: arrays changed in place and written back, and
+the errors of arrays that don't fit together. Run with `pytest` after `maturin develop`."""
+
+import numpy as np
+import pytest
+
+import gs_flux
+
+
+def make_scene(count=4, sh_rest=None):
+    rows = np.arange(count, dtype=np.float32)[:, None]
+    return gs_flux.Scene(
+        positions=np.hstack([rows, -rows, rows * 0.5]),
+        scales=np.full((count, 3), -3.0, dtype=np.float32),
+        rotations=np.tile(np.array([1, 0, 0, 0], dtype=np.float32), (count, 1)),
+        opacities=np.linspace(-2, 2, count, dtype=np.float32),
+        sh_dc=np.full((count, 3), 0.25, dtype=np.float32),
+        sh_rest=sh_rest,
+    )
+
+
+def test_in_place_changes_are_written(tmp_path):
+    sh_rest = np.random.default_rng(7).normal(size=(4, 3, 15)).astype(np.float32)
+    make_scene(sh_rest=sh_rest).write(tmp_path / "scene.ply")
+
+    scene = gs_flux.read(tmp_path / "scene.ply")
+    assert len(scene) == 4
+    assert scene.sh_degree == 3
+    np.testing.assert_array_equal(scene.sh_rest, sh_rest)
+
+    # The getter hands out the scene's own array rather than a copy
+    opacities = scene.opacities
+    opacities *= 0.5
+    opacities[0] = 3.0
+    assert scene.opacities is opacities
+    scene.positions[:, 2] += 10
+    scene.write(tmp_path / "changed.ply")
+
+    changed = gs_flux.read(tmp_path / "changed.ply")
+    np.testing.assert_array_equal(changed.opacities, opacities)
+    np.testing.assert_array_equal(changed.positions, scene.positions)
+    assert changed.positions[:, 2].min() >= 10
+
+
+def test_assigned_arrays_are_kept_and_written():
+    scene = make_scene()
+    opacities = np.zeros(4, dtype=np.float32)
+    scene.opacities = opacities
+    # Already C-contiguous float32, so it isn't copied
+    assert scene.opacities is opacities
+    opacities[:] = 1.5
+
+    data = scene.dumps("ply")
+    np.testing.assert_array_equal(gs_flux.loads(data).opacities, np.full(4, 1.5))
+    # Other layouts are converted
+    scene.scales = np.ones((4, 3), dtype=np.float64)
+    assert scene.scales.dtype == np.float32
+
+
+def test_sh_degree_reduces_the_bands_of_a_ply():
+    scene = make_scene(sh_rest=np.ones((4, 3, 15), dtype=np.float32))
+    assert gs_flux.loads(scene.dumps("ply")).sh_degree == 3
+    assert gs_flux.loads(scene.dumps("ply", sh_degree=1)).sh_degree == 1
+    assert gs_flux.loads(scene.dumps("ply", sh_degree=0)).sh_degree == 0
+
+
+def test_rows_must_match():
+    with pytest.raises(ValueError, match="positions has 4 rows but scales has 3"):
+        gs_flux.Scene(
+            positions=np.zeros((4, 3)),
+            scales=np.zeros((3, 3)),
+            rotations=np.zeros((4, 4)),
+            opacities=np.zeros(4),
+            sh_dc=np.zeros((4, 3)),
+        )
+
+    # Arrays assigned later are checked when the scene is used
+    scene = make_scene()
+    scene.opacities = np.zeros(2, dtype=np.float32)
+    with pytest.raises(ValueError, match="opacities has 2"):
+        scene.dumps("splat")
+    with pytest.raises(ValueError, match="opacities has 2"):
+        scene.apply("min-opacity:0.1")
+
+
+def test_columns_must_match():
+    scene = make_scene()
+    with pytest.raises(ValueError, match="rotations must have 4 columns, not 3"):
+        scene.rotations = np.zeros((4, 3))
+    with pytest.raises(ValueError, match="positions must be a 2-dimensional array"):
+        scene.positions = np.zeros(12)
+    with pytest.raises(ValueError, match="opacities must be a 1-dimensional array"):
+        scene.opacities = np.zeros((4, 1))
+
+
+@pytest.mark.parametrize("shape", [(4, 3, 5), (4, 4, 3), (4, 1, 15), (4, 3, 16)])
+def test_sh_rest_must_hold_whole_bands(shape):
+    with pytest.raises(ValueError, match="sh_rest must be"):
+        make_scene(sh_rest=np.zeros(shape, dtype=np.float32))
+    scene = make_scene()
+    with pytest.raises(ValueError, match="sh_rest must be"):
+        scene.sh_rest = np.zeros(shape, dtype=np.float32)
+
+
+def test_sh_rest_must_be_three_dimensional():
+    scene = make_scene()
+    with pytest.raises(ValueError, match="sh_rest must be a 3-dimensional array"):
+        scene.sh_rest = np.zeros((4, 45), dtype=np.float32)
+
+
+def test_unknown_options_and_formats_are_refused(tmp_path):
+    scene = make_scene()
+    with pytest.raises(TypeError, match="Unknown export option 'shdegree'"):
+        scene.dumps("ply", shdegree=0)
+    with pytest.raises(ValueError, match="sh_degree is 0, 1, 2 or 3"):
+        scene.dumps("ply", sh_degree=4)
+    with pytest.raises(gs_flux.ConversionError):
+        scene.dumps("jpeg")
+    with pytest.raises(gs_flux.ConversionError):
+        gs_flux.loads(b"not a scene")
+    with pytest.raises(FileNotFoundError):
+        gs_flux.read(tmp_path / "missing.ply")
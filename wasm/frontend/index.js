@@ -4,6 +4,7 @@ const fileLabel = document.getElementById('file-label');
 const fileNameSpan = document.getElementById('file-name');
 const sourceFormat = document.getElementById('source-format');
 const targetFormat = document.getElementById('target-format');
+const filterExpression = document.getElementById('filter-expression');
 const convertBtn = document.getElementById('convert-btn');
 const status = document.getElementById('status');
 const downloadContainer = document.getElementById('download-link-container');
@@ -71,6 +72,7 @@ convertBtn.addEventListener('click', async () => {
     const inputData = new Uint8Array(e.target.result);
     const srcFmt = sourceFormat.value;
     const tgtFmt = targetFormat.value;
+    const filter = filterExpression.value.trim();
 
     status.textContent = `Converting from ${srcFmt.toUpperCase()} to ${tgtFmt.replaceAll("_", " ").toUpperCase()}...`;
 
@@ -79,7 +81,8 @@ convertBtn.addEventListener('click', async () => {
     worker.postMessage({
       inputData,
       srcFmt,
-      tgtFmt
+      tgtFmt,
+      filter
     }, [inputData.buffer]);
   };
 
@@ -102,8 +105,19 @@ worker.onmessage = (e) => {
       "splat": "splat",
       "ascii_ply": "ply",
       "binary_ply": "ply",
+      "compressed_ply": "ply",
+      "point_cloud_ply": "ply",
       "spz_v2": "spz",
-      "csv": "csv"
+      "csv": "csv",
+      "ksplat": "ksplat",
+      "sog": "sog",
+      "glb": "glb",
+      "gltf": "gltf",
+      "usda": "usda",
+      "usdz": "usdz",
+      "las": "las",
+      "ellipsoid_ply": "ply",
+      "ellipsoid_obj": "obj"
     }
 
     const newFileName = selectedFile.name.split('.').slice(0, -1).join('.') + `.${formatExtensions[targetFormat.value]}`;
@@ -1,5 +1,5 @@
 // Worker thread logic
-import init, { convert } from '../pkg/wasm.js';
+import init, { convert, convert_where } from '../pkg/wasm.js';
 
 let wasmInitialized = false;
 
@@ -30,10 +30,13 @@ self.onmessage = async (e) => {
     // This will only run the initialization logic on the very first message.
     await initializeWasm();
 
-    const { inputData, srcFmt, tgtFmt } = e.data;
+    const { inputData, srcFmt, tgtFmt, filter } = e.data;
 
     // Perform the heavy computation (this is synchronous within the worker).
-    const outputData = convert(inputData, srcFmt, tgtFmt);
+    // An empty filter keeps every splat.
+    const outputData = filter
+      ? convert_where(inputData, srcFmt, tgtFmt, filter)
+      : convert(inputData, srcFmt, tgtFmt);
 
     // Send the result back to the main thread.
     // The ArrayBuffer is transferred for performance (zero-copy).
@@ -3,11 +3,21 @@ use wasm_bindgen::prelude::*;
 use converter_core::{
   ConversionError, Exporter, Importer, Scene,
   formats::{
+    colmap::ColmapPointsImporter,
+    compressed_ply::CompressedPlyExporter,
     csv::{CsvExporter, CsvImporter},
+    ellipsoid::{EllipsoidObjExporter, EllipsoidPlyExporter},
+    gltf::{GlbExporter, GltfExporter, GltfImporter},
+    ksplat::{KsplatExporter, KsplatImporter},
+    las::{LasExporter, LasImporter},
     ply::{PlyASCIIExporter, PlyBinaryExporter, PlyImporter},
+    point_cloud::PointCloudPlyExporter,
+    sog::{SogExporter, SogImporter},
     splat::{SplatExporter, SplatImporter},
     spz::{SpzImporter, SpzV2Exporter},
+    usd::{UsdImporter, UsdaExporter, UsdzExporter},
   },
+  operations::{expression::Expression, filter::filter_expression},
 };
 
 #[wasm_bindgen]
@@ -16,7 +26,31 @@ pub fn convert(
   source_format: &str,
   target_format: &str,
 ) -> Result<Vec<u8>, JsValue> {
-  run_conversion(input_data, source_format, target_format)
+  run_conversion(input_data, source_format, target_format, None)
+    .map_err(|err| JsValue::from_str(&err.to_string()))
+}
+
+/// Converts like `convert`, keeping only the splats `expression` holds for, e.g.
+/// `opacity > 0.1 and max_scale < 0.5`.
+#[wasm_bindgen]
+pub fn convert_where(
+  input_data: &[u8],
+  source_format: &str,
+  target_format: &str,
+  expression: &str,
+) -> Result<Vec<u8>, JsValue> {
+  Expression::compile(expression)
+    .and_then(|expression| {
+      run_conversion(input_data, source_format, target_format, Some(&expression))
+    })
+    .map_err(|err| JsValue::from_str(&err.to_string()))
+}
+
+/// Checks a filter expression, failing with the reason it doesn't compile.
+#[wasm_bindgen]
+pub fn check_expression(expression: &str) -> Result<(), JsValue> {
+  Expression::compile(expression)
+    .map(|_| ())
     .map_err(|err| JsValue::from_str(&err.to_string()))
 }
 
@@ -24,20 +58,31 @@ fn run_conversion(
   input_data: &[u8],
   source_format: &str,
   target_format: &str,
+  filter: Option<&Expression>,
 ) -> Result<Vec<u8>, ConversionError> {
   // Import data from input bytes
 
   // Use `&[u8]` as a reader. It implements `std::io::Read` directly.
   let mut reader = input_data;
 
-  let scene: Scene = match source_format {
+  let mut scene: Scene = match source_format {
     "ply" => PlyImporter::import(&mut reader)?,
     "spz" => SpzImporter::import(&mut reader)?,
     "csv" => CsvImporter::import(&mut reader)?,
     "splat" => SplatImporter::import(&mut reader)?,
+    "ksplat" => KsplatImporter::import(&mut reader)?,
+    "sog" => SogImporter::import(&mut reader)?,
+    "gltf" | "glb" => GltfImporter::import(&mut reader)?,
+    "usda" | "usdz" => UsdImporter::import(&mut reader)?,
+    "las" => LasImporter::import(&mut reader)?,
+    "colmap" => ColmapPointsImporter::import(&mut reader)?,
     _ => return Err(ConversionError::UnsupportedFormat),
   };
 
+  if let Some(expression) = filter {
+    filter_expression(&mut scene, expression);
+  }
+
   // Export the scene into a new byte vector
 
   // `Vec<u8>` can be used as a writer. It implements `std::io::Write`.
@@ -46,9 +91,20 @@ fn run_conversion(
   match target_format {
     "ascii_ply" => PlyASCIIExporter::export(&scene, &mut writer)?,
     "binary_ply" => PlyBinaryExporter::export(&scene, &mut writer)?,
+    "compressed_ply" => CompressedPlyExporter::export(&scene, &mut writer)?,
+    "point_cloud_ply" => PointCloudPlyExporter::export(&scene, &mut writer)?,
     "spz_v2" => SpzV2Exporter::export(&scene, &mut writer)?,
     "csv" => CsvExporter::export(&scene, &mut writer)?,
     "splat" => SplatExporter::export(&scene, &mut writer)?,
+    "ksplat" => KsplatExporter::export(&scene, &mut writer)?,
+    "sog" => SogExporter::export(&scene, &mut writer)?,
+    "gltf" => GltfExporter::export(&scene, &mut writer)?,
+    "glb" => GlbExporter::export(&scene, &mut writer)?,
+    "usda" => UsdaExporter::export(&scene, &mut writer)?,
+    "usdz" => UsdzExporter::export(&scene, &mut writer)?,
+    "las" => LasExporter::export(&scene, &mut writer)?,
+    "ellipsoid_ply" => EllipsoidPlyExporter::export(&scene, &mut writer)?,
+    "ellipsoid_obj" => EllipsoidObjExporter::export(&scene, &mut writer)?,
     _ => return Err(ConversionError::UnsupportedFormat),
   };
 